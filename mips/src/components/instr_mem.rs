@@ -1,9 +1,13 @@
 use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
 use syncrim::common::{
     Component, Condition, Input, InputPort, OutputType, Ports, SignalUnsigned, SignalValue,
     Simulator,
 };
+use syncrim::mem_image::{mem_word, MemImage};
 
 pub const INSTR_MEM_PC_ID: &str = "pc";
 
@@ -15,6 +19,15 @@ pub struct InstrMem {
     pub(crate) pos: (f32, f32),
     pub(crate) pc: Input,
     pub(crate) instr: Vec<u32>,
+    // optional, replaces `instr` on simulator creation and reset
+    #[serde(default)]
+    pub(crate) image: Option<MemImage>,
+    // bytes of the image by address
+    #[serde(skip)]
+    image_bytes: RefCell<Option<BTreeMap<usize, u8>>>,
+    // directory of the model, a relative image file is relative to it
+    #[serde(skip)]
+    model_dir: PathBuf,
 }
 
 use log::*;
@@ -39,14 +52,36 @@ impl Component for InstrMem {
         )
     }
 
+    fn set_model_dir(&mut self, dir: &Path) {
+        self.model_dir = dir.to_path_buf();
+    }
+
     fn clock(&self, simulator: &mut Simulator) -> Result<(), Condition> {
+        // cycle 0 is evaluated on simulator creation and reset
+        if let (Some(image), 0) = (&self.image, simulator.cycle) {
+            // the pc addresses 32 bits, all of usize on 32-bit targets
+            let range = 0..(u32::MAX as usize).saturating_add(1);
+            match image.load(&self.model_dir, range) {
+                Ok(bytes) => *self.image_bytes.borrow_mut() = Some(bytes),
+                Err(e) => {
+                    simulator.set_out_value(&self.id, INSTR_MEM_OUT_ID, SignalValue::Unknown);
+                    return Err(Condition::Error(format!("instr_mem {}: {}", self.id, e)));
+                }
+            }
+        }
+
+        let image_bytes = self.image_bytes.borrow();
         let instr: SignalValue =
             match TryInto::<SignalUnsigned>::try_into(simulator.get_input_value(&self.pc)) {
                 Ok(pc) => {
                     trace!("--- evaluate instr mem: pc {:?}", pc);
-                    // get instr at pc/4
-                    match self.instr.get((pc / 4) as usize) {
-                        Some(instr) => (*instr).into(),
+                    // get instr at pc/4, MIPS is big endian
+                    let instr = match image_bytes.as_ref() {
+                        Some(bytes) => mem_word(bytes, (pc & !3) as usize, true),
+                        None => self.instr.get((pc / 4) as usize).copied(),
+                    };
+                    match instr {
+                        Some(instr) => instr.into(),
                         _ => SignalValue::Unknown,
                     }
                 }
@@ -71,10 +106,20 @@ impl InstrMem {
             pos,
             pc,
             instr,
+            image: None,
+            image_bytes: RefCell::new(None),
+            model_dir: PathBuf::new(),
         }
     }
 
     pub fn rc_new(id: &str, pos: (f32, f32), pc: Input, instr: Vec<u32>) -> Rc<Self> {
         Rc::new(InstrMem::new(id, pos, pc, instr))
     }
+
+    /// Instruction memory initialised from an image file
+    pub fn rc_new_from_image(id: &str, pos: (f32, f32), pc: Input, image: MemImage) -> Rc<Self> {
+        let mut instr_mem = InstrMem::new(id, pos, pc, vec![]);
+        instr_mem.image = Some(image);
+        Rc::new(instr_mem)
+    }
 }
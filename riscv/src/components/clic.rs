@@ -1,6 +1,7 @@
 use log::trace;
 use serde::{Deserialize, Serialize};
 use syncrim::common::InputPort;
+use syncrim::coverage::CoverPoint;
 use syncrim::{
     common::{Component, Condition, Id, Input, OutputType, Ports, Simulator},
     signal::{SignalSigned, SignalUnsigned, SignalValue},
@@ -33,6 +34,11 @@ pub const CLIC_MEPC_ISR_MUX: &str = "isr_mepc_sel";
 pub const CLIC_RF_RA_WE: &str = "rf_ra_we";
 // pub const CLIC_REG_FILE_WRITE_ID: &str = "reg_file_write";
 pub const CLIC_STACK_DEPTH_OUT_ID: &str = "stack_depth_out";
+// id of the interrupt dispatched this cycle, unknown if none
+pub const CLIC_DISPATCHED_ID_OUT_ID: &str = "dispatched_id";
+
+// interrupt configuration CSRs at 0xB00.., pending bits at 0xB20..
+pub const CLIC_NR_INTERRUPTS: u32 = 32;
 
 pub const TIMER_WIDTH: u32 = 16;
 pub const TIMER_PRES_WIDTH: u32 = 4;
@@ -348,6 +354,7 @@ impl Component for CLIC {
                     CLIC_STACK_DEPTH_OUT_ID,
                     CLIC_MEPC_ISR_MUX,
                     CLIC_RF_RA_WE,
+                    CLIC_DISPATCHED_ID_OUT_ID,
                 ],
             ),
         )
@@ -664,6 +671,14 @@ impl Component for CLIC {
         simulator.set_out_value(&self.id, "mem_int_addr", mem_int_addr);
         simulator.set_out_value(&self.id, CLIC_INTERRUPT_ID, blu_int_value);
         simulator.set_out_value(&self.id, CLIC_INTERRUPT_INV_ID, blu_int_inv_value);
+        simulator.set_out_value(
+            &self.id,
+            CLIC_DISPATCHED_ID_OUT_ID,
+            match dispatched_interrupt_id {
+                Some(interrupt_id) => SignalValue::Data(interrupt_id),
+                None => SignalValue::Unknown,
+            },
+        );
         // simulator.set_out_value(&self.id, CLIC_INTERRUPT_MUX, blu_int);
         simulator.set_out_value(&self.id, "csr_data_o", csr_out as u32);
         simulator.set_out_value(
@@ -719,6 +734,14 @@ impl Component for CLIC {
         }
     }
 
+    fn cover_points(&self) -> Vec<CoverPoint> {
+        vec![CoverPoint::values(
+            CLIC_DISPATCHED_ID_OUT_ID,
+            Input::new(&self.id, CLIC_DISPATCHED_ID_OUT_ID),
+            0..CLIC_NR_INTERRUPTS,
+        )]
+    }
+
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
@@ -803,7 +826,7 @@ impl CLIC {
                     if mmio_entry.clicintie != 1 || mmio_entry.clicintip != 1 {
                         //dequeue self if pending or enabled status is 0
                         if queue
-                            .remove(&(((addr - offset + 4u32 * i as u32 - 0x1000) / 4) - 0x20))
+                            .remove(&((addr - offset + 4u32 * i as u32 - 0x1000) / 4))
                             .is_some()
                         {
                             history_entry.queue_op.push((
@@ -1085,3 +1108,68 @@ impl CLIC {
         };
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::rc::Rc;
+    use syncrim::{
+        common::{ComponentStore, Components},
+        components::ProbeOut,
+    };
+
+    #[test]
+    fn test_dispatched_id_coverage() {
+        let mut store: Components = vec![];
+        for id in [
+            "data", "addr", "data_we", "size", "csr_data", "csr_addr", "csr_ctl", "mret", "pc",
+            "pc_next",
+        ] {
+            store.push(Rc::new(ProbeOut::new(id)));
+        }
+        let input = |id| Input::new(id, "out");
+        store.push(Rc::new(CLIC::new(
+            "clic".to_string(),
+            (0.0, 0.0),
+            0.0,
+            0.0,
+            input("data"),
+            input("addr"),
+            input("data_we"),
+            input("size"),
+            input("csr_data"),
+            input("csr_addr"),
+            input("csr_ctl"),
+            input("mret"),
+            input("pc"),
+            input("pc_next"),
+        )));
+        let mut simulator = Simulator::new(ComponentStore { store }).unwrap();
+        let dispatched = &Input::new("clic", CLIC_DISPATCHED_ID_OUT_ID);
+        assert_eq!(simulator.get_input_value(dispatched), SignalValue::Unknown);
+
+        // set mstatus.MIE and make interrupt 3 pending and enabled, priority 2
+        simulator.set_out_value("csr_ctl", "out", 2);
+        simulator.set_out_value("csr_addr", "out", 0x300);
+        simulator.set_out_value("csr_data", "out", 0x8);
+        simulator.set_out_value("addr", "out", 0x1000 + 3 * 4);
+        simulator.set_out_value("data", "out", 0x0200_0101);
+        simulator.set_out_value("data_we", "out", 2);
+        simulator.set_out_value("size", "out", 4);
+        simulator.clock();
+        assert_eq!(simulator.get_input_value(dispatched), 3.into());
+
+        simulator.set_out_value("csr_ctl", "out", 0);
+        simulator.set_out_value("data_we", "out", 0);
+        simulator.clock();
+        assert_eq!(simulator.get_input_value(dispatched), SignalValue::Unknown);
+
+        let coverage = simulator.coverage();
+        assert_eq!(coverage.points.len(), 1);
+        let point = &coverage.points[0];
+        assert_eq!(point.point.name, CLIC_DISPATCHED_ID_OUT_ID);
+        assert_eq!(point.hits.len(), CLIC_NR_INTERRUPTS as usize);
+        assert_eq!(point.hits[3], 1);
+        assert_eq!(point.covered(), 1);
+    }
+}
@@ -8,6 +8,7 @@ use syncrim::common::{
     Component, Condition, Id, Input, InputPort, OutputType, Ports, SignalValue, Simulator,
 };
 use syncrim::components::MemCtrl;
+use syncrim::coverage::{CoverBin, CoverPoint};
 
 pub const DECODER_INSTRUCTION_ID: &str = "instruction";
 
@@ -52,6 +53,59 @@ pub const DECODER_CSR_CTL_ID: &str = "csr_ctl";
 pub const DECODER_CSR_DATA_MUX_ID: &str = "csr_data_mux";
 pub const DECODER_CSR_ADDR_ID: &str = "csr_addr";
 
+// instruction field masks for coverage bins
+const OPCODE: u32 = 0x7f;
+const OPCODE_FUNCT3: u32 = 0x707f;
+const OPCODE_FUNCT3_FUNCT7: u32 = 0xfe00707f;
+
+/// decoded instructions as (name, mask, value), one coverage bin each
+pub const DECODER_INSTRUCTIONS: [(&str, u32, u32); 44] = [
+    ("add", OPCODE_FUNCT3_FUNCT7, 0x00000033),
+    ("sub", OPCODE_FUNCT3_FUNCT7, 0x40000033),
+    ("sll", OPCODE_FUNCT3_FUNCT7, 0x00001033),
+    ("slt", OPCODE_FUNCT3_FUNCT7, 0x00002033),
+    ("sltu", OPCODE_FUNCT3_FUNCT7, 0x00003033),
+    ("xor", OPCODE_FUNCT3_FUNCT7, 0x00004033),
+    ("srl", OPCODE_FUNCT3_FUNCT7, 0x00005033),
+    ("sra", OPCODE_FUNCT3_FUNCT7, 0x40005033),
+    ("or", OPCODE_FUNCT3_FUNCT7, 0x00006033),
+    ("and", OPCODE_FUNCT3_FUNCT7, 0x00007033),
+    ("addi", OPCODE_FUNCT3, 0x00000013),
+    ("slti", OPCODE_FUNCT3, 0x00002013),
+    ("sltiu", OPCODE_FUNCT3, 0x00003013),
+    ("xori", OPCODE_FUNCT3, 0x00004013),
+    ("ori", OPCODE_FUNCT3, 0x00006013),
+    ("andi", OPCODE_FUNCT3, 0x00007013),
+    ("slli", OPCODE_FUNCT3_FUNCT7, 0x00001013),
+    ("srli", OPCODE_FUNCT3_FUNCT7, 0x00005013),
+    ("srai", OPCODE_FUNCT3_FUNCT7, 0x40005013),
+    ("lui", OPCODE, 0x37),
+    ("auipc", OPCODE, 0x17),
+    ("jal", OPCODE, 0x6f),
+    ("jalr", OPCODE_FUNCT3, 0x00000067),
+    ("beq", OPCODE_FUNCT3, 0x00000063),
+    ("bne", OPCODE_FUNCT3, 0x00001063),
+    ("blt", OPCODE_FUNCT3, 0x00004063),
+    ("bge", OPCODE_FUNCT3, 0x00005063),
+    ("bltu", OPCODE_FUNCT3, 0x00006063),
+    ("bgeu", OPCODE_FUNCT3, 0x00007063),
+    ("lb", OPCODE_FUNCT3, 0x00000003),
+    ("lh", OPCODE_FUNCT3, 0x00001003),
+    ("lw", OPCODE_FUNCT3, 0x00002003),
+    ("lbu", OPCODE_FUNCT3, 0x00004003),
+    ("lhu", OPCODE_FUNCT3, 0x00005003),
+    ("sb", OPCODE_FUNCT3, 0x00000023),
+    ("sh", OPCODE_FUNCT3, 0x00001023),
+    ("sw", OPCODE_FUNCT3, 0x00002023),
+    ("mret", u32::MAX, 0x30200073),
+    ("csrrw", OPCODE_FUNCT3, 0x00001073),
+    ("csrrs", OPCODE_FUNCT3, 0x00002073),
+    ("csrrc", OPCODE_FUNCT3, 0x00003073),
+    ("csrrwi", OPCODE_FUNCT3, 0x00005073),
+    ("csrrsi", OPCODE_FUNCT3, 0x00006073),
+    ("csrrci", OPCODE_FUNCT3, 0x00007073),
+];
+
 pub const DECODER_HEIGHT: f32 = 600.0;
 pub const DECODER_WIDTH: f32 = 30.0;
 
@@ -102,6 +156,18 @@ impl Component for Decoder {
         self
     }
 
+    fn cover_points(&self) -> Vec<CoverPoint> {
+        let bins = DECODER_INSTRUCTIONS
+            .iter()
+            .map(|(name, mask, value)| CoverBin::masked(name, *mask, *value))
+            .collect();
+        vec![CoverPoint::new(
+            DECODER_INSTRUCTION_ID,
+            self.instruction.clone(),
+            bins,
+        )]
+    }
+
     fn to_(&self) {
         println!("Decoder");
     }
@@ -657,7 +723,7 @@ mod test {
     use std::rc::Rc;
     use syncrim::{
         common::{ComponentStore, Input, Simulator},
-        components::ProbeOut,
+        components::{ProbeOut, ProbeStim},
     };
 
     #[test]
@@ -1401,6 +1467,48 @@ mod test {
         assert_eq!(simulator.get_input_value(branch_logic_ctl), 0b111.into());
         assert_eq!(simulator.get_input_value(branch_logic_enable), 1.into());
     }
+
+    #[test]
+    fn test_instruction_coverage() {
+        let cs = ComponentStore {
+            store: vec![
+                ProbeStim::rc_new(
+                    "instruction",
+                    (0.0, 0.0),
+                    vec![
+                        0x003100b3, //add x1, x2, x3
+                        0x00100093, //addi x1, x0, 1
+                        0x00012083, //lw x1, 0(x2)
+                        0x003100b3, //add x1, x2, x3
+                    ],
+                ),
+                Rc::new(Decoder {
+                    width: 0.0,
+                    height: 0.0,
+                    id: "decoder".to_string(),
+                    pos: (0.0, 0.0),
+                    instruction: Input::new("instruction", "out"),
+                }),
+            ],
+        };
+        let mut simulator = Simulator::new(cs).unwrap();
+        simulator.step(3);
+
+        let coverage = simulator.coverage();
+        assert_eq!(coverage.points.len(), 1);
+        let point = &coverage.points[0];
+        assert_eq!(point.hits.len(), 44);
+        let hits = |name: &str| {
+            let index = point.point.bins.iter().position(|b| b.name == name);
+            point.hits[index.unwrap()]
+        };
+        assert_eq!(hits("add"), 2);
+        assert_eq!(hits("addi"), 1);
+        assert_eq!(hits("lw"), 1);
+        assert_eq!(hits("sub"), 0);
+        assert_eq!(point.covered(), 3);
+        assert_eq!(point.holes().len(), 41);
+    }
 }
 
 // 0x0080016f, //jal x2, 8
@@ -488,3 +488,80 @@ impl GPIO {
         val
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use syncrim::common::ComponentStore;
+    use syncrim::components::{Bus, BusDevice, ProbeOut};
+
+    #[test]
+    fn test_gpio_on_bus() {
+        let cs = ComponentStore {
+            store: vec![
+                Rc::new(ProbeOut::new("addr")),
+                Rc::new(ProbeOut::new("ctrl")),
+                Rc::new(ProbeOut::new("data")),
+                Rc::new(ProbeOut::new("size")),
+                Rc::new(ProbeOut::new("sign")),
+                Rc::new(ProbeOut::new("csr")),
+                Bus::rc_new(
+                    "bus",
+                    (0.0, 0.0),
+                    Input::new("addr", "out"),
+                    Input::new("ctrl", "out"),
+                    vec![
+                        BusDevice::new("ram", 0..0x1000),
+                        BusDevice::new("gpio", GPIO_MMIO_BASE..GPIO_MMIO_BASE + 0x1c),
+                    ],
+                ),
+                Rc::new(GPIO {
+                    height: GPIO_HEIGHT,
+                    width: GPIO_WIDTH,
+                    id: "gpio".to_string(),
+                    pos: (0.0, 0.0),
+                    memory: Memory::default(),
+                    pins: Pins::default(),
+                    csrstore: GPIOCsrStore::default(),
+                    data_i: Input::new("data", "out"),
+                    size_i: Input::new("size", "out"),
+                    we_i: Input::new("bus", "gpio_ctrl"),
+                    addr_i: Input::new("bus", "gpio_addr"),
+                    se_i: Input::new("sign", "out"),
+                    csr_d: Input::new("csr", "out"),
+                    csr_a: Input::new("csr", "out"),
+                    csr_ctl: Input::new("csr", "out"),
+                }),
+            ],
+        };
+        let mut simulator = Simulator::new(cs).unwrap();
+        let pin = |simulator: &Simulator, i: u8| {
+            simulator.get_input_value(&Input::new("gpio", &format!("{}{}", GPIO_PIN_O_ID, i)))
+        };
+
+        // set pins 0 and 2 through the pin state register
+        simulator.set_out_value("addr", "out", GPIO_MMIO_BASE);
+        simulator.set_out_value("ctrl", "out", MemCtrl::Write as SignalUnsigned);
+        simulator.set_out_value("data", "out", 0b101);
+        simulator.set_out_value("size", "out", 4);
+        simulator.set_out_value("sign", "out", 0);
+        simulator.clock();
+        assert_eq!(pin(&simulator, 0), 1.into());
+        assert_eq!(pin(&simulator, 1), 0.into());
+        assert_eq!(pin(&simulator, 2), 1.into());
+
+        // a ram access is not seen by the gpio
+        simulator.set_out_value("addr", "out", 0);
+        simulator.set_out_value("data", "out", 0);
+        simulator.clock();
+        assert_eq!(pin(&simulator, 0), 1.into());
+
+        simulator.set_out_value("addr", "out", GPIO_MMIO_BASE);
+        simulator.set_out_value("ctrl", "out", MemCtrl::Read as SignalUnsigned);
+        simulator.clock();
+        assert_eq!(
+            simulator.get_input_value(&Input::new("gpio", GPIO_DATA_O_ID)),
+            0b101.into()
+        );
+    }
+}
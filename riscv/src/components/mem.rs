@@ -4,11 +4,22 @@ use num_enum::TryFromPrimitive;
 use serde::{Deserialize, Serialize};
 use std::ops::Deref;
 use std::ops::Range;
-use std::{cell::RefCell, collections::BTreeMap, convert::TryFrom, rc::Rc};
+use std::path::Path;
+use std::{
+    cell::{Ref, RefCell},
+    collections::{BTreeMap, BTreeSet},
+    convert::TryFrom,
+    rc::Rc,
+};
 use syncrim::common::{
     Component, Condition, Id, Input, InputPort, OutputType, Ports, SignalSigned, SignalUnsigned,
     SignalValue, Simulator,
 };
+use syncrim::components::{
+    FaultCheck, MemAccess, MemFaults, MemLatency, MemTrace, PagedMemory, ResetInput, UninitRead,
+    WaitStates, WriteHistory, MEM_STALL_OUT_ID, MEM_TRAP_CAUSE_OUT_ID, MEM_TRAP_OUT_ID,
+};
+use syncrim::mem_image::{dump_image, MemImageFormat};
 //#[feature("gui-egui")]
 //use egui_extras::TableBuilder;
 pub const RV_MEM_DATA_I_ID: &str = "data_i";
@@ -17,6 +28,8 @@ pub const RV_MEM_CTRL_ID: &str = "ctrl";
 pub const RV_MEM_SEXT_ID: &str = "sext";
 pub const RV_MEM_SIZE_ID: &str = "size";
 pub const RV_INTERRUPT_ID: &str = "interrupt";
+pub const RV_MEM_RESET_ID: &str = "reset";
+pub const RV_MEM_PC_ID: &str = "pc";
 pub const RV_MEM_DATA_O_ID: &str = "data_o";
 #[derive(Serialize, Deserialize, Clone)]
 pub struct RVMem {
@@ -36,6 +49,9 @@ pub struct RVMem {
     pub(crate) size: Input,
     pub(crate) interrupt: Input,
     //  pub(crate) mem_int_addr: Input,
+    // optional, while asserted accesses are ignored and data_o is driven with the reset value
+    #[serde(default)]
+    pub(crate) reset: Option<ResetInput>,
 
     // memory
     #[serde(skip)]
@@ -44,9 +60,36 @@ pub struct RVMem {
     // later history... tbd
     //
     history: RefCell<Vec<MemOp>>,
+    // bytes written by each clock, for the memory view
+    #[serde(skip)]
+    pub(crate) writes: RefCell<WriteHistory>,
+    // wait states, none by default
+    #[serde(default)]
+    pub(crate) latency: MemLatency,
+    #[serde(skip)]
+    pub(crate) wait: RefCell<WaitStates>,
+    // loads and stores, for export and the heat map, recorded when enabled
+    #[serde(default)]
+    pub(crate) record_trace: bool,
     #[serde(skip)]
-    pub init_state: BTreeMap<usize, u8>,
+    pub(crate) trace: RefCell<MemTrace>,
+    // addresses left to the memory mapped devices, signalled on mmio_mux_ctl,
+    // none when the devices are attached through a `Bus`
+    #[serde(default = "default_mmio")]
+    pub(crate) mmio: Option<Range<u32>>,
+    // misaligned, out of range and illegal size accesses, not for the mmio range
+    #[serde(default)]
+    pub(crate) faults: MemFaults,
+    // reads of uninitialised bytes, the optional pc is named in warnings
+    #[serde(default)]
+    pub(crate) uninit_read: UninitRead,
+    #[serde(default)]
+    pub(crate) pc: Option<Input>,
 }
+pub(crate) fn default_mmio() -> Option<Range<u32>> {
+    Some(0x1000..0x5010)
+}
+
 #[derive(Serialize, Deserialize, Clone)]
 struct MemOp {
     pub data: Option<usize>,
@@ -83,10 +126,19 @@ impl RVMem {
             sext,
             size,
             interrupt,
-            memory: Memory::new(memory.clone()),
+            reset: None,
+            memory: Memory::new(memory),
             range,
             history: RefCell::new(vec![]),
-            init_state: memory.clone(),
+            writes: RefCell::default(),
+            latency: MemLatency::default(),
+            wait: RefCell::default(),
+            record_trace: false,
+            trace: RefCell::default(),
+            mmio: default_mmio(),
+            faults: MemFaults::default(),
+            uninit_read: UninitRead::default(),
+            pc: None,
         }
     }
 
@@ -105,13 +157,20 @@ impl RVMem {
         interrupt: Input,
         range: Range<u32>,
     ) -> Rc<Self> {
-        let mut mem = BTreeMap::new();
-        //fill the defined memory range with zeroes
-        for i in range.clone() {
-            mem.insert(i as usize, 0u8);
-        }
         Rc::new(RVMem::new(
-            id, pos, width, height, big_endian, data, addr, ctrl, sext, size, interrupt, mem, range,
+            id,
+            pos,
+            width,
+            height,
+            big_endian,
+            data,
+            addr,
+            ctrl,
+            sext,
+            size,
+            interrupt,
+            BTreeMap::new(),
+            range,
         ))
     }
 
@@ -136,10 +195,71 @@ impl RVMem {
             range,
         ))
     }
+
+    /// Add wait states, see `MemLatency`
+    pub fn with_latency(mut self, latency: MemLatency) -> Self {
+        self.latency = latency;
+        self
+    }
+
+    /// Record the loads and stores, see `MemTrace`
+    pub fn with_trace(mut self) -> Self {
+        self.record_trace = true;
+        self
+    }
+
+    /// Detect reads of uninitialised bytes, see `UninitRead`, warnings name
+    /// the value of `pc` if given
+    pub fn with_uninit_read(mut self, uninit_read: UninitRead, pc: Option<Input>) -> Self {
+        self.uninit_read = uninit_read;
+        self.pc = pc;
+        self
+    }
+
+    /// Handle faulty accesses as given, see `MemFaults`
+    pub fn with_faults(mut self, faults: MemFaults) -> Self {
+        self.faults = faults;
+        self
+    }
+
+    fn is_mmio(&self, addr: u32) -> bool {
+        self.mmio.as_ref().is_some_and(|mmio| mmio.contains(&addr))
+    }
+
+    /// Addresses written in the current cycle
+    pub fn written(&self) -> BTreeSet<usize> {
+        self.writes.borrow().written()
+    }
+
+    /// Bytes changed between cycles `from` and `to`, as (old, new)
+    pub fn diff(&self, from: usize, to: usize) -> BTreeMap<usize, (u8, u8)> {
+        self.writes.borrow().diff(from, to)
+    }
+
+    /// The loads and stores done so far
+    pub fn trace(&self) -> Ref<'_, MemTrace> {
+        self.trace.borrow()
+    }
+
+    /// Write the loads and stores to `path` as csv
+    pub fn export_trace(&self, path: &Path) -> Result<(), String> {
+        self.trace.borrow().export(path)
+    }
+
+    /// Write the bytes in `range` to `path`, see `mem_image::dump_image`
+    pub fn dump(
+        &self,
+        path: &Path,
+        format: MemImageFormat,
+        range: Range<usize>,
+    ) -> Result<(), String> {
+        let start = range.start;
+        dump_image(path, format, &self.memory.borrow().bytes(range), start)
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct Memory(pub Rc<RefCell<BTreeMap<usize, u8>>>);
+pub struct Memory(pub Rc<RefCell<PagedMemory>>);
 
 impl Default for Memory {
     fn default() -> Self {
@@ -149,7 +269,13 @@ impl Default for Memory {
 
 impl Memory {
     pub fn new(data: BTreeMap<usize, u8>) -> Self {
-        Memory(Rc::new(RefCell::new(data)))
+        Memory(Rc::new(RefCell::new(data.into())))
+    }
+
+    /// The first byte of the access not initialised
+    fn uninit(&self, addr: usize, size: usize) -> Option<usize> {
+        let memory = self.0.borrow();
+        (addr..addr + size).find(|addr| !memory.is_init(*addr))
     }
 
     fn align(&self, addr: usize, size: usize) -> SignalValue {
@@ -305,52 +431,96 @@ impl Component for RVMem {
     }
 
     fn get_id_ports(&self) -> (Id, Ports) {
+        let mut inputs = vec![
+            InputPort {
+                port_id: RV_MEM_DATA_I_ID.to_string(),
+                input: self.data.clone(),
+            },
+            InputPort {
+                port_id: RV_MEM_ADDR_ID.to_string(),
+                input: self.addr.clone(),
+            },
+            InputPort {
+                port_id: RV_MEM_CTRL_ID.to_string(),
+                input: self.ctrl.clone(),
+            },
+            InputPort {
+                port_id: RV_MEM_SEXT_ID.to_string(),
+                input: self.sext.clone(),
+            },
+            InputPort {
+                port_id: RV_MEM_SIZE_ID.to_string(),
+                input: self.size.clone(),
+            },
+            InputPort {
+                port_id: RV_INTERRUPT_ID.to_string(),
+                input: self.interrupt.clone(),
+            },
+        ];
+        if let Some(reset) = &self.reset {
+            inputs.push(InputPort {
+                port_id: RV_MEM_RESET_ID.to_string(),
+                input: reset.input.clone(),
+            });
+        }
+        if let Some(pc) = &self.pc {
+            inputs.push(InputPort {
+                port_id: RV_MEM_PC_ID.to_string(),
+                input: pc.clone(),
+            });
+        }
         (
             self.id.clone(),
             Ports::new(
+                inputs.iter().collect(),
+                OutputType::Combinatorial,
                 vec![
-                    &InputPort {
-                        port_id: RV_MEM_DATA_I_ID.to_string(),
-                        input: self.data.clone(),
-                    },
-                    &InputPort {
-                        port_id: RV_MEM_ADDR_ID.to_string(),
-                        input: self.addr.clone(),
-                    },
-                    &InputPort {
-                        port_id: RV_MEM_CTRL_ID.to_string(),
-                        input: self.ctrl.clone(),
-                    },
-                    &InputPort {
-                        port_id: RV_MEM_SEXT_ID.to_string(),
-                        input: self.sext.clone(),
-                    },
-                    &InputPort {
-                        port_id: RV_MEM_SIZE_ID.to_string(),
-                        input: self.size.clone(),
-                    },
-                    &InputPort {
-                        port_id: RV_INTERRUPT_ID.to_string(),
-                        input: self.interrupt.clone(),
-                    },
+                    "data_o",
+                    "err",
+                    "mmio_mux_ctl",
+                    MEM_STALL_OUT_ID,
+                    MEM_TRAP_OUT_ID,
+                    MEM_TRAP_CAUSE_OUT_ID,
                 ],
-                OutputType::Combinatorial,
-                vec!["data_o", "err", "mmio_mux_ctl"],
             ),
         )
     }
 
     fn clock(&self, simulator: &mut Simulator) -> Result<(), Condition> {
+        // cycle 0 is evaluated on simulator creation and reset, undoing the
+        // writes since restores the initial contents
+        if simulator.cycle == 0 {
+            self.writes.borrow_mut().undo(&mut self.memory.borrow_mut());
+        }
+        self.writes.borrow_mut().clock(simulator.cycle);
+        self.wait.borrow_mut().clock();
+        self.trace.borrow_mut().clock(simulator.cycle);
+        simulator.set_out_value(&self.id, MEM_STALL_OUT_ID, false);
+        simulator.set_out_value(&self.id, MEM_TRAP_OUT_ID, false);
+        simulator.set_out_value(&self.id, MEM_TRAP_CAUSE_OUT_ID, 0);
         let mut history_entry = MemOp {
             data: None,
             addr: 0,
             size: 0,
         };
+        // the memory content is left untouched while in reset, see
+        // `ResetInput::is_in_effect`
+        if let Some(reset) = &self.reset {
+            if reset.is_in_effect(simulator) {
+                trace!("mem {} in reset", self.id);
+                simulator.set_out_value(&self.id, "data_o", reset.value);
+                simulator.set_out_value(&self.id, "err", false);
+                simulator.set_out_value(&self.id, "mmio_mux_ctl", 0);
+                self.history.borrow_mut().push(history_entry);
+                return Ok(());
+            }
+        }
         let data = simulator.get_input_value(&self.data);
         let addr = simulator.get_input_value(&self.addr);
         let size = simulator.get_input_value(&self.size);
         let sign = simulator.get_input_value(&self.sext);
         let interrupt = simulator.get_input_value(&self.interrupt);
+        let mut fault_condition = None;
         // let mem_int_addr = simulator.get_input_value(&self.mem_int_addr);
 
         // match mem_int_addr {
@@ -371,6 +541,49 @@ impl Component for RVMem {
                     MemCtrl::try_from(ctrl as u8).unwrap()
                 };
 
+                // mmio accesses are not delayed nor checked
+                if let MemCtrl::Read | MemCtrl::Write = ctrl {
+                    let write = matches!(ctrl, MemCtrl::Write);
+                    let addr: u32 = addr.try_into().unwrap();
+                    let check = match size {
+                        SignalValue::Data(size) if !self.is_mmio(addr) => {
+                            self.faults.check(&self.id, addr, size, write, &self.range)
+                        }
+                        _ => FaultCheck::default(),
+                    };
+                    if let Some(cause) = check.trap {
+                        simulator.set_out_value(&self.id, MEM_TRAP_OUT_ID, true);
+                        simulator.set_out_value(&self.id, MEM_TRAP_CAUSE_OUT_ID, cause);
+                    }
+                    if check.suppress {
+                        trace!("mem {} access not done", self.id);
+                        simulator.set_out_value(&self.id, "data_o", SignalValue::Unknown);
+                        simulator.set_out_value(&self.id, "err", true);
+                        simulator.set_out_value(&self.id, "mmio_mux_ctl", 0);
+                        self.history.borrow_mut().push(history_entry);
+                        return check.condition.map_or(Ok(()), Err);
+                    }
+                    fault_condition = check.condition;
+                    let access = MemAccess {
+                        write,
+                        addr: addr.into(),
+                        size,
+                        data: if write { data } else { SignalValue::Unknown },
+                    };
+                    if !self.is_mmio(addr)
+                        && self
+                            .wait
+                            .borrow_mut()
+                            .wait(access, self.latency.wait_states(addr, write))
+                    {
+                        trace!("mem {} wait", self.id);
+                        simulator.set_out_value(&self.id, MEM_STALL_OUT_ID, true);
+                        simulator.set_out_value(&self.id, "mmio_mux_ctl", 0);
+                        self.history.borrow_mut().push(history_entry);
+                        return Ok(());
+                    }
+                }
+
                 match ctrl {
                     MemCtrl::ReadIsr => {
                         let addr: u32 = addr.try_into().unwrap();
@@ -379,6 +592,9 @@ impl Component for RVMem {
 
                         trace!("read isr {:?}", addr);
                         let value = self.memory.read(addr as usize, 4, false, self.big_endian);
+                        if let (true, SignalValue::Data(value)) = (self.record_trace, value) {
+                            self.trace.borrow_mut().record(addr, 4, false, value);
+                        }
                         simulator.set_out_value(&self.id, "data_o", value);
                         let error = self.memory.align(addr as usize, 4);
                         trace!("align {:?}", error);
@@ -387,7 +603,7 @@ impl Component for RVMem {
                     }
                     MemCtrl::Read => {
                         let addr: u32 = addr.try_into().unwrap();
-                        if !(0x1000..=0x500F).contains(&addr) {
+                        if !self.is_mmio(addr) {
                             //if not in mmio range
                             let size: u32 = size.try_into().unwrap();
                             let sign: u32 = sign.try_into().unwrap();
@@ -398,18 +614,50 @@ impl Component for RVMem {
                                 sign != 0,
                                 self.big_endian,
                             );
+                            if let (true, SignalValue::Data(value)) = (self.record_trace, value) {
+                                self.trace.borrow_mut().record(addr, size, false, value);
+                            }
                             simulator.set_out_value(&self.id, "data_o", value);
                             let error = self.memory.align(addr as usize, size as usize);
                             trace!("align {:?}", error);
                             simulator.set_out_value(&self.id, "err", error); // align
                             simulator.set_out_value(&self.id, "mmio_mux_ctl", 0);
+                            let uninit = match self.uninit_read {
+                                UninitRead::Zero => None,
+                                _ => self.memory.uninit(addr as usize, size as usize),
+                            };
+                            if let Some(uninit) = uninit {
+                                if let UninitRead::Uninitialized = self.uninit_read {
+                                    simulator.set_out_value(
+                                        &self.id,
+                                        "data_o",
+                                        SignalValue::Uninitialized,
+                                    );
+                                } else {
+                                    let pc = match self
+                                        .pc
+                                        .as_ref()
+                                        .map(|pc| simulator.get_input_value(pc))
+                                    {
+                                        Some(SignalValue::Data(pc)) => {
+                                            format!(" at pc {:#x}", pc)
+                                        }
+                                        _ => String::new(),
+                                    };
+                                    fault_condition =
+                                        fault_condition.or(Some(Condition::Warning(format!(
+                                            "mem {}: read of uninitialised address {:#x}{}",
+                                            self.id, uninit, pc
+                                        ))));
+                                }
+                            }
                         } else {
                             simulator.set_out_value(&self.id, "mmio_mux_ctl", 1);
                         }
                     }
                     MemCtrl::Write => {
                         let addr: u32 = addr.try_into().unwrap();
-                        if !(0x1000..=0x500F).contains(&addr) {
+                        if !self.is_mmio(addr) {
                             //if not in mmio range
                             let size: u32 = size.try_into().unwrap();
                             history_entry = MemOp {
@@ -426,8 +674,20 @@ impl Component for RVMem {
                                 size: size as u8,
                             };
                             trace!("write addr {:?} size {:?}", addr, size);
+                            let bytes = addr as usize..addr as usize + size as usize;
+                            let old = self.memory.borrow().init_bytes(bytes.clone());
                             self.memory
                                 .write(addr as usize, size as usize, self.big_endian, data);
+                            let new = self.memory.borrow().bytes(bytes);
+                            self.writes.borrow_mut().record(addr as usize, &old, &new);
+                            if self.record_trace {
+                                self.trace.borrow_mut().record(
+                                    addr,
+                                    size,
+                                    true,
+                                    data.try_into().unwrap(),
+                                );
+                            }
                             let value = self.memory.align(addr as usize, size as usize);
                             trace!("align {:?}", value);
                             simulator.set_out_value(&self.id, "err", value); // align
@@ -458,33 +718,31 @@ impl Component for RVMem {
         //     }
         // }
         self.history.borrow_mut().push(history_entry);
-        Ok(())
+        fault_condition.map_or(Ok(()), Err)
     }
 
     fn un_clock(&self) {
-        let entry = self.history.borrow_mut().pop().unwrap();
-        if let Some(d) = entry.data {
-            self.memory.write(
-                entry.addr,
-                entry.size.into(),
-                self.big_endian,
-                SignalValue::Data(d as u32),
-            )
-        } //self.memory.write(, size, big_endian, data)
+        // restoring the written bytes also makes bytes not initialised
+        // before the write uninitialised again
+        let mut memory = self.memory.borrow_mut();
+        for write in self.writes.borrow_mut().un_clock().iter().rev() {
+            memory.restore(write);
+        }
+        self.wait.borrow_mut().un_clock();
+        self.trace.borrow_mut().un_clock();
+        self.history.borrow_mut().pop();
     }
 
     fn reset(&self) {
-        //let mut mem = self.memory.0.borrow_mut();
-        //let b = self.init_state.0.clone();
-        self.memory.0.replace(self.init_state.clone());
-        //mem = self.init_state.0.borrow_mut();
-        //self.memory.0.swap(&*self.init_state.0.clone());
         self.history.swap(&RefCell::new(vec![]));
+        self.writes.borrow_mut().clear();
+        self.wait.borrow_mut().clear();
+        self.trace.borrow_mut().clear();
     }
 }
 
 impl Deref for Memory {
-    type Target = RefCell<BTreeMap<usize, u8>>;
+    type Target = RefCell<PagedMemory>;
 
     fn deref(&self) -> &Self::Target {
         &self.0
@@ -525,15 +783,24 @@ mod test {
                     sext: Input::new("sign", "out"),
                     //interrupt: Input::new("sign", "out"),
                     interrupt: Input::new("interrupt", "out"),
+                    reset: None,
 
                     // memory
-                    memory: Memory(Rc::new(RefCell::new(BTreeMap::new()))),
+                    memory: Memory::default(),
                     range: Range {
                         start: 0u32,
                         end: 1u32,
                     },
                     history: RefCell::new(vec![]),
-                    init_state: BTreeMap::new(),
+                    writes: RefCell::default(),
+                    latency: MemLatency::default(),
+                    wait: RefCell::default(),
+                    record_trace: false,
+                    trace: RefCell::default(),
+                    mmio: default_mmio(),
+                    faults: MemFaults::default(),
+                    uninit_read: UninitRead::default(),
+                    pc: None,
                 }),
             ],
         };
@@ -712,16 +979,25 @@ mod test {
                     size: Input::new("size", "out"),
                     sext: Input::new("sign", "out"),
                     interrupt: Input::new("interrupt", "out"),
+                    reset: None,
 
                     // memory
-                    memory: Memory(Rc::new(RefCell::new(BTreeMap::new()))),
+                    memory: Memory::default(),
                     // later history... tbd
                     range: Range {
                         start: 0u32,
                         end: 1u32,
                     },
                     history: RefCell::new(vec![]),
-                    init_state: BTreeMap::new(),
+                    writes: RefCell::default(),
+                    latency: MemLatency::default(),
+                    wait: RefCell::default(),
+                    record_trace: false,
+                    trace: RefCell::default(),
+                    mmio: default_mmio(),
+                    faults: MemFaults::default(),
+                    uninit_read: UninitRead::default(),
+                    pc: None,
                 }),
             ],
         };
@@ -815,4 +1091,159 @@ mod test {
         assert_eq!(simulator.cycle, 9);
         assert_eq!(simulator.get_input_value(out), 0x12.into());
     }
+
+    #[test]
+    fn test_mem_reset() {
+        // nothing is allocated up front
+        let empty = RVMem::rc_new(
+            "empty",
+            (0.0, 0.0),
+            0.0,
+            0.0,
+            false,
+            Input::new("data", "out"),
+            Input::new("addr", "out"),
+            Input::new("ctrl", "out"),
+            Input::new("sign", "out"),
+            Input::new("size", "out"),
+            Input::new("interrupt", "out"),
+            0..0x10_0000,
+        );
+        assert_eq!(empty.memory.borrow().pages(), 0);
+
+        let cs = ComponentStore {
+            store: vec![
+                Rc::new(ProbeOut::new("data")),
+                Rc::new(ProbeOut::new("addr")),
+                Rc::new(ProbeOut::new("ctrl")),
+                Rc::new(ProbeOut::new("size")),
+                Rc::new(ProbeOut::new("sign")),
+                Rc::new(ProbeOut::new("interrupt")),
+                RVMem::rc_new_from_bytes(
+                    "mem",
+                    (0.0, 0.0),
+                    0.0,
+                    0.0,
+                    false,
+                    Input::new("data", "out"),
+                    Input::new("addr", "out"),
+                    Input::new("ctrl", "out"),
+                    Input::new("sign", "out"),
+                    Input::new("size", "out"),
+                    Input::new("interrupt", "out"),
+                    BTreeMap::from([(4, 0x11)]),
+                    0..0x100,
+                ),
+            ],
+        };
+        let mut simulator = Simulator::new(cs).unwrap();
+        simulator.set_out_value("ctrl", "out", MemCtrl::Write as SignalUnsigned);
+        simulator.set_out_value("size", "out", 1);
+        for addr in [4, 8] {
+            simulator.set_out_value("addr", "out", addr);
+            simulator.set_out_value("data", "out", 0xf0);
+            simulator.clock();
+        }
+        let memory = simulator
+            .ordered_components
+            .iter()
+            .find_map(|c| c.as_any().downcast_ref::<RVMem>())
+            .unwrap()
+            .memory
+            .clone();
+        assert_eq!(memory.borrow().get(&4), Some(&0xf0));
+
+        // the writes are undone on reset
+        simulator.reset();
+        assert_eq!(memory.borrow().get_init(4), Some(0x11));
+        assert_eq!(memory.borrow().get_init(8), None);
+    }
+
+    #[test]
+    fn test_mem_uninit_read() {
+        let mem = |uninit_read| {
+            let cs = ComponentStore {
+                store: vec![
+                    Rc::new(ProbeOut::new("data")),
+                    Rc::new(ProbeOut::new("addr")),
+                    Rc::new(ProbeOut::new("ctrl")),
+                    Rc::new(ProbeOut::new("size")),
+                    Rc::new(ProbeOut::new("sign")),
+                    Rc::new(ProbeOut::new("interrupt")),
+                    Rc::new(ProbeOut::new("pc")),
+                    Rc::new(
+                        RVMem::new(
+                            "mem",
+                            (0.0, 0.0),
+                            0.0,
+                            0.0,
+                            false,
+                            Input::new("data", "out"),
+                            Input::new("addr", "out"),
+                            Input::new("ctrl", "out"),
+                            Input::new("sign", "out"),
+                            Input::new("size", "out"),
+                            Input::new("interrupt", "out"),
+                            BTreeMap::new(),
+                            0..0x100,
+                        )
+                        .with_uninit_read(uninit_read, Some(Input::new("pc", "out"))),
+                    ),
+                ],
+            };
+            let mut simulator = Simulator::new(cs).unwrap();
+            // a byte written, the rest of the word not
+            simulator.set_out_value("data", "out", 0x42);
+            simulator.set_out_value("addr", "out", 0x10);
+            simulator.set_out_value("ctrl", "out", MemCtrl::Write as SignalUnsigned);
+            simulator.set_out_value("size", "out", 1);
+            simulator.set_out_value("sign", "out", 0);
+            simulator.set_out_value("pc", "out", 0x400);
+            simulator.clock();
+            simulator.set_out_value("ctrl", "out", MemCtrl::Read as SignalUnsigned);
+            simulator.clock();
+            simulator
+        };
+        let data_o = Input::new("mem", RV_MEM_DATA_O_ID);
+
+        let mut simulator = mem(UninitRead::Zero);
+        assert_eq!(simulator.get_input_value(&data_o), 0x42.into());
+        simulator.set_out_value("size", "out", 4);
+        simulator.clock();
+        assert!(simulator.component_condition.is_empty());
+
+        let mut simulator = mem(UninitRead::Uninitialized);
+        assert_eq!(simulator.get_input_value(&data_o), 0x42.into());
+        simulator.set_out_value("size", "out", 4);
+        simulator.clock();
+        assert_eq!(
+            simulator.get_input_value(&data_o),
+            SignalValue::Uninitialized
+        );
+
+        let mut simulator = mem(UninitRead::Warning);
+        simulator.set_out_value("size", "out", 4);
+        simulator.clock();
+        assert_eq!(simulator.get_input_value(&data_o), 0x42.into());
+        assert_eq!(
+            simulator.component_condition,
+            vec![(
+                "mem".to_string(),
+                Condition::Warning(
+                    "mem mem: read of uninitialised address 0x11 at pc 0x400".to_string()
+                )
+            )]
+        );
+
+        // stepping back over the write makes the byte uninitialised again
+        for _ in 0..3 {
+            simulator.un_clock();
+        }
+        let mem = simulator
+            .ordered_components
+            .iter()
+            .find_map(|c| c.as_any().downcast_ref::<RVMem>())
+            .unwrap();
+        assert!(!mem.memory.borrow().is_init(0x10));
+    }
 }
@@ -1,10 +1,6 @@
-use crate::components::RVMem;
+use crate::components::{default_mmio, RVMem};
 use egui::FontId;
-use egui::{
-    Color32, Context, Label, Pos2, Rect, Response, Rounding, Shape, Slider, Stroke, Ui, Vec2,
-    Window,
-};
-use egui_extras::{Column, TableBuilder};
+use egui::{Color32, Context, Pos2, Rect, Response, Rounding, Shape, Slider, Stroke, Ui, Vec2};
 use syncrim::common::{EguiComponent, Ports, Simulator};
 use syncrim::gui_egui::component_ui::{
     drag_logic, input_change_id, input_selector, pos_drag_value, properties_window,
@@ -13,70 +9,23 @@ use syncrim::gui_egui::component_ui::{
 use syncrim::gui_egui::editor::{EditorMode, EditorRenderReturn, GridOptions};
 use syncrim::gui_egui::gui::EguiExtra;
 use syncrim::gui_egui::helper::offset_helper;
+use syncrim::gui_egui::mem_view::{
+    faults_editor, latency_editor, mem_window, trace_window, uninit_read_editor,
+};
 impl RVMem {
-    fn side_panel(&self, ctx: &Context, _simulator: Option<&mut Simulator>) {
-        Window::new("Data Memory").show(ctx, |ui| {
-            TableBuilder::new(ui)
-                .striped(true)
-                .column(Column::initial(75.0))
-                .column(Column::initial(75.0))
-                .column(Column::initial(50.0))
-                .header(30.0, |mut header| {
-                    header.col(|ui| {
-                        ui.heading("Address");
-                    });
-                    header.col(|ui| {
-                        ui.heading("HEX");
-                    });
-                    header.col(|ui| {
-                        ui.heading("ASCII");
-                    });
-                })
-                .body(|body| {
-                    body.rows(
-                        15.0,
-                        ((self.range.end - self.range.start) / 4) as usize,
-                        |index, mut row| {
-                            //println!("{}", index);
-                            let address = self.range.start as usize + index * 4;
-                            let memory = self.memory.0.borrow().clone();
-                            row.col(|ui| {
-                                ui.label(format!("0x{:08x}", address));
-                            });
-                            let mut bytes = [0u8; 4];
-                            if self.big_endian {
-                                bytes[0] = *(memory).get(&address).unwrap();
-                                bytes[1] = *(memory).get(&(address + 1)).unwrap();
-                                bytes[2] = *(memory).get(&(address + 2)).unwrap();
-                                bytes[3] = *(memory).get(&(address + 3)).unwrap();
-                            } else {
-                                bytes[3] = *(memory).get(&address).unwrap();
-                                bytes[2] = *(memory).get(&(address + 1)).unwrap();
-                                bytes[1] = *(memory).get(&(address + 2)).unwrap();
-                                bytes[0] = *(memory).get(&(address + 3)).unwrap();
-                            }
-                            let word = format!(
-                                "0x{:02x}{:02x}{:02x}{:02x}",
-                                bytes[0], bytes[1], bytes[2], bytes[3]
-                            );
-                            let mut ascii = "".to_string();
-                            for b in bytes {
-                                if b > 0x1f && b < 0x7f {
-                                    ascii += &format!("{}", b as char);
-                                } else {
-                                    ascii += " ";
-                                }
-                            }
-                            row.col(|ui| {
-                                ui.add(Label::new(word).truncate(true));
-                            });
-                            row.col(|ui| {
-                                ui.add(Label::new(ascii).truncate(true));
-                            });
-                        },
-                    );
-                });
-        });
+    fn side_panel(&self, ctx: &Context, simulator: Option<&mut Simulator>) {
+        mem_window(
+            ctx,
+            "Data Memory",
+            &self.id,
+            &self.memory.borrow(),
+            &self.writes.borrow(),
+            self.range.start as usize..self.range.end as usize,
+            simulator.map_or(0, |s| s.cycle),
+        );
+        if self.record_trace {
+            trace_window(ctx, "Data Memory Trace", &self.id, &self.trace.borrow());
+        }
     }
 }
 
@@ -219,7 +168,25 @@ impl EguiComponent for RVMem {
                     id_ports,
                     self.id.clone(),
                 );
-                // todo: something about memory?
+                latency_editor(ui, &mut self.latency);
+                ui.checkbox(&mut self.record_trace, "record trace");
+                clicked_dropdown |= faults_editor(ui, &self.id, &mut self.faults);
+                clicked_dropdown |=
+                    uninit_read_editor(ui, &self.id, &mut self.uninit_read, &mut self.pc);
+                if let Some(pc) = &mut self.pc {
+                    clicked_dropdown |= input_selector(
+                        ui,
+                        pc,
+                        crate::components::RV_MEM_PC_ID.to_string(),
+                        id_ports,
+                        self.id.clone(),
+                    );
+                }
+                // without, the devices are attached through a bus
+                let mut mmio = self.mmio.is_some();
+                if ui.checkbox(&mut mmio, "mmio range").changed() {
+                    self.mmio = if mmio { default_mmio() } else { None };
+                }
                 clicked_dropdown
             },
         );
@@ -232,7 +199,7 @@ impl EguiComponent for RVMem {
 
     fn ports_location(&self) -> Vec<(syncrim::common::Id, Pos2)> {
         let own_pos = Vec2::new(self.pos.0, self.pos.1);
-        vec![
+        let mut ports = vec![
             (
                 crate::components::RV_MEM_DATA_I_ID.to_string(),
                 Pos2::new(
@@ -275,7 +242,38 @@ impl EguiComponent for RVMem {
                     -self.height / 2f32,
                 ) + own_pos,
             ),
-        ]
+            (
+                syncrim::components::MEM_STALL_OUT_ID.to_string(),
+                Pos2::new(
+                    -self.width / 10f32 * 1f32 + self.width / 2f32,
+                    self.height / 2f32,
+                ) + own_pos,
+            ),
+            (
+                syncrim::components::MEM_TRAP_OUT_ID.to_string(),
+                Pos2::new(
+                    -self.width / 10f32 * 2f32 + self.width / 2f32,
+                    self.height / 2f32,
+                ) + own_pos,
+            ),
+            (
+                syncrim::components::MEM_TRAP_CAUSE_OUT_ID.to_string(),
+                Pos2::new(
+                    -self.width / 10f32 * 3f32 + self.width / 2f32,
+                    self.height / 2f32,
+                ) + own_pos,
+            ),
+        ];
+        if self.pc.is_some() {
+            ports.push((
+                crate::components::RV_MEM_PC_ID.to_string(),
+                Pos2::new(
+                    self.width / 10f32 * 1f32 - self.width / 2f32,
+                    self.height / 2f32,
+                ) + own_pos,
+            ));
+        }
+        ports
     }
 
     fn top_padding(&self) -> f32 {
@@ -0,0 +1,71 @@
+use clap::{Parser, ValueEnum};
+use std::{fs, path::PathBuf, process::ExitCode};
+use syncrim::report::{junit, run_model, tap};
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum Format {
+    Junit,
+    Tap,
+}
+
+/// Run models headlessly and report the outcome of their assertion probes
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    /// Paths to the models to run
+    #[arg(required = true)]
+    models: Vec<PathBuf>,
+    /// Report format
+    #[arg(short, long, value_enum, default_value = "junit")]
+    format: Format,
+    /// Write the report to this file instead of stdout
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+    /// Number of cycles to run, by default until the last expected value
+    #[arg(short, long)]
+    cycles: Option<usize>,
+    /// Write a functional coverage report, listing holes, to this file
+    #[arg(long)]
+    coverage: Option<PathBuf>,
+}
+
+fn main() -> ExitCode {
+    // no logger is set up, the report may be written to stdout
+    let args = Args::parse();
+
+    let reports: Vec<_> = args
+        .models
+        .iter()
+        .map(|model| run_model(model, args.cycles))
+        .collect();
+    let report = match args.format {
+        Format::Junit => junit(&reports),
+        Format::Tap => tap(&reports),
+    };
+    match &args.output {
+        Some(path) => {
+            if let Err(e) = fs::write(path, report) {
+                eprintln!("failed to write report {:?}: {}", path, e);
+                return ExitCode::FAILURE;
+            }
+        }
+        None => print!("{}", report),
+    }
+
+    if let Some(path) = &args.coverage {
+        let coverage: String = reports
+            .iter()
+            .map(|r| format!("# {}\n{}", r.model, r.coverage))
+            .collect();
+        if let Err(e) = fs::write(path, coverage) {
+            eprintln!("failed to write coverage {:?}: {}", path, e);
+            return ExitCode::FAILURE;
+        }
+    }
+
+    if reports.iter().all(|r| r.passed()) {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
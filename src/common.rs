@@ -1,7 +1,11 @@
 use petgraph::Graph;
 use serde::{Deserialize, Serialize};
 use std::any::Any;
-use std::{collections::HashMap, rc::Rc};
+use std::{
+    collections::{HashMap, HashSet},
+    path::Path,
+    rc::Rc,
+};
 
 #[cfg(feature = "gui-egui")]
 use crate::gui_egui::editor::{EditorMode, EditorRenderReturn, GridOptions, SnapPriority};
@@ -14,11 +18,14 @@ use crate::gui_vizia::ViziaComponent;
 
 pub use crate::signal::*;
 
+use crate::coverage::CoverPoint;
+use crate::simulator::Evaluation;
+
 #[cfg(not(any(feature = "gui-vizia", feature = "gui-egui")))]
-type Components = Vec<Rc<dyn Component>>;
+pub type Components = Vec<Rc<dyn Component>>;
 
 #[cfg(feature = "gui-vizia")]
-type Components = Vec<Rc<dyn ViziaComponent>>;
+pub type Components = Vec<Rc<dyn ViziaComponent>>;
 
 #[cfg(feature = "gui-egui")]
 pub type Components = Vec<Rc<dyn EguiComponent>>;
@@ -58,6 +65,13 @@ pub struct Simulator {
     // stores if components return a condition
     // TODO add component condition history
     pub component_condition: Vec<(Id, Condition)>,
+    // ids of the components instantiated by subcircuits, not part of the model
+    pub sub_component_ids: HashSet<Id>,
+    // the order of evaluation in a clock, see `Evaluation`
+    pub(crate) evaluation_order: Vec<Evaluation>,
+    // inputs are read from the state before the clock, set while clocking
+    // sequential components
+    pub(crate) pre_clock: bool,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -100,10 +114,27 @@ pub trait Component {
     fn clock(&self, _simulator: &mut Simulator) -> Result<(), Condition> {
         Ok(())
     }
+    /// apply asynchronous reset, called once the `async_reset_inputs` are
+    /// evaluated and before any component reads the outputs
+    fn async_reset(&self, _simulator: &mut Simulator) {}
+    /// inputs of asynchronous resets, only sequential components use these
+    fn async_reset_inputs(&self) -> Vec<Input> {
+        vec![]
+    }
     /// update component internal state
     fn un_clock(&self) {}
     /// reset component internal state to initial value
     fn reset(&self) {}
+    /// functional coverage points, sampled every cycle
+    fn cover_points(&self) -> Vec<CoverPoint> {
+        vec![]
+    }
+    /// directory of the model file, relative paths are resolved against it
+    fn set_model_dir(&mut self, _dir: &Path) {}
+    /// components to add to the simulator, e.g., the content of a subcircuit
+    fn sub_components(&self) -> Result<Components, String> {
+        Ok(vec![])
+    }
     /// any
     fn as_any(&self) -> &dyn Any;
 }
@@ -1,6 +1,11 @@
 use crate::common::ComponentStore;
 
-use std::{fs::File, io::prelude::*, path::PathBuf};
+use std::{
+    fs::File,
+    io::prelude::*,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
 
 use log::*;
 
@@ -14,7 +19,20 @@ impl ComponentStore {
         let mut json = String::new();
         file.read_to_string(&mut json).unwrap();
 
-        ComponentStore::load(&json)
+        let mut cs = ComponentStore::load(&json);
+        cs.set_model_dir(path.parent().unwrap_or(Path::new("")));
+        cs
+    }
+
+    /// Let the components resolve relative paths against `dir`, the
+    /// directory of the model file
+    pub fn set_model_dir(&mut self, dir: &Path) {
+        for component in &mut self.store {
+            // the components are not shared right after loading
+            if let Some(component) = Rc::get_mut(component) {
+                component.set_model_dir(dir);
+            }
+        }
     }
 
     pub fn save_file(&self, path: &PathBuf) {
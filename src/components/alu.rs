@@ -0,0 +1,608 @@
+#[cfg(feature = "gui-egui")]
+use crate::common::EguiComponent;
+use crate::common::{
+    Component, Condition, Id, Input, InputPort, OutputType, Ports, SignalFmt, SignalSigned,
+    SignalUnsigned, SignalValue, Simulator, ValueNames,
+};
+use crate::components::alu_op;
+use crate::coverage::{CoverBin, CoverPoint};
+use log::*;
+use serde::{Deserialize, Serialize};
+use std::any::Any;
+use std::rc::Rc;
+
+pub const ALU_A_IN_ID: &str = "a_in";
+pub const ALU_B_IN_ID: &str = "b_in";
+pub const ALU_OP_IN_ID: &str = "op_in";
+pub const ALU_OUT_ID: &str = "out";
+pub const ALU_CARRY_OUT_ID: &str = "carry";
+pub const ALU_ZERO_OUT_ID: &str = "zero";
+pub const ALU_NEGATIVE_OUT_ID: &str = "negative";
+pub const ALU_OVERFLOW_OUT_ID: &str = "overflow";
+
+/// Mask of the low `width` bits, `width` in `1..=32`
+pub(crate) fn width_mask(width: u32) -> SignalUnsigned {
+    SignalUnsigned::MAX >> (SignalUnsigned::BITS - width)
+}
+
+/// Sign extend a `width` bit value
+pub(crate) fn sign_extend(value: SignalUnsigned, width: u32) -> SignalSigned {
+    let shift = SignalUnsigned::BITS - width;
+    ((value << shift) as SignalSigned) >> shift
+}
+
+pub(crate) fn check_width(id: &str, width: u32) -> Result<(), String> {
+    if width == 0 || width > SignalUnsigned::BITS {
+        Err(format!(
+            "{}: width {} outside 1..={}",
+            id,
+            width,
+            SignalUnsigned::BITS
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+/// Operation of an `Alu`
+///
+/// Comparisons give 1 or 0. Shift amounts are taken modulo the width.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AluOp {
+    Add,
+    Sub,
+    And,
+    Or,
+    Xor,
+    Nor,
+    Sll,
+    Srl,
+    Sra,
+    /// signed less than
+    Slt,
+    /// unsigned less than
+    Sltu,
+    Eq,
+    Ne,
+    /// signed greater or equal
+    Ge,
+    /// unsigned greater or equal
+    Geu,
+    /// `b` in the upper half, `a` in the lower half
+    Lui,
+    PassA,
+    PassB,
+}
+
+impl AluOp {
+    pub const ALL: [AluOp; 18] = [
+        AluOp::Add,
+        AluOp::Sub,
+        AluOp::And,
+        AluOp::Or,
+        AluOp::Xor,
+        AluOp::Nor,
+        AluOp::Sll,
+        AluOp::Srl,
+        AluOp::Sra,
+        AluOp::Slt,
+        AluOp::Sltu,
+        AluOp::Eq,
+        AluOp::Ne,
+        AluOp::Ge,
+        AluOp::Geu,
+        AluOp::Lui,
+        AluOp::PassA,
+        AluOp::PassB,
+    ];
+
+    pub fn name(&self) -> String {
+        format!("{:?}", self).to_uppercase()
+    }
+}
+
+/// Entry of the op table, `code` on the op input selects `op`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct AluOpCode {
+    pub code: SignalUnsigned,
+    pub op: AluOp,
+}
+
+impl AluOpCode {
+    pub fn new(code: SignalUnsigned, op: AluOp) -> Self {
+        AluOpCode { code, op }
+    }
+}
+
+/// ALU of `width` bits with a configurable op table
+///
+/// Operands are truncated to `width` bits. Carry and overflow are set by
+/// add and sub only, carry being the inverted borrow for sub.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Alu {
+    pub(crate) id: Id,
+    pub(crate) pos: (f32, f32),
+    pub(crate) a_in: Input,
+    pub(crate) b_in: Input,
+    pub(crate) op_in: Input,
+    pub(crate) width: u32,
+    pub(crate) ops: Vec<AluOpCode>,
+}
+
+#[typetag::serde]
+impl Component for Alu {
+    fn to_(&self) {
+        trace!("alu {} bits {:?}", self.width, self.ops);
+    }
+    #[cfg(feature = "gui-egui")]
+    fn dummy(&self, id: &str, pos: (f32, f32)) -> Box<Rc<dyn EguiComponent>> {
+        let dummy_input = Input::new("dummy", "out");
+        Box::new(Rc::new(Alu {
+            id: id.to_string(),
+            pos: (pos.0, pos.1),
+            a_in: dummy_input.clone(),
+            b_in: dummy_input.clone(),
+            op_in: dummy_input,
+            width: self.width,
+            ops: self.ops.clone(),
+        }))
+    }
+    fn get_id_ports(&self) -> (Id, Ports) {
+        (
+            self.id.clone(),
+            Ports::new(
+                vec![
+                    &InputPort {
+                        port_id: ALU_A_IN_ID.to_string(),
+                        input: self.a_in.clone(),
+                    },
+                    &InputPort {
+                        port_id: ALU_B_IN_ID.to_string(),
+                        input: self.b_in.clone(),
+                    },
+                    &InputPort {
+                        port_id: ALU_OP_IN_ID.to_string(),
+                        input: self.op_in.clone(),
+                    },
+                ],
+                OutputType::Combinatorial,
+                vec![
+                    ALU_OUT_ID,
+                    ALU_CARRY_OUT_ID,
+                    ALU_ZERO_OUT_ID,
+                    ALU_NEGATIVE_OUT_ID,
+                    ALU_OVERFLOW_OUT_ID,
+                ],
+            ),
+        )
+    }
+
+    fn set_id_port(&mut self, target_port_id: Id, new_input: Input) {
+        match target_port_id.as_str() {
+            ALU_A_IN_ID => self.a_in = new_input,
+            ALU_B_IN_ID => self.b_in = new_input,
+            ALU_OP_IN_ID => self.op_in = new_input,
+            _ => {}
+        }
+    }
+
+    fn clock(&self, simulator: &mut Simulator) -> Result<(), Condition> {
+        let a = simulator.get_input_value(&self.a_in);
+        let b = simulator.get_input_value(&self.b_in);
+        let op = simulator.get_input_value(&self.op_in);
+        let (res, outputs) = match (a, b, op) {
+            (SignalValue::Data(a), SignalValue::Data(b), SignalValue::Data(code)) => {
+                match (check_width(&self.id, self.width), self.op(code)) {
+                    (Err(e), _) => (Err(Condition::Error(e)), None),
+                    (_, None) => (
+                        Err(Condition::Error(format!(
+                            "alu {}: undefined operation {}",
+                            self.id, code
+                        ))),
+                        None,
+                    ),
+                    (Ok(()), Some(op)) => (Ok(()), Some(self.execute(op, a, b))),
+                }
+            }
+            _ => (Ok(()), None),
+        };
+        let outputs: [SignalValue; 5] = match outputs {
+            Some((out, carry, overflow)) => [
+                out.into(),
+                carry.into(),
+                (out == 0).into(),
+                (out >> (self.width - 1) & 1 == 1).into(),
+                overflow.into(),
+            ],
+            None => [SignalValue::Unknown; 5],
+        };
+        for (field, value) in [
+            ALU_OUT_ID,
+            ALU_CARRY_OUT_ID,
+            ALU_ZERO_OUT_ID,
+            ALU_NEGATIVE_OUT_ID,
+            ALU_OVERFLOW_OUT_ID,
+        ]
+        .into_iter()
+        .zip(outputs)
+        {
+            simulator.set_out_value(&self.id, field, value);
+        }
+        res
+    }
+
+    fn cover_points(&self) -> Vec<CoverPoint> {
+        let bins = self
+            .ops
+            .iter()
+            .map(|entry| CoverBin::new(&entry.op.name(), entry.code))
+            .collect();
+        vec![CoverPoint::new(ALU_OP_IN_ID, self.op_in.clone(), bins)]
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl Alu {
+    pub fn new(
+        id: &str,
+        pos: (f32, f32),
+        a_in: Input,
+        b_in: Input,
+        op_in: Input,
+        width: u32,
+        ops: Vec<AluOpCode>,
+    ) -> Self {
+        Alu {
+            id: id.to_string(),
+            pos,
+            a_in,
+            b_in,
+            op_in,
+            width,
+            ops,
+        }
+    }
+
+    pub fn rc_new(
+        id: &str,
+        pos: (f32, f32),
+        a_in: Input,
+        b_in: Input,
+        op_in: Input,
+        width: u32,
+        ops: Vec<AluOpCode>,
+    ) -> Rc<Self> {
+        Rc::new(Alu::new(id, pos, a_in, b_in, op_in, width, ops))
+    }
+
+    /// 32 bit ALU using the MIPS `alu_op` codes of `FullAdd`
+    pub fn rc_new_mips(
+        id: &str,
+        pos: (f32, f32),
+        a_in: Input,
+        b_in: Input,
+        op_in: Input,
+    ) -> Rc<Self> {
+        Alu::rc_new(id, pos, a_in, b_in, op_in, 32, Alu::mips_ops())
+    }
+
+    /// 32 bit ALU using the operation codes of the RISC-V model
+    pub fn rc_new_riscv(
+        id: &str,
+        pos: (f32, f32),
+        a_in: Input,
+        b_in: Input,
+        op_in: Input,
+    ) -> Rc<Self> {
+        Alu::rc_new(id, pos, a_in, b_in, op_in, 32, Alu::riscv_ops())
+    }
+
+    /// Op table of the MIPS `alu_op` codes
+    pub fn mips_ops() -> Vec<AluOpCode> {
+        vec![
+            AluOpCode::new(alu_op::ADD, AluOp::Add),
+            AluOpCode::new(alu_op::ADDU, AluOp::Add),
+            AluOpCode::new(alu_op::SUB, AluOp::Sub),
+            AluOpCode::new(alu_op::SUBU, AluOp::Sub),
+            AluOpCode::new(alu_op::AND, AluOp::And),
+            AluOpCode::new(alu_op::OR, AluOp::Or),
+            AluOpCode::new(alu_op::XOR, AluOp::Xor),
+            AluOpCode::new(alu_op::NOR, AluOp::Nor),
+            AluOpCode::new(alu_op::SLT, AluOp::Slt),
+            AluOpCode::new(alu_op::SLTU, AluOp::Sltu),
+            AluOpCode::new(alu_op::SLL, AluOp::Sll),
+            AluOpCode::new(alu_op::SRL, AluOp::Srl),
+            AluOpCode::new(alu_op::SRA, AluOp::Sra),
+            AluOpCode::new(alu_op::LUI, AluOp::Lui),
+        ]
+    }
+
+    /// Op table of the RISC-V model, codes 1 to 14
+    pub fn riscv_ops() -> Vec<AluOpCode> {
+        [
+            AluOp::Add,
+            AluOp::Sub,
+            AluOp::Sll,
+            AluOp::Srl,
+            AluOp::Sra,
+            AluOp::Xor,
+            AluOp::Or,
+            AluOp::And,
+            AluOp::Sltu,
+            AluOp::Slt,
+            AluOp::Eq,
+            AluOp::Ne,
+            AluOp::Ge,
+            AluOp::Geu,
+        ]
+        .into_iter()
+        .enumerate()
+        .map(|(i, op)| AluOpCode::new(i as SignalUnsigned + 1, op))
+        .collect()
+    }
+
+    /// The operation selected by `code`
+    pub fn op(&self, code: SignalUnsigned) -> Option<AluOp> {
+        self.ops.iter().find(|e| e.code == code).map(|e| e.op)
+    }
+
+    /// Format a value of the op input as the name of the operation
+    pub fn format_op(&self, value: SignalValue) -> String {
+        match value {
+            SignalValue::Data(code) => match self.op(code) {
+                Some(op) => op.name(),
+                None => format!("UNDEF({})", code),
+            },
+            _ => format!("{:?}", value),
+        }
+    }
+
+    /// Signal format showing the op input by the names of the op table
+    ///
+    /// Set it on the signal driving the op input with `Simulator::set_out_fmt`.
+    pub fn op_fmt(&self) -> SignalFmt {
+        SignalFmt::Names(ValueNames::new(
+            self.ops.iter().map(|e| (e.code, e.op.name())).collect(),
+        ))
+    }
+
+    /// Result, carry and overflow of `op`
+    pub fn execute(
+        &self,
+        op: AluOp,
+        a: SignalUnsigned,
+        b: SignalUnsigned,
+    ) -> (SignalUnsigned, bool, bool) {
+        let mask = width_mask(self.width);
+        let (a, b) = (a & mask, b & mask);
+        let sign = |v: SignalUnsigned| v >> (self.width - 1) & 1 == 1;
+        let shamt = b % self.width;
+        let (out, carry, overflow) = match op {
+            AluOp::Add | AluOp::Sub => {
+                // subtraction as a + !b + 1
+                let (b, carry_in) = match op {
+                    AluOp::Add => (b, 0),
+                    _ => (!b & mask, 1),
+                };
+                let sum = a as u64 + b as u64 + carry_in;
+                let out = sum as SignalUnsigned & mask;
+                (
+                    out,
+                    sum >> self.width & 1 == 1,
+                    sign(a) == sign(b) && sign(out) != sign(a),
+                )
+            }
+            AluOp::And => (a & b, false, false),
+            AluOp::Or => (a | b, false, false),
+            AluOp::Xor => (a ^ b, false, false),
+            AluOp::Nor => (!(a | b), false, false),
+            AluOp::Sll => (a << shamt, false, false),
+            AluOp::Srl => (a >> shamt, false, false),
+            AluOp::Sra => (
+                (sign_extend(a, self.width) >> shamt) as SignalUnsigned,
+                false,
+                false,
+            ),
+            AluOp::Slt => (
+                (sign_extend(a, self.width) < sign_extend(b, self.width)).into(),
+                false,
+                false,
+            ),
+            AluOp::Sltu => ((a < b).into(), false, false),
+            AluOp::Eq => ((a == b).into(), false, false),
+            AluOp::Ne => ((a != b).into(), false, false),
+            AluOp::Ge => (
+                (sign_extend(a, self.width) >= sign_extend(b, self.width)).into(),
+                false,
+                false,
+            ),
+            AluOp::Geu => ((a >= b).into(), false, false),
+            AluOp::Lui => {
+                let half = self.width / 2;
+                (
+                    (a & (mask >> (self.width - half))) | b << half,
+                    false,
+                    false,
+                )
+            }
+            AluOp::PassA => (a, false, false),
+            AluOp::PassB => (b, false, false),
+        };
+        (out & mask, carry, overflow)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::common::{ComponentStore, RunningState, Signal};
+    use crate::components::ProbeOut;
+
+    fn alu_simulator(width: u32, ops: Vec<AluOpCode>) -> Simulator {
+        let cs = ComponentStore {
+            store: vec![
+                Rc::new(ProbeOut::new("a")),
+                Rc::new(ProbeOut::new("b")),
+                Rc::new(ProbeOut::new("op")),
+                Alu::rc_new(
+                    "alu",
+                    (0.0, 0.0),
+                    Input::new("a", "out"),
+                    Input::new("b", "out"),
+                    Input::new("op", "out"),
+                    width,
+                    ops,
+                ),
+            ],
+        };
+        Simulator::new(cs).unwrap()
+    }
+
+    // outputs out, carry, zero, negative, overflow after one clock
+    fn run(
+        simulator: &mut Simulator,
+        a: SignalUnsigned,
+        b: SignalUnsigned,
+        op: SignalUnsigned,
+    ) -> [SignalValue; 5] {
+        simulator.set_out_value("a", "out", a);
+        simulator.set_out_value("b", "out", b);
+        simulator.set_out_value("op", "out", op);
+        simulator.clock();
+        [
+            ALU_OUT_ID,
+            ALU_CARRY_OUT_ID,
+            ALU_ZERO_OUT_ID,
+            ALU_NEGATIVE_OUT_ID,
+            ALU_OVERFLOW_OUT_ID,
+        ]
+        .map(|field| simulator.get_input_value(&Input::new("alu", field)))
+    }
+
+    fn flags(out: SignalUnsigned, c: bool, z: bool, n: bool, v: bool) -> [SignalValue; 5] {
+        [out.into(), c.into(), z.into(), n.into(), v.into()]
+    }
+
+    #[test]
+    fn test_alu_flags() {
+        let ops: Vec<AluOpCode> = AluOp::ALL
+            .into_iter()
+            .enumerate()
+            .map(|(i, op)| AluOpCode::new(i as SignalUnsigned, op))
+            .collect();
+        let code = |op| AluOp::ALL.iter().position(|o| *o == op).unwrap() as SignalUnsigned;
+        let mut simulator = alu_simulator(8, ops);
+
+        // 8 bit add, carry out without overflow
+        assert_eq!(
+            run(&mut simulator, 0xff, 0x01, code(AluOp::Add)),
+            flags(0, true, true, false, false)
+        );
+        // signed overflow
+        assert_eq!(
+            run(&mut simulator, 0x7f, 0x01, code(AluOp::Add)),
+            flags(0x80, false, false, true, true)
+        );
+        // sub without borrow sets carry
+        assert_eq!(
+            run(&mut simulator, 5, 3, code(AluOp::Sub)),
+            flags(2, true, false, false, false)
+        );
+        // borrow, -128 - 1 overflows
+        assert_eq!(
+            run(&mut simulator, 0x80, 0x01, code(AluOp::Sub)),
+            flags(0x7f, true, false, false, true)
+        );
+        assert_eq!(
+            run(&mut simulator, 3, 5, code(AluOp::Sub)),
+            flags(0xfe, false, false, true, false)
+        );
+        // operands are truncated to the width
+        assert_eq!(
+            run(&mut simulator, 0x1f0, 0x0f, code(AluOp::Or)),
+            flags(0xff, false, false, true, false)
+        );
+        assert_eq!(
+            run(&mut simulator, 0x80, 3, code(AluOp::Sra)),
+            flags(0xf0, false, false, true, false)
+        );
+        assert_eq!(
+            run(&mut simulator, 0xff, 1, code(AluOp::Slt)),
+            flags(1, false, false, false, false)
+        );
+        assert_eq!(
+            run(&mut simulator, 0xff, 1, code(AluOp::Sltu)),
+            flags(0, false, true, false, false)
+        );
+
+        // undefined operation
+        simulator.set_out_value("op", "out", 100);
+        simulator.clock();
+        assert_eq!(*simulator.get_state(), RunningState::Err);
+    }
+
+    #[test]
+    fn test_alu_op_tables() {
+        let mut simulator = alu_simulator(32, Alu::mips_ops());
+        assert_eq!(
+            run(&mut simulator, -100i32 as u32, 1337, alu_op::SUB)[0],
+            ((-100i32 - 1337) as u32).into()
+        );
+        assert_eq!(
+            run(&mut simulator, 0x1234, 0xabcd, alu_op::LUI)[0],
+            0xabcd_1234.into()
+        );
+
+        let mut simulator = alu_simulator(32, Alu::riscv_ops());
+        // sra
+        assert_eq!(
+            run(&mut simulator, 0x8000_0000, 4, 5)[0],
+            0xf800_0000.into()
+        );
+        // geu
+        assert_eq!(run(&mut simulator, 1, -1i32 as u32, 14)[0], false.into());
+
+        let alu = Alu::new(
+            "alu",
+            (0.0, 0.0),
+            Input::new("a", "out"),
+            Input::new("b", "out"),
+            Input::new("op", "out"),
+            32,
+            Alu::riscv_ops(),
+        );
+        assert_eq!(alu.format_op(11.into()), "EQ");
+        assert_eq!(alu.format_op(0.into()), "UNDEF(0)");
+
+        // the op names as signal format
+        simulator.set_out_fmt("op", "out", alu.op_fmt());
+        simulator.set_out_value("op", "out", 11);
+        assert_eq!(
+            format!("{}", simulator.get_input_signal(&Input::new("op", "out"))),
+            "EQ"
+        );
+
+        // also for a custom op table
+        let custom = Alu::new(
+            "custom",
+            (0.0, 0.0),
+            Input::new("a", "out"),
+            Input::new("b", "out"),
+            Input::new("op", "out"),
+            8,
+            vec![
+                AluOpCode::new(7, AluOp::PassB),
+                AluOpCode::new(9, AluOp::Nor),
+            ],
+        );
+        let mut signal: Signal = 9.into();
+        signal.set_fmt(custom.op_fmt());
+        assert_eq!(format!("{}", signal), "NOR");
+        signal.set_value(8.into());
+        assert_eq!(format!("{}", signal), "UNDEF(8)");
+        assert_eq!(custom.op_fmt(), custom.op_fmt());
+    }
+}
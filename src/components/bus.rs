@@ -0,0 +1,286 @@
+#[cfg(feature = "gui-egui")]
+use crate::common::EguiComponent;
+use crate::common::{
+    Component, Condition, Id, Input, InputPort, OutputType, Ports, SignalUnsigned, SignalValue,
+    Simulator,
+};
+use crate::components::MemCtrl;
+use log::*;
+use serde::{Deserialize, Serialize};
+use std::any::Any;
+use std::ops::Range;
+use std::rc::Rc;
+
+pub const BUS_ADDR_ID: &str = "addr";
+pub const BUS_CTRL_ID: &str = "ctrl";
+
+pub const BUS_SELECT_OUT_ID: &str = "select";
+pub const BUS_FAULT_OUT_ID: &str = "fault";
+
+/// An entry of the memory map, a device at the addresses in `range`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct BusDevice {
+    pub name: String,
+    pub range: Range<u32>,
+}
+
+impl BusDevice {
+    pub fn new(name: &str, range: Range<u32>) -> Self {
+        BusDevice {
+            name: name.to_string(),
+            range,
+        }
+    }
+
+    /// Output with the memory control for the device
+    pub fn ctrl_out(&self) -> String {
+        format!("{}_ctrl", self.name)
+    }
+
+    /// Output with the address for the device, only valid while selected
+    pub fn addr_out(&self) -> String {
+        format!("{}_addr", self.name)
+    }
+}
+
+/// Address decoder routing memory accesses to the devices of a memory map
+///
+/// The control (`MemCtrl`) is passed to the device mapped at the address,
+/// the other devices see no access. Each device gets the full address, so
+/// devices decoding their own register addresses (e.g., GPIO and CLIC) can be
+/// attached as is, data, size and sign extension are taken from the CPU. The
+/// read data is returned through a `Mux` driven by `select`, the index of the
+/// device, or the number of devices when none, so an extra last `Mux` input
+/// gives the value read from unmapped addresses. Reads and writes to unmapped
+/// addresses set `fault` and give a warning.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Bus {
+    pub(crate) id: Id,
+    pub(crate) pos: (f32, f32),
+    pub(crate) addr: Input,
+    pub(crate) ctrl: Input,
+    pub(crate) map: Vec<BusDevice>,
+}
+
+#[typetag::serde]
+impl Component for Bus {
+    fn to_(&self) {
+        trace!("bus {:?}", self.map);
+    }
+    #[cfg(feature = "gui-egui")]
+    fn dummy(&self, id: &str, pos: (f32, f32)) -> Box<Rc<dyn EguiComponent>> {
+        let dummy_input = Input::new("dummy", "out");
+        Box::new(Rc::new(Bus::new(
+            id,
+            pos,
+            dummy_input.clone(),
+            dummy_input,
+            self.map.clone(),
+        )))
+    }
+    fn get_id_ports(&self) -> (Id, Ports) {
+        let device_outputs: Vec<String> = self
+            .map
+            .iter()
+            .flat_map(|d| [d.ctrl_out(), d.addr_out()])
+            .collect();
+        let mut outputs = vec![BUS_SELECT_OUT_ID, BUS_FAULT_OUT_ID];
+        outputs.extend(device_outputs.iter().map(|o| o.as_str()));
+        (
+            self.id.clone(),
+            Ports::new(
+                vec![
+                    &InputPort {
+                        port_id: BUS_ADDR_ID.to_string(),
+                        input: self.addr.clone(),
+                    },
+                    &InputPort {
+                        port_id: BUS_CTRL_ID.to_string(),
+                        input: self.ctrl.clone(),
+                    },
+                ],
+                OutputType::Combinatorial,
+                outputs,
+            ),
+        )
+    }
+
+    fn set_id_port(&mut self, target_port_id: Id, new_input: Input) {
+        match target_port_id.as_str() {
+            BUS_ADDR_ID => self.addr = new_input,
+            BUS_CTRL_ID => self.ctrl = new_input,
+            _ => {}
+        }
+    }
+
+    fn clock(&self, simulator: &mut Simulator) -> Result<(), Condition> {
+        self.check_map().map_err(Condition::Error)?;
+        let addr = simulator.get_input_value(&self.addr);
+        let ctrl = simulator.get_input_value(&self.ctrl);
+        let access = matches!(ctrl, SignalValue::Data(c) if c != MemCtrl::None as SignalUnsigned);
+
+        let selected = match addr {
+            SignalValue::Data(addr) => self.decode(addr),
+            _ => None,
+        };
+        for (i, device) in self.map.iter().enumerate() {
+            let (device_ctrl, device_addr) = match addr {
+                SignalValue::Data(addr) if selected == Some(i) => (ctrl, addr.into()),
+                _ => (
+                    (MemCtrl::None as SignalUnsigned).into(),
+                    SignalValue::Unknown,
+                ),
+            };
+            simulator.set_out_value(&self.id, &device.ctrl_out(), device_ctrl);
+            simulator.set_out_value(&self.id, &device.addr_out(), device_addr);
+        }
+        simulator.set_out_value(
+            &self.id,
+            BUS_SELECT_OUT_ID,
+            selected.unwrap_or(self.map.len()) as SignalUnsigned,
+        );
+        let fault = access && selected.is_none();
+        simulator.set_out_value(&self.id, BUS_FAULT_OUT_ID, fault);
+        if fault {
+            let addr = match addr {
+                SignalValue::Data(addr) => format!("{:#x}", addr),
+                other => format!("{:?}", other),
+            };
+            Err(Condition::Warning(format!(
+                "bus {}: access to unmapped address {}",
+                self.id, addr
+            )))
+        } else {
+            Ok(())
+        }
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl Bus {
+    pub fn new(id: &str, pos: (f32, f32), addr: Input, ctrl: Input, map: Vec<BusDevice>) -> Self {
+        Bus {
+            id: id.to_string(),
+            pos,
+            addr,
+            ctrl,
+            map,
+        }
+    }
+
+    pub fn rc_new(
+        id: &str,
+        pos: (f32, f32),
+        addr: Input,
+        ctrl: Input,
+        map: Vec<BusDevice>,
+    ) -> Rc<Self> {
+        Rc::new(Bus::new(id, pos, addr, ctrl, map))
+    }
+
+    /// Index of the device mapped at `addr`
+    pub fn decode(&self, addr: SignalUnsigned) -> Option<usize> {
+        self.map.iter().position(|d| d.range.contains(&addr))
+    }
+
+    // the device ranges must be disjoint
+    fn check_map(&self) -> Result<(), String> {
+        for (i, a) in self.map.iter().enumerate() {
+            for b in &self.map[i + 1..] {
+                if a.range.start < b.range.end && b.range.start < a.range.end {
+                    return Err(format!(
+                        "bus {}: devices {} and {} overlap",
+                        self.id, a.name, b.name
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::common::ComponentStore;
+    use crate::components::ProbeOut;
+
+    fn simulator(map: Vec<BusDevice>) -> Simulator {
+        let cs = ComponentStore {
+            store: vec![
+                Rc::new(ProbeOut::new("addr")),
+                Rc::new(ProbeOut::new("ctrl")),
+                Bus::rc_new(
+                    "bus",
+                    (0.0, 0.0),
+                    Input::new("addr", "out"),
+                    Input::new("ctrl", "out"),
+                    map,
+                ),
+            ],
+        };
+        Simulator::new(cs).unwrap()
+    }
+
+    #[test]
+    fn test_bus() {
+        let mut simulator = simulator(vec![
+            BusDevice::new("ram", 0..0x1000),
+            BusDevice::new("gpio", 0x6000_0000..0x6000_0010),
+        ]);
+        let out = |simulator: &Simulator, field: &str| {
+            simulator.get_input_value(&Input::new("bus", field))
+        };
+
+        simulator.set_out_value("addr", "out", 0x6000_0004);
+        simulator.set_out_value("ctrl", "out", MemCtrl::Write as SignalUnsigned);
+        simulator.clock();
+        assert_eq!(out(&simulator, "gpio_ctrl"), 2.into());
+        assert_eq!(out(&simulator, "gpio_addr"), 0x6000_0004.into());
+        assert_eq!(out(&simulator, "ram_ctrl"), 0.into());
+        assert_eq!(out(&simulator, BUS_SELECT_OUT_ID), 1.into());
+        assert_eq!(out(&simulator, BUS_FAULT_OUT_ID), false.into());
+
+        simulator.set_out_value("addr", "out", 0x10);
+        simulator.set_out_value("ctrl", "out", MemCtrl::Read as SignalUnsigned);
+        simulator.clock();
+        assert_eq!(out(&simulator, "ram_ctrl"), 1.into());
+        assert_eq!(out(&simulator, "ram_addr"), 0x10.into());
+        assert_eq!(out(&simulator, "gpio_ctrl"), 0.into());
+        assert_eq!(out(&simulator, BUS_SELECT_OUT_ID), 0.into());
+
+        // unmapped, a fault only when accessed
+        simulator.set_out_value("addr", "out", 0x2000);
+        simulator.clock();
+        assert_eq!(out(&simulator, BUS_FAULT_OUT_ID), true.into());
+        assert_eq!(out(&simulator, BUS_SELECT_OUT_ID), 2.into());
+        assert_eq!(
+            simulator.component_condition,
+            vec![(
+                "bus".to_string(),
+                Condition::Warning("bus bus: access to unmapped address 0x2000".to_string())
+            )]
+        );
+        simulator.set_out_value("ctrl", "out", MemCtrl::None as SignalUnsigned);
+        simulator.clock();
+        assert_eq!(out(&simulator, BUS_FAULT_OUT_ID), false.into());
+    }
+
+    #[test]
+    fn test_bus_overlap() {
+        let simulator = simulator(vec![
+            BusDevice::new("ram", 0..0x1000),
+            BusDevice::new("rom", 0xf00..0x2000),
+        ]);
+        assert_eq!(
+            simulator.component_condition,
+            vec![(
+                "bus".to_string(),
+                Condition::Error("bus bus: devices ram and rom overlap".to_string())
+            )]
+        );
+    }
+}
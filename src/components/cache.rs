@@ -0,0 +1,616 @@
+#[cfg(feature = "gui-egui")]
+use crate::common::EguiComponent;
+use crate::common::{
+    Component, Condition, Id, Input, InputPort, OutputType, Ports, SignalUnsigned, SignalValue,
+    Simulator,
+};
+use crate::components::{MemAccess, MemCtrl};
+use log::*;
+use serde::{Deserialize, Serialize};
+use std::any::Any;
+use std::cell::{Ref, RefCell};
+use std::rc::Rc;
+
+pub const CACHE_DATA_ID: &str = "data";
+pub const CACHE_ADDR_ID: &str = "addr";
+pub const CACHE_CTRL_ID: &str = "ctrl";
+pub const CACHE_SEXT_ID: &str = "sext";
+pub const CACHE_SIZE_ID: &str = "size";
+
+pub const CACHE_MEM_DATA_OUT_ID: &str = "mem_data";
+pub const CACHE_MEM_ADDR_OUT_ID: &str = "mem_addr";
+pub const CACHE_MEM_CTRL_OUT_ID: &str = "mem_ctrl";
+pub const CACHE_MEM_SEXT_OUT_ID: &str = "mem_sext";
+pub const CACHE_MEM_SIZE_OUT_ID: &str = "mem_size";
+pub const CACHE_STALL_OUT_ID: &str = "stall";
+pub const CACHE_HIT_OUT_ID: &str = "hit";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum WritePolicy {
+    /// Writes go to the memory, a write miss does not allocate a line
+    #[default]
+    WriteThrough,
+    /// Writes mark the line dirty, a write miss allocates a line and dirty
+    /// lines are written back when replaced
+    WriteBack,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Replacement {
+    /// Least recently used
+    #[default]
+    Lru,
+    /// First in first out
+    Fifo,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheLine {
+    pub valid: bool,
+    pub dirty: bool,
+    pub tag: usize,
+    // access count at the last use and at the fill, for the replacement
+    pub used: usize,
+    pub filled: usize,
+}
+
+/// Counters since the simulator was created or reset
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub hits: usize,
+    pub misses: usize,
+    pub write_backs: usize,
+}
+
+impl CacheStats {
+    pub fn hit_rate(&self) -> f32 {
+        match self.hits + self.misses {
+            0 => 0.0,
+            accesses => self.hits as f32 / accesses as f32,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CacheState {
+    // lines by set and way
+    pub lines: Vec<Vec<CacheLine>>,
+    pub stats: CacheStats,
+    // the access waited for and the remaining stall cycles
+    pending: Option<(MemAccess, u32)>,
+    accesses: usize,
+}
+
+// what a clock changed, to step back
+#[derive(Debug, Clone)]
+struct CacheUndo {
+    // set, way and the line before
+    line: Option<(usize, usize, CacheLine)>,
+    stats: CacheStats,
+    pending: Option<(MemAccess, u32)>,
+    accesses: usize,
+}
+
+/// Cache between a CPU and a `Mem` or `RVMem`
+///
+/// The CPU side ports are those of the memory, the `mem_` outputs connect to
+/// the memory which still provides the read data. The cache models the tag
+/// arrays, the timing and the statistics, the data is always in the memory.
+///
+/// The address is split in tag, set index and the offset in a line of
+/// `line_size` bytes, each set has `ways` lines, so one way is direct mapped.
+/// A miss stalls for `miss_penalty` cycles to fill the line, and as much more
+/// when a dirty line is written back. While stalled the access must be held
+/// and the memory sees no access, on the cycle `stall` goes low the access is
+/// forwarded to the memory. `hit` is set when the current access hit.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Cache {
+    pub(crate) id: Id,
+    pub(crate) pos: (f32, f32),
+    pub(crate) data: Input,
+    pub(crate) addr: Input,
+    pub(crate) ctrl: Input,
+    pub(crate) sext: Input,
+    pub(crate) size: Input,
+    pub(crate) sets: usize,
+    pub(crate) ways: usize,
+    pub(crate) line_size: usize,
+    #[serde(default)]
+    pub(crate) write_policy: WritePolicy,
+    #[serde(default)]
+    pub(crate) replacement: Replacement,
+    pub(crate) miss_penalty: u32,
+    #[serde(skip)]
+    state: RefCell<CacheState>,
+    #[serde(skip)]
+    history: RefCell<Vec<CacheUndo>>,
+}
+
+#[typetag::serde]
+impl Component for Cache {
+    fn to_(&self) {
+        trace!(
+            "cache {} sets {} ways {} bytes",
+            self.sets,
+            self.ways,
+            self.line_size
+        );
+    }
+    #[cfg(feature = "gui-egui")]
+    fn dummy(&self, id: &str, pos: (f32, f32)) -> Box<Rc<dyn EguiComponent>> {
+        let dummy_input = Input::new("dummy", "out");
+        let mut cache = Cache::new(
+            id,
+            pos,
+            dummy_input.clone(),
+            dummy_input.clone(),
+            dummy_input.clone(),
+            dummy_input.clone(),
+            dummy_input,
+            self.sets,
+            self.ways,
+            self.line_size,
+            self.miss_penalty,
+        );
+        cache.write_policy = self.write_policy;
+        cache.replacement = self.replacement;
+        Box::new(Rc::new(cache))
+    }
+    fn get_id_ports(&self) -> (Id, Ports) {
+        (
+            self.id.clone(),
+            Ports::new(
+                vec![
+                    &InputPort {
+                        port_id: CACHE_DATA_ID.to_string(),
+                        input: self.data.clone(),
+                    },
+                    &InputPort {
+                        port_id: CACHE_ADDR_ID.to_string(),
+                        input: self.addr.clone(),
+                    },
+                    &InputPort {
+                        port_id: CACHE_CTRL_ID.to_string(),
+                        input: self.ctrl.clone(),
+                    },
+                    &InputPort {
+                        port_id: CACHE_SEXT_ID.to_string(),
+                        input: self.sext.clone(),
+                    },
+                    &InputPort {
+                        port_id: CACHE_SIZE_ID.to_string(),
+                        input: self.size.clone(),
+                    },
+                ],
+                OutputType::Combinatorial,
+                vec![
+                    CACHE_MEM_DATA_OUT_ID,
+                    CACHE_MEM_ADDR_OUT_ID,
+                    CACHE_MEM_CTRL_OUT_ID,
+                    CACHE_MEM_SEXT_OUT_ID,
+                    CACHE_MEM_SIZE_OUT_ID,
+                    CACHE_STALL_OUT_ID,
+                    CACHE_HIT_OUT_ID,
+                ],
+            ),
+        )
+    }
+
+    fn set_id_port(&mut self, target_port_id: Id, new_input: Input) {
+        match target_port_id.as_str() {
+            CACHE_DATA_ID => self.data = new_input,
+            CACHE_ADDR_ID => self.addr = new_input,
+            CACHE_CTRL_ID => self.ctrl = new_input,
+            CACHE_SEXT_ID => self.sext = new_input,
+            CACHE_SIZE_ID => self.size = new_input,
+            _ => {}
+        }
+    }
+
+    fn clock(&self, simulator: &mut Simulator) -> Result<(), Condition> {
+        for (name, value) in [
+            ("sets", self.sets),
+            ("ways", self.ways),
+            ("line size", self.line_size),
+        ] {
+            if !value.is_power_of_two() {
+                return Err(Condition::Error(format!(
+                    "cache {}: {} {} is not a power of two",
+                    self.id, name, value
+                )));
+            }
+        }
+        // cycle 0 is evaluated on simulator creation and reset, start out cold
+        if simulator.cycle == 0 {
+            *self.state.borrow_mut() = CacheState::default();
+            self.history.borrow_mut().clear();
+        }
+        let mut state = self.state.borrow_mut();
+        if state.lines.is_empty() {
+            state.lines = vec![vec![CacheLine::default(); self.ways]; self.sets];
+        }
+
+        let data = simulator.get_input_value(&self.data);
+        let addr = simulator.get_input_value(&self.addr);
+        let ctrl = simulator.get_input_value(&self.ctrl);
+        let size = simulator.get_input_value(&self.size);
+        simulator.set_out_value(&self.id, CACHE_MEM_DATA_OUT_ID, data);
+        simulator.set_out_value(&self.id, CACHE_MEM_ADDR_OUT_ID, addr);
+        simulator.set_out_value(&self.id, CACHE_MEM_CTRL_OUT_ID, ctrl);
+        simulator.set_out_value(
+            &self.id,
+            CACHE_MEM_SEXT_OUT_ID,
+            simulator.get_input_value(&self.sext),
+        );
+        simulator.set_out_value(&self.id, CACHE_MEM_SIZE_OUT_ID, size);
+
+        let write = match ctrl {
+            SignalValue::Data(c) if c == MemCtrl::Read as SignalUnsigned => Some(false),
+            SignalValue::Data(c) if c == MemCtrl::Write as SignalUnsigned => Some(true),
+            _ => None,
+        };
+        let mut undo = CacheUndo {
+            line: None,
+            stats: state.stats,
+            pending: state.pending,
+            accesses: state.accesses,
+        };
+        let (stall, hit) = match (write, addr) {
+            (Some(write), SignalValue::Data(a)) => {
+                let access = MemAccess {
+                    write,
+                    addr,
+                    size,
+                    data: if write { data } else { SignalValue::Unknown },
+                };
+                match state.pending.take() {
+                    // waiting for the line
+                    Some((pending, remaining)) if pending == access => {
+                        if remaining > 1 {
+                            state.pending = Some((access, remaining - 1));
+                        }
+                        (remaining > 1, false)
+                    }
+                    _ => {
+                        let (hit, penalty, line) = self.access(&mut state, a as usize, write);
+                        undo.line = line;
+                        if penalty > 0 {
+                            state.pending = Some((access, penalty));
+                        }
+                        (penalty > 0, hit)
+                    }
+                }
+            }
+            _ => {
+                state.pending = None;
+                (false, false)
+            }
+        };
+        if stall {
+            trace!("cache {} stall", self.id);
+            simulator.set_out_value(&self.id, CACHE_MEM_CTRL_OUT_ID, MemCtrl::None as u32);
+        }
+        simulator.set_out_value(&self.id, CACHE_STALL_OUT_ID, stall);
+        simulator.set_out_value(&self.id, CACHE_HIT_OUT_ID, hit);
+        self.history.borrow_mut().push(undo);
+        Ok(())
+    }
+
+    fn un_clock(&self) {
+        if let Some(undo) = self.history.borrow_mut().pop() {
+            let mut state = self.state.borrow_mut();
+            if let Some((set, way, line)) = undo.line {
+                state.lines[set][way] = line;
+            }
+            state.stats = undo.stats;
+            state.pending = undo.pending;
+            state.accesses = undo.accesses;
+        }
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl Cache {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        id: &str,
+        pos: (f32, f32),
+        data: Input,
+        addr: Input,
+        ctrl: Input,
+        sext: Input,
+        size: Input,
+        sets: usize,
+        ways: usize,
+        line_size: usize,
+        miss_penalty: u32,
+    ) -> Self {
+        Cache {
+            id: id.to_string(),
+            pos,
+            data,
+            addr,
+            ctrl,
+            sext,
+            size,
+            sets,
+            ways,
+            line_size,
+            write_policy: WritePolicy::default(),
+            replacement: Replacement::default(),
+            miss_penalty,
+            state: RefCell::default(),
+            history: RefCell::new(vec![]),
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn rc_new(
+        id: &str,
+        pos: (f32, f32),
+        data: Input,
+        addr: Input,
+        ctrl: Input,
+        sext: Input,
+        size: Input,
+        sets: usize,
+        ways: usize,
+        line_size: usize,
+        miss_penalty: u32,
+        write_policy: WritePolicy,
+        replacement: Replacement,
+    ) -> Rc<Self> {
+        let mut cache = Cache::new(
+            id,
+            pos,
+            data,
+            addr,
+            ctrl,
+            sext,
+            size,
+            sets,
+            ways,
+            line_size,
+            miss_penalty,
+        );
+        cache.write_policy = write_policy;
+        cache.replacement = replacement;
+        Rc::new(cache)
+    }
+
+    /// Tag arrays and counters after the last clock
+    pub fn state(&self) -> Ref<'_, CacheState> {
+        self.state.borrow()
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        self.state.borrow().stats
+    }
+
+    /// Set index and tag of `addr`
+    pub fn split(&self, addr: usize) -> (usize, usize) {
+        let line = addr / self.line_size;
+        (line % self.sets, line / self.sets)
+    }
+
+    // look up a new access, updating the lines, returns hit, the stall cycles
+    // and the changed line as set, way and the line before
+    fn access(
+        &self,
+        state: &mut CacheState,
+        addr: usize,
+        write: bool,
+    ) -> (bool, u32, Option<(usize, usize, CacheLine)>) {
+        let (set, tag) = self.split(addr);
+        state.accesses += 1;
+        let now = state.accesses;
+        let write_back = self.write_policy == WritePolicy::WriteBack;
+        let lines = &mut state.lines[set];
+        if let Some(way) = lines.iter().position(|l| l.valid && l.tag == tag) {
+            trace!("cache {} hit set {} tag {:#x}", self.id, set, tag);
+            state.stats.hits += 1;
+            let old = lines[way];
+            lines[way].used = now;
+            lines[way].dirty |= write && write_back;
+            return (true, 0, Some((set, way, old)));
+        }
+        trace!("cache {} miss set {} tag {:#x}", self.id, set, tag);
+        state.stats.misses += 1;
+        if write && !write_back {
+            // no write allocate
+            return (false, 0, None);
+        }
+        let victim = match lines.iter().position(|l| !l.valid) {
+            Some(way) => way,
+            None => {
+                let age = |l: &CacheLine| match self.replacement {
+                    Replacement::Lru => l.used,
+                    Replacement::Fifo => l.filled,
+                };
+                (0..lines.len())
+                    .min_by_key(|way| age(&lines[*way]))
+                    .unwrap()
+            }
+        };
+        let mut penalty = self.miss_penalty;
+        if lines[victim].valid && lines[victim].dirty {
+            state.stats.write_backs += 1;
+            penalty += self.miss_penalty;
+        }
+        let old = lines[victim];
+        lines[victim] = CacheLine {
+            valid: true,
+            dirty: write,
+            tag,
+            used: now,
+            filled: now,
+        };
+        (false, penalty, Some((set, victim, old)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::common::ComponentStore;
+    use crate::components::{ProbeOut, ProbeStim};
+
+    fn simulator(cache: Rc<Cache>) -> Simulator {
+        let cs = ComponentStore {
+            store: vec![
+                Rc::new(ProbeOut::new("data")),
+                Rc::new(ProbeOut::new("addr")),
+                Rc::new(ProbeOut::new("ctrl")),
+                Rc::new(ProbeOut::new("size")),
+                Rc::new(ProbeOut::new("sext")),
+                cache,
+            ],
+        };
+        Simulator::new(cs).unwrap()
+    }
+
+    fn cache(
+        sets: usize,
+        ways: usize,
+        miss_penalty: u32,
+        write_policy: WritePolicy,
+        replacement: Replacement,
+    ) -> Rc<Cache> {
+        Cache::rc_new(
+            "cache",
+            (0.0, 0.0),
+            Input::new("data", "out"),
+            Input::new("addr", "out"),
+            Input::new("ctrl", "out"),
+            Input::new("sext", "out"),
+            Input::new("size", "out"),
+            sets,
+            ways,
+            16,
+            miss_penalty,
+            write_policy,
+            replacement,
+        )
+    }
+
+    fn get_cache(simulator: &Simulator) -> &Cache {
+        simulator
+            .ordered_components
+            .iter()
+            .find_map(|c| c.as_any().downcast_ref::<Cache>())
+            .unwrap()
+    }
+
+    // clock until the access is done, returns the stall cycles
+    fn access(simulator: &mut Simulator, ctrl: MemCtrl, addr: u32) -> usize {
+        simulator.set_out_value("ctrl", "out", ctrl as SignalUnsigned);
+        simulator.set_out_value("addr", "out", addr);
+        let stall = Input::new("cache", CACHE_STALL_OUT_ID);
+        let mem_ctrl = Input::new("cache", CACHE_MEM_CTRL_OUT_ID);
+        let mut cycles = 0;
+        simulator.clock();
+        while simulator.get_input_value(&stall) == true.into() {
+            // the memory is not accessed while stalled
+            assert_eq!(simulator.get_input_value(&mem_ctrl), 0.into());
+            cycles += 1;
+            simulator.clock();
+        }
+        assert_eq!(simulator.get_input_value(&mem_ctrl), (ctrl as u32).into());
+        cycles
+    }
+
+    #[test]
+    fn test_cache_write_back() {
+        let mut simulator = simulator(cache(2, 1, 2, WritePolicy::WriteBack, Replacement::Lru));
+        let hit = Input::new("cache", CACHE_HIT_OUT_ID);
+        assert_eq!(access(&mut simulator, MemCtrl::Read, 0x00), 2);
+        assert_eq!(access(&mut simulator, MemCtrl::Read, 0x04), 0);
+        assert_eq!(simulator.get_input_value(&hit), true.into());
+        // same set, other tag
+        assert_eq!(access(&mut simulator, MemCtrl::Read, 0x20), 2);
+        assert_eq!(simulator.get_input_value(&hit), false.into());
+        assert_eq!(access(&mut simulator, MemCtrl::Write, 0x24), 0);
+        let line = get_cache(&simulator).state().lines[0][0];
+        assert!(line.valid && line.dirty && line.tag == 1);
+        // replacing the dirty line writes it back
+        assert_eq!(access(&mut simulator, MemCtrl::Read, 0x00), 4);
+        assert_eq!(
+            get_cache(&simulator).stats(),
+            CacheStats {
+                hits: 2,
+                misses: 3,
+                write_backs: 1
+            }
+        );
+
+        // stepping back restores the lines, a reset clears the counters
+        simulator.un_clock();
+        simulator.un_clock();
+        simulator.un_clock();
+        simulator.un_clock();
+        simulator.un_clock();
+        assert_eq!(get_cache(&simulator).state().lines[0][0], line);
+        assert_eq!(
+            get_cache(&simulator).stats(),
+            CacheStats {
+                hits: 2,
+                misses: 2,
+                write_backs: 0
+            }
+        );
+        assert_eq!(access(&mut simulator, MemCtrl::Read, 0x00), 4);
+        simulator.reset();
+        assert_eq!(get_cache(&simulator).stats(), CacheStats::default());
+    }
+
+    #[test]
+    fn test_cache_reset() {
+        let cs = ComponentStore {
+            store: vec![
+                Rc::new(ProbeOut::new("data")),
+                Rc::new(ProbeOut::new("addr")),
+                ProbeStim::rc_new("ctrl", (0.0, 0.0), vec![MemCtrl::Read as u32; 4]),
+                Rc::new(ProbeOut::new("size")),
+                Rc::new(ProbeOut::new("sext")),
+                cache(2, 1, 2, WritePolicy::WriteBack, Replacement::Lru),
+            ],
+        };
+        let mut simulator = Simulator::new(cs).unwrap();
+        let stall = Input::new("cache", CACHE_STALL_OUT_ID);
+        simulator.clock();
+        simulator.clock();
+        simulator.clock();
+        assert_eq!(
+            simulator.get_input_value(&Input::new("cache", CACHE_HIT_OUT_ID)),
+            true.into()
+        );
+
+        // after a reset the line is missed again
+        simulator.reset();
+        assert_eq!(simulator.get_input_value(&stall), true.into());
+        assert_eq!(get_cache(&simulator).stats().misses, 1);
+        simulator.clock();
+        simulator.clock();
+        assert_eq!(simulator.get_input_value(&stall), false.into());
+    }
+
+    #[test]
+    fn test_cache_replacement() {
+        for (replacement, hits) in [(Replacement::Lru, 2), (Replacement::Fifo, 1)] {
+            let mut simulator = simulator(cache(1, 2, 1, WritePolicy::WriteThrough, replacement));
+            for addr in [0x00, 0x10, 0x00, 0x20, 0x00] {
+                access(&mut simulator, MemCtrl::Read, addr);
+            }
+            assert_eq!(get_cache(&simulator).stats().hits, hits);
+        }
+
+        // write through does not allocate on a write miss
+        let mut simulator = simulator(cache(1, 2, 1, WritePolicy::WriteThrough, Replacement::Lru));
+        assert_eq!(access(&mut simulator, MemCtrl::Write, 0x00), 0);
+        assert_eq!(access(&mut simulator, MemCtrl::Read, 0x00), 1);
+        assert_eq!(get_cache(&simulator).stats().misses, 2);
+    }
+}
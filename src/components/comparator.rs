@@ -0,0 +1,199 @@
+#[cfg(feature = "gui-egui")]
+use crate::common::EguiComponent;
+use crate::common::{
+    Component, Condition, Id, Input, InputPort, OutputType, Ports, SignalValue, Simulator,
+};
+use crate::components::{check_width, sign_extend, width_mask};
+use log::*;
+use serde::{Deserialize, Serialize};
+use std::any::Any;
+use std::cmp::Ordering;
+use std::rc::Rc;
+
+pub const COMPARATOR_A_IN_ID: &str = "a_in";
+pub const COMPARATOR_B_IN_ID: &str = "b_in";
+pub const COMPARATOR_LT_OUT_ID: &str = "lt";
+pub const COMPARATOR_EQ_OUT_ID: &str = "eq";
+pub const COMPARATOR_GT_OUT_ID: &str = "gt";
+
+/// Compares `width` bit operands as signed or unsigned numbers
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Comparator {
+    pub(crate) id: Id,
+    pub(crate) pos: (f32, f32),
+    pub(crate) a_in: Input,
+    pub(crate) b_in: Input,
+    pub(crate) width: u32,
+    pub(crate) signed: bool,
+}
+
+#[typetag::serde]
+impl Component for Comparator {
+    fn to_(&self) {
+        trace!("comparator {} bits, signed {}", self.width, self.signed);
+    }
+    #[cfg(feature = "gui-egui")]
+    fn dummy(&self, id: &str, pos: (f32, f32)) -> Box<Rc<dyn EguiComponent>> {
+        let dummy_input = Input::new("dummy", "out");
+        Box::new(Rc::new(Comparator {
+            id: id.to_string(),
+            pos: (pos.0, pos.1),
+            a_in: dummy_input.clone(),
+            b_in: dummy_input,
+            width: self.width,
+            signed: self.signed,
+        }))
+    }
+    fn get_id_ports(&self) -> (Id, Ports) {
+        (
+            self.id.clone(),
+            Ports::new(
+                vec![
+                    &InputPort {
+                        port_id: COMPARATOR_A_IN_ID.to_string(),
+                        input: self.a_in.clone(),
+                    },
+                    &InputPort {
+                        port_id: COMPARATOR_B_IN_ID.to_string(),
+                        input: self.b_in.clone(),
+                    },
+                ],
+                OutputType::Combinatorial,
+                vec![
+                    COMPARATOR_LT_OUT_ID,
+                    COMPARATOR_EQ_OUT_ID,
+                    COMPARATOR_GT_OUT_ID,
+                ],
+            ),
+        )
+    }
+
+    fn set_id_port(&mut self, target_port_id: Id, new_input: Input) {
+        match target_port_id.as_str() {
+            COMPARATOR_A_IN_ID => self.a_in = new_input,
+            COMPARATOR_B_IN_ID => self.b_in = new_input,
+            _ => {}
+        }
+    }
+
+    fn clock(&self, simulator: &mut Simulator) -> Result<(), Condition> {
+        let a = simulator.get_input_value(&self.a_in);
+        let b = simulator.get_input_value(&self.b_in);
+        let (res, ordering) = match (a, b, check_width(&self.id, self.width)) {
+            (_, _, Err(e)) => (Err(Condition::Error(e)), None),
+            (SignalValue::Data(a), SignalValue::Data(b), Ok(())) => {
+                let mask = width_mask(self.width);
+                let ordering = if self.signed {
+                    sign_extend(a & mask, self.width).cmp(&sign_extend(b & mask, self.width))
+                } else {
+                    (a & mask).cmp(&(b & mask))
+                };
+                (Ok(()), Some(ordering))
+            }
+            _ => (Ok(()), None),
+        };
+        for (field, expected) in [
+            (COMPARATOR_LT_OUT_ID, Ordering::Less),
+            (COMPARATOR_EQ_OUT_ID, Ordering::Equal),
+            (COMPARATOR_GT_OUT_ID, Ordering::Greater),
+        ] {
+            simulator.set_out_value(
+                &self.id,
+                field,
+                match ordering {
+                    Some(ordering) => (ordering == expected).into(),
+                    None => SignalValue::Unknown,
+                },
+            );
+        }
+        res
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl Comparator {
+    pub fn new(
+        id: &str,
+        pos: (f32, f32),
+        a_in: Input,
+        b_in: Input,
+        width: u32,
+        signed: bool,
+    ) -> Self {
+        Comparator {
+            id: id.to_string(),
+            pos,
+            a_in,
+            b_in,
+            width,
+            signed,
+        }
+    }
+
+    pub fn rc_new(
+        id: &str,
+        pos: (f32, f32),
+        a_in: Input,
+        b_in: Input,
+        width: u32,
+        signed: bool,
+    ) -> Rc<Self> {
+        Rc::new(Comparator::new(id, pos, a_in, b_in, width, signed))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::common::ComponentStore;
+    use crate::components::ProbeStim;
+
+    #[test]
+    fn test_comparator() {
+        let cs = ComponentStore {
+            store: vec![
+                ProbeStim::rc_new("a", (0.0, 0.0), vec![1, 0xff, 7]),
+                ProbeStim::rc_new("b", (0.0, 0.0), vec![0xff, 1, 0x107]),
+                Comparator::rc_new(
+                    "u",
+                    (0.0, 0.0),
+                    Input::new("a", "out"),
+                    Input::new("b", "out"),
+                    8,
+                    false,
+                ),
+                Comparator::rc_new(
+                    "s",
+                    (0.0, 0.0),
+                    Input::new("a", "out"),
+                    Input::new("b", "out"),
+                    8,
+                    true,
+                ),
+            ],
+        };
+        let mut simulator = Simulator::new(cs).unwrap();
+        let outputs = |simulator: &Simulator, id: &str| {
+            [
+                COMPARATOR_LT_OUT_ID,
+                COMPARATOR_EQ_OUT_ID,
+                COMPARATOR_GT_OUT_ID,
+            ]
+            .map(|field| simulator.get_input_value(&Input::new(id, field)))
+        };
+        let (t, f) = (true.into(), false.into());
+        // 1 vs 0xff, -1 when signed
+        assert_eq!(outputs(&simulator, "u"), [t, f, f]);
+        assert_eq!(outputs(&simulator, "s"), [f, f, t]);
+        simulator.clock();
+        assert_eq!(outputs(&simulator, "u"), [f, f, t]);
+        assert_eq!(outputs(&simulator, "s"), [t, f, f]);
+        // only the low 8 bits are compared
+        simulator.clock();
+        assert_eq!(outputs(&simulator, "u"), [f, t, f]);
+        assert_eq!(outputs(&simulator, "s"), [f, t, f]);
+    }
+}
@@ -0,0 +1,478 @@
+use super::mem::Memory;
+#[cfg(feature = "gui-egui")]
+use crate::common::EguiComponent;
+use crate::common::{
+    Component, Condition, Id, Input, InputPort, OutputType, Ports, SignalUnsigned, SignalValue,
+    Simulator,
+};
+use crate::components::{MemCtrl, WriteHistory};
+use log::*;
+use serde::{Deserialize, Serialize};
+use std::any::Any;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet};
+use std::convert::TryFrom;
+use std::ops::Range;
+use std::rc::Rc;
+
+pub const DPMEM_A_DATA_ID: &str = "a_data";
+pub const DPMEM_A_ADDR_ID: &str = "a_addr";
+pub const DPMEM_A_CTRL_ID: &str = "a_ctrl";
+pub const DPMEM_A_SEXT_ID: &str = "a_sext";
+pub const DPMEM_A_SIZE_ID: &str = "a_size";
+pub const DPMEM_B_DATA_ID: &str = "b_data";
+pub const DPMEM_B_ADDR_ID: &str = "b_addr";
+pub const DPMEM_B_CTRL_ID: &str = "b_ctrl";
+pub const DPMEM_B_SEXT_ID: &str = "b_sext";
+pub const DPMEM_B_SIZE_ID: &str = "b_size";
+
+pub const DPMEM_A_DATA_OUT_ID: &str = "a_data_o";
+pub const DPMEM_A_ERR_OUT_ID: &str = "a_err";
+pub const DPMEM_B_DATA_OUT_ID: &str = "b_data_o";
+pub const DPMEM_B_ERR_OUT_ID: &str = "b_err";
+
+/// The inputs of a memory port, as those of `Mem`
+#[derive(Serialize, Deserialize, Clone)]
+pub struct MemPort {
+    pub data: Input,
+    pub addr: Input,
+    pub ctrl: Input,
+    pub sext: Input,
+    pub size: Input,
+}
+
+impl MemPort {
+    pub fn new(data: Input, addr: Input, ctrl: Input, sext: Input, size: Input) -> Self {
+        MemPort {
+            data,
+            addr,
+            ctrl,
+            sext,
+            size,
+        }
+    }
+}
+
+/// What happens when both ports write the same bytes in a cycle
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum WriteConflict {
+    /// The write of port a is kept
+    #[default]
+    PortA,
+    /// The write of port b is kept
+    PortB,
+    /// The write of port a is kept and a warning given
+    Warning,
+}
+
+/// Memory with two independent ports
+///
+/// Both ports are accessed in the same cycle, reads see the content before
+/// the writes of the cycle. When the writes of both ports overlap,
+/// `write_conflict` decides which is kept.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct DualPortMem {
+    pub(crate) id: Id,
+    pub(crate) pos: (f32, f32),
+    pub(crate) width: f32,
+    pub(crate) height: f32,
+
+    // configuration
+    pub big_endian: bool,
+    #[serde(default)]
+    pub(crate) write_conflict: WriteConflict,
+
+    // ports
+    pub(crate) port_a: MemPort,
+    pub(crate) port_b: MemPort,
+
+    // memory
+    pub(crate) memory: Memory,
+    pub(crate) range: Range<u32>,
+    #[serde(skip)]
+    pub(crate) writes: RefCell<WriteHistory>,
+}
+
+// a decoded access of a port, (ctrl, addr, size, sign, data)
+type PortAccess = (MemCtrl, usize, usize, bool, SignalValue);
+
+#[typetag::serde]
+impl Component for DualPortMem {
+    fn to_(&self) {
+        trace!("DualPortMem");
+    }
+    #[cfg(feature = "gui-egui")]
+    fn dummy(&self, id: &str, pos: (f32, f32)) -> Box<Rc<dyn EguiComponent>> {
+        let dummy_input = Input::new("dummy", "out");
+        let port = MemPort::new(
+            dummy_input.clone(),
+            dummy_input.clone(),
+            dummy_input.clone(),
+            dummy_input.clone(),
+            dummy_input,
+        );
+        let mut mem = DualPortMem::new(
+            id,
+            pos,
+            self.width,
+            self.height,
+            self.big_endian,
+            port.clone(),
+            port,
+            BTreeMap::new(),
+            self.range.clone(),
+        );
+        mem.write_conflict = self.write_conflict;
+        Box::new(Rc::new(mem))
+    }
+    fn get_id_ports(&self) -> (Id, Ports) {
+        let inputs: Vec<InputPort> = self
+            .port_ids()
+            .into_iter()
+            .map(|(port_id, input)| InputPort {
+                port_id: port_id.to_string(),
+                input: input.clone(),
+            })
+            .collect();
+        (
+            self.id.clone(),
+            Ports::new(
+                inputs.iter().collect(),
+                OutputType::Combinatorial,
+                vec![
+                    DPMEM_A_DATA_OUT_ID,
+                    DPMEM_A_ERR_OUT_ID,
+                    DPMEM_B_DATA_OUT_ID,
+                    DPMEM_B_ERR_OUT_ID,
+                ],
+            ),
+        )
+    }
+
+    fn set_id_port(&mut self, target_port_id: Id, new_input: Input) {
+        match target_port_id.as_str() {
+            DPMEM_A_DATA_ID => self.port_a.data = new_input,
+            DPMEM_A_ADDR_ID => self.port_a.addr = new_input,
+            DPMEM_A_CTRL_ID => self.port_a.ctrl = new_input,
+            DPMEM_A_SEXT_ID => self.port_a.sext = new_input,
+            DPMEM_A_SIZE_ID => self.port_a.size = new_input,
+            DPMEM_B_DATA_ID => self.port_b.data = new_input,
+            DPMEM_B_ADDR_ID => self.port_b.addr = new_input,
+            DPMEM_B_CTRL_ID => self.port_b.ctrl = new_input,
+            DPMEM_B_SEXT_ID => self.port_b.sext = new_input,
+            DPMEM_B_SIZE_ID => self.port_b.size = new_input,
+            _ => (),
+        }
+    }
+
+    fn clock(&self, simulator: &mut Simulator) -> Result<(), Condition> {
+        // cycle 0 is evaluated on simulator creation and reset, undoing the
+        // writes since restores the initial contents
+        if simulator.cycle == 0 {
+            self.writes.borrow_mut().undo(&mut self.memory.borrow_mut());
+        }
+        self.writes.borrow_mut().clock(simulator.cycle);
+        let ports = [
+            (&self.port_a, DPMEM_A_DATA_OUT_ID, DPMEM_A_ERR_OUT_ID),
+            (&self.port_b, DPMEM_B_DATA_OUT_ID, DPMEM_B_ERR_OUT_ID),
+        ];
+        let mut accesses = vec![];
+        for (port, data_out, err_out) in ports {
+            let access = self.decode(simulator, port)?;
+            if access.is_none() {
+                simulator.set_out_value(&self.id, data_out, SignalValue::Unknown);
+                simulator.set_out_value(&self.id, err_out, SignalValue::Unknown);
+            }
+            accesses.push((access, data_out, err_out));
+        }
+
+        // reads before writes
+        for (access, data_out, err_out) in &accesses {
+            if let Some((MemCtrl::Read, addr, size, sign, _)) = access {
+                trace!("read addr {:?} size {:?}", addr, size);
+                let value = self.memory.read(*addr, *size, *sign, self.big_endian);
+                simulator.set_out_value(&self.id, data_out, value);
+                simulator.set_out_value(&self.id, err_out, self.memory.align(*addr, *size));
+            }
+        }
+
+        let mut writes: Vec<(usize, usize, SignalValue)> = vec![];
+        for (access, _, err_out) in &accesses {
+            if let Some((MemCtrl::Write, addr, size, _, data)) = access {
+                simulator.set_out_value(&self.id, err_out, self.memory.align(*addr, *size));
+                writes.push((*addr, *size, *data));
+            }
+        }
+        let mut res = Ok(());
+        let order: Vec<_> = match writes.as_slice() {
+            [a, b] if a.0 < b.0 + b.1 && b.0 < a.0 + a.1 => {
+                trace!("write conflict {:x?} {:x?}", a, b);
+                match self.write_conflict {
+                    WriteConflict::PortA => vec![b, a],
+                    WriteConflict::PortB => vec![a, b],
+                    WriteConflict::Warning => {
+                        res = Err(Condition::Warning(format!(
+                            "dual port mem {}: both ports write address {:#x}",
+                            self.id,
+                            a.0.max(b.0)
+                        )));
+                        vec![b, a]
+                    }
+                }
+            }
+            writes => writes.iter().collect(),
+        };
+        for (addr, size, data) in order {
+            trace!("write addr {:?} size {:?}", addr, size);
+            let bytes = *addr..addr + size;
+            let old = self.memory.borrow().init_bytes(bytes.clone());
+            self.memory.write(*addr, *size, self.big_endian, *data);
+            let new = self.memory.borrow().bytes(bytes);
+            self.writes.borrow_mut().record(*addr, &old, &new);
+        }
+        res
+    }
+
+    fn un_clock(&self) {
+        let mut memory = self.memory.borrow_mut();
+        for write in self.writes.borrow_mut().un_clock().iter().rev() {
+            memory.restore(write);
+        }
+    }
+
+    fn reset(&self) {
+        self.writes.borrow_mut().clear();
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl DualPortMem {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        id: &str,
+        pos: (f32, f32),
+        width: f32,
+        height: f32,
+        big_endian: bool,
+        port_a: MemPort,
+        port_b: MemPort,
+        memory: BTreeMap<usize, u8>,
+        range: Range<u32>,
+    ) -> Self {
+        DualPortMem {
+            id: id.to_string(),
+            pos,
+            width,
+            height,
+            big_endian,
+            write_conflict: WriteConflict::default(),
+            port_a,
+            port_b,
+            memory: Memory::new(memory),
+            range,
+            writes: RefCell::default(),
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn rc_new(
+        id: &str,
+        pos: (f32, f32),
+        width: f32,
+        height: f32,
+        big_endian: bool,
+        port_a: MemPort,
+        port_b: MemPort,
+        range: Range<u32>,
+        write_conflict: WriteConflict,
+    ) -> Rc<Self> {
+        let mut mem = DualPortMem::new(
+            id,
+            pos,
+            width,
+            height,
+            big_endian,
+            port_a,
+            port_b,
+            BTreeMap::new(),
+            range,
+        );
+        mem.write_conflict = write_conflict;
+        Rc::new(mem)
+    }
+
+    /// Addresses written in the current cycle
+    pub fn written(&self) -> BTreeSet<usize> {
+        self.writes.borrow().written()
+    }
+
+    pub(crate) fn port_ids(&self) -> [(&str, &Input); 10] {
+        [
+            (DPMEM_A_DATA_ID, &self.port_a.data),
+            (DPMEM_A_ADDR_ID, &self.port_a.addr),
+            (DPMEM_A_CTRL_ID, &self.port_a.ctrl),
+            (DPMEM_A_SEXT_ID, &self.port_a.sext),
+            (DPMEM_A_SIZE_ID, &self.port_a.size),
+            (DPMEM_B_DATA_ID, &self.port_b.data),
+            (DPMEM_B_ADDR_ID, &self.port_b.addr),
+            (DPMEM_B_CTRL_ID, &self.port_b.ctrl),
+            (DPMEM_B_SEXT_ID, &self.port_b.sext),
+            (DPMEM_B_SIZE_ID, &self.port_b.size),
+        ]
+    }
+
+    // the access of a port, none for an unknown control
+    fn decode(
+        &self,
+        simulator: &Simulator,
+        port: &MemPort,
+    ) -> Result<Option<PortAccess>, Condition> {
+        let ctrl = match simulator.get_input_value(&port.ctrl) {
+            SignalValue::Data(ctrl) => MemCtrl::try_from(ctrl as u8).map_err(|_| {
+                Condition::Error(format!("dual port mem {}: illegal ctrl {}", self.id, ctrl))
+            })?,
+            _ => return Ok(None),
+        };
+        if let MemCtrl::None = ctrl {
+            return Ok(Some((ctrl, 0, 0, false, SignalValue::Unknown)));
+        }
+        let value = |input: &Input| -> Result<SignalUnsigned, Condition> {
+            simulator.get_input_value(input).try_into().map_err(|_| {
+                Condition::Error(format!(
+                    "dual port mem {}: {} is not defined",
+                    self.id, input.id
+                ))
+            })
+        };
+        let addr = value(&port.addr)? as usize;
+        let size = value(&port.size)? as usize;
+        if ![1, 2, 4].contains(&size) {
+            return Err(Condition::Error(format!(
+                "dual port mem {}: illegal size {}",
+                self.id, size
+            )));
+        }
+        let sign = value(&port.sext)? != 0;
+        let data = simulator.get_input_value(&port.data);
+        if matches!(ctrl, MemCtrl::Write) && !matches!(data, SignalValue::Data(_)) {
+            return Err(Condition::Error(format!(
+                "dual port mem {}: write of {:?}",
+                self.id, data
+            )));
+        }
+        Ok(Some((ctrl, addr, size, sign, data)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::common::{ComponentStore, Components};
+    use crate::components::ProbeOut;
+
+    fn port(prefix: &str) -> MemPort {
+        let input = |name: &str| Input::new(&format!("{}_{}", prefix, name), "out");
+        MemPort::new(
+            input("data"),
+            input("addr"),
+            input("ctrl"),
+            input("sext"),
+            input("size"),
+        )
+    }
+
+    fn simulator(write_conflict: WriteConflict) -> Simulator {
+        let mut store: Components = vec![];
+        for prefix in ["a", "b"] {
+            for name in ["data", "addr", "ctrl", "sext", "size"] {
+                store.push(Rc::new(ProbeOut::new(&format!("{}_{}", prefix, name))));
+            }
+        }
+        store.push(DualPortMem::rc_new(
+            "mem",
+            (0.0, 0.0),
+            0.0,
+            0.0,
+            true,
+            port("a"),
+            port("b"),
+            0..0x100,
+            write_conflict,
+        ));
+        Simulator::new(ComponentStore { store }).unwrap()
+    }
+
+    fn access(simulator: &mut Simulator, port: &str, ctrl: MemCtrl, addr: u32, data: u32) {
+        simulator.set_out_value(&format!("{}_ctrl", port), "out", ctrl as SignalUnsigned);
+        simulator.set_out_value(&format!("{}_addr", port), "out", addr);
+        simulator.set_out_value(&format!("{}_data", port), "out", data);
+        simulator.set_out_value(&format!("{}_size", port), "out", 4);
+    }
+
+    #[test]
+    fn test_dual_port_mem() {
+        let mut simulator = simulator(WriteConflict::PortA);
+        let b_out = &Input::new("mem", DPMEM_B_DATA_OUT_ID);
+
+        // a read in the cycle of the write sees the old content
+        access(&mut simulator, "a", MemCtrl::Write, 8, 0x1234_5678);
+        access(&mut simulator, "b", MemCtrl::Read, 8, 0);
+        simulator.clock();
+        assert_eq!(simulator.get_input_value(b_out), 0.into());
+        access(&mut simulator, "a", MemCtrl::None, 0, 0);
+        simulator.clock();
+        assert_eq!(simulator.get_input_value(b_out), 0x1234_5678.into());
+
+        // both write, port a is kept
+        access(&mut simulator, "a", MemCtrl::Write, 8, 1);
+        access(&mut simulator, "b", MemCtrl::Write, 8, 2);
+        simulator.clock();
+        access(&mut simulator, "a", MemCtrl::Read, 8, 0);
+        access(&mut simulator, "b", MemCtrl::None, 0, 0);
+        simulator.clock();
+        assert_eq!(
+            simulator.get_input_value(&Input::new("mem", DPMEM_A_DATA_OUT_ID)),
+            1.into()
+        );
+
+        // stepping back restores the memory
+        simulator.un_clock();
+        simulator.un_clock();
+        access(&mut simulator, "b", MemCtrl::Read, 8, 0);
+        simulator.clock();
+        assert_eq!(simulator.get_input_value(b_out), 0x1234_5678.into());
+
+        // a reset restores the initial contents
+        simulator.reset();
+        access(&mut simulator, "b", MemCtrl::Read, 8, 0);
+        simulator.clock();
+        assert_eq!(simulator.get_input_value(b_out), 0.into());
+    }
+
+    #[test]
+    fn test_dual_port_mem_conflict() {
+        for (write_conflict, value, warning) in [
+            (WriteConflict::PortB, 2, false),
+            (WriteConflict::Warning, 1, true),
+        ] {
+            let mut simulator = simulator(write_conflict);
+            access(&mut simulator, "a", MemCtrl::Write, 8, 1);
+            // overlapping, not at the same address
+            access(&mut simulator, "b", MemCtrl::Write, 10, 2);
+            simulator.set_out_value("b_size", "out", 2);
+            simulator.clock();
+            assert_eq!(!simulator.component_condition.is_empty(), warning);
+            access(&mut simulator, "a", MemCtrl::Read, 8, 0);
+            simulator.set_out_value("a_size", "out", 4);
+            access(&mut simulator, "b", MemCtrl::None, 0, 0);
+            simulator.clock();
+            assert_eq!(
+                simulator.get_input_value(&Input::new("mem", DPMEM_A_DATA_OUT_ID)),
+                value.into()
+            );
+        }
+    }
+}
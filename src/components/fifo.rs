@@ -0,0 +1,354 @@
+#[cfg(feature = "gui-egui")]
+use crate::common::EguiComponent;
+use crate::common::{
+    Component, Condition, Id, Input, InputPort, OutputType, Ports, SignalUnsigned, SignalValue,
+    Simulator,
+};
+use crate::components::{check_width, width_mask};
+use crate::coverage::{CoverBin, CoverPoint};
+use log::*;
+use serde::{Deserialize, Serialize};
+use std::any::Any;
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::rc::Rc;
+
+pub const FIFO_DATA_IN_ID: &str = "data_in";
+pub const FIFO_PUSH_IN_ID: &str = "push_in";
+pub const FIFO_POP_IN_ID: &str = "pop_in";
+pub const FIFO_OUT_ID: &str = "out";
+pub const FIFO_FULL_OUT_ID: &str = "full";
+pub const FIFO_EMPTY_OUT_ID: &str = "empty";
+pub const FIFO_COUNT_OUT_ID: &str = "count";
+
+/// Synchronous first-in first-out queue
+///
+/// On a clock with `pop` set the head is removed, then with `push` set the
+/// data input is appended, so both can be done in the same cycle also when
+/// full. `out` shows the head after the clock, unknown when empty. Pushing
+/// when full drops the data and popping when empty does nothing, both giving
+/// a warning.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Fifo {
+    pub(crate) id: Id,
+    pub(crate) pos: (f32, f32),
+    pub(crate) data_in: Input,
+    pub(crate) push_in: Input,
+    pub(crate) pop_in: Input,
+    pub(crate) depth: usize,
+    pub(crate) width: u32,
+    #[serde(skip)]
+    history: RefCell<Vec<VecDeque<SignalValue>>>,
+}
+
+#[typetag::serde]
+impl Component for Fifo {
+    fn to_(&self) {
+        trace!("fifo {}x{}", self.depth, self.width);
+    }
+    #[cfg(feature = "gui-egui")]
+    fn dummy(&self, id: &str, pos: (f32, f32)) -> Box<Rc<dyn EguiComponent>> {
+        let dummy_input = Input::new("dummy", "out");
+        Box::new(Rc::new(Fifo::new(
+            id,
+            pos,
+            dummy_input.clone(),
+            dummy_input.clone(),
+            dummy_input,
+            self.depth,
+            self.width,
+        )))
+    }
+    fn get_id_ports(&self) -> (Id, Ports) {
+        (
+            self.id.clone(),
+            Ports::new(
+                vec![
+                    &InputPort {
+                        port_id: FIFO_DATA_IN_ID.to_string(),
+                        input: self.data_in.clone(),
+                    },
+                    &InputPort {
+                        port_id: FIFO_PUSH_IN_ID.to_string(),
+                        input: self.push_in.clone(),
+                    },
+                    &InputPort {
+                        port_id: FIFO_POP_IN_ID.to_string(),
+                        input: self.pop_in.clone(),
+                    },
+                ],
+                OutputType::Sequential,
+                vec![
+                    FIFO_OUT_ID,
+                    FIFO_FULL_OUT_ID,
+                    FIFO_EMPTY_OUT_ID,
+                    FIFO_COUNT_OUT_ID,
+                ],
+            ),
+        )
+    }
+
+    fn set_id_port(&mut self, target_port_id: Id, new_input: Input) {
+        match target_port_id.as_str() {
+            FIFO_DATA_IN_ID => self.data_in = new_input,
+            FIFO_PUSH_IN_ID => self.push_in = new_input,
+            FIFO_POP_IN_ID => self.pop_in = new_input,
+            _ => {}
+        }
+    }
+
+    fn clock(&self, simulator: &mut Simulator) -> Result<(), Condition> {
+        check_width(&self.id, self.width).map_err(Condition::Error)?;
+        if self.depth == 0 {
+            return Err(Condition::Error(format!("fifo {}: depth 0", self.id)));
+        }
+        // cycle 0 is evaluated on simulator creation and reset, the latter
+        // before `reset`, so start out empty
+        if simulator.cycle == 0 {
+            self.history.borrow_mut().clear();
+        }
+        // any nonzero push or pop is asserted
+        let push =
+            matches!(simulator.get_input_value(&self.push_in), SignalValue::Data(v) if v != 0);
+        let pop = matches!(simulator.get_input_value(&self.pop_in), SignalValue::Data(v) if v != 0);
+        let mut contents = self.contents();
+        let mut res = Ok(());
+        if pop && contents.pop_front().is_none() {
+            res = Err(Condition::Warning(format!("fifo {}: underflow", self.id)));
+        }
+        if push {
+            if contents.len() < self.depth {
+                let data = match simulator.get_input_value(&self.data_in) {
+                    SignalValue::Data(data) => (data & width_mask(self.width)).into(),
+                    other => other,
+                };
+                contents.push_back(data);
+            } else {
+                res = Err(Condition::Warning(format!("fifo {}: overflow", self.id)));
+            }
+        }
+        let head = contents.front().copied().unwrap_or(SignalValue::Unknown);
+        simulator.set_out_value(&self.id, FIFO_OUT_ID, head);
+        simulator.set_out_value(&self.id, FIFO_FULL_OUT_ID, contents.len() == self.depth);
+        simulator.set_out_value(&self.id, FIFO_EMPTY_OUT_ID, contents.is_empty());
+        simulator.set_out_value(
+            &self.id,
+            FIFO_COUNT_OUT_ID,
+            contents.len() as SignalUnsigned,
+        );
+        self.history.borrow_mut().push(contents);
+        res
+    }
+
+    fn un_clock(&self) {
+        self.history.borrow_mut().pop();
+    }
+
+    fn reset(&self) {
+        self.history.borrow_mut().clear();
+    }
+
+    fn cover_points(&self) -> Vec<CoverPoint> {
+        let bins = vec![CoverBin::new("idle", 0), CoverBin::new("active", 1)];
+        vec![
+            CoverPoint::new(FIFO_PUSH_IN_ID, self.push_in.clone(), bins.clone()),
+            CoverPoint::new(FIFO_POP_IN_ID, self.pop_in.clone(), bins),
+        ]
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl Fifo {
+    pub fn new(
+        id: &str,
+        pos: (f32, f32),
+        data_in: Input,
+        push_in: Input,
+        pop_in: Input,
+        depth: usize,
+        width: u32,
+    ) -> Self {
+        Fifo {
+            id: id.to_string(),
+            pos,
+            data_in,
+            push_in,
+            pop_in,
+            depth,
+            width,
+            history: RefCell::new(vec![]),
+        }
+    }
+
+    pub fn rc_new(
+        id: &str,
+        pos: (f32, f32),
+        data_in: Input,
+        push_in: Input,
+        pop_in: Input,
+        depth: usize,
+        width: u32,
+    ) -> Rc<Self> {
+        Rc::new(Fifo::new(id, pos, data_in, push_in, pop_in, depth, width))
+    }
+
+    /// Current contents, head first
+    pub fn contents(&self) -> VecDeque<SignalValue> {
+        self.history.borrow().last().cloned().unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::common::ComponentStore;
+    use crate::components::ProbeStim;
+
+    fn fifo_outputs(simulator: &Simulator) -> [SignalValue; 4] {
+        [
+            FIFO_OUT_ID,
+            FIFO_FULL_OUT_ID,
+            FIFO_EMPTY_OUT_ID,
+            FIFO_COUNT_OUT_ID,
+        ]
+        .map(|field| simulator.get_input_value(&Input::new("fifo", field)))
+    }
+
+    fn fifo_contents(simulator: &Simulator) -> VecDeque<SignalValue> {
+        simulator
+            .ordered_components
+            .iter()
+            .find_map(|c| c.as_any().downcast_ref::<Fifo>())
+            .unwrap()
+            .contents()
+    }
+
+    #[test]
+    fn test_fifo() {
+        let cs = ComponentStore {
+            store: vec![
+                ProbeStim::rc_new("data", (0.0, 0.0), vec![1, 2, 0x1f3, 4, 5, 6, 7, 8, 9]),
+                ProbeStim::rc_new("push", (0.0, 0.0), vec![1, 1, 1, 1, 0, 0, 0, 0, 0]),
+                ProbeStim::rc_new("pop", (0.0, 0.0), vec![0, 0, 0, 1, 1, 1, 1, 1, 0]),
+                Fifo::rc_new(
+                    "fifo",
+                    (0.0, 0.0),
+                    Input::new("data", "out"),
+                    Input::new("push", "out"),
+                    Input::new("pop", "out"),
+                    3,
+                    8,
+                ),
+            ],
+        };
+        let mut simulator = Simulator::new(cs).unwrap();
+        let mut trace = vec![];
+        for _ in 0..8 {
+            simulator.clock();
+            trace.push(fifo_outputs(&simulator));
+        }
+        let (t, f) = (true.into(), false.into());
+        let unknown = SignalValue::Unknown;
+        assert_eq!(
+            trace,
+            vec![
+                [1.into(), f, f, 1.into()],
+                [1.into(), f, f, 2.into()],
+                // data is masked to the width
+                [1.into(), t, f, 3.into()],
+                // push and pop at once when full
+                [2.into(), t, f, 3.into()],
+                [0xf3.into(), f, f, 2.into()],
+                [4.into(), f, f, 1.into()],
+                [unknown, f, t, 0.into()],
+                [unknown, f, t, 0.into()],
+            ]
+        );
+        assert_eq!(
+            simulator.component_condition,
+            vec![(
+                "fifo".to_string(),
+                Condition::Warning("fifo fifo: underflow".to_string())
+            )]
+        );
+
+        // stepping back restores the contents
+        simulator.un_clock();
+        simulator.un_clock();
+        assert_eq!(fifo_contents(&simulator), [4.into()]);
+        simulator.clock();
+        simulator.clock();
+        assert!(fifo_contents(&simulator).is_empty());
+    }
+
+    #[test]
+    fn test_fifo_reset() {
+        let cs = ComponentStore {
+            store: vec![
+                ProbeStim::rc_new("data", (0.0, 0.0), vec![1, 2, 0, 0]),
+                // any nonzero value pushes
+                ProbeStim::rc_new("push", (0.0, 0.0), vec![1, 2, 0, 0]),
+                ProbeStim::rc_new("pop", (0.0, 0.0), vec![0, 0, 0, 0]),
+                Fifo::rc_new(
+                    "fifo",
+                    (0.0, 0.0),
+                    Input::new("data", "out"),
+                    Input::new("push", "out"),
+                    Input::new("pop", "out"),
+                    3,
+                    8,
+                ),
+            ],
+        };
+        let mut simulator = Simulator::new(cs).unwrap();
+        simulator.clock();
+        simulator.clock();
+        assert_eq!(fifo_contents(&simulator), [1.into(), 2.into()]);
+
+        // a reset empties the fifo, also on the outputs
+        simulator.reset();
+        let (f, t) = (false.into(), true.into());
+        assert_eq!(
+            fifo_outputs(&simulator),
+            [SignalValue::Unknown, f, t, 0.into()]
+        );
+        assert!(fifo_contents(&simulator).is_empty());
+    }
+
+    #[test]
+    fn test_fifo_overflow() {
+        let cs = ComponentStore {
+            store: vec![
+                ProbeStim::rc_new("data", (0.0, 0.0), vec![1, 2, 3, 0, 0]),
+                ProbeStim::rc_new("push", (0.0, 0.0), vec![1, 1, 1, 0, 0]),
+                ProbeStim::rc_new("pop", (0.0, 0.0), vec![0, 0, 0, 0, 0]),
+                Fifo::rc_new(
+                    "fifo",
+                    (0.0, 0.0),
+                    Input::new("data", "out"),
+                    Input::new("push", "out"),
+                    Input::new("pop", "out"),
+                    2,
+                    32,
+                ),
+            ],
+        };
+        let mut simulator = Simulator::new(cs).unwrap();
+        simulator.clock();
+        simulator.clock();
+        assert!(simulator.component_condition.is_empty());
+        simulator.clock();
+        assert_eq!(
+            simulator.component_condition,
+            vec![(
+                "fifo".to_string(),
+                Condition::Warning("fifo fifo: overflow".to_string())
+            )]
+        );
+        // the pushed data is dropped
+        assert_eq!(fifo_contents(&simulator), [1.into(), 2.into()]);
+    }
+}
@@ -0,0 +1,372 @@
+#[cfg(feature = "gui-egui")]
+use crate::common::EguiComponent;
+use crate::common::{
+    Component, Condition, Id, Input, InputPort, OutputType, Ports, SignalValue, Simulator,
+};
+use crate::components::{ResetInput, ResetKind};
+use log::*;
+use serde::{Deserialize, Serialize};
+use std::any::Any;
+use std::rc::Rc;
+
+pub const FLIP_FLOP_D_ID: &str = "d";
+pub const FLIP_FLOP_T_ID: &str = "t";
+pub const FLIP_FLOP_J_ID: &str = "j";
+pub const FLIP_FLOP_K_ID: &str = "k";
+pub const FLIP_FLOP_ENABLE_ID: &str = "enable";
+pub const FLIP_FLOP_SET_ID: &str = "set";
+pub const FLIP_FLOP_RESET_ID: &str = "reset";
+
+pub const FLIP_FLOP_Q_ID: &str = "q";
+pub const FLIP_FLOP_Q_N_ID: &str = "q_n";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlipFlopKind {
+    D,
+    T,
+    JK,
+}
+
+/// Single-bit flip-flop
+///
+/// `d_in` is the D, T or J input depending on `kind`, `k_in` the K input of
+/// a JK flip-flop. While the optional `enable` is 0 the state is kept. The
+/// optional `set` and `reset` inputs force the state to their value (1 and
+/// 0 respectively), reset taking precedence. Only bit 0 of the inputs is
+/// used, an undefined input makes the state unknown.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct FlipFlop {
+    pub(crate) id: Id,
+    pub(crate) pos: (f32, f32),
+    pub(crate) kind: FlipFlopKind,
+    pub(crate) d_in: Input,
+    #[serde(default)]
+    pub(crate) k_in: Option<Input>,
+    #[serde(default)]
+    pub(crate) enable: Option<Input>,
+    #[serde(default)]
+    pub(crate) set: Option<ResetInput>,
+    #[serde(default)]
+    pub(crate) reset: Option<ResetInput>,
+}
+
+#[typetag::serde]
+impl Component for FlipFlop {
+    fn to_(&self) {
+        trace!("flip-flop {:?}", self.kind);
+    }
+    #[cfg(feature = "gui-egui")]
+    fn dummy(&self, id: &str, pos: (f32, f32)) -> Box<Rc<dyn EguiComponent>> {
+        let dummy_input = Input::new("dummy", "out");
+        Box::new(Rc::new(FlipFlop {
+            id: id.to_string(),
+            pos: (pos.0, pos.1),
+            kind: self.kind,
+            d_in: dummy_input.clone(),
+            k_in: self.k_in.as_ref().map(|_| dummy_input.clone()),
+            enable: None,
+            set: None,
+            reset: None,
+        }))
+    }
+    fn get_id_ports(&self) -> (Id, Ports) {
+        let mut inputs = vec![InputPort {
+            port_id: self.d_id().to_string(),
+            input: self.d_in.clone(),
+        }];
+        for (port_id, input) in [
+            (FLIP_FLOP_K_ID, self.k_in.as_ref()),
+            (FLIP_FLOP_ENABLE_ID, self.enable.as_ref()),
+            (FLIP_FLOP_SET_ID, self.set.as_ref().map(|s| &s.input)),
+            (FLIP_FLOP_RESET_ID, self.reset.as_ref().map(|r| &r.input)),
+        ] {
+            if let Some(input) = input {
+                inputs.push(InputPort {
+                    port_id: port_id.to_string(),
+                    input: input.clone(),
+                });
+            }
+        }
+        (
+            self.id.clone(),
+            Ports::new(
+                inputs.iter().collect(),
+                OutputType::Sequential,
+                vec![FLIP_FLOP_Q_ID, FLIP_FLOP_Q_N_ID],
+            ),
+        )
+    }
+
+    fn clock(&self, simulator: &mut Simulator) -> Result<(), Condition> {
+        let bit = |input: &Input| match simulator.get_input_value(input) {
+            SignalValue::Data(v) => Some(v & 1 != 0),
+            _ => None,
+        };
+        // the state before the clock edge
+        let q = bit(&Input::new(&self.id, FLIP_FLOP_Q_ID));
+        let next = if self
+            .reset
+            .as_ref()
+            .is_some_and(|r| r.is_asserted(simulator))
+        {
+            Some(false)
+        } else if self.set.as_ref().is_some_and(|s| s.is_asserted(simulator)) {
+            Some(true)
+        } else {
+            match self.enable.as_ref().map(bit) {
+                Some(Some(false)) => q,
+                Some(None) => None,
+                _ => match self.kind {
+                    FlipFlopKind::D => bit(&self.d_in),
+                    FlipFlopKind::T => bit(&self.d_in).and_then(|t| Some(q? ^ t)),
+                    FlipFlopKind::JK => {
+                        let k = self.k_in.as_ref().and_then(bit);
+                        match (bit(&self.d_in), k) {
+                            (Some(false), Some(false)) => q,
+                            (Some(false), Some(true)) => Some(false),
+                            (Some(true), Some(false)) => Some(true),
+                            (Some(true), Some(true)) => q.map(|q| !q),
+                            _ => None,
+                        }
+                    }
+                },
+            }
+        };
+        self.set_state(simulator, next);
+        trace!("eval: flip-flop id {} {:?}", self.id, next);
+        Ok(())
+    }
+
+    // force the state while an asynchronous set or reset is asserted
+    fn async_reset(&self, simulator: &mut Simulator) {
+        for (input, state) in [(&self.set, true), (&self.reset, false)] {
+            if let Some(input) = input {
+                if input.kind == ResetKind::Async && input.is_asserted(simulator) {
+                    trace!("async set/reset: flip-flop id {}", self.id);
+                    self.set_state(simulator, Some(state));
+                }
+            }
+        }
+    }
+
+    fn async_reset_inputs(&self) -> Vec<Input> {
+        [&self.set, &self.reset]
+            .into_iter()
+            .flatten()
+            .filter(|input| input.kind == ResetKind::Async)
+            .map(|input| input.input.clone())
+            .collect()
+    }
+
+    fn set_id_port(&mut self, target_port_id: Id, new_input: Input) {
+        match target_port_id.as_str() {
+            FLIP_FLOP_D_ID | FLIP_FLOP_T_ID | FLIP_FLOP_J_ID => self.d_in = new_input,
+            FLIP_FLOP_K_ID => self.k_in = Some(new_input),
+            FLIP_FLOP_ENABLE_ID => self.enable = Some(new_input),
+            FLIP_FLOP_SET_ID => {
+                if let Some(set) = &mut self.set {
+                    set.input = new_input;
+                }
+            }
+            FLIP_FLOP_RESET_ID => {
+                if let Some(reset) = &mut self.reset {
+                    reset.input = new_input;
+                }
+            }
+            _ => (),
+        }
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl FlipFlop {
+    pub fn new(id: &str, pos: (f32, f32), kind: FlipFlopKind, d_in: Input) -> Self {
+        FlipFlop {
+            id: id.to_string(),
+            pos,
+            kind,
+            d_in,
+            k_in: None,
+            enable: None,
+            set: None,
+            reset: None,
+        }
+    }
+
+    pub fn rc_new(id: &str, pos: (f32, f32), kind: FlipFlopKind, d_in: Input) -> Rc<Self> {
+        Rc::new(FlipFlop::new(id, pos, kind, d_in))
+    }
+
+    pub fn rc_new_jk(id: &str, pos: (f32, f32), j_in: Input, k_in: Input) -> Rc<Self> {
+        Rc::new(FlipFlop {
+            k_in: Some(k_in),
+            ..FlipFlop::new(id, pos, FlipFlopKind::JK, j_in)
+        })
+    }
+
+    /// flip-flop with enable, set and reset inputs, the set and reset values are ignored
+    #[allow(clippy::too_many_arguments)]
+    pub fn rc_new_with_control(
+        id: &str,
+        pos: (f32, f32),
+        kind: FlipFlopKind,
+        d_in: Input,
+        k_in: Option<Input>,
+        enable: Option<Input>,
+        set: Option<ResetInput>,
+        reset: Option<ResetInput>,
+    ) -> Rc<Self> {
+        Rc::new(FlipFlop {
+            id: id.to_string(),
+            pos,
+            kind,
+            d_in,
+            k_in,
+            enable,
+            set,
+            reset,
+        })
+    }
+
+    /// port id of the data input
+    pub fn d_id(&self) -> &'static str {
+        match self.kind {
+            FlipFlopKind::D => FLIP_FLOP_D_ID,
+            FlipFlopKind::T => FLIP_FLOP_T_ID,
+            FlipFlopKind::JK => FLIP_FLOP_J_ID,
+        }
+    }
+
+    fn set_state(&self, simulator: &mut Simulator, state: Option<bool>) {
+        let (q, q_n) = match state {
+            Some(q) => ((q as u32).into(), (!q as u32).into()),
+            None => (SignalValue::Unknown, SignalValue::Unknown),
+        };
+        simulator.set_out_value(&self.id, FLIP_FLOP_Q_ID, q);
+        simulator.set_out_value(&self.id, FLIP_FLOP_Q_N_ID, q_n);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::common::ComponentStore;
+    use crate::components::ProbeStim;
+
+    fn q(simulator: &Simulator, id: &str) -> SignalValue {
+        simulator.get_input_value(&Input::new(id, FLIP_FLOP_Q_ID))
+    }
+
+    #[test]
+    fn test_flip_flops() {
+        let cs = ComponentStore {
+            store: vec![
+                ProbeStim::rc_new("a", (0.0, 0.0), vec![1, 0, 1, 1, 0, 1]),
+                ProbeStim::rc_new("b", (0.0, 0.0), vec![0, 0, 0, 1, 1, 1]),
+                FlipFlop::rc_new("d", (0.0, 0.0), FlipFlopKind::D, Input::new("a", "out")),
+                FlipFlop::rc_new("t", (0.0, 0.0), FlipFlopKind::T, Input::new("a", "out")),
+                FlipFlop::rc_new_jk(
+                    "jk",
+                    (0.0, 0.0),
+                    Input::new("a", "out"),
+                    Input::new("b", "out"),
+                ),
+            ],
+        };
+        let mut simulator = Simulator::new(cs).unwrap();
+        let mut states = vec![];
+        for _ in 0..6 {
+            simulator.clock();
+            states.push(["d", "t", "jk"].map(|id| q(&simulator, id)));
+        }
+        let d = |v: u32| SignalValue::Data(v);
+        assert_eq!(
+            states,
+            vec![
+                [d(1), d(1), d(1)],
+                [d(0), d(1), d(1)],
+                [d(1), d(0), d(1)],
+                [d(1), d(1), d(0)],
+                [d(0), d(1), d(0)],
+                [d(1), d(0), d(1)],
+            ]
+        );
+        assert_eq!(
+            simulator.get_input_value(&Input::new("t", FLIP_FLOP_Q_N_ID)),
+            d(1)
+        );
+    }
+
+    #[test]
+    fn test_flip_flop_control() {
+        let cs = ComponentStore {
+            store: vec![
+                ProbeStim::rc_new("d_in", (0.0, 0.0), vec![1, 0, 0, 0, 0, 0, 0]),
+                ProbeStim::rc_new("en", (0.0, 0.0), vec![1, 0, 0, 1, 0, 0, 0]),
+                ProbeStim::rc_new("set", (0.0, 0.0), vec![0, 0, 0, 0, 1, 0, 0]),
+                ProbeStim::rc_new("rst", (0.0, 0.0), vec![0, 0, 0, 1, 0, 0, 0]),
+                FlipFlop::rc_new_with_control(
+                    "ff",
+                    (0.0, 0.0),
+                    FlipFlopKind::D,
+                    Input::new("d_in", "out"),
+                    None,
+                    Some(Input::new("en", "out")),
+                    Some(ResetInput::new(
+                        Input::new("set", "out"),
+                        1,
+                        ResetKind::Sync,
+                    )),
+                    Some(ResetInput::new(
+                        Input::new("rst", "out"),
+                        0,
+                        ResetKind::Async,
+                    )),
+                ),
+            ],
+        };
+        let mut simulator = Simulator::new(cs).unwrap();
+        let mut states = vec![q(&simulator, "ff")];
+        for _ in 0..6 {
+            simulator.clock();
+            states.push(q(&simulator, "ff"));
+        }
+        // loaded, kept while disabled, reset asynchronously (and in the
+        // following cycle synchronously), set and kept
+        assert_eq!(
+            states,
+            [0, 1, 1, 0, 0, 1, 1].map(SignalValue::Data).to_vec(),
+        );
+    }
+
+    #[test]
+    fn test_flip_flop_chain() {
+        // the clocking order of the flip-flops varies with the hashing of ids
+        for _ in 0..8 {
+            let cs = ComponentStore {
+                store: vec![
+                    ProbeStim::rc_new("in", (0.0, 0.0), vec![1, 0, 0, 0, 0]),
+                    FlipFlop::rc_new("ff0", (0.0, 0.0), FlipFlopKind::D, Input::new("in", "out")),
+                    FlipFlop::rc_new("ff1", (0.0, 0.0), FlipFlopKind::D, Input::new("ff0", "q")),
+                    FlipFlop::rc_new("ff2", (0.0, 0.0), FlipFlopKind::D, Input::new("ff1", "q")),
+                ],
+            };
+            let mut simulator = Simulator::new(cs).unwrap();
+            let mut states = vec![];
+            for _ in 0..4 {
+                simulator.clock();
+                states.push(["ff0", "ff1", "ff2"].map(|id| q(&simulator, id)));
+            }
+            // each flip-flop delays by one cycle
+            assert_eq!(
+                states,
+                [[1, 0, 0], [0, 1, 0], [0, 0, 1], [0, 0, 0]]
+                    .map(|s| s.map(SignalValue::Data))
+                    .to_vec()
+            );
+        }
+    }
+}
@@ -0,0 +1,558 @@
+#[cfg(feature = "gui-egui")]
+use crate::common::EguiComponent;
+use crate::common::{
+    Component, Components, Condition, Id, Input, InputPort, OutputType, Ports, SignalUnsigned,
+    SignalValue, Simulator,
+};
+use crate::components::SUBCIRCUIT_SEPARATOR;
+use crate::coverage::{CoverBin, CoverPoint};
+use log::*;
+use serde::{Deserialize, Serialize};
+use std::any::Any;
+use std::rc::Rc;
+
+pub const FSM_STATE_OUT_ID: &str = "state";
+pub const FSM_STATE_IN_ID: &str = "state_in";
+
+/// id of the Mealy outputs within the `Fsm`, e.g., `fsm/mealy`
+pub const FSM_MEALY_ID: &str = "mealy";
+
+/// Named input of an `Fsm`, the name is used in the transition conditions
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FsmInput {
+    pub name: Id,
+    pub input: Input,
+}
+
+impl FsmInput {
+    pub fn new(name: &str, input: Input) -> Self {
+        FsmInput {
+            name: name.to_string(),
+            input,
+        }
+    }
+}
+
+/// Assignment of `value` to the output `output`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct FsmAssign {
+    pub output: Id,
+    pub value: SignalUnsigned,
+}
+
+impl FsmAssign {
+    pub fn new(output: &str, value: SignalUnsigned) -> Self {
+        FsmAssign {
+            output: output.to_string(),
+            value,
+        }
+    }
+}
+
+/// Condition that the input named `input` has the value `value`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct FsmCondition {
+    pub input: Id,
+    pub value: SignalUnsigned,
+}
+
+impl FsmCondition {
+    pub fn new(input: &str, value: SignalUnsigned) -> Self {
+        FsmCondition {
+            input: input.to_string(),
+            value,
+        }
+    }
+}
+
+/// Transition to the state `to` when all conditions in `when` hold
+///
+/// The `outputs` (Mealy) are assigned while the transition is enabled, see
+/// `FsmMealy`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct FsmTransition {
+    pub to: Id,
+    pub when: Vec<FsmCondition>,
+    #[serde(default)]
+    pub outputs: Vec<FsmAssign>,
+}
+
+impl FsmTransition {
+    pub fn new(to: &str, when: Vec<FsmCondition>) -> Self {
+        FsmTransition {
+            to: to.to_string(),
+            when,
+            outputs: vec![],
+        }
+    }
+
+    pub fn new_mealy(to: &str, when: Vec<FsmCondition>, outputs: Vec<FsmAssign>) -> Self {
+        FsmTransition {
+            to: to.to_string(),
+            when,
+            outputs,
+        }
+    }
+}
+
+/// State with its (Moore) outputs and its transitions, tried in order
+///
+/// `pos` is the position in the state diagram, relative to the `Fsm`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct FsmState {
+    pub name: Id,
+    pub pos: (f32, f32),
+    #[serde(default)]
+    pub outputs: Vec<FsmAssign>,
+    pub transitions: Vec<FsmTransition>,
+}
+
+impl FsmState {
+    pub fn new(
+        name: &str,
+        pos: (f32, f32),
+        outputs: Vec<FsmAssign>,
+        transitions: Vec<FsmTransition>,
+    ) -> Self {
+        FsmState {
+            name: name.to_string(),
+            pos,
+            outputs,
+            transitions,
+        }
+    }
+}
+
+/// Table-driven finite state machine
+///
+/// The first state is the initial state. On each clock the first
+/// transition of the current state whose condition holds is taken, or the
+/// state is kept. The outputs are the (Moore) outputs of the state, outputs
+/// not assigned are 0.
+///
+/// The `state` output holds the index of the current state, see `state_name`.
+///
+/// If a transition assigns outputs, an `FsmMealy` with the id
+/// `<id>/mealy` is instantiated, giving the outputs of the state overridden
+/// by those of the transition enabled by the current inputs.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Fsm {
+    pub(crate) id: Id,
+    pub(crate) pos: (f32, f32),
+    pub(crate) inputs: Vec<FsmInput>,
+    pub(crate) outputs: Vec<Id>,
+    pub(crate) states: Vec<FsmState>,
+}
+
+#[typetag::serde]
+impl Component for Fsm {
+    fn to_(&self) {
+        trace!("fsm {} states", self.states.len());
+    }
+    #[cfg(feature = "gui-egui")]
+    fn dummy(&self, id: &str, pos: (f32, f32)) -> Box<Rc<dyn EguiComponent>> {
+        let dummy_input = Input::new("dummy", "out");
+        Box::new(Rc::new(Fsm {
+            id: id.to_string(),
+            pos: (pos.0, pos.1),
+            inputs: self
+                .inputs
+                .iter()
+                .map(|i| FsmInput::new(&i.name, dummy_input.clone()))
+                .collect(),
+            outputs: self.outputs.clone(),
+            states: self.states.clone(),
+        }))
+    }
+    fn get_id_ports(&self) -> (Id, Ports) {
+        let inputs: Vec<InputPort> = self
+            .inputs
+            .iter()
+            .map(|i| InputPort {
+                port_id: i.name.clone(),
+                input: i.input.clone(),
+            })
+            .collect();
+        let mut outputs = vec![FSM_STATE_OUT_ID];
+        outputs.extend(self.outputs.iter().map(|o| o.as_str()));
+        (
+            self.id.clone(),
+            Ports::new(inputs.iter().collect(), OutputType::Sequential, outputs),
+        )
+    }
+
+    fn set_id_port(&mut self, target_port_id: Id, new_input: Input) {
+        if let Some(i) = self.inputs.iter_mut().find(|i| i.name == target_port_id) {
+            i.input = new_input;
+        }
+    }
+
+    fn clock(&self, simulator: &mut Simulator) -> Result<(), Condition> {
+        // the state is kept in the state output
+        let current = simulator.get_input_value(&Input::new(&self.id, FSM_STATE_OUT_ID));
+        let next = match current {
+            SignalValue::Data(current) => self.step(current as usize, |name| {
+                self.inputs
+                    .iter()
+                    .find(|i| i.name == name)
+                    .map_or(SignalValue::Unknown, |i| {
+                        simulator.get_input_value(&i.input)
+                    })
+            }),
+            _ => Err(format!("fsm {}: state {:?}", self.id, current)),
+        };
+        match next {
+            Ok((state, outputs)) => {
+                simulator.set_out_value(&self.id, FSM_STATE_OUT_ID, state as SignalUnsigned);
+                for (output, value) in self.outputs.iter().zip(outputs) {
+                    simulator.set_out_value(&self.id, output, value);
+                }
+                Ok(())
+            }
+            Err(e) => {
+                simulator.set_out_value(&self.id, FSM_STATE_OUT_ID, SignalValue::Unknown);
+                for output in &self.outputs {
+                    simulator.set_out_value(&self.id, output, SignalValue::Unknown);
+                }
+                Err(Condition::Error(e))
+            }
+        }
+    }
+
+    fn sub_components(&self) -> Result<Components, String> {
+        let mealy = self
+            .states
+            .iter()
+            .flat_map(|s| &s.transitions)
+            .any(|t| !t.outputs.is_empty());
+        Ok(if mealy {
+            vec![Rc::new(FsmMealy {
+                id: format!("{}{}{}", self.id, SUBCIRCUIT_SEPARATOR, FSM_MEALY_ID),
+                fsm: self.clone(),
+            })]
+        } else {
+            vec![]
+        })
+    }
+
+    fn cover_points(&self) -> Vec<CoverPoint> {
+        let bins = self
+            .states
+            .iter()
+            .enumerate()
+            .map(|(i, s)| CoverBin::new(&s.name, i as SignalUnsigned))
+            .collect();
+        vec![CoverPoint::new(
+            FSM_STATE_OUT_ID,
+            Input::new(&self.id, FSM_STATE_OUT_ID),
+            bins,
+        )]
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl Fsm {
+    pub fn new(
+        id: &str,
+        pos: (f32, f32),
+        inputs: Vec<FsmInput>,
+        outputs: Vec<&str>,
+        states: Vec<FsmState>,
+    ) -> Self {
+        Fsm {
+            id: id.to_string(),
+            pos,
+            inputs,
+            outputs: outputs.into_iter().map(|o| o.to_string()).collect(),
+            states,
+        }
+    }
+
+    pub fn rc_new(
+        id: &str,
+        pos: (f32, f32),
+        inputs: Vec<FsmInput>,
+        outputs: Vec<&str>,
+        states: Vec<FsmState>,
+    ) -> Rc<Self> {
+        Rc::new(Fsm::new(id, pos, inputs, outputs, states))
+    }
+
+    /// Format a value of the state output as the name of the state
+    pub fn state_name(&self, value: SignalValue) -> String {
+        match value {
+            SignalValue::Data(i) => self
+                .states
+                .get(i as usize)
+                .map_or(format!("UNDEF({})", i), |s| s.name.clone()),
+            _ => format!("{:?}", value),
+        }
+    }
+
+    /// Index of the state named `name`
+    pub fn state_index(&self, name: &str) -> Option<usize> {
+        self.states.iter().position(|s| s.name == name)
+    }
+
+    /// Next state and output values from state `current`, given the input values
+    pub fn step(
+        &self,
+        current: usize,
+        input: impl Fn(&str) -> SignalValue,
+    ) -> Result<(usize, Vec<SignalUnsigned>), String> {
+        let error = |e: String| format!("fsm {}: {}", self.id, e);
+        let state = self
+            .states
+            .get(current)
+            .ok_or_else(|| error(format!("no state {}", current)))?;
+        let next = match self.transition(state, input) {
+            Some(t) => self
+                .state_index(&t.to)
+                .ok_or_else(|| error(format!("no state {:?}", t.to)))?,
+            None => current,
+        };
+        let outputs = self.assign(&self.states[next].outputs)?;
+        Ok((next, outputs))
+    }
+
+    /// Mealy output values in state `current`, given the input values
+    pub fn mealy(
+        &self,
+        current: usize,
+        input: impl Fn(&str) -> SignalValue,
+    ) -> Result<Vec<SignalUnsigned>, String> {
+        let state = self
+            .states
+            .get(current)
+            .ok_or_else(|| format!("fsm {}: no state {}", self.id, current))?;
+        let transition = self.transition(state, input);
+        self.assign(
+            state
+                .outputs
+                .iter()
+                .chain(transition.iter().flat_map(|t| &t.outputs)),
+        )
+    }
+
+    // the first transition of `state` whose conditions hold
+    fn transition<'a>(
+        &self,
+        state: &'a FsmState,
+        input: impl Fn(&str) -> SignalValue,
+    ) -> Option<&'a FsmTransition> {
+        state.transitions.iter().find(|t| {
+            t.when
+                .iter()
+                .all(|c| input(&c.input) == SignalValue::Data(c.value))
+        })
+    }
+
+    // output values of `assigns`, later assignments win
+    fn assign<'a>(
+        &self,
+        assigns: impl IntoIterator<Item = &'a FsmAssign>,
+    ) -> Result<Vec<SignalUnsigned>, String> {
+        let error = |e: String| format!("fsm {}: {}", self.id, e);
+        let mut outputs = vec![0; self.outputs.len()];
+        for assign in assigns {
+            let i = self
+                .outputs
+                .iter()
+                .position(|o| *o == assign.output)
+                .ok_or_else(|| error(format!("no output {:?}", assign.output)))?;
+            outputs[i] = assign.value;
+        }
+        Ok(outputs)
+    }
+}
+
+/// Combinational Mealy outputs of an `Fsm`, instantiated by the `Fsm`
+///
+/// Reads the state of the `Fsm` and its inputs, the outputs are named as
+/// those of the `Fsm`.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct FsmMealy {
+    pub(crate) id: Id,
+    pub(crate) fsm: Fsm,
+}
+
+#[typetag::serde]
+impl Component for FsmMealy {
+    fn to_(&self) {
+        trace!("fsm mealy {}", self.id);
+    }
+
+    fn get_id_ports(&self) -> (Id, Ports) {
+        let mut inputs = vec![InputPort {
+            port_id: FSM_STATE_IN_ID.to_string(),
+            input: Input::new(&self.fsm.id, FSM_STATE_OUT_ID),
+        }];
+        inputs.extend(self.fsm.inputs.iter().map(|i| InputPort {
+            port_id: i.name.clone(),
+            input: i.input.clone(),
+        }));
+        (
+            self.id.clone(),
+            Ports::new(
+                inputs.iter().collect(),
+                OutputType::Combinatorial,
+                self.fsm.outputs.iter().map(|o| o.as_str()).collect(),
+            ),
+        )
+    }
+
+    fn clock(&self, simulator: &mut Simulator) -> Result<(), Condition> {
+        let current = simulator.get_input_value(&Input::new(&self.fsm.id, FSM_STATE_OUT_ID));
+        let outputs = match current {
+            SignalValue::Data(current) => self
+                .fsm
+                .mealy(current as usize, |name| {
+                    self.fsm
+                        .inputs
+                        .iter()
+                        .find(|i| i.name == name)
+                        .map_or(SignalValue::Unknown, |i| {
+                            simulator.get_input_value(&i.input)
+                        })
+                })
+                .map(Some),
+            // the state is unknown after an error in the fsm
+            _ => Ok(None),
+        };
+        let values: Vec<SignalValue> = match &outputs {
+            Ok(Some(outputs)) => outputs.iter().map(|v| (*v).into()).collect(),
+            _ => vec![SignalValue::Unknown; self.fsm.outputs.len()],
+        };
+        for (output, value) in self.fsm.outputs.iter().zip(values) {
+            simulator.set_out_value(&self.id, output, value);
+        }
+        outputs.map(|_| ()).map_err(Condition::Error)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::common::ComponentStore;
+    use crate::components::ProbeStim;
+
+    // detects the sequence 1 0 1, overlapping
+    fn detector() -> Rc<Fsm> {
+        let x = |v| vec![FsmCondition::new("x", v)];
+        Fsm::rc_new(
+            "fsm",
+            (0.0, 0.0),
+            vec![FsmInput::new("x", Input::new("x", "out"))],
+            vec!["found", "edge"],
+            vec![
+                FsmState::new(
+                    "idle",
+                    (0.0, 0.0),
+                    vec![],
+                    vec![FsmTransition::new("one", x(1))],
+                ),
+                FsmState::new(
+                    "one",
+                    (60.0, 0.0),
+                    vec![],
+                    vec![FsmTransition::new("one_zero", x(0))],
+                ),
+                FsmState::new(
+                    "one_zero",
+                    (60.0, 60.0),
+                    vec![],
+                    vec![
+                        // Mealy output on the transition
+                        FsmTransition::new_mealy("found", x(1), vec![FsmAssign::new("edge", 1)]),
+                        FsmTransition::new("idle", x(0)),
+                    ],
+                ),
+                FsmState::new(
+                    "found",
+                    (0.0, 60.0),
+                    // Moore output
+                    vec![FsmAssign::new("found", 1)],
+                    vec![
+                        FsmTransition::new("one_zero", x(0)),
+                        FsmTransition::new("one", x(1)),
+                    ],
+                ),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_fsm() {
+        let cs = ComponentStore {
+            store: vec![
+                ProbeStim::rc_new("x", (0.0, 0.0), vec![1, 0, 1, 0, 1, 1, 0, 0]),
+                detector(),
+            ],
+        };
+        let mut simulator = Simulator::new(cs).unwrap();
+        let fsm = detector();
+        let mut states = vec![];
+        let mut found = vec![];
+        let mut edge = vec![];
+        let mut mealy_found = vec![];
+        for _ in 0..8 {
+            simulator.clock();
+            states.push(
+                fsm.state_name(simulator.get_input_value(&Input::new("fsm", FSM_STATE_OUT_ID))),
+            );
+            found.push(simulator.get_input_value(&Input::new("fsm", "found")));
+            edge.push(simulator.get_input_value(&Input::new("fsm/mealy", "edge")));
+            mealy_found.push(simulator.get_input_value(&Input::new("fsm/mealy", "found")));
+        }
+        assert_eq!(
+            states,
+            vec!["one", "one_zero", "found", "one_zero", "found", "one", "one_zero", "idle"]
+        );
+        let values = |v: [u32; 8]| v.map(SignalValue::from).to_vec();
+        assert_eq!(found, values([0, 0, 1, 0, 1, 0, 0, 0]));
+        // the Mealy output is set in the cycle the transition is enabled
+        assert_eq!(edge, values([0, 1, 0, 1, 0, 0, 0, 0]));
+        assert_eq!(mealy_found, found);
+        assert_eq!(
+            simulator.get_input_value(&Input::new("fsm", "edge")),
+            0.into()
+        );
+
+        // stepping back restores the state
+        simulator.un_clock();
+        assert_eq!(
+            fsm.state_name(simulator.get_input_value(&Input::new("fsm", FSM_STATE_OUT_ID))),
+            "one_zero"
+        );
+    }
+
+    #[test]
+    fn test_fsm_errors() {
+        let mut fsm = Fsm::new(
+            "fsm",
+            (0.0, 0.0),
+            vec![],
+            vec![],
+            vec![FsmState::new(
+                "s",
+                (0.0, 0.0),
+                vec![],
+                vec![FsmTransition::new("missing", vec![])],
+            )],
+        );
+        assert!(fsm.step(0, |_| SignalValue::Unknown).is_err());
+        assert!(fsm.step(1, |_| SignalValue::Unknown).is_err());
+        fsm.states[0].transitions.clear();
+        fsm.states[0].outputs.push(FsmAssign::new("missing", 1));
+        assert!(fsm.step(0, |_| SignalValue::Unknown).is_err());
+        assert!(fsm.mealy(0, |_| SignalValue::Unknown).is_err());
+        assert!(fsm.mealy(1, |_| SignalValue::Unknown).is_err());
+    }
+}
@@ -5,6 +5,7 @@ use crate::common::{
     Component, Condition, Id, Input, InputPort, OutputType, Ports, SignalSigned, SignalUnsigned,
     SignalValue, Simulator,
 };
+use crate::coverage::{CoverBin, CoverPoint};
 use log::*;
 use serde::{Deserialize, Serialize};
 use std::any::Any;
@@ -30,6 +31,24 @@ pub mod alu_op {
     pub const SRL: u32 = 11;
     pub const SRA: u32 = 12;
     pub const LUI: u32 = 13;
+
+    /// all operations by name
+    pub const ALL: [(&str, u32); 14] = [
+        ("ADD", ADD),
+        ("ADDU", ADDU),
+        ("SUB", SUB),
+        ("SUBU", SUBU),
+        ("AND", AND),
+        ("OR", OR),
+        ("XOR", XOR),
+        ("NOR", NOR),
+        ("SLT", SLT),
+        ("SLTU", SLTU),
+        ("SLL", SLL),
+        ("SRL", SRL),
+        ("SRA", SRA),
+        ("LUI", LUI),
+    ];
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -151,6 +170,14 @@ impl Component for FullAdd {
         Ok(())
     }
 
+    fn cover_points(&self) -> Vec<CoverPoint> {
+        let bins = alu_op::ALL
+            .iter()
+            .map(|(name, op)| CoverBin::new(name, *op))
+            .collect();
+        vec![CoverPoint::new(FULL_ADD_OP_IN_ID, self.op_in.clone(), bins)]
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -177,7 +204,7 @@ mod test {
 
     use crate::{
         common::{ComponentStore, Input, SignalUnsigned, Simulator},
-        components::ProbeOut,
+        components::{ProbeOut, ProbeStim},
     };
     use std::rc::Rc;
 
@@ -210,7 +237,7 @@ mod test {
         println!("<setup for clock 2>");
         simulator.set_out_value("a", "out", 42);
         simulator.set_out_value("b", "out", 1337);
-        simulator.set_out_value("op", "out", OPADD);
+        simulator.set_out_value("op", "out", alu_op::ADD);
         println!("sim_state {:?}", simulator.sim_state);
         println!("<clock>");
         simulator.clock();
@@ -225,7 +252,7 @@ mod test {
         println!("<setup for clock 3>");
         simulator.set_out_value("a", "out", (-100i32 as u32));
         simulator.set_out_value("b", "out", 1337);
-        simulator.set_out_value("op", "out", OPADD);
+        simulator.set_out_value("op", "out", alu_op::ADD);
         println!("sim_state {:?}", simulator.sim_state);
         println!("<clock>");
         simulator.clock();
@@ -239,7 +266,7 @@ mod test {
         println!("<setup for clock 4>");
         simulator.set_out_value("a", "out", (-100i32 as u32));
         simulator.set_out_value("b", "out", 1337);
-        simulator.set_out_value("op", "out", OPSUB);
+        simulator.set_out_value("op", "out", alu_op::SUB);
         println!("sim_state {:?}", simulator.sim_state);
         println!("<clock>");
         simulator.clock();
@@ -253,7 +280,7 @@ mod test {
         println!("<setup for clock 5>");
         simulator.set_out_value("a", "out", (-100i32 as u32));
         simulator.set_out_value("b", "out", 1337);
-        simulator.set_out_value("op", "out", OPSLT);
+        simulator.set_out_value("op", "out", alu_op::SLT);
         println!("sim_state {:?}", simulator.sim_state);
         println!("<clock>");
         simulator.clock();
@@ -267,7 +294,7 @@ mod test {
         println!("<setup for clock 5>");
         simulator.set_out_value("a", "out", (-100i32 as u32));
         simulator.set_out_value("b", "out", 1337);
-        simulator.set_out_value("op", "out", OPSLTU);
+        simulator.set_out_value("op", "out", alu_op::SLTU);
         println!("sim_state {:?}", simulator.sim_state);
         println!("<clock>");
         simulator.clock();
@@ -278,4 +305,38 @@ mod test {
             "testing SLT"
         );
     }
+
+    #[test]
+    fn test_op_coverage() {
+        let cs = ComponentStore {
+            store: vec![
+                ProbeStim::rc_new(
+                    "op",
+                    (0.0, 0.0),
+                    vec![alu_op::ADD, alu_op::SUB, alu_op::LUI, alu_op::SUB],
+                ),
+                Rc::new(ProbeOut::new("a")),
+                Rc::new(ProbeOut::new("b")),
+                FullAdd::rc_new(
+                    "ALU",
+                    (0.0, 0.0),
+                    Input::new("a", "out"),
+                    Input::new("b", "out"),
+                    Input::new("op", "out"),
+                ),
+            ],
+        };
+        let mut simulator = Simulator::new(cs).unwrap();
+        simulator.step(3);
+
+        let coverage = simulator.coverage();
+        assert_eq!(coverage.points.len(), 1);
+        let point = &coverage.points[0];
+        assert_eq!(point.point.name, FULL_ADD_OP_IN_ID);
+        assert_eq!(point.hits.len(), alu_op::ALL.len());
+        assert_eq!(point.hits[alu_op::ADD as usize], 1);
+        assert_eq!(point.hits[alu_op::SUB as usize], 2);
+        assert_eq!(point.hits[alu_op::LUI as usize], 1);
+        assert_eq!(point.covered(), 3);
+    }
 }
@@ -0,0 +1,244 @@
+#[cfg(feature = "gui-egui")]
+use crate::common::EguiComponent;
+use crate::common::{
+    Component, Condition, Id, Input, InputPort, OutputType, Ports, SignalValue, Simulator,
+};
+use log::*;
+use serde::{Deserialize, Serialize};
+use std::any::Any;
+use std::rc::Rc;
+
+pub const GATE_TEMPLATE_ID: &str = "in";
+pub const GATE_OUT_ID: &str = "out";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GateOp {
+    And,
+    Or,
+    // odd parity for more than two inputs
+    Xor,
+}
+
+/// Single-bit logic gate
+///
+/// Only bit 0 of the inputs is used, the output is 0 or 1. Any input (and
+/// the output) may be inverted, e.g., NAND is an `And` with inverted output,
+/// NOT a single input `And` with inverted output. The output is unknown if
+/// an input is not defined.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Gate {
+    pub(crate) id: Id,
+    pub(crate) pos: (f32, f32),
+    pub(crate) op: GateOp,
+    pub(crate) inputs: Vec<Input>,
+    // inversion bubbles, per input, missing entries are not inverted
+    #[serde(default)]
+    pub(crate) invert_in: Vec<bool>,
+    #[serde(default)]
+    pub(crate) invert_out: bool,
+}
+
+#[typetag::serde]
+impl Component for Gate {
+    fn to_(&self) {
+        trace!("gate {:?}", self.op);
+    }
+    #[cfg(feature = "gui-egui")]
+    fn dummy(&self, id: &str, pos: (f32, f32)) -> Box<Rc<dyn EguiComponent>> {
+        let dummy_input = Input::new("dummy", "out");
+        Box::new(Rc::new(Gate {
+            id: id.to_string(),
+            pos: (pos.0, pos.1),
+            op: self.op,
+            inputs: vec![dummy_input; self.inputs.len()],
+            invert_in: self.invert_in.clone(),
+            invert_out: self.invert_out,
+        }))
+    }
+    fn get_id_ports(&self) -> (Id, Ports) {
+        let inputs: Vec<InputPort> = self
+            .inputs
+            .iter()
+            .enumerate()
+            .map(|(i, input)| InputPort {
+                port_id: format!("{}{}", GATE_TEMPLATE_ID, i),
+                input: input.clone(),
+            })
+            .collect();
+        (
+            self.id.clone(),
+            Ports::new(
+                inputs.iter().collect(),
+                OutputType::Combinatorial,
+                vec![GATE_OUT_ID],
+            ),
+        )
+    }
+
+    fn clock(&self, simulator: &mut Simulator) -> Result<(), Condition> {
+        let mut bits = vec![];
+        for (i, input) in self.inputs.iter().enumerate() {
+            match simulator.get_input_value(input) {
+                SignalValue::Data(v) => bits.push((v & 1 != 0) ^ self.is_inverted(i)),
+                _ => {
+                    simulator.set_out_value(&self.id, GATE_OUT_ID, SignalValue::Unknown);
+                    return Ok(());
+                }
+            }
+        }
+        let value = match self.op {
+            GateOp::And => bits.iter().all(|b| *b),
+            GateOp::Or => bits.iter().any(|b| *b),
+            GateOp::Xor => bits.iter().filter(|b| **b).count() % 2 == 1,
+        } ^ self.invert_out;
+        simulator.set_out_value(&self.id, GATE_OUT_ID, value as u32);
+        Ok(())
+    }
+
+    fn set_id_port(&mut self, target_port_id: Id, new_input: Input) {
+        for (i, input) in self.inputs.iter_mut().enumerate() {
+            if target_port_id == format!("{}{}", GATE_TEMPLATE_ID, i) {
+                *input = new_input;
+                return;
+            }
+        }
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl Gate {
+    pub fn new(id: &str, pos: (f32, f32), op: GateOp, inputs: Vec<Input>) -> Self {
+        Gate {
+            id: id.to_string(),
+            pos,
+            op,
+            inputs,
+            invert_in: vec![],
+            invert_out: false,
+        }
+    }
+
+    pub fn rc_new(id: &str, pos: (f32, f32), op: GateOp, inputs: Vec<Input>) -> Rc<Self> {
+        Rc::new(Gate::new(id, pos, op, inputs))
+    }
+
+    /// gate with inversion bubbles
+    pub fn new_inverted(
+        id: &str,
+        pos: (f32, f32),
+        op: GateOp,
+        inputs: Vec<Input>,
+        invert_in: Vec<bool>,
+        invert_out: bool,
+    ) -> Self {
+        Gate {
+            invert_in,
+            invert_out,
+            ..Gate::new(id, pos, op, inputs)
+        }
+    }
+
+    pub fn rc_new_inverted(
+        id: &str,
+        pos: (f32, f32),
+        op: GateOp,
+        inputs: Vec<Input>,
+        invert_in: Vec<bool>,
+        invert_out: bool,
+    ) -> Rc<Self> {
+        Rc::new(Gate::new_inverted(
+            id, pos, op, inputs, invert_in, invert_out,
+        ))
+    }
+
+    pub fn rc_new_not(id: &str, pos: (f32, f32), input: Input) -> Rc<Self> {
+        Gate::rc_new_inverted(id, pos, GateOp::And, vec![input], vec![], true)
+    }
+
+    pub fn is_inverted(&self, index: usize) -> bool {
+        self.invert_in.get(index).copied().unwrap_or(false)
+    }
+
+    /// conventional name, e.g., NAND or NOT
+    pub fn name(&self) -> &'static str {
+        if self.inputs.len() == 1 && !self.is_inverted(0) && self.op != GateOp::Xor {
+            return if self.invert_out { "NOT" } else { "BUF" };
+        }
+        match (self.op, self.invert_out) {
+            (GateOp::And, false) => "AND",
+            (GateOp::And, true) => "NAND",
+            (GateOp::Or, false) => "OR",
+            (GateOp::Or, true) => "NOR",
+            (GateOp::Xor, false) => "XOR",
+            (GateOp::Xor, true) => "XNOR",
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::common::ComponentStore;
+    use crate::components::ProbeStim;
+
+    #[test]
+    fn test_gates() {
+        let a = Input::new("a", "out");
+        let b = Input::new("b", "out");
+        let c = Input::new("c", "out");
+        let cs = ComponentStore {
+            store: vec![
+                ProbeStim::rc_new("a", (0.0, 0.0), vec![0, 0, 1, 1]),
+                ProbeStim::rc_new("b", (0.0, 0.0), vec![0, 1, 0, 1]),
+                // only bit 0 is used
+                ProbeStim::rc_new("c", (0.0, 0.0), vec![3, 3, 2, 2]),
+                Gate::rc_new_inverted(
+                    "nand",
+                    (0.0, 0.0),
+                    GateOp::And,
+                    vec![a.clone(), b.clone()],
+                    vec![],
+                    true,
+                ),
+                Gate::rc_new(
+                    "xor3",
+                    (0.0, 0.0),
+                    GateOp::Xor,
+                    vec![a.clone(), b.clone(), c],
+                ),
+                // a and not b
+                Gate::rc_new_inverted(
+                    "andn",
+                    (0.0, 0.0),
+                    GateOp::And,
+                    vec![a.clone(), b],
+                    vec![false, true],
+                    false,
+                ),
+                Gate::rc_new_not("not", (0.0, 0.0), a),
+            ],
+        };
+        let mut simulator = Simulator::new(cs).unwrap();
+        let mut outputs = vec![];
+        for _ in 0..4 {
+            outputs.push(
+                ["nand", "xor3", "andn", "not"]
+                    .map(|id| simulator.get_input_value(&Input::new(id, GATE_OUT_ID))),
+            );
+            simulator.clock();
+        }
+        let d = |v: u32| SignalValue::Data(v);
+        assert_eq!(
+            outputs,
+            vec![
+                [d(1), d(1), d(0), d(1)],
+                [d(1), d(0), d(0), d(1)],
+                [d(1), d(1), d(1), d(0)],
+                [d(0), d(0), d(0), d(0)],
+            ]
+        );
+    }
+}
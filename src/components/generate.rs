@@ -0,0 +1,366 @@
+#[cfg(feature = "gui-egui")]
+use crate::common::EguiComponent;
+use crate::common::{Component, ComponentStore, Components, Id, OutputType, Ports};
+use crate::components::SUBCIRCUIT_SEPARATOR;
+use log::*;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::any::Any;
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+/// Result of substituting the placeholders in a string
+#[derive(Debug, PartialEq)]
+enum Substituted {
+    Text(String),
+    // the string was a single placeholder
+    Number(i64),
+    // an index was out of range, the fallback replaces the string
+    Fallback(String),
+}
+
+/// Generator for repeated structures
+///
+/// The `template` components are instantiated `count` times when the
+/// simulator is created. In all strings of the template, i.e., ids and
+/// connections, `{expr}` is replaced by the value of `expr`, built from the
+/// instance index `i`, the count `n` and integer constants combined with `+`,
+/// `-` and `*` evaluated from left to right. A string consisting of a single
+/// placeholder becomes a number.
+///
+/// `{expr:fallback}` is replaced by the fallback if `expr` is not an index
+/// in `0..n`, e.g., the carry input `{"id": "fa{i-1:cin.out}", "field":
+/// "c_out"}` of the first adder in a chain is `cin.out`. Instance `i` is
+/// placed `i * step` from the template position.
+///
+/// The generated ids are prefixed by the generator id, `shift/r0` for the
+/// template `r{i}` of `shift`, and so are the connections between them.
+/// Connections to other components of the model are left as they are.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Generate {
+    pub(crate) id: Id,
+    pub(crate) pos: (f32, f32),
+    pub(crate) count: usize,
+    pub(crate) step: (f32, f32),
+    pub(crate) template: Vec<Value>,
+    // the generated components, kept for rendering
+    #[serde(skip)]
+    pub(crate) components: RefCell<Components>,
+    // passed on to the generated components
+    #[serde(skip)]
+    pub(crate) model_dir: PathBuf,
+}
+
+#[typetag::serde]
+impl Component for Generate {
+    fn to_(&self) {
+        trace!("Generate {} x {}", self.id, self.count);
+    }
+
+    #[cfg(feature = "gui-egui")]
+    fn dummy(&self, id: &str, pos: (f32, f32)) -> Box<Rc<dyn EguiComponent>> {
+        let mut generate = Generate::new(id, pos, self.count, self.step, self.template.clone());
+        generate.model_dir = self.model_dir.clone();
+        Box::new(Rc::new(generate))
+    }
+
+    fn get_id_ports(&self) -> (Id, Ports) {
+        (
+            self.id.clone(),
+            Ports::new(vec![], OutputType::Combinatorial, vec![]),
+        )
+    }
+
+    fn set_model_dir(&mut self, dir: &Path) {
+        self.model_dir = dir.to_path_buf();
+    }
+
+    fn sub_components(&self) -> Result<Components, String> {
+        let components = self.expand()?;
+        *self.components.borrow_mut() = components.clone();
+        Ok(components)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl Generate {
+    pub fn new(
+        id: &str,
+        pos: (f32, f32),
+        count: usize,
+        step: (f32, f32),
+        template: Vec<Value>,
+    ) -> Self {
+        Generate {
+            id: id.to_string(),
+            pos,
+            count,
+            step,
+            template,
+            components: RefCell::new(vec![]),
+            model_dir: PathBuf::new(),
+        }
+    }
+
+    pub fn rc_new(
+        id: &str,
+        pos: (f32, f32),
+        count: usize,
+        step: (f32, f32),
+        template: Vec<Value>,
+    ) -> Rc<Self> {
+        Rc::new(Generate::new(id, pos, count, step, template))
+    }
+
+    /// Generator with a template of components, e.g., `Register::rc_new("r{i}", ..)`
+    pub fn rc_new_from_components(
+        id: &str,
+        pos: (f32, f32),
+        count: usize,
+        step: (f32, f32),
+        template: Components,
+    ) -> Rc<Self> {
+        let template = template
+            .iter()
+            .map(|c| serde_json::to_value(c).unwrap())
+            .collect();
+        Generate::rc_new(id, pos, count, step, template)
+    }
+
+    /// The template instantiated `count` times
+    pub fn expand(&self) -> Result<Components, String> {
+        let mut store = vec![];
+        for i in 0..self.count {
+            for component in &self.template {
+                let mut component = component.clone();
+                self.substitute(&mut component, i)?;
+                store.push(component);
+            }
+        }
+        let ids: HashSet<String> = store
+            .iter()
+            .filter_map(|c| c.get("id").and_then(Value::as_str).map(str::to_string))
+            .collect();
+        for component in &mut store {
+            self.prefix(component, &ids, true);
+        }
+        let mut store: ComponentStore =
+            serde_json::from_value(serde_json::json!({ "store": store }))
+                .map_err(|e| format!("generator {}: {}", self.id, e))?;
+        store.set_model_dir(&self.model_dir);
+        Ok(store.store)
+    }
+
+    // substitute placeholders for instance `i` in `value`
+    fn substitute(&self, value: &mut Value, i: usize) -> Result<(), String> {
+        let error = |e: String| format!("generator {}: {}", self.id, e);
+        match value {
+            Value::String(s) => {
+                *value = match substitute(s, i, self.count).map_err(error)? {
+                    Substituted::Text(t) | Substituted::Fallback(t) => Value::String(t),
+                    Substituted::Number(n) => Value::from(n),
+                }
+            }
+            Value::Object(map) if is_input(map) => {
+                let id = map["id"].as_str().unwrap().to_string();
+                let (id, field) = match substitute(&id, i, self.count).map_err(error)? {
+                    Substituted::Text(t) => (t, None),
+                    Substituted::Number(n) => (n.to_string(), None),
+                    // a fallback `id.field` replaces the whole input
+                    Substituted::Fallback(f) => match f.rsplit_once('.') {
+                        Some((id, field)) => (id.to_string(), Some(field.to_string())),
+                        None => (f, None),
+                    },
+                };
+                map.insert("id".to_string(), Value::String(id));
+                match field {
+                    Some(field) => {
+                        map.insert("field".to_string(), Value::String(field));
+                    }
+                    None => self.substitute(map.get_mut("field").unwrap(), i)?,
+                }
+            }
+            Value::Object(map) => {
+                for (key, v) in map.iter_mut() {
+                    if key == "pos" {
+                        self.offset_pos(v, i);
+                    } else {
+                        self.substitute(v, i)?;
+                    }
+                }
+            }
+            Value::Array(values) => {
+                for v in values {
+                    self.substitute(v, i)?;
+                }
+            }
+            _ => (),
+        }
+        Ok(())
+    }
+
+    // prefix the generated `ids`, as component id at the top level and in inputs
+    fn prefix(&self, value: &mut Value, ids: &HashSet<String>, top: bool) {
+        match value {
+            Value::Object(map) if top || is_input(map) => {
+                if let Some(Value::String(id)) = map.get_mut("id") {
+                    if ids.contains(id.as_str()) {
+                        *id = format!("{}{}{}", self.id, SUBCIRCUIT_SEPARATOR, id);
+                    }
+                }
+                map.iter_mut()
+                    .filter(|(key, _)| *key != "id")
+                    .for_each(|(_, v)| self.prefix(v, ids, false));
+            }
+            Value::Object(map) => map.values_mut().for_each(|v| self.prefix(v, ids, false)),
+            Value::Array(values) => values.iter_mut().for_each(|v| self.prefix(v, ids, false)),
+            _ => (),
+        }
+    }
+
+    // move positions, `(x, y)` or lists of those as in wires, by `i * step`
+    fn offset_pos(&self, value: &mut Value, i: usize) {
+        if let Value::Array(values) = value {
+            if let [Value::Number(x), Value::Number(y)] = values.as_slice() {
+                let x = x.as_f64().unwrap_or_default() + (i as f32 * self.step.0) as f64;
+                let y = y.as_f64().unwrap_or_default() + (i as f32 * self.step.1) as f64;
+                *values = vec![Value::from(x), Value::from(y)];
+            } else {
+                values.iter_mut().for_each(|v| self.offset_pos(v, i));
+            }
+        }
+    }
+}
+
+// an object with exactly an `id` and a `field` is taken to be an input
+fn is_input(map: &Map<String, Value>) -> bool {
+    map.len() == 2 && map.get("id").is_some_and(Value::is_string) && map.get("field").is_some()
+}
+
+// evaluate `expr` from left to right
+fn eval(expr: &str, i: usize, n: usize) -> Result<i64, String> {
+    let operand = |s: &str| match s.trim() {
+        "i" => Ok(i as i64),
+        "n" => Ok(n as i64),
+        s => s
+            .parse::<i64>()
+            .map_err(|_| format!("invalid operand {:?} in {{{}}}", s, expr)),
+    };
+    let mut ops = expr.match_indices(['+', '-', '*']).peekable();
+    let first_end = ops.peek().map_or(expr.len(), |(pos, _)| *pos);
+    let mut value = operand(&expr[..first_end])?;
+    while let Some((pos, op)) = ops.next() {
+        let end = ops.peek().map_or(expr.len(), |(pos, _)| *pos);
+        let rhs = operand(&expr[pos + 1..end])?;
+        value = match op {
+            "+" => value + rhs,
+            "-" => value - rhs,
+            _ => value * rhs,
+        };
+    }
+    Ok(value)
+}
+
+// replace all placeholders in `s`
+fn substitute(s: &str, i: usize, n: usize) -> Result<Substituted, String> {
+    let mut out = String::new();
+    let mut rest = s;
+    while let Some(start) = rest.find('{') {
+        let end = rest[start..]
+            .find('}')
+            .ok_or(format!("unterminated placeholder in {:?}", s))?
+            + start;
+        let (expr, fallback) = match rest[start + 1..end].split_once(':') {
+            Some((expr, fallback)) => (expr, Some(fallback)),
+            None => (&rest[start + 1..end], None),
+        };
+        let value = eval(expr, i, n)?;
+        if let Some(fallback) = fallback {
+            if value < 0 || value >= n as i64 {
+                return Ok(Substituted::Fallback(fallback.to_string()));
+            }
+        }
+        if start == 0 && end == s.len() - 1 {
+            return Ok(Substituted::Number(value));
+        }
+        out.push_str(&rest[..start]);
+        out.push_str(&value.to_string());
+        rest = &rest[end + 1..];
+    }
+    out.push_str(rest);
+    Ok(Substituted::Text(out))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::common::{Input, Simulator};
+    use crate::components::{Constant, ProbeStim, Register};
+
+    #[test]
+    fn test_substitute() {
+        assert_eq!(
+            substitute("fa{i+1}_{n-1}", 2, 4),
+            Ok(Substituted::Text("fa3_3".to_string()))
+        );
+        assert_eq!(substitute("{i*2+1}", 3, 4), Ok(Substituted::Number(7)));
+        assert_eq!(
+            substitute("fa{i-1:cin.out}", 0, 4),
+            Ok(Substituted::Fallback("cin.out".to_string()))
+        );
+        assert_eq!(
+            substitute("fa{i-1:cin.out}", 1, 4),
+            Ok(Substituted::Text("fa0".to_string()))
+        );
+        assert!(substitute("fa{j}", 0, 4).is_err());
+        assert!(substitute("fa{i", 0, 4).is_err());
+    }
+
+    #[test]
+    fn test_shift_register() {
+        let cs = ComponentStore {
+            store: vec![
+                ProbeStim::rc_new("in", (0.0, 0.0), vec![1, 2, 3, 4, 5, 6]),
+                // same id as a generated register, not connected to the chain
+                Constant::rc_new("r0", (0.0, 0.0), 7),
+                Generate::rc_new_from_components(
+                    "shift",
+                    (0.0, 0.0),
+                    4,
+                    (40.0, 0.0),
+                    vec![Register::rc_new(
+                        "r{i}",
+                        (100.0, 50.0),
+                        Input::new("r{i-1:in.out}", "out"),
+                    )],
+                ),
+            ],
+        };
+        let mut simulator = Simulator::new(cs).unwrap();
+        assert!(simulator.sub_component_ids.contains("shift/r3"));
+        simulator.step(4);
+        // five cycles in, the value of the first
+        assert_eq!(
+            simulator.get_input_value(&Input::new("shift/r3", "out")),
+            1.into()
+        );
+        assert_eq!(
+            simulator.get_input_value(&Input::new("shift/r0", "out")),
+            4.into()
+        );
+        assert_eq!(
+            simulator.get_input_value(&Input::new("r0", "out")),
+            7.into()
+        );
+
+        let generate = Generate::new("g", (0.0, 0.0), 2, (10.0, 5.0), vec![]);
+        let mut pos = serde_json::json!([[1.0, 2.0], [3.0, 4.0]]);
+        generate.offset_pos(&mut pos, 2);
+        assert_eq!(pos, serde_json::json!([[21.0, 12.0], [23.0, 14.0]]));
+    }
+}
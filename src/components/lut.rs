@@ -0,0 +1,469 @@
+#[cfg(feature = "gui-egui")]
+use crate::common::EguiComponent;
+use crate::common::{
+    Component, Condition, Id, Input, InputPort, OutputType, Ports, SignalUnsigned, SignalValue,
+    Simulator,
+};
+use crate::components::BitField;
+use crate::vector_table::parse_value;
+use log::*;
+use serde::{Deserialize, Serialize};
+use std::any::Any;
+use std::cell::RefCell;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+pub const LUT_ADDR_IN_ID: &str = "addr_in";
+
+// at most 64k entries
+const LUT_MAX_ADDR_WIDTH: u32 = 16;
+
+/// Row of a truth table
+///
+/// `pattern` holds the address bits, most significant first, where `-`
+/// matches either value. `values` holds one value per output field.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct LutRow {
+    pub pattern: String,
+    pub values: Vec<SignalUnsigned>,
+}
+
+impl LutRow {
+    pub fn new(pattern: &str, values: Vec<SignalUnsigned>) -> Self {
+        LutRow {
+            pattern: pattern.to_string(),
+            values,
+        }
+    }
+
+    pub fn matches(&self, addr: SignalUnsigned) -> bool {
+        let width = self.pattern.len() as u32;
+        self.pattern.chars().enumerate().all(|(i, c)| {
+            let bit = addr >> (width - 1 - i as u32) & 1;
+            match c {
+                '0' => bit == 0,
+                '1' => bit == 1,
+                _ => true,
+            }
+        })
+    }
+}
+
+/// Lookup table ROM, the word at the address is split into named fields
+///
+/// The contents are given by the truth table `rows`, the first matching row
+/// giving the word. Addresses without a matching row read as unknown.
+///
+/// If `file` is set, the contents are read from it instead, either a truth
+/// table with a row per line, e.g., `10-1 1 0 0x3`, or for files ending in
+/// `.hex` one hexadecimal word per address from 0. `N*word` repeats a word
+/// N times, and unset words are 0. `#` starts a comment. A relative `file`
+/// is resolved against the directory of the model.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Lut {
+    pub(crate) id: Id,
+    pub(crate) pos: (f32, f32),
+    pub(crate) addr_in: Input,
+    pub(crate) addr_width: u32,
+    pub(crate) fields: Vec<BitField>,
+    #[serde(default)]
+    pub(crate) rows: Vec<LutRow>,
+    #[serde(default)]
+    pub(crate) file: Option<PathBuf>,
+    // contents by address or the load error, loaded when first clocked
+    #[serde(skip)]
+    pub(crate) contents: RefCell<Option<Result<Vec<SignalValue>, String>>>,
+    // directory of the model, `file` is relative to it
+    #[serde(skip)]
+    pub(crate) model_dir: PathBuf,
+}
+
+#[typetag::serde]
+impl Component for Lut {
+    fn to_(&self) {
+        trace!("lut {} {:?}", self.id, self.file);
+    }
+    #[cfg(feature = "gui-egui")]
+    fn dummy(&self, id: &str, pos: (f32, f32)) -> Box<Rc<dyn EguiComponent>> {
+        let mut lut = Lut::new(
+            id,
+            pos,
+            Input::new("dummy", "out"),
+            self.addr_width,
+            self.fields.clone(),
+            self.rows.clone(),
+        );
+        lut.file.clone_from(&self.file);
+        lut.model_dir.clone_from(&self.model_dir);
+        Box::new(Rc::new(lut))
+    }
+    fn get_id_ports(&self) -> (Id, Ports) {
+        (
+            self.id.clone(),
+            Ports::new(
+                vec![&InputPort {
+                    port_id: LUT_ADDR_IN_ID.to_string(),
+                    input: self.addr_in.clone(),
+                }],
+                OutputType::Combinatorial,
+                self.fields.iter().map(|f| f.name.as_str()).collect(),
+            ),
+        )
+    }
+
+    fn set_id_port(&mut self, target_port_id: Id, new_input: Input) {
+        if target_port_id == LUT_ADDR_IN_ID {
+            self.addr_in = new_input;
+        }
+    }
+
+    fn clock(&self, simulator: &mut Simulator) -> Result<(), Condition> {
+        let mut res = Ok(());
+        let word = match simulator.get_input_value(&self.addr_in) {
+            SignalValue::Data(addr) => match self.read(addr) {
+                Ok(word) => word,
+                Err(e) => {
+                    res = Err(Condition::Error(e));
+                    SignalValue::Unknown
+                }
+            },
+            _ => SignalValue::Unknown,
+        };
+        for field in &self.fields {
+            let out = match word {
+                SignalValue::Data(word) => field.extract(word).into(),
+                _ => SignalValue::Unknown,
+            };
+            simulator.set_out_value(&self.id, &field.name, out);
+        }
+        res
+    }
+
+    fn set_model_dir(&mut self, dir: &Path) {
+        self.model_dir = dir.to_path_buf();
+        *self.contents.get_mut() = None;
+    }
+
+    // re-read the file on reset
+    fn reset(&self) {
+        *self.contents.borrow_mut() = None;
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl Lut {
+    pub fn new(
+        id: &str,
+        pos: (f32, f32),
+        addr_in: Input,
+        addr_width: u32,
+        fields: Vec<BitField>,
+        rows: Vec<LutRow>,
+    ) -> Self {
+        Lut {
+            id: id.to_string(),
+            pos,
+            addr_in,
+            addr_width,
+            fields,
+            rows,
+            file: None,
+            contents: RefCell::new(None),
+            model_dir: PathBuf::new(),
+        }
+    }
+
+    pub fn rc_new(
+        id: &str,
+        pos: (f32, f32),
+        addr_in: Input,
+        addr_width: u32,
+        fields: Vec<BitField>,
+        rows: Vec<LutRow>,
+    ) -> Rc<Self> {
+        Rc::new(Lut::new(id, pos, addr_in, addr_width, fields, rows))
+    }
+
+    /// Lookup table with contents read from `file`
+    pub fn rc_new_from_file(
+        id: &str,
+        pos: (f32, f32),
+        addr_in: Input,
+        addr_width: u32,
+        fields: Vec<BitField>,
+        file: impl Into<PathBuf>,
+    ) -> Rc<Self> {
+        let mut lut = Lut::new(id, pos, addr_in, addr_width, fields, vec![]);
+        lut.file = Some(file.into());
+        Rc::new(lut)
+    }
+
+    /// The word at `addr`, the address is truncated to `addr_width` bits
+    ///
+    /// The contents are loaded once, a load error is kept until reset.
+    pub fn read(&self, addr: SignalUnsigned) -> Result<SignalValue, String> {
+        if self.contents.borrow().is_none() {
+            *self.contents.borrow_mut() = Some(self.load());
+        }
+        match self.contents.borrow().as_ref().unwrap() {
+            Ok(contents) => Ok(contents[addr as usize & (contents.len() - 1)]),
+            Err(e) => Err(e.clone()),
+        }
+    }
+
+    /// The contents by address
+    pub fn load(&self) -> Result<Vec<SignalValue>, String> {
+        let error = |e: String| format!("lut {}: {}", self.id, e);
+        if self.addr_width > LUT_MAX_ADDR_WIDTH {
+            return Err(error(format!(
+                "address width {} above {}",
+                self.addr_width, LUT_MAX_ADDR_WIDTH
+            )));
+        }
+        for field in &self.fields {
+            field.check().map_err(error)?;
+        }
+        let size = 1 << self.addr_width;
+        let file = match &self.file {
+            Some(file) => Some((
+                file,
+                fs::read_to_string(self.model_dir.join(file))
+                    .map_err(|e| error(format!("{:?}: {}", file, e)))?,
+            )),
+            None => None,
+        };
+        match file {
+            Some((file, text)) if file.extension().is_some_and(|e| e == "hex") => {
+                let mut contents = vec![SignalValue::Data(0); size];
+                let words = parse_hex(&text).map_err(error)?;
+                if words.len() > size {
+                    return Err(error(format!(
+                        "{:?}: {} words for {} addresses",
+                        file,
+                        words.len(),
+                        size
+                    )));
+                }
+                for (i, word) in words.into_iter().enumerate() {
+                    contents[i] = word.into();
+                }
+                Ok(contents)
+            }
+            Some((file, text)) => {
+                let rows =
+                    parse_truth_table(&text).map_err(|e| error(format!("{:?}: {}", file, e)))?;
+                self.contents_of(&rows).map_err(error)
+            }
+            None => self.contents_of(&self.rows).map_err(error),
+        }
+    }
+
+    fn contents_of(&self, rows: &[LutRow]) -> Result<Vec<SignalValue>, String> {
+        let mut contents = vec![SignalValue::Unknown; 1 << self.addr_width];
+        // applied from the last row, so the first matching row wins
+        for row in rows.iter().rev() {
+            if row.pattern.len() != self.addr_width as usize
+                || !row.pattern.chars().all(|c| "01-".contains(c))
+            {
+                return Err(format!(
+                    "pattern {:?} is not {} bits of 0, 1 or -",
+                    row.pattern, self.addr_width
+                ));
+            }
+            if row.values.len() != self.fields.len() {
+                return Err(format!(
+                    "pattern {:?} has {} values for {} fields",
+                    row.pattern,
+                    row.values.len(),
+                    self.fields.len()
+                ));
+            }
+            let word = self
+                .fields
+                .iter()
+                .zip(&row.values)
+                .fold(0, |word, (field, value)| {
+                    word | (value & field.mask()) << field.lsb
+                });
+            for (addr, entry) in contents.iter_mut().enumerate() {
+                if row.matches(addr as SignalUnsigned) {
+                    *entry = word.into();
+                }
+            }
+        }
+        Ok(contents)
+    }
+}
+
+// a value as in vector tables, but not don't care
+fn parse_word(s: &str) -> Result<SignalUnsigned, String> {
+    match parse_value(s)? {
+        SignalValue::Data(word) => Ok(word),
+        _ => Err(format!("invalid value {:?}", s)),
+    }
+}
+
+// text without comments, by line
+fn lines(text: &str) -> impl Iterator<Item = &str> {
+    text.lines()
+        .map(|line| line.split('#').next().unwrap().trim())
+        .filter(|line| !line.is_empty())
+}
+
+fn parse_truth_table(text: &str) -> Result<Vec<LutRow>, String> {
+    lines(text)
+        .map(|line| {
+            let mut tokens = line.split_whitespace();
+            let pattern = tokens.next().unwrap();
+            let values = tokens.map(parse_word).collect::<Result<_, _>>()?;
+            Ok(LutRow::new(pattern, values))
+        })
+        .collect()
+}
+
+fn parse_hex(text: &str) -> Result<Vec<SignalUnsigned>, String> {
+    let mut words = vec![];
+    // the logisim header
+    for token in lines(text)
+        .filter(|line| !line.starts_with("v2.0"))
+        .flat_map(str::split_whitespace)
+    {
+        let (count, word) = match token.split_once('*') {
+            Some((count, word)) => (
+                count
+                    .parse::<usize>()
+                    .map_err(|e| format!("{:?}: {}", token, e))?,
+                word,
+            ),
+            None => (1, token),
+        };
+        let word = SignalUnsigned::from_str_radix(word.trim_start_matches("0x"), 16)
+            .map_err(|e| format!("{:?}: {}", token, e))?;
+        words.extend(std::iter::repeat_n(word, count));
+    }
+    Ok(words)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::common::ComponentStore;
+    use crate::components::ProbeStim;
+
+    #[test]
+    fn test_lut() {
+        let dir = std::env::temp_dir().join(format!("syncrim_lut_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let hex = dir.join("squares.hex");
+        fs::write(&hex, "v2.0 raw\n0 1 4 9 # comment\n4*10\n").unwrap();
+        let table = dir.join("decode.tt");
+        fs::write(&table, "# op  reg_dst alu_src\n00 1 0\n1- 0 1\n").unwrap();
+
+        let fields = || {
+            vec![
+                BitField::new("reg_dst", 0, 1),
+                BitField::new("alu_src", 1, 1),
+            ]
+        };
+        let mut cs = ComponentStore {
+            store: vec![
+                ProbeStim::rc_new("addr", (0.0, 0.0), vec![0, 1, 2, 7]),
+                // a control unit as a truth table, the first matching row is used
+                Lut::rc_new(
+                    "ctrl",
+                    (0.0, 0.0),
+                    Input::new("addr", "out"),
+                    2,
+                    fields(),
+                    vec![LutRow::new("00", vec![1, 0]), LutRow::new("-0", vec![0, 1])],
+                ),
+                // relative to the model directory
+                Lut::rc_new_from_file(
+                    "file",
+                    (0.0, 0.0),
+                    Input::new("addr", "out"),
+                    2,
+                    fields(),
+                    "decode.tt",
+                ),
+                Lut::rc_new_from_file(
+                    "squares",
+                    (0.0, 0.0),
+                    Input::new("addr", "out"),
+                    3,
+                    vec![BitField::new("square", 0, 8)],
+                    &hex,
+                ),
+            ],
+        };
+        cs.set_model_dir(&dir);
+        let mut simulator = Simulator::new(cs).unwrap();
+        let mut outputs = vec![];
+        for _ in 0..4 {
+            outputs.push(
+                [
+                    ("ctrl", "reg_dst"),
+                    ("ctrl", "alu_src"),
+                    ("file", "alu_src"),
+                    ("squares", "square"),
+                ]
+                .map(|(id, field)| simulator.get_input_value(&Input::new(id, field))),
+            );
+            simulator.clock();
+        }
+        let u = SignalValue::Unknown;
+        assert_eq!(
+            outputs,
+            vec![
+                [1.into(), 0.into(), 0.into(), 0.into()],
+                [u, u, u, 1.into()],
+                [0.into(), 1.into(), 1.into(), 4.into()],
+                // the address is truncated to 2 bits
+                [u, u, 1.into(), 0x10.into()],
+            ]
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_lut_errors() {
+        let lut = |rows| {
+            Lut::new(
+                "lut",
+                (0.0, 0.0),
+                Input::new("a", "out"),
+                2,
+                vec![BitField::new("f", 0, 4)],
+                rows,
+            )
+        };
+        assert!(lut(vec![LutRow::new("0", vec![1])]).load().is_err());
+        assert!(lut(vec![LutRow::new("0x", vec![1])]).load().is_err());
+        assert!(lut(vec![LutRow::new("01", vec![1, 2])]).load().is_err());
+        assert!(
+            lut(vec![LutRow::new("01", vec![1]), LutRow::new("10", vec![0])])
+                .load()
+                .is_ok()
+        );
+        assert!(parse_truth_table("01 -").is_err());
+        assert!(parse_hex("1 2 zz").is_err());
+        assert_eq!(parse_hex("3*a 0x1"), Ok(vec![10, 10, 10, 1]));
+
+        // a failing load is not retried until reset
+        let dir = std::env::temp_dir().join(format!("syncrim_lut_err_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let mut lut = lut(vec![]);
+        lut.file = Some("late.hex".into());
+        lut.set_model_dir(&dir);
+        assert!(lut.read(0).is_err());
+        fs::write(dir.join("late.hex"), "5").unwrap();
+        assert!(lut.read(0).is_err());
+        lut.reset();
+        assert_eq!(lut.read(0), Ok(5.into()));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
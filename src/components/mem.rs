@@ -4,6 +4,11 @@ use crate::common::{
     Component, Condition, Id, Input, InputPort, OutputType, Ports, SignalSigned, SignalUnsigned,
     SignalValue, Simulator,
 };
+use crate::components::{
+    FaultCheck, MemAccess, MemFaults, MemLatency, MemTrace, ResetInput, WaitStates,
+    MEM_STALL_OUT_ID, MEM_TRAP_CAUSE_OUT_ID, MEM_TRAP_OUT_ID,
+};
+use crate::mem_image::{dump_image, MemImage, MemImageFormat};
 use log::*;
 use num_enum::IntoPrimitive;
 use num_enum::TryFromPrimitive;
@@ -11,17 +16,37 @@ use serde::{Deserialize, Serialize};
 use std::any::Any;
 use std::ops::Deref;
 use std::ops::Range;
-use std::{cell::RefCell, collections::BTreeMap, convert::TryFrom, rc::Rc};
+use std::path::{Path, PathBuf};
+use std::{
+    cell::{Ref, RefCell},
+    collections::{BTreeMap, BTreeSet},
+    convert::TryFrom,
+    rc::Rc,
+};
 
 pub const MEM_DATA_ID: &str = "data";
 pub const MEM_ADDR_ID: &str = "addr";
 pub const MEM_CTRL_ID: &str = "ctrl";
 pub const MEM_SEXT_ID: &str = "sext";
 pub const MEM_SIZE_ID: &str = "size";
+pub const MEM_RESET_ID: &str = "reset";
+pub const MEM_PC_ID: &str = "pc";
 
 pub const MEM_DATA_OUT_ID: &str = "data_o";
 pub const MEM_ERR_OUT_ID: &str = "err";
 
+/// What a read of bytes never written gives
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum UninitRead {
+    /// The bytes read as zero
+    #[default]
+    Zero,
+    /// The read gives `SignalValue::Uninitialized`
+    Uninitialized,
+    /// The bytes read as zero and a warning is given
+    Warning,
+}
+
 #[derive(Serialize, Deserialize, Clone)]
 pub struct Mem {
     pub(crate) id: Id,
@@ -38,11 +63,40 @@ pub struct Mem {
     pub(crate) ctrl: Input,
     pub(crate) sext: Input,
     pub(crate) size: Input,
+    // optional, while asserted accesses are ignored and data_o is driven with the reset value
+    #[serde(default)]
+    pub(crate) reset: Option<ResetInput>,
 
     // memory
     pub(crate) memory: Memory,
     pub(crate) range: Range<u32>,
-    // later history... tbd
+    // optional, replaces the memory content on simulator creation and reset
+    #[serde(default)]
+    pub(crate) image: Option<MemImage>,
+    // directory of the model, a relative image file is relative to it
+    #[serde(skip)]
+    pub(crate) model_dir: PathBuf,
+    // bytes written by each clock, for stepping back and the memory view
+    #[serde(skip)]
+    pub(crate) writes: RefCell<WriteHistory>,
+    // wait states, none by default
+    #[serde(default)]
+    pub(crate) latency: MemLatency,
+    #[serde(skip)]
+    pub(crate) wait: RefCell<WaitStates>,
+    // loads and stores, for export and the heat map, recorded when enabled
+    #[serde(default)]
+    pub(crate) record_trace: bool,
+    #[serde(skip)]
+    pub(crate) trace: RefCell<MemTrace>,
+    // reads of uninitialised bytes, the optional pc is named in warnings
+    #[serde(default)]
+    pub(crate) uninit_read: UninitRead,
+    #[serde(default)]
+    pub(crate) pc: Option<Input>,
+    // misaligned, out of range and illegal size accesses
+    #[serde(default)]
+    pub(crate) faults: MemFaults,
 }
 
 impl Mem {
@@ -72,8 +126,19 @@ impl Mem {
             ctrl,
             sext,
             size,
+            reset: None,
             memory: Memory::new(memory),
             range,
+            image: None,
+            writes: RefCell::default(),
+            latency: MemLatency::default(),
+            wait: RefCell::default(),
+            record_trace: false,
+            trace: RefCell::default(),
+            uninit_read: UninitRead::default(),
+            pc: None,
+            faults: MemFaults::default(),
+            model_dir: PathBuf::new(),
         }
     }
 
@@ -91,13 +156,20 @@ impl Mem {
         size: Input,
         range: Range<u32>,
     ) -> Rc<Self> {
-        let mut mem = BTreeMap::new();
-        //fill the defined memory range with zeroes
-        for i in range.clone() {
-            mem.insert(i as usize, 0u8);
-        }
+        // bytes not written read as zero
         Rc::new(Mem::new(
-            id, pos, width, height, big_endian, data, addr, ctrl, sext, size, mem, range,
+            id,
+            pos,
+            width,
+            height,
+            big_endian,
+            data,
+            addr,
+            ctrl,
+            sext,
+            size,
+            BTreeMap::new(),
+            range,
         ))
     }
 
@@ -120,10 +192,339 @@ impl Mem {
             id, pos, width, height, big_endian, data, addr, ctrl, sext, size, memory, range,
         ))
     }
+
+    /// Memory initialised from an image file, see `mem_image`
+    #[allow(clippy::too_many_arguments)]
+    pub fn rc_new_from_image(
+        id: &str,
+        pos: (f32, f32),
+        width: f32,
+        height: f32,
+        big_endian: bool,
+        data: Input,
+        addr: Input,
+        ctrl: Input,
+        sext: Input,
+        size: Input,
+        image: MemImage,
+        range: Range<u32>,
+    ) -> Rc<Self> {
+        let mut mem = Mem::new(
+            id,
+            pos,
+            width,
+            height,
+            big_endian,
+            data,
+            addr,
+            ctrl,
+            sext,
+            size,
+            BTreeMap::new(),
+            range,
+        );
+        mem.image = Some(image);
+        Rc::new(mem)
+    }
+
+    /// Add wait states, see `MemLatency`
+    pub fn with_latency(mut self, latency: MemLatency) -> Self {
+        self.latency = latency;
+        self
+    }
+
+    /// Record the loads and stores, see `MemTrace`
+    pub fn with_trace(mut self) -> Self {
+        self.record_trace = true;
+        self
+    }
+
+    /// Detect reads of uninitialised bytes, see `UninitRead`, warnings name
+    /// the value of `pc` if given
+    pub fn with_uninit_read(mut self, uninit_read: UninitRead, pc: Option<Input>) -> Self {
+        self.uninit_read = uninit_read;
+        self.pc = pc;
+        self
+    }
+
+    /// Handle faulty accesses as given, see `MemFaults`
+    pub fn with_faults(mut self, faults: MemFaults) -> Self {
+        self.faults = faults;
+        self
+    }
+
+    /// Addresses written in the current cycle
+    pub fn written(&self) -> BTreeSet<usize> {
+        self.writes.borrow().written()
+    }
+
+    /// Bytes changed between cycles `from` and `to`, as (old, new)
+    pub fn diff(&self, from: usize, to: usize) -> BTreeMap<usize, (u8, u8)> {
+        self.writes.borrow().diff(from, to)
+    }
+
+    /// The loads and stores done so far
+    pub fn trace(&self) -> Ref<'_, MemTrace> {
+        self.trace.borrow()
+    }
+
+    /// Write the loads and stores to `path` as csv
+    pub fn export_trace(&self, path: &Path) -> Result<(), String> {
+        self.trace.borrow().export(path)
+    }
+
+    /// Write the bytes in `range` to `path`, see `mem_image::dump_image`
+    pub fn dump(
+        &self,
+        path: &Path,
+        format: MemImageFormat,
+        range: Range<usize>,
+    ) -> Result<(), String> {
+        let start = range.start;
+        dump_image(path, format, &self.memory.borrow().bytes(range), start)
+    }
+}
+
+const PAGE_BITS: usize = 12;
+pub const MEMORY_PAGE_SIZE: usize = 1 << PAGE_BITS;
+
+/// Sparse byte store, allocated in pages of `MEMORY_PAGE_SIZE` bytes
+///
+/// All bytes of an allocated page are present, initially zero, bytes outside
+/// are absent. Each byte is also tracked as initialised once written.
+/// Serialized as a map of the initialised bytes, so bytes written as zero
+/// are still initialised when loaded.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(from = "BTreeMap<usize, u8>", into = "BTreeMap<usize, u8>")]
+pub struct PagedMemory {
+    pages: BTreeMap<usize, Page>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Page {
+    bytes: Box<[u8]>,
+    // one bit per byte, set when initialised
+    init: Box<[u64]>,
+}
+
+impl Page {
+    fn new() -> Self {
+        Page {
+            bytes: vec![0; MEMORY_PAGE_SIZE].into_boxed_slice(),
+            init: vec![0; MEMORY_PAGE_SIZE / 64].into_boxed_slice(),
+        }
+    }
+
+    fn is_init(&self, offset: usize) -> bool {
+        self.init[offset / 64] & (1 << (offset % 64)) != 0
+    }
+}
+
+impl PagedMemory {
+    pub fn new() -> Self {
+        PagedMemory::default()
+    }
+
+    pub fn get(&self, addr: &usize) -> Option<&u8> {
+        self.pages
+            .get(&(addr >> PAGE_BITS))
+            .map(|page| &page.bytes[addr % MEMORY_PAGE_SIZE])
+    }
+
+    /// The byte at `addr` if initialised
+    pub fn get_init(&self, addr: usize) -> Option<u8> {
+        let offset = addr % MEMORY_PAGE_SIZE;
+        self.pages
+            .get(&(addr >> PAGE_BITS))
+            .filter(|page| page.is_init(offset))
+            .map(|page| page.bytes[offset])
+    }
+
+    pub fn is_init(&self, addr: usize) -> bool {
+        self.get_init(addr).is_some()
+    }
+
+    pub fn insert(&mut self, addr: usize, byte: u8) {
+        let offset = addr % MEMORY_PAGE_SIZE;
+        let page = self
+            .pages
+            .entry(addr >> PAGE_BITS)
+            .or_insert_with(Page::new);
+        page.bytes[offset] = byte;
+        page.init[offset / 64] |= 1 << (offset % 64);
+    }
+
+    /// Mark the byte at `addr` as not initialised, it reads as zero
+    pub fn uninit(&mut self, addr: usize) {
+        let offset = addr % MEMORY_PAGE_SIZE;
+        if let Some(page) = self.pages.get_mut(&(addr >> PAGE_BITS)) {
+            page.bytes[offset] = 0;
+            page.init[offset / 64] &= !(1 << (offset % 64));
+        }
+    }
+
+    /// All present bytes in address order
+    pub fn iter(&self) -> impl Iterator<Item = (usize, u8)> + '_ {
+        self.pages.iter().flat_map(|(page, data)| {
+            data.bytes
+                .iter()
+                .enumerate()
+                .map(move |(i, byte)| ((page << PAGE_BITS) + i, *byte))
+        })
+    }
+
+    /// The initialised bytes in address order
+    pub fn iter_init(&self) -> impl Iterator<Item = (usize, u8)> + '_ {
+        self.pages.iter().flat_map(|(page, data)| {
+            (0..MEMORY_PAGE_SIZE)
+                .filter(|i| data.is_init(*i))
+                .map(move |i| ((page << PAGE_BITS) + i, data.bytes[i]))
+        })
+    }
+
+    /// Number of allocated pages
+    pub fn pages(&self) -> usize {
+        self.pages.len()
+    }
+
+    /// The bytes in `range`, absent bytes read as zero
+    pub fn bytes(&self, range: Range<usize>) -> Vec<u8> {
+        range.map(|addr| *self.get(&addr).unwrap_or(&0)).collect()
+    }
+
+    /// The bytes in `range`, none where not initialised
+    pub fn init_bytes(&self, range: Range<usize>) -> Vec<Option<u8>> {
+        range.map(|addr| self.get_init(addr)).collect()
+    }
+
+    /// Undo a write, restoring the old byte or the uninitialised state
+    pub fn restore(&mut self, write: &ByteWrite) {
+        match write.old {
+            Some(byte) => self.insert(write.addr, byte),
+            None => self.uninit(write.addr),
+        }
+    }
+}
+
+/// A byte changed by a memory write
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ByteWrite {
+    pub addr: usize,
+    // none if not initialised before the write
+    pub old: Option<u8>,
+    pub new: u8,
+}
+
+/// The bytes written by each clock of a memory, by simulator cycle
+///
+/// Used to step memories back, to show what was written in the current
+/// cycle and to compare the contents between cycles.
+#[derive(Debug, Clone, Default)]
+pub struct WriteHistory {
+    entries: Vec<(usize, Vec<ByteWrite>)>,
+}
+
+impl WriteHistory {
+    /// Start the entry of the clock at `cycle`, dropping any later entries
+    pub fn clock(&mut self, cycle: usize) {
+        // entries are in cycle order
+        let keep = self.entries.partition_point(|(c, _)| *c < cycle);
+        self.entries.truncate(keep);
+        self.entries.push((cycle, vec![]));
+    }
+
+    /// Record a write of `new` over `old` at `addr` in the current entry,
+    /// see `PagedMemory::init_bytes`
+    pub fn record(&mut self, addr: usize, old: &[Option<u8>], new: &[u8]) {
+        if let Some((_, writes)) = self.entries.last_mut() {
+            writes.extend(
+                old.iter()
+                    .zip(new)
+                    .enumerate()
+                    .map(|(i, (old, new))| ByteWrite {
+                        addr: addr + i,
+                        old: *old,
+                        new: *new,
+                    }),
+            );
+        }
+    }
+
+    /// Remove the last entry, returning its writes
+    pub fn un_clock(&mut self) -> Vec<ByteWrite> {
+        self.entries
+            .pop()
+            .map(|(_, writes)| writes)
+            .unwrap_or_default()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Undo all recorded writes in `memory`, clearing the history
+    pub fn undo(&mut self, memory: &mut PagedMemory) {
+        for (_, writes) in self.entries.drain(..).rev() {
+            for write in writes.iter().rev() {
+                memory.restore(write);
+            }
+        }
+    }
+
+    /// Addresses written by the last clock
+    pub fn written(&self) -> BTreeSet<usize> {
+        self.entries
+            .last()
+            .map(|(_, writes)| writes.iter().map(|w| w.addr).collect())
+            .unwrap_or_default()
+    }
+
+    /// Bytes that differ between the contents seen at `from` and at `to`,
+    /// i.e., changed by the clocks of cycles `from..to`, as (old, new)
+    pub fn diff(&self, from: usize, to: usize) -> BTreeMap<usize, (u8, u8)> {
+        let mut diff = BTreeMap::new();
+        for (_, writes) in self.entries.iter().filter(|(c, _)| (from..to).contains(c)) {
+            for write in writes {
+                diff.entry(write.addr)
+                    .and_modify(|(_, new)| *new = write.new)
+                    .or_insert((write.old.unwrap_or(0), write.new));
+            }
+        }
+        diff.retain(|_, (old, new)| old != new);
+        diff
+    }
+}
+
+impl Extend<(usize, u8)> for PagedMemory {
+    fn extend<T: IntoIterator<Item = (usize, u8)>>(&mut self, iter: T) {
+        for (addr, byte) in iter {
+            self.insert(addr, byte);
+        }
+    }
+}
+
+impl FromIterator<(usize, u8)> for PagedMemory {
+    fn from_iter<T: IntoIterator<Item = (usize, u8)>>(iter: T) -> Self {
+        let mut memory = PagedMemory::new();
+        memory.extend(iter);
+        memory
+    }
+}
+
+impl From<BTreeMap<usize, u8>> for PagedMemory {
+    fn from(bytes: BTreeMap<usize, u8>) -> Self {
+        bytes.into_iter().collect()
+    }
+}
+
+impl From<PagedMemory> for BTreeMap<usize, u8> {
+    fn from(memory: PagedMemory) -> Self {
+        memory.iter_init().collect()
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct Memory(pub Rc<RefCell<BTreeMap<usize, u8>>>);
+pub struct Memory(pub Rc<RefCell<PagedMemory>>);
 
 impl Default for Memory {
     fn default() -> Self {
@@ -133,14 +534,26 @@ impl Default for Memory {
 
 impl Memory {
     pub fn new(data: BTreeMap<usize, u8>) -> Self {
-        Memory(Rc::new(RefCell::new(data)))
+        Memory(Rc::new(RefCell::new(data.into())))
     }
 
-    fn align(&self, addr: usize, size: usize) -> SignalValue {
+    /// The first byte of the access not initialised
+    pub(crate) fn uninit(&self, addr: usize, size: usize) -> Option<usize> {
+        let memory = self.0.borrow();
+        (addr..addr + size).find(|addr| !memory.is_init(*addr))
+    }
+
+    pub(crate) fn align(&self, addr: usize, size: usize) -> SignalValue {
         ((addr % size != 0) as SignalUnsigned).into()
     }
 
-    fn read(&self, addr: usize, size: usize, sign: bool, big_endian: bool) -> SignalValue {
+    pub(crate) fn read(
+        &self,
+        addr: usize,
+        size: usize,
+        sign: bool,
+        big_endian: bool,
+    ) -> SignalValue {
         let data: Vec<u8> = (0..size)
             .map(|i| *self.0.borrow().get(&(addr + i)).unwrap_or(&0))
             .collect();
@@ -208,7 +621,7 @@ impl Memory {
         .into()
     }
 
-    fn write(&self, addr: usize, size: usize, big_endian: bool, data: SignalValue) {
+    pub(crate) fn write(&self, addr: usize, size: usize, big_endian: bool, data: SignalValue) {
         let data: SignalUnsigned = data.try_into().unwrap();
         match size {
             1 => {
@@ -296,54 +709,156 @@ impl Component for Mem {
             ctrl: dummy_input.clone(),
             size: dummy_input.clone(),
             sext: dummy_input.clone(),
+            reset: None,
             range: Range {
                 start: 0,
                 end: 0x20,
             },
             memory: Memory::new(BTreeMap::new()),
+            image: None,
+            writes: RefCell::default(),
+            latency: MemLatency::default(),
+            wait: RefCell::default(),
+            record_trace: self.record_trace,
+            trace: RefCell::default(),
+            uninit_read: self.uninit_read,
+            pc: None,
+            faults: self.faults,
+            model_dir: self.model_dir.clone(),
         }))
     }
     fn get_id_ports(&self) -> (Id, Ports) {
+        let mut inputs = vec![
+            InputPort {
+                port_id: MEM_DATA_ID.to_string(),
+                input: self.data.clone(),
+            },
+            InputPort {
+                port_id: MEM_ADDR_ID.to_string(),
+                input: self.addr.clone(),
+            },
+            InputPort {
+                port_id: MEM_CTRL_ID.to_string(),
+                input: self.ctrl.clone(),
+            },
+            InputPort {
+                port_id: MEM_SEXT_ID.to_string(),
+                input: self.sext.clone(),
+            },
+            InputPort {
+                port_id: MEM_SIZE_ID.to_string(),
+                input: self.size.clone(),
+            },
+        ];
+        if let Some(reset) = &self.reset {
+            inputs.push(InputPort {
+                port_id: MEM_RESET_ID.to_string(),
+                input: reset.input.clone(),
+            });
+        }
+        if let Some(pc) = &self.pc {
+            inputs.push(InputPort {
+                port_id: MEM_PC_ID.to_string(),
+                input: pc.clone(),
+            });
+        }
         (
             self.id.clone(),
             Ports::new(
+                inputs.iter().collect(),
+                OutputType::Combinatorial,
                 vec![
-                    &InputPort {
-                        port_id: MEM_DATA_ID.to_string(),
-                        input: self.data.clone(),
-                    },
-                    &InputPort {
-                        port_id: MEM_ADDR_ID.to_string(),
-                        input: self.addr.clone(),
-                    },
-                    &InputPort {
-                        port_id: MEM_CTRL_ID.to_string(),
-                        input: self.ctrl.clone(),
-                    },
-                    &InputPort {
-                        port_id: MEM_SEXT_ID.to_string(),
-                        input: self.sext.clone(),
-                    },
-                    &InputPort {
-                        port_id: MEM_SIZE_ID.to_string(),
-                        input: self.size.clone(),
-                    },
+                    MEM_DATA_OUT_ID,
+                    MEM_ERR_OUT_ID,
+                    MEM_STALL_OUT_ID,
+                    MEM_TRAP_OUT_ID,
+                    MEM_TRAP_CAUSE_OUT_ID,
                 ],
-                OutputType::Combinatorial,
-                vec![MEM_DATA_OUT_ID, MEM_ERR_OUT_ID],
             ),
         )
     }
 
+    fn set_model_dir(&mut self, dir: &Path) {
+        self.model_dir = dir.to_path_buf();
+    }
+
     fn clock(&self, simulator: &mut Simulator) -> Result<(), Condition> {
+        // cycle 0 is evaluated on simulator creation and reset, without an
+        // image undoing the writes since restores the initial contents
+        if simulator.cycle == 0 && self.image.is_none() {
+            self.writes.borrow_mut().undo(&mut self.memory.borrow_mut());
+        }
+        self.writes.borrow_mut().clock(simulator.cycle);
+        self.wait.borrow_mut().clock();
+        self.trace.borrow_mut().clock(simulator.cycle);
+        simulator.set_out_value(&self.id, MEM_STALL_OUT_ID, false);
+        simulator.set_out_value(&self.id, MEM_TRAP_OUT_ID, false);
+        simulator.set_out_value(&self.id, MEM_TRAP_CAUSE_OUT_ID, 0);
+        // cycle 0 is evaluated on simulator creation and reset
+        if let (Some(image), 0) = (&self.image, simulator.cycle) {
+            let range = self.range.start as usize..self.range.end as usize;
+            match image.load(&self.model_dir, range) {
+                Ok(bytes) => *self.memory.borrow_mut() = bytes.into(),
+                Err(e) => {
+                    simulator.set_out_value(&self.id, MEM_DATA_OUT_ID, SignalValue::Unknown);
+                    simulator.set_out_value(&self.id, MEM_ERR_OUT_ID, SignalValue::Unknown);
+                    return Err(Condition::Error(format!("mem {}: {}", self.id, e)));
+                }
+            }
+        }
+
+        // an asynchronous reset is effective immediately, a synchronous one
+        // from the clock edge on, the memory content is left untouched
+        if let Some(reset) = &self.reset {
+            if reset.is_in_effect(simulator) {
+                trace!("mem {} in reset", self.id);
+                simulator.set_out_value(&self.id, MEM_DATA_OUT_ID, reset.value);
+                simulator.set_out_value(&self.id, MEM_ERR_OUT_ID, false);
+                return Ok(());
+            }
+        }
+
         let data = simulator.get_input_value(&self.data);
         let addr = simulator.get_input_value(&self.addr);
         let size = simulator.get_input_value(&self.size);
         let sign = simulator.get_input_value(&self.sext);
 
+        let mut fault_condition = None;
         match simulator.get_input_value(&self.ctrl) {
             SignalValue::Data(ctrl) => {
                 let ctrl = MemCtrl::try_from(ctrl as u8).unwrap();
+                if let MemCtrl::Read | MemCtrl::Write = ctrl {
+                    let write = matches!(ctrl, MemCtrl::Write);
+                    let check = match (addr, size) {
+                        (SignalValue::Data(addr), SignalValue::Data(size)) => {
+                            self.faults.check(&self.id, addr, size, write, &self.range)
+                        }
+                        _ => FaultCheck::default(),
+                    };
+                    if let Some(cause) = check.trap {
+                        simulator.set_out_value(&self.id, MEM_TRAP_OUT_ID, true);
+                        simulator.set_out_value(&self.id, MEM_TRAP_CAUSE_OUT_ID, cause);
+                    }
+                    if check.suppress {
+                        trace!("mem {} access not done", self.id);
+                        simulator.set_out_value(&self.id, MEM_DATA_OUT_ID, SignalValue::Unknown);
+                        simulator.set_out_value(&self.id, MEM_ERR_OUT_ID, true);
+                        return check.condition.map_or(Ok(()), Err);
+                    }
+                    fault_condition = check.condition;
+                    let access = MemAccess {
+                        write,
+                        addr,
+                        size,
+                        data: if write { data } else { SignalValue::Unknown },
+                    };
+                    let wait_states = self.latency.wait_states(addr.try_into().unwrap(), write);
+                    if self.wait.borrow_mut().wait(access, wait_states) {
+                        trace!("mem {} wait", self.id);
+                        simulator.set_out_value(&self.id, MEM_STALL_OUT_ID, true);
+                        return Ok(());
+                    }
+                }
                 match ctrl {
                     MemCtrl::Read => {
                         let addr: u32 = addr.try_into().unwrap();
@@ -356,17 +871,58 @@ impl Component for Mem {
                             sign != 0,
                             self.big_endian,
                         );
+                        if let (true, SignalValue::Data(value)) = (self.record_trace, value) {
+                            self.trace.borrow_mut().record(addr, size, false, value);
+                        }
+                        let uninit = match self.uninit_read {
+                            UninitRead::Zero => None,
+                            _ => self.memory.uninit(addr as usize, size as usize),
+                        };
                         simulator.set_out_value(&self.id, "data_o", value);
                         let value = self.memory.align(addr as usize, size as usize);
                         trace!("align {:?}", value);
                         simulator.set_out_value(&self.id, "err", value); // align
+                        if let Some(uninit) = uninit {
+                            if let UninitRead::Uninitialized = self.uninit_read {
+                                simulator.set_out_value(
+                                    &self.id,
+                                    "data_o",
+                                    SignalValue::Uninitialized,
+                                );
+                            } else {
+                                let pc = match self
+                                    .pc
+                                    .as_ref()
+                                    .map(|pc| simulator.get_input_value(pc))
+                                {
+                                    Some(SignalValue::Data(pc)) => format!(" at pc {:#x}", pc),
+                                    _ => String::new(),
+                                };
+                                return Err(Condition::Warning(format!(
+                                    "mem {}: read of uninitialised address {:#x}{}",
+                                    self.id, uninit, pc
+                                )));
+                            }
+                        }
                     }
                     MemCtrl::Write => {
                         let addr: u32 = addr.try_into().unwrap();
                         let size: u32 = size.try_into().unwrap();
                         trace!("write addr {:?} size {:?}", addr, size);
+                        let bytes = addr as usize..addr as usize + size as usize;
+                        let old = self.memory.borrow().init_bytes(bytes.clone());
                         self.memory
                             .write(addr as usize, size as usize, self.big_endian, data);
+                        let new = self.memory.borrow().bytes(bytes);
+                        self.writes.borrow_mut().record(addr as usize, &old, &new);
+                        if self.record_trace {
+                            self.trace.borrow_mut().record(
+                                addr,
+                                size,
+                                true,
+                                data.try_into().unwrap(),
+                            );
+                        }
                         let value = self.memory.align(addr as usize, size as usize);
                         trace!("align {:?}", value);
                         simulator.set_out_value(&self.id, "err", value); // align
@@ -382,21 +938,35 @@ impl Component for Mem {
             }
         }
 
-        for (idx, i) in self.memory.0.borrow().iter().enumerate() {
-            if i.0 % 4 == 0 && idx < 40 {
-                //only print 40 bytes so the trace isn't busy
-                trace!(
-                    "0x{:08x} : 0x{:02x}{:02x}{:02x}{:02x}",
-                    i.0,
-                    self.memory.0.borrow().get(i.0).unwrap_or(&0u8),
-                    self.memory.0.borrow().get(&(i.0 + 1)).unwrap_or(&0u8),
-                    self.memory.0.borrow().get(&(i.0 + 2)).unwrap_or(&0u8),
-                    self.memory.0.borrow().get(&(i.0 + 3)).unwrap_or(&0u8),
-                )
-            }
+        //only print 40 bytes so the trace isn't busy
+        let memory = self.memory.0.borrow();
+        for (addr, _) in memory.iter().take(40).filter(|(addr, _)| addr % 4 == 0) {
+            trace!(
+                "0x{:08x} : 0x{:02x}{:02x}{:02x}{:02x}",
+                addr,
+                memory.get(&addr).unwrap_or(&0u8),
+                memory.get(&(addr + 1)).unwrap_or(&0u8),
+                memory.get(&(addr + 2)).unwrap_or(&0u8),
+                memory.get(&(addr + 3)).unwrap_or(&0u8),
+            )
+        }
+
+        fault_condition.map_or(Ok(()), Err)
+    }
+
+    fn un_clock(&self) {
+        let mut memory = self.memory.borrow_mut();
+        for write in self.writes.borrow_mut().un_clock().iter().rev() {
+            memory.restore(write);
         }
+        self.wait.borrow_mut().un_clock();
+        self.trace.borrow_mut().un_clock();
+    }
 
-        Ok(())
+    fn reset(&self) {
+        self.writes.borrow_mut().clear();
+        self.wait.borrow_mut().clear();
+        self.trace.borrow_mut().clear();
     }
 
     fn set_id_port(&mut self, target_port_id: Id, new_input: Input) {
@@ -406,6 +976,12 @@ impl Component for Mem {
             MEM_CTRL_ID => self.ctrl = new_input,
             MEM_SEXT_ID => self.sext = new_input,
             MEM_SIZE_ID => self.size = new_input,
+            MEM_RESET_ID => {
+                if let Some(reset) = &mut self.reset {
+                    reset.input = new_input;
+                }
+            }
+            MEM_PC_ID if self.pc.is_some() => self.pc = Some(new_input),
             _ => (),
         }
     }
@@ -416,7 +992,7 @@ impl Component for Mem {
 }
 
 impl Deref for Memory {
-    type Target = RefCell<BTreeMap<usize, u8>>;
+    type Target = RefCell<PagedMemory>;
 
     fn deref(&self) -> &Self::Target {
         &self.0
@@ -426,8 +1002,11 @@ impl Deref for Memory {
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::common::ComponentStore;
-    use crate::components::ProbeOut;
+    use crate::common::{ComponentStore, RunningState};
+    use crate::components::{
+        FaultAction, LatencyRegion, ProbeOut, ProbeStim, RegionAccesses, Register, ResetInput,
+        ResetKind,
+    };
     use std::rc::Rc;
 
     #[test]
@@ -454,13 +1033,24 @@ mod test {
                     ctrl: Input::new("ctrl", "out"),
                     size: Input::new("size", "out"),
                     sext: Input::new("sign", "out"),
+                    reset: None,
 
                     // memory
-                    memory: Memory(Rc::new(RefCell::new(BTreeMap::new()))),
+                    memory: Memory::default(),
                     range: Range {
                         start: 0u32,
                         end: 1u32,
                     },
+                    image: None,
+                    writes: RefCell::default(),
+                    latency: MemLatency::default(),
+                    wait: RefCell::default(),
+                    record_trace: false,
+                    trace: RefCell::default(),
+                    uninit_read: UninitRead::default(),
+                    pc: None,
+                    faults: MemFaults::default(),
+                    model_dir: PathBuf::new(),
                 }),
             ],
         };
@@ -636,14 +1226,25 @@ mod test {
                     ctrl: Input::new("ctrl", "out"),
                     size: Input::new("size", "out"),
                     sext: Input::new("sign", "out"),
+                    reset: None,
 
                     // memory
-                    memory: Memory(Rc::new(RefCell::new(BTreeMap::new()))),
+                    memory: Memory::default(),
                     // later history... tbd
                     range: Range {
                         start: 0u32,
                         end: 1u32,
                     },
+                    image: None,
+                    writes: RefCell::default(),
+                    latency: MemLatency::default(),
+                    wait: RefCell::default(),
+                    record_trace: false,
+                    trace: RefCell::default(),
+                    uninit_read: UninitRead::default(),
+                    pc: None,
+                    faults: MemFaults::default(),
+                    model_dir: PathBuf::new(),
                 }),
             ],
         };
@@ -737,4 +1338,628 @@ mod test {
         assert_eq!(simulator.cycle, 9);
         assert_eq!(simulator.get_input_value(out), 0x12.into());
     }
+
+    #[test]
+    fn test_paged_memory() {
+        let memory = Memory::default();
+        // a word across a page boundary
+        let addr = MEMORY_PAGE_SIZE - 2;
+        memory.write(addr, 4, true, 0x1234_5678.into());
+        assert_eq!(memory.read(addr, 4, false, true), 0x1234_5678.into());
+        assert_eq!(memory.read(addr, 2, false, false), 0x3412.into());
+        assert_eq!(memory.borrow().pages(), 2);
+        // untouched bytes read as zero, absent outside allocated pages
+        assert_eq!(memory.borrow().get(&0), Some(&0));
+        assert!(!memory.borrow().is_init(0));
+        assert!(memory.borrow().is_init(addr + 3));
+        assert_eq!(memory.borrow().get(&(4 * MEMORY_PAGE_SIZE)), None);
+        assert_eq!(memory.read(0x1000_0000, 4, false, true), 0.into());
+        assert_eq!(memory.borrow().iter().count(), 2 * MEMORY_PAGE_SIZE);
+
+        // serialized as the initialised bytes, zeros included
+        memory.write(addr + 4, 1, true, 0.into());
+        let json = serde_json::to_string(&memory).unwrap();
+        assert_eq!(
+            json,
+            r#"{"4094":18,"4095":52,"4096":86,"4097":120,"4098":0}"#
+        );
+        let memory: Memory = serde_json::from_str(&json).unwrap();
+        assert_eq!(memory.read(addr, 4, false, false), 0x7856_3412.into());
+        assert!(memory.borrow().is_init(addr + 4));
+        assert!(!memory.borrow().is_init(addr + 5));
+    }
+
+    #[test]
+    fn test_mem_image() {
+        let dir = std::env::temp_dir().join(format!("syncrim_mem_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let hex = dir.join("prog.hex");
+        std::fs::write(&hex, ":0400100001020304E2\n:00000001FF\n").unwrap();
+
+        let mut cs = ComponentStore {
+            store: vec![
+                Rc::new(ProbeOut::new("data_o")),
+                Rc::new(ProbeOut::new("addr")),
+                Rc::new(ProbeOut::new("ctrl")),
+                Rc::new(ProbeOut::new("size")),
+                Rc::new(ProbeOut::new("sign")),
+                Mem::rc_new_from_image(
+                    "mem",
+                    (0.0, 0.0),
+                    0.0,
+                    0.0,
+                    true,
+                    Input::new("data_o", "out"),
+                    Input::new("addr", "out"),
+                    Input::new("ctrl", "out"),
+                    Input::new("sign", "out"),
+                    Input::new("size", "out"),
+                    // relative to the model
+                    MemImage::new("prog.hex", MemImageFormat::Auto, 0),
+                    0..0x20,
+                ),
+            ],
+        };
+        cs.set_model_dir(&dir);
+        let mut simulator = Simulator::new(cs).unwrap();
+        let out = &Input::new("mem", "data_o");
+
+        simulator.set_out_value("addr", "out", 0x10);
+        simulator.set_out_value("ctrl", "out", MemCtrl::Read as SignalUnsigned);
+        simulator.set_out_value("size", "out", 4);
+        simulator.clock();
+        assert_eq!(simulator.get_input_value(out), 0x0102_0304.into());
+
+        simulator.set_out_value("data_o", "out", 0xff);
+        simulator.set_out_value("ctrl", "out", MemCtrl::Write as SignalUnsigned);
+        simulator.set_out_value("size", "out", 1);
+        simulator.clock();
+        assert_eq!(*simulator.get_state(), RunningState::Stopped);
+
+        // the image is loaded again on reset, overwriting the written byte
+        simulator.reset();
+        let memory = simulator
+            .ordered_components
+            .iter()
+            .find_map(|c| c.as_any().downcast_ref::<Mem>())
+            .unwrap()
+            .memory
+            .clone();
+        assert_eq!(memory.borrow().get(&0x10), Some(&1));
+
+        // a missing image is an error
+        std::fs::remove_file(&hex).unwrap();
+        simulator.reset();
+        assert_eq!(*simulator.get_state(), RunningState::Err);
+    }
+
+    #[test]
+    fn test_write_history() {
+        let mut writes = WriteHistory::default();
+        for cycle in 1..4 {
+            writes.clock(cycle);
+            writes.record(cycle, &[None], &[cycle as u8]);
+        }
+        // clocking an earlier cycle again drops it and the later entries
+        writes.clock(2);
+        assert!(writes.written().is_empty());
+        assert_eq!(writes.diff(0, 4), BTreeMap::from([(1, (0, 1))]));
+        assert_eq!(writes.un_clock(), vec![]);
+        assert_eq!(writes.un_clock().len(), 1);
+        assert!(writes.un_clock().is_empty());
+    }
+
+    #[test]
+    fn test_mem_async_reset() {
+        let cs = ComponentStore {
+            store: vec![
+                Rc::new(ProbeOut::new("data_o")),
+                Rc::new(ProbeOut::new("next")),
+                Rc::new(ProbeOut::new("rst")),
+                Rc::new(ProbeOut::new("ctrl")),
+                Rc::new(ProbeOut::new("size")),
+                Rc::new(ProbeOut::new("sign")),
+                Register::rc_new_with_reset(
+                    "pc",
+                    (0.0, 0.0),
+                    Input::new("next", "out"),
+                    ResetInput::new(Input::new("rst", "out"), 0x10, ResetKind::Async),
+                ),
+                Mem::rc_new_from_bytes(
+                    "mem",
+                    (0.0, 0.0),
+                    0.0,
+                    0.0,
+                    true,
+                    Input::new("data_o", "out"),
+                    Input::new("pc", "out"),
+                    Input::new("ctrl", "out"),
+                    Input::new("sign", "out"),
+                    Input::new("size", "out"),
+                    BTreeMap::from([(0x10, 0xaa), (0x20, 0xbb)]),
+                    0..0x40,
+                ),
+            ],
+        };
+        let mut simulator = Simulator::new(cs).unwrap();
+        let out = &Input::new("mem", MEM_DATA_OUT_ID);
+
+        simulator.set_out_value("next", "out", 0x20);
+        simulator.set_out_value("ctrl", "out", MemCtrl::Read as SignalUnsigned);
+        simulator.set_out_value("size", "out", 1);
+        simulator.clock();
+        assert_eq!(simulator.get_input_value(out), 0xbb.into());
+
+        // the memory reads the reset address in the cycle the reset is asserted
+        simulator.set_out_value("rst", "out", 1);
+        simulator.clock();
+        assert_eq!(
+            simulator.get_input_value(&Input::new("pc", "out")),
+            0x10.into()
+        );
+        assert_eq!(simulator.get_input_value(out), 0xaa.into());
+
+        simulator.set_out_value("rst", "out", 0);
+        simulator.clock();
+        assert_eq!(simulator.get_input_value(out), 0xbb.into());
+    }
+
+    #[test]
+    fn test_mem_reset_kind() {
+        let read = |kind| {
+            let mut mem = Mem::new(
+                "mem",
+                (0.0, 0.0),
+                0.0,
+                0.0,
+                true,
+                Input::new("data_i", "out"),
+                Input::new("addr", "out"),
+                Input::new("ctrl", "out"),
+                Input::new("sign", "out"),
+                Input::new("size", "out"),
+                BTreeMap::from([(0x10, 0xaa)]),
+                0..0x40,
+            );
+            mem.reset = Some(ResetInput::new(Input::new("rst", "out"), 0x55, kind));
+            let cs = ComponentStore {
+                store: vec![
+                    Rc::new(ProbeOut::new("data_i")),
+                    Rc::new(ProbeOut::new("addr")),
+                    Rc::new(ProbeOut::new("ctrl")),
+                    Rc::new(ProbeOut::new("size")),
+                    Rc::new(ProbeOut::new("sign")),
+                    ProbeStim::rc_new("rst", (0.0, 0.0), vec![0, 1, 0, 0]),
+                    Rc::new(mem),
+                ],
+            };
+            let mut simulator = Simulator::new(cs).unwrap();
+            simulator.set_out_value("addr", "out", 0x10);
+            simulator.set_out_value("ctrl", "out", MemCtrl::Read as SignalUnsigned);
+            simulator.set_out_value("size", "out", 1);
+            (1..4)
+                .map(|_| {
+                    simulator.clock();
+                    simulator.get_input_value(&Input::new("mem", MEM_DATA_OUT_ID))
+                })
+                .collect::<Vec<_>>()
+        };
+        // an asynchronous reset acts in the cycle it is asserted, a
+        // synchronous one from the following clock edge
+        assert_eq!(
+            read(ResetKind::Async),
+            vec![0x55.into(), 0xaa.into(), 0xaa.into()]
+        );
+        assert_eq!(
+            read(ResetKind::Sync),
+            vec![0xaa.into(), 0x55.into(), 0xaa.into()]
+        );
+    }
+
+    fn get_mem(simulator: &Simulator) -> &Mem {
+        simulator
+            .ordered_components
+            .iter()
+            .find_map(|c| c.as_any().downcast_ref::<Mem>())
+            .unwrap()
+    }
+
+    #[test]
+    fn test_mem_writes() {
+        let cs = ComponentStore {
+            store: vec![
+                Rc::new(ProbeOut::new("data_o")),
+                Rc::new(ProbeOut::new("addr")),
+                Rc::new(ProbeOut::new("ctrl")),
+                Rc::new(ProbeOut::new("size")),
+                Rc::new(ProbeOut::new("sign")),
+                Mem::rc_new(
+                    "mem",
+                    (0.0, 0.0),
+                    0.0,
+                    0.0,
+                    true,
+                    Input::new("data_o", "out"),
+                    Input::new("addr", "out"),
+                    Input::new("ctrl", "out"),
+                    Input::new("sign", "out"),
+                    Input::new("size", "out"),
+                    0..0x20,
+                ),
+            ],
+        };
+        let mut simulator = Simulator::new(cs).unwrap();
+
+        simulator.set_out_value("data_o", "out", 0x1234_5678);
+        simulator.set_out_value("addr", "out", 4);
+        simulator.set_out_value("ctrl", "out", MemCtrl::Write as SignalUnsigned);
+        simulator.set_out_value("size", "out", 4);
+        simulator.clock();
+        assert_eq!(get_mem(&simulator).written(), BTreeSet::from([4, 5, 6, 7]));
+
+        simulator.set_out_value("data_o", "out", 0xaa);
+        simulator.set_out_value("addr", "out", 5);
+        simulator.set_out_value("size", "out", 1);
+        simulator.clock();
+        assert_eq!(get_mem(&simulator).written(), BTreeSet::from([5]));
+
+        simulator.set_out_value("ctrl", "out", MemCtrl::None as SignalUnsigned);
+        simulator.clock();
+        assert!(get_mem(&simulator).written().is_empty());
+        assert_eq!(
+            get_mem(&simulator).diff(1, 4),
+            BTreeMap::from([
+                (4, (0, 0x12)),
+                (5, (0, 0xaa)),
+                (6, (0, 0x56)),
+                (7, (0, 0x78))
+            ])
+        );
+        assert_eq!(
+            get_mem(&simulator).diff(2, 4),
+            BTreeMap::from([(5, (0x34, 0xaa))])
+        );
+
+        let path = std::env::temp_dir().join(format!("syncrim_dump_{}.bin", std::process::id()));
+        get_mem(&simulator)
+            .dump(&path, MemImageFormat::Binary, 4..8)
+            .unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), [0x12, 0xaa, 0x56, 0x78]);
+        std::fs::remove_file(path).unwrap();
+
+        // stepping back restores the overwritten byte
+        simulator.un_clock();
+        simulator.un_clock();
+        assert_eq!(get_mem(&simulator).memory.borrow().get(&5), Some(&0x34));
+        assert_eq!(get_mem(&simulator).written(), BTreeSet::from([4, 5, 6, 7]));
+
+        // a reset restores the initial contents
+        simulator.reset();
+        assert_eq!(get_mem(&simulator).memory.borrow().get(&5), Some(&0));
+        assert!(get_mem(&simulator).written().is_empty());
+    }
+
+    #[test]
+    fn test_mem_latency() {
+        let cs = ComponentStore {
+            store: vec![
+                Rc::new(ProbeOut::new("data_o")),
+                Rc::new(ProbeOut::new("addr")),
+                Rc::new(ProbeOut::new("ctrl")),
+                Rc::new(ProbeOut::new("size")),
+                Rc::new(ProbeOut::new("sign")),
+                Rc::new(
+                    Mem::new(
+                        "mem",
+                        (0.0, 0.0),
+                        0.0,
+                        0.0,
+                        true,
+                        Input::new("data_o", "out"),
+                        Input::new("addr", "out"),
+                        Input::new("ctrl", "out"),
+                        Input::new("sign", "out"),
+                        Input::new("size", "out"),
+                        BTreeMap::new(),
+                        0..0x200,
+                    )
+                    .with_latency(MemLatency::new(
+                        2,
+                        1,
+                        vec![LatencyRegion::new(0x100..0x200, 0, 0)],
+                    )),
+                ),
+            ],
+        };
+        let mut simulator = Simulator::new(cs).unwrap();
+        let out = &Input::new("mem", MEM_DATA_OUT_ID);
+        let stall = &Input::new("mem", MEM_STALL_OUT_ID);
+        assert_eq!(simulator.get_input_value(stall), false.into());
+
+        // one wait state for writes
+        simulator.set_out_value("data_o", "out", 0x42);
+        simulator.set_out_value("addr", "out", 4);
+        simulator.set_out_value("ctrl", "out", MemCtrl::Write as SignalUnsigned);
+        simulator.set_out_value("size", "out", 1);
+        simulator.clock();
+        assert_eq!(simulator.get_input_value(stall), true.into());
+        assert!(get_mem(&simulator).written().is_empty());
+        simulator.clock();
+        assert_eq!(simulator.get_input_value(stall), false.into());
+        assert_eq!(get_mem(&simulator).written(), BTreeSet::from([4]));
+
+        // two for reads
+        simulator.set_out_value("ctrl", "out", MemCtrl::Read as SignalUnsigned);
+        let mut trace = vec![];
+        for _ in 0..3 {
+            simulator.clock();
+            trace.push(simulator.get_input_value(stall));
+        }
+        assert_eq!(trace, vec![true.into(), true.into(), false.into()]);
+        assert_eq!(simulator.get_input_value(out), 0x42.into());
+
+        // stepping back into the wait
+        simulator.un_clock();
+        simulator.clock();
+        assert_eq!(simulator.get_input_value(stall), false.into());
+
+        // none in the region
+        simulator.set_out_value("addr", "out", 0x104);
+        simulator.clock();
+        assert_eq!(simulator.get_input_value(stall), false.into());
+        assert_eq!(simulator.get_input_value(out), 0.into());
+    }
+
+    #[test]
+    fn test_mem_uninit_read() {
+        let mem = |uninit_read| {
+            let cs = ComponentStore {
+                store: vec![
+                    Rc::new(ProbeOut::new("data_o")),
+                    Rc::new(ProbeOut::new("addr")),
+                    Rc::new(ProbeOut::new("ctrl")),
+                    Rc::new(ProbeOut::new("size")),
+                    Rc::new(ProbeOut::new("sign")),
+                    Rc::new(ProbeOut::new("pc")),
+                    Rc::new(
+                        Mem::new(
+                            "mem",
+                            (0.0, 0.0),
+                            0.0,
+                            0.0,
+                            true,
+                            Input::new("data_o", "out"),
+                            Input::new("addr", "out"),
+                            Input::new("ctrl", "out"),
+                            Input::new("sign", "out"),
+                            Input::new("size", "out"),
+                            BTreeMap::new(),
+                            0..0x100,
+                        )
+                        .with_uninit_read(uninit_read, Some(Input::new("pc", "out"))),
+                    ),
+                ],
+            };
+            let mut simulator = Simulator::new(cs).unwrap();
+            // a byte written, the rest of the word not
+            simulator.set_out_value("data_o", "out", 0x42);
+            simulator.set_out_value("addr", "out", 0x10);
+            simulator.set_out_value("ctrl", "out", MemCtrl::Write as SignalUnsigned);
+            simulator.set_out_value("size", "out", 1);
+            simulator.set_out_value("pc", "out", 0x400);
+            simulator.clock();
+            simulator.set_out_value("ctrl", "out", MemCtrl::Read as SignalUnsigned);
+            simulator.clock();
+            simulator
+        };
+        let out = &Input::new("mem", MEM_DATA_OUT_ID);
+
+        let mut simulator = mem(UninitRead::Zero);
+        assert_eq!(simulator.get_input_value(out), 0x42.into());
+        simulator.set_out_value("size", "out", 4);
+        simulator.clock();
+        assert_eq!(simulator.get_input_value(out), 0x4200_0000.into());
+        assert!(simulator.component_condition.is_empty());
+
+        let mut simulator = mem(UninitRead::Uninitialized);
+        assert_eq!(simulator.get_input_value(out), 0x42.into());
+        simulator.set_out_value("size", "out", 4);
+        simulator.clock();
+        assert_eq!(simulator.get_input_value(out), SignalValue::Uninitialized);
+
+        let mut simulator = mem(UninitRead::Warning);
+        assert!(simulator.component_condition.is_empty());
+        simulator.set_out_value("size", "out", 4);
+        simulator.clock();
+        assert_eq!(simulator.get_input_value(out), 0x4200_0000.into());
+        assert_eq!(
+            simulator.component_condition,
+            vec![(
+                "mem".to_string(),
+                Condition::Warning(
+                    "mem mem: read of uninitialised address 0x11 at pc 0x400".to_string()
+                )
+            )]
+        );
+
+        // stepping back over the write makes the byte uninitialised again
+        for _ in 0..3 {
+            simulator.un_clock();
+        }
+        assert!(!get_mem(&simulator).memory.borrow().is_init(0x10));
+        // the trace is not recorded unless enabled
+        assert!(get_mem(&simulator).trace().entries().is_empty());
+    }
+
+    #[test]
+    fn test_mem_trace() {
+        let cs = ComponentStore {
+            store: vec![
+                Rc::new(ProbeOut::new("data_o")),
+                Rc::new(ProbeOut::new("addr")),
+                Rc::new(ProbeOut::new("ctrl")),
+                Rc::new(ProbeOut::new("size")),
+                Rc::new(ProbeOut::new("sign")),
+                Rc::new(
+                    Mem::new(
+                        "mem",
+                        (0.0, 0.0),
+                        0.0,
+                        0.0,
+                        false,
+                        Input::new("data_o", "out"),
+                        Input::new("addr", "out"),
+                        Input::new("ctrl", "out"),
+                        Input::new("sign", "out"),
+                        Input::new("size", "out"),
+                        BTreeMap::new(),
+                        0..0x200,
+                    )
+                    .with_trace(),
+                ),
+            ],
+        };
+        let mut simulator = Simulator::new(cs).unwrap();
+        simulator.set_out_value("data_o", "out", 0x1234);
+        simulator.set_out_value("addr", "out", 0x100);
+        simulator.set_out_value("ctrl", "out", MemCtrl::Write as SignalUnsigned);
+        simulator.set_out_value("size", "out", 2);
+        simulator.clock();
+        simulator.set_out_value("ctrl", "out", MemCtrl::Read as SignalUnsigned);
+        simulator.clock();
+        simulator.set_out_value("addr", "out", 0x10);
+        simulator.clock();
+        // no access
+        simulator.set_out_value("ctrl", "out", MemCtrl::None as SignalUnsigned);
+        simulator.clock();
+
+        assert_eq!(
+            get_mem(&simulator).trace().to_csv(),
+            "cycle,addr,size,op,value\n\
+             1,0x00000100,2,W,0x00001234\n\
+             2,0x00000100,2,R,0x00001234\n\
+             3,0x00000010,2,R,0x00000000\n"
+        );
+        let heat_map = get_mem(&simulator).trace().heat_map(0x100);
+        assert_eq!(
+            heat_map.into_iter().collect::<Vec<_>>(),
+            vec![
+                (
+                    0,
+                    RegionAccesses {
+                        reads: 1,
+                        writes: 0
+                    }
+                ),
+                (
+                    0x100,
+                    RegionAccesses {
+                        reads: 1,
+                        writes: 1
+                    }
+                ),
+            ]
+        );
+
+        // stepping back drops the accesses
+        simulator.un_clock();
+        simulator.un_clock();
+        assert_eq!(get_mem(&simulator).trace().entries().len(), 2);
+        simulator.clock();
+        assert_eq!(get_mem(&simulator).trace().entries().len(), 3);
+        simulator.reset();
+        assert!(get_mem(&simulator).trace().entries().is_empty());
+    }
+
+    #[test]
+    fn test_mem_faults() {
+        let mem = |faults| {
+            let cs = ComponentStore {
+                store: vec![
+                    Rc::new(ProbeOut::new("data_o")),
+                    Rc::new(ProbeOut::new("addr")),
+                    Rc::new(ProbeOut::new("ctrl")),
+                    Rc::new(ProbeOut::new("size")),
+                    Rc::new(ProbeOut::new("sign")),
+                    Rc::new(
+                        Mem::new(
+                            "mem",
+                            (0.0, 0.0),
+                            0.0,
+                            0.0,
+                            true,
+                            Input::new("data_o", "out"),
+                            Input::new("addr", "out"),
+                            Input::new("ctrl", "out"),
+                            Input::new("sign", "out"),
+                            Input::new("size", "out"),
+                            BTreeMap::new(),
+                            0..0x100,
+                        )
+                        .with_faults(faults),
+                    ),
+                ],
+            };
+            let mut simulator = Simulator::new(cs).unwrap();
+            simulator.set_out_value("data_o", "out", 0x42);
+            simulator.set_out_value("ctrl", "out", MemCtrl::Write as SignalUnsigned);
+            simulator.set_out_value("size", "out", 4);
+            simulator
+        };
+        let trap = &Input::new("mem", MEM_TRAP_OUT_ID);
+        let cause = &Input::new("mem", MEM_TRAP_CAUSE_OUT_ID);
+
+        // a trapped store is not done
+        let mut simulator = mem(MemFaults::new(
+            FaultAction::Trap,
+            FaultAction::Trap,
+            FaultAction::Trap,
+        ));
+        simulator.set_out_value("addr", "out", 0x12);
+        simulator.clock();
+        assert_eq!(simulator.get_input_value(trap), true.into());
+        assert_eq!(simulator.get_input_value(cause), 6.into());
+        assert!(get_mem(&simulator).written().is_empty());
+        simulator.set_out_value("addr", "out", 0xfe);
+        simulator.set_out_value("ctrl", "out", MemCtrl::Read as SignalUnsigned);
+        simulator.clock();
+        assert_eq!(simulator.get_input_value(cause), 5.into());
+        simulator.set_out_value("addr", "out", 0x10);
+        simulator.set_out_value("size", "out", 3);
+        simulator.clock();
+        assert_eq!(simulator.get_input_value(cause), 5.into());
+        simulator.set_out_value("size", "out", 4);
+        simulator.clock();
+        assert_eq!(simulator.get_input_value(trap), false.into());
+
+        // a warned store is done
+        let mut simulator = mem(MemFaults::new(
+            FaultAction::Warn,
+            FaultAction::Halt,
+            FaultAction::Panic,
+        ));
+        simulator.set_out_value("addr", "out", 0x12);
+        simulator.clock();
+        assert_eq!(get_mem(&simulator).written().len(), 4);
+        assert_eq!(
+            simulator.component_condition,
+            vec![(
+                "mem".to_string(),
+                Condition::Warning("mem mem: misaligned store of size 4 at 0x12".to_string())
+            )]
+        );
+        simulator.set_out_value("addr", "out", 0x100);
+        simulator.clock();
+        assert_eq!(simulator.running_state, RunningState::Halt);
+        assert!(get_mem(&simulator).written().is_empty());
+
+        // by default a store at the top of the address space is done
+        let mut simulator = mem(MemFaults::default());
+        simulator.set_out_value("addr", "out", 0xffff_fffc);
+        simulator.clock();
+        assert_eq!(
+            get_mem(&simulator).written(),
+            BTreeSet::from([0xffff_fffc, 0xffff_fffd, 0xffff_fffe, 0xffff_ffff])
+        );
+    }
 }
@@ -0,0 +1,176 @@
+use crate::common::{Condition, SignalUnsigned};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::ops::Range;
+
+pub const MEM_TRAP_OUT_ID: &str = "trap";
+pub const MEM_TRAP_CAUSE_OUT_ID: &str = "trap_cause";
+
+/// A faulty memory access
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MemFault {
+    /// The address is not a multiple of the size
+    Misaligned,
+    /// Some byte of the access is outside the range of the memory
+    OutOfRange,
+    /// The size is not 1, 2 or 4
+    IllegalSize,
+}
+
+impl MemFault {
+    /// The RISC-V exception code (mcause) of the fault
+    pub fn cause(&self, write: bool) -> SignalUnsigned {
+        match (self, write) {
+            (MemFault::Misaligned, false) => 4,
+            (MemFault::Misaligned, true) => 6,
+            (_, false) => 5,
+            (_, true) => 7,
+        }
+    }
+}
+
+impl fmt::Display for MemFault {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MemFault::Misaligned => write!(f, "misaligned"),
+            MemFault::OutOfRange => write!(f, "out of range"),
+            MemFault::IllegalSize => write!(f, "illegal size"),
+        }
+    }
+}
+
+/// What a memory does on a faulty access
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FaultAction {
+    /// The access is done as far as possible
+    Ignore,
+    /// The simulator panics
+    Panic,
+    /// The access is done as far as possible and a warning given
+    Warn,
+    /// The access is not done and the simulation halted
+    Halt,
+    /// The access is not done, `trap` is set and `trap_cause` gives the
+    /// RISC-V exception code, see `MemFault::cause`
+    Trap,
+}
+
+impl FaultAction {
+    pub const ALL: [FaultAction; 5] = [
+        FaultAction::Ignore,
+        FaultAction::Panic,
+        FaultAction::Warn,
+        FaultAction::Halt,
+        FaultAction::Trap,
+    ];
+}
+
+/// What a memory does on misaligned, out of range and illegal size accesses
+///
+/// The defaults keep the plain memory behaviour, misaligned and out of range
+/// accesses are done (only `err` tells misalignment) and an illegal size
+/// panics. An access with an illegal size is never done.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemFaults {
+    pub misaligned: FaultAction,
+    pub out_of_range: FaultAction,
+    pub illegal_size: FaultAction,
+}
+
+impl Default for MemFaults {
+    fn default() -> Self {
+        MemFaults {
+            misaligned: FaultAction::Ignore,
+            out_of_range: FaultAction::Ignore,
+            illegal_size: FaultAction::Panic,
+        }
+    }
+}
+
+/// The outcome of checking an access, see `MemFaults::check`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FaultCheck {
+    // the access is not to be done
+    pub suppress: bool,
+    // the exception code to drive on `trap_cause`
+    pub trap: Option<SignalUnsigned>,
+    pub condition: Option<Condition>,
+}
+
+impl MemFaults {
+    pub fn new(
+        misaligned: FaultAction,
+        out_of_range: FaultAction,
+        illegal_size: FaultAction,
+    ) -> Self {
+        MemFaults {
+            misaligned,
+            out_of_range,
+            illegal_size,
+        }
+    }
+
+    /// The first fault of an access, in the order illegal size, out of range
+    /// and misaligned
+    pub fn fault(&self, addr: u32, size: u32, range: &Range<u32>) -> Option<MemFault> {
+        if ![1, 2, 4].contains(&size) {
+            Some(MemFault::IllegalSize)
+        } else if addr < range.start || addr as u64 + size as u64 > range.end as u64 {
+            Some(MemFault::OutOfRange)
+        } else if addr & (size - 1) != 0 {
+            Some(MemFault::Misaligned)
+        } else {
+            None
+        }
+    }
+
+    /// Check an access of memory `id`, panics if so configured
+    pub fn check(
+        &self,
+        id: &str,
+        addr: u32,
+        size: u32,
+        write: bool,
+        range: &Range<u32>,
+    ) -> FaultCheck {
+        let Some(fault) = self.fault(addr, size, range) else {
+            return FaultCheck::default();
+        };
+        let action = match fault {
+            MemFault::Misaligned => self.misaligned,
+            MemFault::OutOfRange => self.out_of_range,
+            MemFault::IllegalSize => self.illegal_size,
+        };
+        let message = format!(
+            "mem {}: {} {} of size {} at {:#x}",
+            id,
+            fault,
+            if write { "store" } else { "load" },
+            size,
+            addr
+        );
+        let suppress = fault == MemFault::IllegalSize;
+        match action {
+            FaultAction::Ignore => FaultCheck {
+                suppress,
+                ..FaultCheck::default()
+            },
+            FaultAction::Panic => panic!("{}", message),
+            FaultAction::Warn => FaultCheck {
+                suppress,
+                trap: None,
+                condition: Some(Condition::Warning(message)),
+            },
+            FaultAction::Halt => FaultCheck {
+                suppress: true,
+                trap: None,
+                condition: Some(Condition::Halt(message)),
+            },
+            FaultAction::Trap => FaultCheck {
+                suppress: true,
+                trap: Some(fault.cause(write)),
+                condition: None,
+            },
+        }
+    }
+}
@@ -0,0 +1,115 @@
+use crate::common::SignalValue;
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+
+pub const MEM_STALL_OUT_ID: &str = "stall";
+
+/// Wait states of an address region, overriding the memory defaults
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct LatencyRegion {
+    pub range: Range<u32>,
+    pub read_wait: u32,
+    pub write_wait: u32,
+}
+
+impl LatencyRegion {
+    pub fn new(range: Range<u32>, read_wait: u32, write_wait: u32) -> Self {
+        LatencyRegion {
+            range,
+            read_wait,
+            write_wait,
+        }
+    }
+}
+
+/// Wait states of a memory access, by address region
+///
+/// An access with n wait states takes n + 1 cycles. During the first n the
+/// `stall` output is set and the memory is left untouched, the access is
+/// done in the cycle `stall` goes low, so the access must be held until then.
+/// Changing the access while stalled starts a new one. The first region
+/// containing the address decides, else the defaults. The default is no wait
+/// states, i.e., every access is done in the cycle it is presented.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct MemLatency {
+    pub read_wait: u32,
+    pub write_wait: u32,
+    #[serde(default)]
+    pub regions: Vec<LatencyRegion>,
+}
+
+impl MemLatency {
+    pub fn new(read_wait: u32, write_wait: u32, regions: Vec<LatencyRegion>) -> Self {
+        MemLatency {
+            read_wait,
+            write_wait,
+            regions,
+        }
+    }
+
+    pub fn wait_states(&self, addr: u32, write: bool) -> u32 {
+        let (read_wait, write_wait) = self
+            .regions
+            .iter()
+            .find(|r| r.range.contains(&addr))
+            .map_or((self.read_wait, self.write_wait), |r| {
+                (r.read_wait, r.write_wait)
+            });
+        if write {
+            write_wait
+        } else {
+            read_wait
+        }
+    }
+}
+
+/// An access presented to a memory, data is unknown for reads
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MemAccess {
+    pub write: bool,
+    pub addr: SignalValue,
+    pub size: SignalValue,
+    pub data: SignalValue,
+}
+
+/// The access waited for and the wait states passed, by clock
+#[derive(Debug, Clone, Default)]
+pub struct WaitStates {
+    history: Vec<Option<(MemAccess, u32)>>,
+}
+
+impl WaitStates {
+    /// Start the entry of a clock, no access waiting
+    pub fn clock(&mut self) {
+        self.history.push(None);
+    }
+
+    /// True if `access` has to wait in this clock, given its `wait_states`
+    pub fn wait(&mut self, access: MemAccess, wait_states: u32) -> bool {
+        let passed = match self.history.iter().rev().nth(1) {
+            Some(Some((waiting, passed))) if *waiting == access => *passed,
+            _ => 0,
+        };
+        if passed < wait_states {
+            if let Some(last) = self.history.last_mut() {
+                *last = Some((access, passed + 1));
+            }
+            true
+        } else {
+            false
+        }
+    }
+
+    /// The access waited for in the last clock and the wait states passed
+    pub fn waiting(&self) -> Option<(MemAccess, u32)> {
+        self.history.last().copied().flatten()
+    }
+
+    pub fn un_clock(&mut self) {
+        self.history.pop();
+    }
+
+    pub fn clear(&mut self) {
+        self.history.clear();
+    }
+}
@@ -0,0 +1,113 @@
+use crate::common::SignalUnsigned;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+/// A load or store done by a memory
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TraceEntry {
+    pub cycle: usize,
+    pub addr: u32,
+    pub size: u32,
+    pub write: bool,
+    // the value read or written
+    pub value: SignalUnsigned,
+}
+
+/// Accesses seen per address region, see `MemTrace::heat_map`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RegionAccesses {
+    pub reads: usize,
+    pub writes: usize,
+}
+
+impl RegionAccesses {
+    pub fn total(&self) -> usize {
+        self.reads + self.writes
+    }
+}
+
+/// Log of the loads and stores done by a memory, when enabled on it
+///
+/// Entries are kept in cycle order, clocking a cycle again after stepping
+/// back drops the entries of that cycle and later.
+#[derive(Debug, Clone, Default)]
+pub struct MemTrace {
+    entries: Vec<TraceEntry>,
+    cycle: usize,
+}
+
+impl MemTrace {
+    /// Start the clock at `cycle`, dropping any entries from it on
+    pub fn clock(&mut self, cycle: usize) {
+        self.cycle = cycle;
+        self.truncate(cycle);
+    }
+
+    pub fn record(&mut self, addr: u32, size: u32, write: bool, value: SignalUnsigned) {
+        self.entries.push(TraceEntry {
+            cycle: self.cycle,
+            addr,
+            size,
+            write,
+            value,
+        });
+    }
+
+    /// Drop the entries of the last clock
+    pub fn un_clock(&mut self) {
+        self.truncate(self.cycle);
+        self.cycle = self.cycle.saturating_sub(1);
+    }
+
+    // drop the entries from `cycle` on, entries are in cycle order
+    fn truncate(&mut self, cycle: usize) {
+        let keep = self.entries.partition_point(|e| e.cycle < cycle);
+        self.entries.truncate(keep);
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    pub fn entries(&self) -> &[TraceEntry] {
+        &self.entries
+    }
+
+    /// Accesses by region of `region_size` bytes, keyed by the region start
+    pub fn heat_map(&self, region_size: u32) -> BTreeMap<u32, RegionAccesses> {
+        let mut map: BTreeMap<u32, RegionAccesses> = BTreeMap::new();
+        for entry in &self.entries {
+            let region = map
+                .entry(entry.addr - entry.addr % region_size.max(1))
+                .or_default();
+            if entry.write {
+                region.writes += 1;
+            } else {
+                region.reads += 1;
+            }
+        }
+        map
+    }
+
+    /// The trace as csv, one access per line
+    pub fn to_csv(&self) -> String {
+        let mut csv = "cycle,addr,size,op,value\n".to_string();
+        for e in &self.entries {
+            let op = if e.write { "W" } else { "R" };
+            writeln!(
+                csv,
+                "{},{:#010x},{},{},{:#010x}",
+                e.cycle, e.addr, e.size, op, e.value
+            )
+            .unwrap();
+        }
+        csv
+    }
+
+    /// Write the trace to `path` as csv
+    pub fn export(&self, path: &Path) -> Result<(), String> {
+        fs::write(path, self.to_csv()).map_err(|e| format!("{:?}: {}", path, e))
+    }
+}
@@ -0,0 +1,183 @@
+#[cfg(feature = "gui-egui")]
+use crate::common::EguiComponent;
+use crate::common::{
+    Component, Condition, Id, Input, InputPort, OutputType, Ports, SignalUnsigned, SignalValue,
+    Simulator,
+};
+use crate::components::BitField;
+use log::*;
+use serde::{Deserialize, Serialize};
+use std::any::Any;
+use std::rc::Rc;
+
+pub const MERGER_TEMPLATE_ID: &str = "in";
+pub const MERGER_OUT_ID: &str = "out";
+
+/// Field of the merged bus, the low `width` bits of `input`
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MergerField {
+    pub input: Input,
+    pub width: u32,
+}
+
+impl MergerField {
+    pub fn new(input: Input, width: u32) -> Self {
+        MergerField { input, width }
+    }
+}
+
+/// Concatenates fields into a bus, the first field in the least significant bits
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Merger {
+    pub(crate) id: Id,
+    pub(crate) pos: (f32, f32),
+    pub(crate) fields: Vec<MergerField>,
+}
+
+#[typetag::serde]
+impl Component for Merger {
+    fn to_(&self) {
+        trace!("merger {:?}", self.fields);
+    }
+    #[cfg(feature = "gui-egui")]
+    fn dummy(&self, id: &str, pos: (f32, f32)) -> Box<Rc<dyn EguiComponent>> {
+        let dummy_input = Input::new("dummy", "out");
+        Box::new(Rc::new(Merger {
+            id: id.to_string(),
+            pos: (pos.0, pos.1),
+            fields: self
+                .fields
+                .iter()
+                .map(|f| MergerField::new(dummy_input.clone(), f.width))
+                .collect(),
+        }))
+    }
+    fn get_id_ports(&self) -> (Id, Ports) {
+        let inputs: Vec<InputPort> = self
+            .fields
+            .iter()
+            .enumerate()
+            .map(|(i, field)| InputPort {
+                port_id: format!("{}{}", MERGER_TEMPLATE_ID, i),
+                input: field.input.clone(),
+            })
+            .collect();
+        (
+            self.id.clone(),
+            Ports::new(
+                inputs.iter().collect(),
+                OutputType::Combinatorial,
+                vec![MERGER_OUT_ID],
+            ),
+        )
+    }
+
+    fn clock(&self, simulator: &mut Simulator) -> Result<(), Condition> {
+        let mut value: SignalUnsigned = 0;
+        let mut lsb = 0;
+        let mut res = Ok(());
+        for (i, field) in self.fields.iter().enumerate() {
+            let bits = BitField::new(&format!("{}{}", MERGER_TEMPLATE_ID, i), lsb, field.width);
+            if let Err(e) = bits.check() {
+                res = Err(Condition::Error(e));
+                break;
+            }
+            match simulator.get_input_value(&field.input) {
+                SignalValue::Data(v) => value |= (v & bits.mask()) << lsb,
+                _ => {
+                    simulator.set_out_value(&self.id, MERGER_OUT_ID, SignalValue::Unknown);
+                    return Ok(());
+                }
+            }
+            lsb += field.width;
+        }
+        simulator.set_out_value(
+            &self.id,
+            MERGER_OUT_ID,
+            match res {
+                Ok(()) => value.into(),
+                Err(_) => SignalValue::Unknown,
+            },
+        );
+        res
+    }
+
+    fn set_id_port(&mut self, target_port_id: Id, new_input: Input) {
+        for (i, field) in self.fields.iter_mut().enumerate() {
+            if target_port_id == format!("{}{}", MERGER_TEMPLATE_ID, i) {
+                field.input = new_input;
+                return;
+            }
+        }
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl Merger {
+    pub fn new(id: &str, pos: (f32, f32), fields: Vec<MergerField>) -> Self {
+        Merger {
+            id: id.to_string(),
+            pos,
+            fields,
+        }
+    }
+
+    pub fn rc_new(id: &str, pos: (f32, f32), fields: Vec<MergerField>) -> Rc<Self> {
+        Rc::new(Merger::new(id, pos, fields))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::common::ComponentStore;
+    use crate::components::{ProbeStim, Splitter};
+
+    #[test]
+    fn test_merger() {
+        let cs = ComponentStore {
+            store: vec![
+                ProbeStim::rc_new("word", (0.0, 0.0), vec![0x1234_5678]),
+                ProbeStim::rc_new("bit", (0.0, 0.0), vec![3]),
+                Splitter::rc_new(
+                    "bytes",
+                    (0.0, 0.0),
+                    Input::new("word", "out"),
+                    (0..4)
+                        .map(|i| BitField::new(&format!("b{}", i), 8 * i, 8))
+                        .collect(),
+                ),
+                // byte swap
+                Merger::rc_new(
+                    "swap",
+                    (0.0, 0.0),
+                    (0..4)
+                        .rev()
+                        .map(|i| MergerField::new(Input::new("bytes", &format!("b{}", i)), 8))
+                        .collect(),
+                ),
+                // only the low bits of a field are used
+                Merger::rc_new(
+                    "pad",
+                    (0.0, 0.0),
+                    vec![
+                        MergerField::new(Input::new("bit", "out"), 1),
+                        MergerField::new(Input::new("bytes", "b0"), 8),
+                    ],
+                ),
+            ],
+        };
+        let simulator = Simulator::new(cs).unwrap();
+        assert_eq!(
+            simulator.get_input_value(&Input::new("swap", MERGER_OUT_ID)),
+            0x7856_3412.into()
+        );
+        assert_eq!(
+            simulator.get_input_value(&Input::new("pad", MERGER_OUT_ID)),
+            0xf1.into()
+        );
+    }
+}
@@ -1,37 +1,79 @@
 mod add;
+mod alu;
+mod bus;
+mod cache;
 mod clk;
+mod comparator;
 mod constant;
 mod cross;
+mod dual_port_mem;
+mod fifo;
+mod flip_flop;
+mod fsm;
 mod full_adder;
+mod gate;
+mod generate;
+mod lut;
 mod mem;
+mod mem_fault;
+mod mem_latency;
+mod mem_trace;
+mod merger;
 mod mips_controll_unit;
 mod mips_im;
 mod mips_reg_file;
+mod mul_div;
 mod mux;
 mod probe;
 mod probe_assert;
 mod probe_edit;
 mod probe_out;
 mod probe_stim;
+mod property_assert;
 mod register;
+mod reset;
 mod sext;
+mod shifter;
+mod splitter;
+mod subcircuit;
 mod wire;
 
 pub use add::*;
+pub use alu::*;
+pub use bus::*;
+pub use cache::*;
 pub use clk::*;
+pub use comparator::*;
 pub use constant::*;
 pub use cross::*;
+pub use dual_port_mem::*;
+pub use fifo::*;
+pub use flip_flop::*;
+pub use fsm::*;
 pub use full_adder::*;
+pub use gate::*;
+pub use generate::*;
+pub use lut::*;
 pub use mem::*;
+pub use mem_fault::*;
+pub use mem_latency::*;
+pub use mem_trace::*;
+pub use merger::*;
 pub use mips_controll_unit::*;
 pub use mips_im::*;
 pub use mips_reg_file::*;
+pub use mul_div::*;
 pub use mux::*;
 pub use probe::*;
 pub use probe_assert::*;
 pub use probe_edit::*;
 pub use probe_out::*;
 pub use probe_stim::*;
+pub use property_assert::*;
 pub use register::*;
+pub use reset::*;
 pub use sext::*;
+pub use shifter::*;
+pub use splitter::*;
+pub use subcircuit::*;
 pub use wire::*;
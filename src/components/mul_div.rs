@@ -0,0 +1,387 @@
+#[cfg(feature = "gui-egui")]
+use crate::common::EguiComponent;
+use crate::common::{
+    Component, Condition, Id, Input, InputPort, OutputType, Ports, SignalSigned, SignalUnsigned,
+    SignalValue, Simulator,
+};
+use crate::coverage::{CoverBin, CoverPoint};
+use log::*;
+use serde::{Deserialize, Serialize};
+use std::any::Any;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+pub const MUL_DIV_A_IN_ID: &str = "a_in";
+pub const MUL_DIV_B_IN_ID: &str = "b_in";
+pub const MUL_DIV_OP_IN_ID: &str = "op_in";
+pub const MUL_DIV_START_IN_ID: &str = "start_in";
+pub const MUL_DIV_OUT_ID: &str = "out";
+pub const MUL_DIV_BUSY_OUT_ID: &str = "busy";
+pub const MUL_DIV_DONE_OUT_ID: &str = "done";
+
+/// Operations, numbered as funct3 of the RISC-V M extension
+pub mod mul_div_op {
+    pub const MUL: u32 = 0;
+    pub const MULH: u32 = 1;
+    pub const MULHSU: u32 = 2;
+    pub const MULHU: u32 = 3;
+    pub const DIV: u32 = 4;
+    pub const DIVU: u32 = 5;
+    pub const REM: u32 = 6;
+    pub const REMU: u32 = 7;
+
+    /// all operations by name
+    pub const ALL: [(&str, u32); 8] = [
+        ("MUL", MUL),
+        ("MULH", MULH),
+        ("MULHSU", MULHSU),
+        ("MULHU", MULHU),
+        ("DIV", DIV),
+        ("DIVU", DIVU),
+        ("REM", REM),
+        ("REMU", REMU),
+    ];
+}
+
+#[derive(Debug, Clone, Copy)]
+struct MulDivState {
+    // cycles until done, 0 when idle
+    remaining: u32,
+    result: SignalValue,
+}
+
+/// Multi-cycle multiplier/divider
+///
+/// Operands and operation are sampled on a clock with `start` nonzero while
+/// idle. `busy` is then set for `cycles` cycles, after which the result is
+/// output with `done` set for one cycle. `start` is ignored while busy and
+/// the result is kept until the next operation is done.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct MulDiv {
+    pub(crate) id: Id,
+    pub(crate) pos: (f32, f32),
+    pub(crate) a_in: Input,
+    pub(crate) b_in: Input,
+    pub(crate) op_in: Input,
+    pub(crate) start_in: Input,
+    pub(crate) cycles: u32,
+    #[serde(skip)]
+    history: RefCell<Vec<MulDivState>>,
+}
+
+#[typetag::serde]
+impl Component for MulDiv {
+    fn to_(&self) {
+        trace!("mul_div {} cycles", self.cycles);
+    }
+    #[cfg(feature = "gui-egui")]
+    fn dummy(&self, id: &str, pos: (f32, f32)) -> Box<Rc<dyn EguiComponent>> {
+        let dummy_input = Input::new("dummy", "out");
+        Box::new(Rc::new(MulDiv::new(
+            id,
+            pos,
+            dummy_input.clone(),
+            dummy_input.clone(),
+            dummy_input.clone(),
+            dummy_input,
+            self.cycles,
+        )))
+    }
+    fn get_id_ports(&self) -> (Id, Ports) {
+        (
+            self.id.clone(),
+            Ports::new(
+                vec![
+                    &InputPort {
+                        port_id: MUL_DIV_A_IN_ID.to_string(),
+                        input: self.a_in.clone(),
+                    },
+                    &InputPort {
+                        port_id: MUL_DIV_B_IN_ID.to_string(),
+                        input: self.b_in.clone(),
+                    },
+                    &InputPort {
+                        port_id: MUL_DIV_OP_IN_ID.to_string(),
+                        input: self.op_in.clone(),
+                    },
+                    &InputPort {
+                        port_id: MUL_DIV_START_IN_ID.to_string(),
+                        input: self.start_in.clone(),
+                    },
+                ],
+                OutputType::Sequential,
+                vec![MUL_DIV_OUT_ID, MUL_DIV_BUSY_OUT_ID, MUL_DIV_DONE_OUT_ID],
+            ),
+        )
+    }
+
+    fn set_id_port(&mut self, target_port_id: Id, new_input: Input) {
+        match target_port_id.as_str() {
+            MUL_DIV_A_IN_ID => self.a_in = new_input,
+            MUL_DIV_B_IN_ID => self.b_in = new_input,
+            MUL_DIV_OP_IN_ID => self.op_in = new_input,
+            MUL_DIV_START_IN_ID => self.start_in = new_input,
+            _ => {}
+        }
+    }
+
+    fn clock(&self, simulator: &mut Simulator) -> Result<(), Condition> {
+        // cycle 0 is evaluated on simulator creation and reset, the latter
+        // before `reset`, so start from the idle state
+        if simulator.cycle == 0 {
+            self.history.borrow_mut().clear();
+        }
+        let state = self.state();
+        let mut res = Ok(());
+        // like a reset, any nonzero start is asserted
+        let start =
+            matches!(simulator.get_input_value(&self.start_in), SignalValue::Data(v) if v != 0);
+        let (next, done) = if state.remaining > 0 {
+            let remaining = state.remaining - 1;
+            (MulDivState { remaining, ..state }, remaining == 0)
+        } else if start {
+            let a = simulator.get_input_value(&self.a_in);
+            let b = simulator.get_input_value(&self.b_in);
+            let op = simulator.get_input_value(&self.op_in);
+            let result = match (a, b, op) {
+                (SignalValue::Data(a), SignalValue::Data(b), SignalValue::Data(op)) => {
+                    match MulDiv::compute(op, a, b) {
+                        Some(result) => result.into(),
+                        None => {
+                            res = Err(Condition::Error(format!(
+                                "mul_div {}: undefined operation {}",
+                                self.id, op
+                            )));
+                            SignalValue::Unknown
+                        }
+                    }
+                }
+                _ => SignalValue::Unknown,
+            };
+            let next = MulDivState {
+                remaining: self.cycles,
+                result,
+            };
+            (next, self.cycles == 0)
+        } else {
+            (state, false)
+        };
+        // the output is kept until the next result is done
+        if done {
+            simulator.set_out_value(&self.id, MUL_DIV_OUT_ID, next.result);
+        }
+        simulator.set_out_value(&self.id, MUL_DIV_BUSY_OUT_ID, next.remaining > 0);
+        simulator.set_out_value(&self.id, MUL_DIV_DONE_OUT_ID, done);
+        self.history.borrow_mut().push(next);
+        res
+    }
+
+    fn un_clock(&self) {
+        self.history.borrow_mut().pop();
+    }
+
+    fn reset(&self) {
+        self.history.borrow_mut().clear();
+    }
+
+    fn cover_points(&self) -> Vec<CoverPoint> {
+        let bins = mul_div_op::ALL
+            .iter()
+            .map(|(name, op)| CoverBin::new(name, *op))
+            .collect();
+        vec![CoverPoint::new(MUL_DIV_OP_IN_ID, self.op_in.clone(), bins)]
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl MulDiv {
+    pub fn new(
+        id: &str,
+        pos: (f32, f32),
+        a_in: Input,
+        b_in: Input,
+        op_in: Input,
+        start_in: Input,
+        cycles: u32,
+    ) -> Self {
+        MulDiv {
+            id: id.to_string(),
+            pos,
+            a_in,
+            b_in,
+            op_in,
+            start_in,
+            cycles,
+            history: RefCell::new(vec![]),
+        }
+    }
+
+    pub fn rc_new(
+        id: &str,
+        pos: (f32, f32),
+        a_in: Input,
+        b_in: Input,
+        op_in: Input,
+        start_in: Input,
+        cycles: u32,
+    ) -> Rc<Self> {
+        Rc::new(MulDiv::new(id, pos, a_in, b_in, op_in, start_in, cycles))
+    }
+
+    /// Cycles left of the current operation, 0 when idle
+    pub fn remaining(&self) -> u32 {
+        self.state().remaining
+    }
+
+    fn state(&self) -> MulDivState {
+        self.history
+            .borrow()
+            .last()
+            .copied()
+            .unwrap_or(MulDivState {
+                remaining: 0,
+                result: SignalValue::Unknown,
+            })
+    }
+
+    /// Result of `op`, division by zero gives the RISC-V results
+    pub fn compute(
+        op: SignalUnsigned,
+        a: SignalUnsigned,
+        b: SignalUnsigned,
+    ) -> Option<SignalUnsigned> {
+        let (sa, sb) = (a as SignalSigned, b as SignalSigned);
+        Some(match op {
+            mul_div_op::MUL => a.wrapping_mul(b),
+            mul_div_op::MULH => ((sa as i64 * sb as i64) >> 32) as SignalUnsigned,
+            mul_div_op::MULHSU => ((sa as i64 * b as i64) >> 32) as SignalUnsigned,
+            mul_div_op::MULHU => ((a as u64 * b as u64) >> 32) as SignalUnsigned,
+            mul_div_op::DIV if b == 0 => SignalUnsigned::MAX,
+            mul_div_op::DIV => sa.wrapping_div(sb) as SignalUnsigned,
+            mul_div_op::DIVU => a.checked_div(b).unwrap_or(SignalUnsigned::MAX),
+            mul_div_op::REM if b == 0 => a,
+            mul_div_op::REM => sa.wrapping_rem(sb) as SignalUnsigned,
+            mul_div_op::REMU => a.checked_rem(b).unwrap_or(a),
+            _ => return None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::common::ComponentStore;
+    use crate::components::ProbeStim;
+
+    #[test]
+    fn test_mul_div() {
+        let cs = ComponentStore {
+            store: vec![
+                ProbeStim::rc_new("a", (0.0, 0.0), vec![6, 6, 6, 6, 6, 100, 0, 0, 0]),
+                ProbeStim::rc_new("b", (0.0, 0.0), vec![7, 7, 7, 7, 7, 0, 0, 0, 0]),
+                ProbeStim::rc_new(
+                    "op",
+                    (0.0, 0.0),
+                    vec![0, 0, 0, 0, 0, mul_div_op::DIVU, 0, 0, 0],
+                ),
+                // start is held, but only sampled when idle, any nonzero value starts
+                ProbeStim::rc_new("start", (0.0, 0.0), vec![1, 1, 0, 0, 0, 2, 0, 0, 0]),
+                MulDiv::rc_new(
+                    "md",
+                    (0.0, 0.0),
+                    Input::new("a", "out"),
+                    Input::new("b", "out"),
+                    Input::new("op", "out"),
+                    Input::new("start", "out"),
+                    3,
+                ),
+            ],
+        };
+        let mut simulator = Simulator::new(cs).unwrap();
+        let mut trace = vec![];
+        for _ in 0..9 {
+            simulator.clock();
+            trace.push(
+                [MUL_DIV_BUSY_OUT_ID, MUL_DIV_DONE_OUT_ID, MUL_DIV_OUT_ID]
+                    .map(|field| simulator.get_input_value(&Input::new("md", field))),
+            );
+        }
+        let (t, f) = (true.into(), false.into());
+        let max = SignalUnsigned::MAX.into();
+        assert_eq!(
+            trace,
+            vec![
+                [t, f, 0.into()],
+                [t, f, 0.into()],
+                [t, f, 0.into()],
+                [f, t, 42.into()],
+                [f, f, 42.into()],
+                [t, f, 42.into()],
+                [t, f, 42.into()],
+                [t, f, 42.into()],
+                [f, t, max],
+            ]
+        );
+
+        // stepping back restores the internal state
+        simulator.un_clock();
+        simulator.un_clock();
+        assert_eq!(
+            simulator.get_input_value(&Input::new("md", MUL_DIV_OUT_ID)),
+            42.into()
+        );
+        simulator.clock();
+        simulator.clock();
+        assert_eq!(
+            simulator.get_input_value(&Input::new("md", MUL_DIV_OUT_ID)),
+            max
+        );
+    }
+
+    #[test]
+    fn test_mul_div_reset() {
+        let cs = ComponentStore {
+            store: vec![
+                ProbeStim::rc_new("start", (0.0, 0.0), vec![1, 0, 0, 0]),
+                MulDiv::rc_new(
+                    "md",
+                    (0.0, 0.0),
+                    Input::new("start", "out"),
+                    Input::new("start", "out"),
+                    Input::new("start", "out"),
+                    Input::new("start", "out"),
+                    3,
+                ),
+            ],
+        };
+        let mut simulator = Simulator::new(cs).unwrap();
+        let busy = &Input::new("md", MUL_DIV_BUSY_OUT_ID);
+        simulator.clock();
+        assert_eq!(simulator.get_input_value(busy), true.into());
+
+        // a reset aborts the operation
+        simulator.reset();
+        assert_eq!(simulator.get_input_value(busy), false.into());
+        simulator.clock();
+        assert_eq!(simulator.get_input_value(busy), true.into());
+    }
+
+    #[test]
+    fn test_mul_div_compute() {
+        let m1 = -1i32 as u32;
+        assert_eq!(MulDiv::compute(mul_div_op::MULH, m1, m1), Some(0));
+        assert_eq!(MulDiv::compute(mul_div_op::MULHU, m1, m1), Some(m1 - 1));
+        assert_eq!(MulDiv::compute(mul_div_op::MULHSU, m1, 2), Some(m1));
+        assert_eq!(
+            MulDiv::compute(mul_div_op::DIV, -7i32 as u32, 2),
+            Some(-3i32 as u32)
+        );
+        assert_eq!(MulDiv::compute(mul_div_op::REM, -7i32 as u32, 2), Some(m1));
+        assert_eq!(MulDiv::compute(mul_div_op::DIV, 1 << 31, m1), Some(1 << 31));
+        assert_eq!(MulDiv::compute(mul_div_op::REM, 5, 0), Some(5));
+        assert_eq!(MulDiv::compute(8, 5, 0), None);
+    }
+}
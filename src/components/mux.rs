@@ -4,6 +4,7 @@ use crate::common::{
     Component, Condition, Id, Input, InputPort, OutputType, Ports, SignalUnsigned, SignalValue,
     Simulator,
 };
+use crate::coverage::CoverPoint;
 use log::*;
 use serde::{Deserialize, Serialize};
 use std::any::Any;
@@ -101,6 +102,14 @@ impl Component for Mux {
         }
     }
 
+    fn cover_points(&self) -> Vec<CoverPoint> {
+        vec![CoverPoint::values(
+            MUX_SELECT_ID,
+            self.select.clone(),
+            0..self.m_in.len() as SignalUnsigned,
+        )]
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
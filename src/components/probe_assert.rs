@@ -1,10 +1,13 @@
 use crate::{
     common::{Component, Condition, Id, Input, InputPort, OutputType, Ports, Signal, Simulator},
+    harness::value_matches,
     signal::SignalValue,
+    vector_table::VectorSource,
 };
 use log::*;
 use serde::{Deserialize, Serialize};
 use std::any::Any;
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
 
 pub const PROBE_ASSERT_IN_ID: &str = "in";
@@ -15,6 +18,9 @@ pub struct ProbeAssert {
     pub(crate) pos: (f32, f32),
     pub(crate) input: Input,
     pub(crate) values: Vec<Signal>,
+    // if set, expected values are taken from a column of a table file instead
+    #[serde(default)]
+    pub(crate) table: Option<VectorSource>,
 }
 
 #[typetag::serde]
@@ -40,26 +46,48 @@ impl Component for ProbeAssert {
     fn clock(&self, simulator: &mut Simulator) -> Result<(), Condition> {
         trace!("-- cycle {} --", simulator.cycle);
         let lhs = simulator.get_input_value(&self.input);
-        let rhs = match self.values.get(simulator.cycle) {
-            Some(rhs) => rhs.get_value(),
-            _ => SignalValue::Unknown,
+        let (rhs, location) = match &self.table {
+            Some(table) => match table.get_reload(simulator.cycle) {
+                Ok(Some(entry)) => (
+                    entry.value,
+                    format!(
+                        " at {:?} row {} (line {}), column {:?}",
+                        table.file, entry.row, entry.line, table.column
+                    ),
+                ),
+                Ok(None) => (
+                    SignalValue::Unknown,
+                    format!(" past the end of {:?}", table.file),
+                ),
+                Err(e) => return Err(Condition::Error(e)),
+            },
+            None => match self.values.get(simulator.cycle) {
+                Some(rhs) => (rhs.get_value(), String::new()),
+                _ => (SignalValue::Unknown, String::new()),
+            },
         };
 
+        // don't-care expected values match anything
+        let ok = value_matches(&rhs, &lhs);
+        let message = format!("assertion failed {:?} != {:?}{}", lhs, rhs, location);
         // the assertion is checked only in test mode
         #[cfg(test)]
-        assert_eq!(lhs, rhs);
-        if lhs == rhs {
+        assert!(ok, "{}", message);
+        if ok {
             Ok(())
         } else {
-            Err(Condition::Assert(format!(
-                "assertion failed {:?} != {:?}",
-                lhs, rhs
-            )))
+            Err(Condition::Assert(message))
         }
     }
 
     // notice we don't implement `un_clock` since the state is already kept in history
 
+    fn set_model_dir(&mut self, dir: &Path) {
+        if let Some(table) = &mut self.table {
+            table.set_model_dir(dir);
+        }
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -72,6 +100,7 @@ impl ProbeAssert {
             pos,
             input,
             values: values.into_iter().map(|v| v.into()).collect(),
+            table: None,
         }
     }
 
@@ -83,6 +112,53 @@ impl ProbeAssert {
     ) -> Rc<Self> {
         Rc::new(ProbeAssert::new(id, pos, input, values))
     }
+
+    /// expected values from `column` of the table in `file`
+    pub fn new_from_table(
+        id: &str,
+        pos: (f32, f32),
+        input: Input,
+        file: impl Into<PathBuf>,
+        column: &str,
+    ) -> Self {
+        ProbeAssert {
+            id: id.to_string(),
+            pos,
+            input,
+            values: vec![],
+            table: Some(VectorSource::new(file, column)),
+        }
+    }
+
+    pub fn rc_new_from_table(
+        id: &str,
+        pos: (f32, f32),
+        input: Input,
+        file: impl Into<PathBuf>,
+        column: &str,
+    ) -> Rc<Self> {
+        Rc::new(ProbeAssert::new_from_table(id, pos, input, file, column))
+    }
+
+    /// number of cycles with an expected value
+    pub fn cycles(&self) -> Result<usize, String> {
+        match &self.table {
+            Some(table) => table.cycles(),
+            None => Ok(self.values.len()),
+        }
+    }
+
+    /// the expected value for `cycle`, `Unknown` if not defined
+    pub fn expected(&self, cycle: usize) -> Signal {
+        match &self.table {
+            Some(table) => table.signal(cycle),
+            None => self
+                .values
+                .get(cycle)
+                .copied()
+                .unwrap_or(SignalValue::Unknown.into()),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -239,4 +315,102 @@ mod test {
         assert_eq!(simulator.cycle, 1);
         assert_eq!(simulator.get_input_value(out), 0.into());
     }
+
+    // write a vector table to a unique temporary file
+    fn table_file(name: &str, text: &str) -> PathBuf {
+        let path =
+            std::env::temp_dir().join(format!("syncrim_{}_{}.csv", name, std::process::id()));
+        std::fs::write(&path, text).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_probe_table() {
+        let path = table_file(
+            "probe_table",
+            "repeat, in, out\n\
+             2,      1,  1\n\
+             ,       2,  -\n\
+             2,      3,  3\n",
+        );
+        let cs = ComponentStore {
+            store: vec![
+                ProbeStim::rc_new_from_table("stim", (0.0, 0.0), &path, "in"),
+                ProbeAssert::rc_new_from_table(
+                    "assert",
+                    (0.0, 0.0),
+                    Input::new("stim", "out"),
+                    &path,
+                    "out",
+                ),
+            ],
+        };
+
+        let mut simulator = Simulator::new(cs).unwrap();
+        let out = &Input::new("stim", "out");
+        assert_eq!(simulator.get_input_value(out), 1.into());
+        simulator.clock();
+        assert_eq!(simulator.get_input_value(out), 1.into());
+        simulator.clock();
+        assert_eq!(simulator.get_input_value(out), 2.into());
+        simulator.clock();
+        simulator.clock();
+        assert_eq!(simulator.cycle, 5);
+        assert_eq!(simulator.get_input_value(out), 3.into());
+
+        // the table is read again on reset
+        std::fs::write(&path, "in out\n7 7\n").unwrap();
+        simulator.reset();
+        assert_eq!(simulator.get_input_value(out), 7.into());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_probe_table_model_dir() {
+        let path = table_file("probe_table_dir", "in, out\n5, 5\n");
+        let file = PathBuf::from(path.file_name().unwrap());
+        let mut cs = ComponentStore {
+            store: vec![
+                ProbeStim::rc_new_from_table("stim", (0.0, 0.0), &file, "in"),
+                ProbeAssert::rc_new_from_table(
+                    "assert",
+                    (0.0, 0.0),
+                    Input::new("stim", "out"),
+                    &file,
+                    "out",
+                ),
+            ],
+        };
+        // the file name is relative to the model
+        cs.set_model_dir(path.parent().unwrap());
+        let simulator = Simulator::new(cs).unwrap();
+        assert_eq!(
+            simulator.get_input_value(&Input::new("stim", "out")),
+            5.into()
+        );
+        assert!(simulator.component_condition.is_empty());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "row 2 (line 3), column \"out\"")]
+    fn test_probe_table_fail() {
+        let path = table_file("probe_table_fail", "in, out\n1, 1\n2, 3\n");
+        let cs = ComponentStore {
+            store: vec![
+                ProbeStim::rc_new_from_table("stim", (0.0, 0.0), &path, "in"),
+                ProbeAssert::rc_new_from_table(
+                    "assert",
+                    (0.0, 0.0),
+                    Input::new("stim", "out"),
+                    &path,
+                    "out",
+                ),
+            ],
+        };
+
+        let mut simulator = Simulator::new(cs).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        simulator.clock();
+    }
 }
@@ -1,7 +1,9 @@
 use crate::common::{Component, Condition, Id, OutputType, Ports, Signal, SignalValue, Simulator};
+use crate::vector_table::VectorSource;
 use log::*;
 use serde::{Deserialize, Serialize};
 use std::any::Any;
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -9,6 +11,9 @@ pub struct ProbeStim {
     pub(crate) id: Id,
     pub(crate) pos: (f32, f32),
     pub(crate) values: Vec<Signal>,
+    // if set, values are taken from a column of a table file instead
+    #[serde(default)]
+    pub(crate) table: Option<VectorSource>,
 }
 
 #[typetag::serde]
@@ -31,8 +36,21 @@ impl Component for ProbeStim {
 
     fn clock(&self, simulator: &mut Simulator) -> Result<(), Condition> {
         trace!("-- cycle {} --", simulator.cycle);
-        let (out, res) = if let Some(signal) = self.values.get(simulator.cycle) {
-            (signal.get_value(), Ok(()))
+        let value = match &self.table {
+            Some(table) => match table.get_reload(simulator.cycle) {
+                Ok(entry) => entry.map(|entry| entry.value),
+                Err(e) => {
+                    simulator.set_out_value(&self.id, "out", SignalValue::Unknown);
+                    return Err(Condition::Error(e));
+                }
+            },
+            None => self
+                .values
+                .get(simulator.cycle)
+                .map(|signal| signal.get_value()),
+        };
+        let (out, res) = if let Some(value) = value {
+            (value, Ok(()))
         } else {
             (
                 SignalValue::Unknown,
@@ -47,6 +65,13 @@ impl Component for ProbeStim {
     }
 
     // notice we don't implement `un_clock` since the state is already kept in history
+
+    fn set_model_dir(&mut self, dir: &Path) {
+        if let Some(table) = &mut self.table {
+            table.set_model_dir(dir);
+        }
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -58,12 +83,49 @@ impl ProbeStim {
             id: id.to_string(),
             pos,
             values: values.into_iter().map(|v| v.into()).collect(),
+            table: None,
         }
     }
 
     pub fn rc_new(id: &str, pos: (f32, f32), values: Vec<impl Into<Signal>>) -> Rc<Self> {
         Rc::new(ProbeStim::new(id, pos, values))
     }
+
+    /// stimuli from `column` of the table in `file`
+    pub fn new_from_table(
+        id: &str,
+        pos: (f32, f32),
+        file: impl Into<PathBuf>,
+        column: &str,
+    ) -> Self {
+        ProbeStim {
+            id: id.to_string(),
+            pos,
+            values: vec![],
+            table: Some(VectorSource::new(file, column)),
+        }
+    }
+
+    pub fn rc_new_from_table(
+        id: &str,
+        pos: (f32, f32),
+        file: impl Into<PathBuf>,
+        column: &str,
+    ) -> Rc<Self> {
+        Rc::new(ProbeStim::new_from_table(id, pos, file, column))
+    }
+
+    /// the stimulus for `cycle`, `Unknown` if not defined
+    pub fn signal(&self, cycle: usize) -> Signal {
+        match &self.table {
+            Some(table) => table.signal(cycle),
+            None => self
+                .values
+                .get(cycle)
+                .copied()
+                .unwrap_or(SignalValue::Unknown.into()),
+        }
+    }
 }
 
 #[cfg(test)]
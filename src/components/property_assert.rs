@@ -0,0 +1,345 @@
+#[cfg(feature = "gui-egui")]
+use crate::common::EguiComponent;
+use crate::common::{
+    Component, Condition, Id, Input, InputPort, OutputType, Ports, SignalUnsigned, SignalValue,
+    Simulator,
+};
+use log::*;
+use serde::{Deserialize, Serialize};
+use std::any::Any;
+use std::fmt;
+use std::rc::Rc;
+
+pub const PROPERTY_ASSERT_TRIGGER_ID: &str = "trigger";
+pub const PROPERTY_ASSERT_TRIGGER_OPERAND_ID: &str = "trigger_operand";
+pub const PROPERTY_ASSERT_CHECK_ID: &str = "check";
+pub const PROPERTY_ASSERT_CHECK_OPERAND_ID: &str = "check_operand";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compare {
+    Eq,
+    Ne,
+    // unsigned comparisons
+    Lt,
+    Ge,
+    // value is a multiple of the operand
+    Aligned,
+    // any of the operand bits are set in the value
+    AnySet,
+}
+
+impl Compare {
+    pub const ALL: [Compare; 6] = [
+        Compare::Eq,
+        Compare::Ne,
+        Compare::Lt,
+        Compare::Ge,
+        Compare::Aligned,
+        Compare::AnySet,
+    ];
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum Operand {
+    Const(SignalUnsigned),
+    Signal(Input),
+}
+
+impl From<SignalUnsigned> for Operand {
+    fn from(value: SignalUnsigned) -> Self {
+        Operand::Const(value)
+    }
+}
+
+impl From<Input> for Operand {
+    fn from(input: Input) -> Self {
+        Operand::Signal(input)
+    }
+}
+
+/// Comparison of an input against a constant or another input
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Predicate {
+    pub input: Input,
+    pub compare: Compare,
+    pub operand: Operand,
+}
+
+impl Predicate {
+    pub fn new(input: Input, compare: Compare, operand: impl Into<Operand>) -> Self {
+        Predicate {
+            input,
+            compare,
+            operand: operand.into(),
+        }
+    }
+
+    /// evaluate in `cycle`, never holds for undefined values
+    pub fn holds(&self, simulator: &Simulator, cycle: usize) -> bool {
+        let value = simulator.get_input_value_at(&self.input, cycle);
+        let operand = match &self.operand {
+            Operand::Const(c) => SignalValue::Data(*c),
+            Operand::Signal(input) => simulator.get_input_value_at(input, cycle),
+        };
+        let (SignalValue::Data(a), SignalValue::Data(b)) = (value, operand) else {
+            return false;
+        };
+        match self.compare {
+            Compare::Eq => a == b,
+            Compare::Ne => a != b,
+            Compare::Lt => a < b,
+            Compare::Ge => a >= b,
+            Compare::Aligned => b != 0 && a % b == 0,
+            Compare::AnySet => a & b != 0,
+        }
+    }
+}
+
+impl fmt::Display for Predicate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let compare = match self.compare {
+            Compare::Eq => "==",
+            Compare::Ne => "!=",
+            Compare::Lt => "<",
+            Compare::Ge => ">=",
+            Compare::Aligned => "aligned to",
+            Compare::AnySet => "has any of",
+        };
+        write!(f, "{}.{} {} ", self.input.id, self.input.field, compare)?;
+        match &self.operand {
+            Operand::Const(c) => write!(f, "{:#x}", c),
+            Operand::Signal(input) => write!(f, "{}.{}", input.id, input.field),
+        }
+    }
+}
+
+/// Assertion over time
+///
+/// Whenever `trigger` holds, `check` must hold in the same cycle or one of
+/// the following `within` cycles. A violation is reported with
+/// `Condition::Assert` in the cycle the deadline passes.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct PropertyAssert {
+    pub(crate) id: Id,
+    pub(crate) pos: (f32, f32),
+    pub(crate) trigger: Predicate,
+    pub(crate) check: Predicate,
+    pub(crate) within: usize,
+}
+
+#[typetag::serde]
+impl Component for PropertyAssert {
+    fn to_(&self) {
+        trace!(
+            "PropertyAssert {} followed by {} within {}",
+            self.trigger,
+            self.check,
+            self.within
+        );
+    }
+    #[cfg(feature = "gui-egui")]
+    fn dummy(&self, id: &str, pos: (f32, f32)) -> Box<Rc<dyn EguiComponent>> {
+        let dummy_input = Input::new("dummy", "out");
+        let predicate = |p: &Predicate| Predicate {
+            input: dummy_input.clone(),
+            compare: p.compare,
+            operand: match p.operand {
+                Operand::Const(c) => Operand::Const(c),
+                Operand::Signal(_) => Operand::Signal(dummy_input.clone()),
+            },
+        };
+        Box::new(Rc::new(PropertyAssert {
+            id: id.to_string(),
+            pos: (pos.0, pos.1),
+            trigger: predicate(&self.trigger),
+            check: predicate(&self.check),
+            within: self.within,
+        }))
+    }
+
+    fn get_id_ports(&self) -> (Id, Ports) {
+        let mut inputs = vec![];
+        for (predicate, id, operand_id) in [
+            (
+                &self.trigger,
+                PROPERTY_ASSERT_TRIGGER_ID,
+                PROPERTY_ASSERT_TRIGGER_OPERAND_ID,
+            ),
+            (
+                &self.check,
+                PROPERTY_ASSERT_CHECK_ID,
+                PROPERTY_ASSERT_CHECK_OPERAND_ID,
+            ),
+        ] {
+            inputs.push(InputPort {
+                port_id: id.to_string(),
+                input: predicate.input.clone(),
+            });
+            if let Operand::Signal(input) = &predicate.operand {
+                inputs.push(InputPort {
+                    port_id: operand_id.to_string(),
+                    input: input.clone(),
+                });
+            }
+        }
+        (
+            self.id.clone(),
+            Ports::new(inputs.iter().collect(), OutputType::Combinatorial, vec![]),
+        )
+    }
+
+    fn set_id_port(&mut self, target_port_id: Id, new_input: Input) {
+        match target_port_id.as_str() {
+            PROPERTY_ASSERT_TRIGGER_ID => self.trigger.input = new_input,
+            PROPERTY_ASSERT_TRIGGER_OPERAND_ID => self.trigger.operand = Operand::Signal(new_input),
+            PROPERTY_ASSERT_CHECK_ID => self.check.input = new_input,
+            PROPERTY_ASSERT_CHECK_OPERAND_ID => self.check.operand = Operand::Signal(new_input),
+            _ => (),
+        }
+    }
+
+    fn clock(&self, simulator: &mut Simulator) -> Result<(), Condition> {
+        // the cycle being evaluated, earlier cycles are read from history
+        let now = simulator.history.len();
+        // the trigger whose deadline is now, cycle 0 is the state before reset
+        let start = match now.checked_sub(self.within) {
+            Some(start) if start > 0 => start,
+            _ => return Ok(()),
+        };
+        if self.trigger.holds(simulator, start)
+            && !(start..=now).any(|cycle| self.check.holds(simulator, cycle))
+        {
+            Err(Condition::Assert(format!(
+                "property failed at cycle {}: {} in cycle {} not followed by {} within {} cycles",
+                now, self.trigger, start, self.check, self.within
+            )))
+        } else {
+            Ok(())
+        }
+    }
+
+    // notice we don't implement `un_clock` since the state is already kept in history
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl PropertyAssert {
+    pub fn new(
+        id: &str,
+        pos: (f32, f32),
+        trigger: Predicate,
+        check: Predicate,
+        within: usize,
+    ) -> Self {
+        PropertyAssert {
+            id: id.to_string(),
+            pos,
+            trigger,
+            check,
+            within,
+        }
+    }
+
+    pub fn rc_new(
+        id: &str,
+        pos: (f32, f32),
+        trigger: Predicate,
+        check: Predicate,
+        within: usize,
+    ) -> Rc<Self> {
+        Rc::new(PropertyAssert::new(id, pos, trigger, check, within))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::common::ComponentStore;
+    use crate::components::{MemCtrl, ProbeStim};
+
+    fn conditions(simulator: &Simulator) -> Vec<String> {
+        simulator
+            .component_condition
+            .iter()
+            .map(|(id, c)| match c {
+                Condition::Assert(msg) => format!("{}: {}", id, msg),
+                _ => panic!("unexpected condition {:?}", c),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_property_same_cycle() {
+        const MEM_STORE: SignalUnsigned = MemCtrl::Write as SignalUnsigned;
+        let cs = ComponentStore {
+            store: vec![
+                ProbeStim::rc_new("ctrl", (0.0, 0.0), vec![0, MEM_STORE, 0, MEM_STORE]),
+                ProbeStim::rc_new("addr", (0.0, 0.0), vec![3, 4, 5, 6]),
+                PropertyAssert::rc_new(
+                    "aligned",
+                    (0.0, 0.0),
+                    Predicate::new(Input::new("ctrl", "out"), Compare::Eq, MEM_STORE),
+                    Predicate::new(Input::new("addr", "out"), Compare::Aligned, 4),
+                    0,
+                ),
+            ],
+        };
+        let mut simulator = Simulator::new(cs).unwrap();
+        assert!(conditions(&simulator).is_empty());
+        simulator.step(2);
+        assert!(conditions(&simulator).is_empty());
+        simulator.clock();
+        assert_eq!(
+            conditions(&simulator),
+            vec![format!(
+                "aligned: property failed at cycle 4: ctrl.out == {:#x} in cycle 4 \
+                 not followed by addr.out aligned to 0x4 within 0 cycles",
+                MEM_STORE
+            )]
+        );
+    }
+
+    #[test]
+    fn test_property_within() {
+        let cs = ComponentStore {
+            store: vec![
+                ProbeStim::rc_new("irq", (0.0, 0.0), vec![1, 0, 0, 0, 1, 0, 0, 0, 0]),
+                ProbeStim::rc_new("pc", (0.0, 0.0), vec![0, 4, 8, 0x40, 4, 8, 12, 16, 0x40]),
+                ProbeStim::rc_new("mtvec", (0.0, 0.0), vec![0x40; 9]),
+                PropertyAssert::rc_new(
+                    "vector",
+                    (0.0, 0.0),
+                    Predicate::new(Input::new("irq", "out"), Compare::Ne, 0),
+                    Predicate::new(
+                        Input::new("pc", "out"),
+                        Compare::Eq,
+                        Input::new("mtvec", "out"),
+                    ),
+                    3,
+                ),
+            ],
+        };
+        let mut simulator = Simulator::new(cs).unwrap();
+        // the first interrupt is taken after 3 cycles
+        for _ in 0..7 {
+            assert!(conditions(&simulator).is_empty());
+            simulator.clock();
+        }
+        // the second is not taken within 3 cycles
+        assert_eq!(simulator.cycle, 8);
+        assert_eq!(
+            conditions(&simulator),
+            vec![
+                "vector: property failed at cycle 8: irq.out != 0x0 in cycle 5 \
+                  not followed by pc.out == mtvec.out within 3 cycles"
+            ]
+        );
+
+        // stepping back and forth re-evaluates from history
+        simulator.un_clock();
+        simulator.clock();
+        assert_eq!(conditions(&simulator).len(), 1);
+    }
+}
@@ -1,12 +1,14 @@
 #[cfg(feature = "gui-egui")]
 use crate::common::EguiComponent;
 use crate::common::{Component, Condition, Id, Input, InputPort, OutputType, Ports, Simulator};
+use crate::components::{ResetInput, ResetKind};
 use log::*;
 use serde::{Deserialize, Serialize};
 use std::any::Any;
 use std::rc::Rc;
 
 pub const REGISTER_R_IN_ID: &str = "r_in";
+pub const REGISTER_RESET_ID: &str = "reset";
 
 pub const REGISTER_OUT_ID: &str = "out";
 
@@ -15,6 +17,8 @@ pub struct Register {
     pub(crate) id: Id,
     pub(crate) pos: (f32, f32),
     pub(crate) r_in: Input,
+    #[serde(default)]
+    pub(crate) reset: Option<ResetInput>,
 }
 
 #[typetag::serde]
@@ -29,17 +33,25 @@ impl Component for Register {
             id: id.to_string(),
             pos: (pos.0, pos.1),
             r_in: dummy_input.clone(),
+            reset: None,
         }))
     }
     fn get_id_ports(&self) -> (Id, Ports) {
+        let mut inputs = vec![InputPort {
+            port_id: REGISTER_R_IN_ID.to_string(),
+            input: self.r_in.clone(),
+        }];
+        if let Some(reset) = &self.reset {
+            inputs.push(InputPort {
+                port_id: REGISTER_RESET_ID.to_string(),
+                input: reset.input.clone(),
+            });
+        }
         (
             self.id.clone(),
             Ports::new(
                 // Vector of inputs
-                vec![&InputPort {
-                    port_id: REGISTER_R_IN_ID.to_string(),
-                    input: self.r_in.clone(),
-                }],
+                inputs.iter().collect(),
                 OutputType::Sequential,
                 vec![REGISTER_OUT_ID],
             ),
@@ -48,17 +60,44 @@ impl Component for Register {
 
     // propagate input value to output
     fn clock(&self, simulator: &mut Simulator) -> Result<(), Condition> {
-        // get input value
-        let value = simulator.get_input_value(&self.r_in);
+        // get input value, or the reset value if reset was asserted
+        let value = match &self.reset {
+            Some(reset) if reset.is_asserted(simulator) => reset.value.into(),
+            _ => simulator.get_input_value(&self.r_in),
+        };
         // set output
         simulator.set_out_value(&self.id, "out", value);
         trace!("eval: register id {} in {:?}", self.id, value);
         Ok(())
     }
 
+    // force output while an asynchronous reset is asserted
+    fn async_reset(&self, simulator: &mut Simulator) {
+        if let Some(reset) = &self.reset {
+            if reset.kind == ResetKind::Async && reset.is_asserted(simulator) {
+                trace!("async reset: register id {}", self.id);
+                simulator.set_out_value(&self.id, REGISTER_OUT_ID, reset.value);
+            }
+        }
+    }
+
+    fn async_reset_inputs(&self) -> Vec<Input> {
+        self.reset
+            .iter()
+            .filter(|reset| reset.kind == ResetKind::Async)
+            .map(|reset| reset.input.clone())
+            .collect()
+    }
+
     fn set_id_port(&mut self, target_port_id: Id, new_input: Input) {
-        if target_port_id == REGISTER_R_IN_ID {
-            self.r_in = new_input;
+        match target_port_id.as_str() {
+            REGISTER_R_IN_ID => self.r_in = new_input,
+            REGISTER_RESET_ID => {
+                if let Some(reset) = &mut self.reset {
+                    reset.input = new_input;
+                }
+            }
+            _ => (),
         }
     }
 
@@ -73,10 +112,29 @@ impl Register {
             id: id.to_string(),
             pos,
             r_in,
+            reset: None,
         }
     }
 
     pub fn rc_new(id: &str, pos: (f32, f32), r_in: Input) -> Rc<Self> {
         Rc::new(Register::new(id, pos, r_in))
     }
+
+    pub fn new_with_reset(id: &str, pos: (f32, f32), r_in: Input, reset: ResetInput) -> Self {
+        Register {
+            id: id.to_string(),
+            pos,
+            r_in,
+            reset: Some(reset),
+        }
+    }
+
+    pub fn rc_new_with_reset(
+        id: &str,
+        pos: (f32, f32),
+        r_in: Input,
+        reset: ResetInput,
+    ) -> Rc<Self> {
+        Rc::new(Register::new_with_reset(id, pos, r_in, reset))
+    }
 }
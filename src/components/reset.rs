@@ -0,0 +1,258 @@
+#[cfg(feature = "gui-egui")]
+use crate::common::EguiComponent;
+use crate::common::{
+    Component, Condition, Id, Input, InputPort, OutputType, Ports, SignalUnsigned, SignalValue,
+    Simulator,
+};
+use log::*;
+use serde::{Deserialize, Serialize};
+use std::any::Any;
+use std::rc::Rc;
+
+pub const RESET_TRIGGER_ID: &str = "trigger";
+
+pub const RESET_OUT_ID: &str = "out";
+
+/// When an asserted reset input takes effect on a sequential component
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq, Eq, Copy, Clone)]
+pub enum ResetKind {
+    // sampled together with the data inputs on the clock edge
+    #[default]
+    Sync,
+    // forces the output in the same cycle the reset is asserted
+    Async,
+}
+
+/// Optional reset input, shared by components supporting reset as a signal
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ResetInput {
+    pub input: Input,
+    // value driven on the output(s) while in reset
+    #[serde(default)]
+    pub value: SignalUnsigned,
+    #[serde(default)]
+    pub kind: ResetKind,
+}
+
+impl ResetInput {
+    pub fn new(input: Input, value: SignalUnsigned, kind: ResetKind) -> Self {
+        ResetInput { input, value, kind }
+    }
+
+    /// reset is active high, an undefined reset signal is treated as not asserted
+    pub fn is_asserted(&self, simulator: &Simulator) -> bool {
+        matches!(simulator.get_input_value(&self.input), SignalValue::Data(v) if v != 0)
+    }
+
+    /// reset in effect for a component evaluated with the combinatorial ones,
+    /// e.g., a memory, an asynchronous reset acts on the current value, a
+    /// synchronous reset on the value sampled on the clock edge
+    pub fn is_in_effect(&self, simulator: &Simulator) -> bool {
+        match self.kind {
+            ResetKind::Async => self.is_asserted(simulator),
+            // while clocking, the state before the clock is that of `cycle`
+            ResetKind::Sync => matches!(
+                simulator.get_input_value_at(&self.input, simulator.cycle),
+                SignalValue::Data(v) if v != 0
+            ),
+        }
+    }
+}
+
+/// Model level reset source
+///
+/// The output is asserted during the first `por_cycles` cycles after
+/// (simulator) reset, and whenever the optional `trigger` input is asserted,
+/// e.g., by a watchdog.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Reset {
+    pub(crate) id: Id,
+    pub(crate) pos: (f32, f32),
+    pub(crate) por_cycles: usize,
+    #[serde(default)]
+    pub(crate) trigger: Option<Input>,
+}
+
+#[typetag::serde]
+impl Component for Reset {
+    fn to_(&self) {
+        trace!("reset");
+    }
+    #[cfg(feature = "gui-egui")]
+    fn dummy(&self, id: &str, pos: (f32, f32)) -> Box<Rc<dyn EguiComponent>> {
+        Box::new(Rc::new(Reset {
+            id: id.to_string(),
+            pos: (pos.0, pos.1),
+            por_cycles: 1,
+            trigger: None,
+        }))
+    }
+    fn get_id_ports(&self) -> (Id, Ports) {
+        let trigger = self.trigger.as_ref().map(|trigger| InputPort {
+            port_id: RESET_TRIGGER_ID.to_string(),
+            input: trigger.clone(),
+        });
+        (
+            self.id.clone(),
+            Ports::new(
+                trigger.iter().collect(),
+                OutputType::Combinatorial,
+                vec![RESET_OUT_ID],
+            ),
+        )
+    }
+
+    fn clock(&self, simulator: &mut Simulator) -> Result<(), Condition> {
+        let por = simulator.cycle < self.por_cycles;
+        let triggered = match &self.trigger {
+            Some(trigger) => {
+                matches!(simulator.get_input_value(trigger), SignalValue::Data(v) if v != 0)
+            }
+            None => false,
+        };
+        trace!("reset {} por {} triggered {}", self.id, por, triggered);
+        simulator.set_out_value(&self.id, RESET_OUT_ID, por || triggered);
+        Ok(())
+    }
+
+    fn set_id_port(&mut self, target_port_id: Id, new_input: Input) {
+        if target_port_id == RESET_TRIGGER_ID {
+            self.trigger = Some(new_input);
+        }
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl Reset {
+    pub fn new(id: &str, pos: (f32, f32), por_cycles: usize, trigger: Option<Input>) -> Self {
+        Reset {
+            id: id.to_string(),
+            pos,
+            por_cycles,
+            trigger,
+        }
+    }
+
+    pub fn rc_new(
+        id: &str,
+        pos: (f32, f32),
+        por_cycles: usize,
+        trigger: Option<Input>,
+    ) -> Rc<Self> {
+        Rc::new(Reset::new(id, pos, por_cycles, trigger))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::common::ComponentStore;
+    use crate::components::{Add, Constant, ProbeStim, Register};
+
+    #[test]
+    fn test_reset_sync_async() {
+        let cs = ComponentStore {
+            store: vec![
+                ProbeStim::rc_new("stim", (0.0, 0.0), vec![1, 2, 3, 4, 5, 6, 7]),
+                ProbeStim::rc_new("wdt", (0.0, 0.0), vec![0, 0, 0, 0, 1, 0, 0]),
+                Reset::rc_new("rst", (0.0, 0.0), 2, Some(Input::new("wdt", "out"))),
+                Register::rc_new_with_reset(
+                    "sync",
+                    (0.0, 0.0),
+                    Input::new("stim", "out"),
+                    ResetInput::new(Input::new("rst", "out"), 0xdead, ResetKind::Sync),
+                ),
+                Register::rc_new_with_reset(
+                    "async",
+                    (0.0, 0.0),
+                    Input::new("stim", "out"),
+                    ResetInput::new(Input::new("rst", "out"), 0xbeef, ResetKind::Async),
+                ),
+            ],
+        };
+
+        let mut simulator = Simulator::new(cs).unwrap();
+        let rst = &Input::new("rst", "out");
+        let sync = &Input::new("sync", "out");
+        let r#async = &Input::new("async", "out");
+
+        // power on reset, the async register is in reset immediately
+        assert_eq!(simulator.cycle, 1);
+        assert_eq!(simulator.get_input_value(rst), true.into());
+        assert_eq!(simulator.get_input_value(r#async), 0xbeef.into());
+
+        simulator.clock();
+        assert_eq!(simulator.cycle, 2);
+        assert_eq!(simulator.get_input_value(rst), true.into());
+        assert_eq!(simulator.get_input_value(sync), 0xdead.into());
+        assert_eq!(simulator.get_input_value(r#async), 0xbeef.into());
+
+        // reset released, the registers still sample the reset from the last cycle
+        simulator.clock();
+        assert_eq!(simulator.cycle, 3);
+        assert_eq!(simulator.get_input_value(rst), false.into());
+        assert_eq!(simulator.get_input_value(sync), 0xdead.into());
+        assert_eq!(simulator.get_input_value(r#async), 0xbeef.into());
+
+        // out of reset
+        simulator.clock();
+        assert_eq!(simulator.cycle, 4);
+        assert_eq!(simulator.get_input_value(sync), 3.into());
+        assert_eq!(simulator.get_input_value(r#async), 3.into());
+
+        // watchdog triggered reset, only the async register reacts this cycle
+        simulator.clock();
+        assert_eq!(simulator.cycle, 5);
+        assert_eq!(simulator.get_input_value(rst), true.into());
+        assert_eq!(simulator.get_input_value(sync), 4.into());
+        assert_eq!(simulator.get_input_value(r#async), 0xbeef.into());
+
+        simulator.clock();
+        assert_eq!(simulator.cycle, 6);
+        assert_eq!(simulator.get_input_value(rst), false.into());
+        assert_eq!(simulator.get_input_value(sync), 0xdead.into());
+        assert_eq!(simulator.get_input_value(r#async), 0xbeef.into());
+
+        simulator.clock();
+        assert_eq!(simulator.cycle, 7);
+        assert_eq!(simulator.get_input_value(sync), 6.into());
+        assert_eq!(simulator.get_input_value(r#async), 6.into());
+
+        // simulator reset restarts the power on reset
+        simulator.reset();
+        assert_eq!(simulator.cycle, 1);
+        assert_eq!(simulator.get_input_value(rst), true.into());
+        assert_eq!(simulator.get_input_value(r#async), 0xbeef.into());
+    }
+
+    #[test]
+    fn test_reset_loop() {
+        // a watchdog resetting the counter it watches
+        let store = |kind| ComponentStore {
+            store: vec![
+                Constant::rc_new("one", (0.0, 0.0), 1),
+                Add::rc_new(
+                    "add",
+                    (0.0, 0.0),
+                    Input::new("cnt", "out"),
+                    Input::new("one", "out"),
+                ),
+                Reset::rc_new("rst", (0.0, 0.0), 1, Some(Input::new("add", "overflow"))),
+                Register::rc_new_with_reset(
+                    "cnt",
+                    (0.0, 0.0),
+                    Input::new("add", "out"),
+                    ResetInput::new(Input::new("rst", "out"), 0, kind),
+                ),
+            ],
+        };
+        assert!(Simulator::new(store(ResetKind::Sync)).is_ok());
+        assert_eq!(
+            Simulator::new(store(ResetKind::Async)).err(),
+            Some("Topological sort failed, an asynchronous reset depends on the output it resets.")
+        );
+    }
+}
@@ -0,0 +1,207 @@
+#[cfg(feature = "gui-egui")]
+use crate::common::EguiComponent;
+use crate::common::{
+    Component, Condition, Id, Input, InputPort, OutputType, Ports, SignalUnsigned, SignalValue,
+    Simulator,
+};
+use crate::components::{check_width, sign_extend, width_mask};
+use crate::coverage::{CoverBin, CoverPoint};
+use log::*;
+use serde::{Deserialize, Serialize};
+use std::any::Any;
+use std::rc::Rc;
+
+pub const SHIFTER_IN_ID: &str = "in";
+pub const SHIFTER_SHAMT_IN_ID: &str = "shamt_in";
+pub const SHIFTER_OP_IN_ID: &str = "op_in";
+pub const SHIFTER_OUT_ID: &str = "out";
+
+pub mod shift_op {
+    pub const SLL: u32 = 0;
+    pub const SRL: u32 = 1;
+    pub const SRA: u32 = 2;
+
+    /// all operations by name
+    pub const ALL: [(&str, u32); 3] = [("SLL", SLL), ("SRL", SRL), ("SRA", SRA)];
+}
+
+/// Barrel shifter of `width` bits, the shift amount is taken modulo the width
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Shifter {
+    pub(crate) id: Id,
+    pub(crate) pos: (f32, f32),
+    pub(crate) data_in: Input,
+    pub(crate) shamt_in: Input,
+    pub(crate) op_in: Input,
+    pub(crate) width: u32,
+}
+
+#[typetag::serde]
+impl Component for Shifter {
+    fn to_(&self) {
+        trace!("shifter {} bits", self.width);
+    }
+    #[cfg(feature = "gui-egui")]
+    fn dummy(&self, id: &str, pos: (f32, f32)) -> Box<Rc<dyn EguiComponent>> {
+        let dummy_input = Input::new("dummy", "out");
+        Box::new(Rc::new(Shifter {
+            id: id.to_string(),
+            pos: (pos.0, pos.1),
+            data_in: dummy_input.clone(),
+            shamt_in: dummy_input.clone(),
+            op_in: dummy_input,
+            width: self.width,
+        }))
+    }
+    fn get_id_ports(&self) -> (Id, Ports) {
+        (
+            self.id.clone(),
+            Ports::new(
+                vec![
+                    &InputPort {
+                        port_id: SHIFTER_IN_ID.to_string(),
+                        input: self.data_in.clone(),
+                    },
+                    &InputPort {
+                        port_id: SHIFTER_SHAMT_IN_ID.to_string(),
+                        input: self.shamt_in.clone(),
+                    },
+                    &InputPort {
+                        port_id: SHIFTER_OP_IN_ID.to_string(),
+                        input: self.op_in.clone(),
+                    },
+                ],
+                OutputType::Combinatorial,
+                vec![SHIFTER_OUT_ID],
+            ),
+        )
+    }
+
+    fn set_id_port(&mut self, target_port_id: Id, new_input: Input) {
+        match target_port_id.as_str() {
+            SHIFTER_IN_ID => self.data_in = new_input,
+            SHIFTER_SHAMT_IN_ID => self.shamt_in = new_input,
+            SHIFTER_OP_IN_ID => self.op_in = new_input,
+            _ => {}
+        }
+    }
+
+    fn clock(&self, simulator: &mut Simulator) -> Result<(), Condition> {
+        let value = simulator.get_input_value(&self.data_in);
+        let shamt = simulator.get_input_value(&self.shamt_in);
+        let op = simulator.get_input_value(&self.op_in);
+        let (res, out) = match (value, shamt, op) {
+            (SignalValue::Data(value), SignalValue::Data(shamt), SignalValue::Data(op)) => {
+                match check_width(&self.id, self.width).and_then(|_| self.shift(value, shamt, op)) {
+                    Ok(out) => (Ok(()), out.into()),
+                    Err(e) => (Err(Condition::Error(e)), SignalValue::Unknown),
+                }
+            }
+            _ => (Ok(()), SignalValue::Unknown),
+        };
+        simulator.set_out_value(&self.id, SHIFTER_OUT_ID, out);
+        res
+    }
+
+    fn cover_points(&self) -> Vec<CoverPoint> {
+        let bins = shift_op::ALL
+            .iter()
+            .map(|(name, op)| CoverBin::new(name, *op))
+            .collect();
+        vec![CoverPoint::new(SHIFTER_OP_IN_ID, self.op_in.clone(), bins)]
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl Shifter {
+    pub fn new(
+        id: &str,
+        pos: (f32, f32),
+        data_in: Input,
+        shamt_in: Input,
+        op_in: Input,
+        width: u32,
+    ) -> Self {
+        Shifter {
+            id: id.to_string(),
+            pos,
+            data_in,
+            shamt_in,
+            op_in,
+            width,
+        }
+    }
+
+    pub fn rc_new(
+        id: &str,
+        pos: (f32, f32),
+        data_in: Input,
+        shamt_in: Input,
+        op_in: Input,
+        width: u32,
+    ) -> Rc<Self> {
+        Rc::new(Shifter::new(id, pos, data_in, shamt_in, op_in, width))
+    }
+
+    pub fn shift(
+        &self,
+        value: SignalUnsigned,
+        shamt: SignalUnsigned,
+        op: SignalUnsigned,
+    ) -> Result<SignalUnsigned, String> {
+        let mask = width_mask(self.width);
+        let value = value & mask;
+        let shamt = shamt % self.width;
+        let out = match op {
+            shift_op::SLL => value << shamt,
+            shift_op::SRL => value >> shamt,
+            shift_op::SRA => (sign_extend(value, self.width) >> shamt) as SignalUnsigned,
+            _ => return Err(format!("shifter {}: undefined operation {}", self.id, op)),
+        };
+        Ok(out & mask)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::common::ComponentStore;
+    use crate::components::ProbeOut;
+
+    #[test]
+    fn test_shifter() {
+        let cs = ComponentStore {
+            store: vec![
+                Rc::new(ProbeOut::new("in")),
+                Rc::new(ProbeOut::new("shamt")),
+                Rc::new(ProbeOut::new("op")),
+                Shifter::rc_new(
+                    "shift",
+                    (0.0, 0.0),
+                    Input::new("in", "out"),
+                    Input::new("shamt", "out"),
+                    Input::new("op", "out"),
+                    8,
+                ),
+            ],
+        };
+        let mut simulator = Simulator::new(cs).unwrap();
+        let mut run = |value: u32, shamt: u32, op: u32| {
+            simulator.set_out_value("in", "out", value);
+            simulator.set_out_value("shamt", "out", shamt);
+            simulator.set_out_value("op", "out", op);
+            simulator.clock();
+            simulator.get_input_value(&Input::new("shift", SHIFTER_OUT_ID))
+        };
+        assert_eq!(run(0x81, 1, shift_op::SLL), 0x02.into());
+        assert_eq!(run(0x81, 1, shift_op::SRL), 0x40.into());
+        assert_eq!(run(0x81, 1, shift_op::SRA), 0xc0.into());
+        assert_eq!(run(0x81, 4, shift_op::SRA), 0xf8.into());
+        // modulo the width
+        assert_eq!(run(0x81, 9, shift_op::SRL), 0x40.into());
+        assert_eq!(run(0x81, 1, 3), SignalValue::Unknown);
+    }
+}
@@ -0,0 +1,177 @@
+#[cfg(feature = "gui-egui")]
+use crate::common::EguiComponent;
+use crate::common::{
+    Component, Condition, Id, Input, InputPort, OutputType, Ports, SignalUnsigned, SignalValue,
+    Simulator,
+};
+use log::*;
+use serde::{Deserialize, Serialize};
+use std::any::Any;
+use std::rc::Rc;
+
+pub const SPLITTER_IN_ID: &str = "in";
+
+/// Bit range `lsb..lsb + width` of a bus
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct BitField {
+    pub name: Id,
+    pub lsb: u32,
+    pub width: u32,
+}
+
+impl BitField {
+    pub fn new(name: &str, lsb: u32, width: u32) -> Self {
+        BitField {
+            name: name.to_string(),
+            lsb,
+            width,
+        }
+    }
+
+    pub fn mask(&self) -> SignalUnsigned {
+        SignalUnsigned::MAX
+            .checked_shr(SignalUnsigned::BITS - self.width)
+            .filter(|_| self.width > 0)
+            .unwrap_or(0)
+    }
+
+    pub fn extract(&self, value: SignalUnsigned) -> SignalUnsigned {
+        value.checked_shr(self.lsb).unwrap_or(0) & self.mask()
+    }
+
+    pub fn check(&self) -> Result<(), String> {
+        let msb = self.lsb.checked_add(self.width);
+        if self.width == 0 || msb.is_none_or(|msb| msb > SignalUnsigned::BITS) {
+            Err(format!(
+                "field {} [{}..{}] outside of {} bits",
+                self.name,
+                self.lsb,
+                self.lsb as u64 + self.width as u64,
+                SignalUnsigned::BITS
+            ))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Slices a bus into bit fields, one output per field
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Splitter {
+    pub(crate) id: Id,
+    pub(crate) pos: (f32, f32),
+    pub(crate) bus: Input,
+    pub(crate) fields: Vec<BitField>,
+}
+
+#[typetag::serde]
+impl Component for Splitter {
+    fn to_(&self) {
+        trace!("splitter {:?}", self.fields);
+    }
+    #[cfg(feature = "gui-egui")]
+    fn dummy(&self, id: &str, pos: (f32, f32)) -> Box<Rc<dyn EguiComponent>> {
+        let dummy_input = Input::new("dummy", "out");
+        Box::new(Rc::new(Splitter {
+            id: id.to_string(),
+            pos: (pos.0, pos.1),
+            bus: dummy_input,
+            fields: self.fields.clone(),
+        }))
+    }
+    fn get_id_ports(&self) -> (Id, Ports) {
+        (
+            self.id.clone(),
+            Ports::new(
+                vec![&InputPort {
+                    port_id: SPLITTER_IN_ID.to_string(),
+                    input: self.bus.clone(),
+                }],
+                OutputType::Combinatorial,
+                self.fields.iter().map(|f| f.name.as_str()).collect(),
+            ),
+        )
+    }
+
+    fn clock(&self, simulator: &mut Simulator) -> Result<(), Condition> {
+        let value = simulator.get_input_value(&self.bus);
+        let mut res = Ok(());
+        for field in &self.fields {
+            let out = match (value, field.check()) {
+                (SignalValue::Data(v), Ok(())) => field.extract(v).into(),
+                (_, Err(e)) => {
+                    res = Err(Condition::Error(e));
+                    SignalValue::Unknown
+                }
+                _ => SignalValue::Unknown,
+            };
+            simulator.set_out_value(&self.id, &field.name, out);
+        }
+        res
+    }
+
+    fn set_id_port(&mut self, target_port_id: Id, new_input: Input) {
+        if target_port_id == SPLITTER_IN_ID {
+            self.bus = new_input;
+        }
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl Splitter {
+    pub fn new(id: &str, pos: (f32, f32), bus: Input, fields: Vec<BitField>) -> Self {
+        Splitter {
+            id: id.to_string(),
+            pos,
+            bus,
+            fields,
+        }
+    }
+
+    pub fn rc_new(id: &str, pos: (f32, f32), bus: Input, fields: Vec<BitField>) -> Rc<Self> {
+        Rc::new(Splitter::new(id, pos, bus, fields))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::common::ComponentStore;
+    use crate::components::ProbeStim;
+
+    #[test]
+    fn test_splitter() {
+        let cs = ComponentStore {
+            store: vec![
+                // lw x1, 8(x2)
+                ProbeStim::rc_new("instr", (0.0, 0.0), vec![0x0081_2083]),
+                Splitter::rc_new(
+                    "fields",
+                    (0.0, 0.0),
+                    Input::new("instr", "out"),
+                    vec![
+                        BitField::new("opcode", 0, 7),
+                        BitField::new("rd", 7, 5),
+                        BitField::new("funct3", 12, 3),
+                        BitField::new("rs1", 15, 5),
+                        BitField::new("imm", 20, 12),
+                        BitField::new("word", 0, 32),
+                    ],
+                ),
+            ],
+        };
+        let simulator = Simulator::new(cs).unwrap();
+        let field = |name| simulator.get_input_value(&Input::new("fields", name));
+        assert_eq!(field("opcode"), 0x03.into());
+        assert_eq!(field("rd"), 1.into());
+        assert_eq!(field("funct3"), 2.into());
+        assert_eq!(field("rs1"), 2.into());
+        assert_eq!(field("imm"), 8.into());
+        assert_eq!(field("word"), 0x0081_2083.into());
+        assert!(BitField::new("bad", 30, 3).check().is_err());
+        assert!(BitField::new("wrap", u32::MAX, 2).check().is_err());
+    }
+}
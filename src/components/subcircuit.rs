@@ -0,0 +1,404 @@
+#[cfg(feature = "gui-egui")]
+use crate::common::EguiComponent;
+use crate::common::{
+    Component, ComponentStore, Components, Condition, Id, Input, InputPort, OutputType, Ports,
+    Simulator,
+};
+use log::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::any::Any;
+use std::cell::RefCell;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+/// separates the subcircuit id from the ids of the instantiated components
+pub const SUBCIRCUIT_SEPARATOR: &str = "/";
+
+// guards against a model instantiating itself
+const SUBCIRCUIT_MAX_DEPTH: usize = 16;
+
+/// Boundary input, the stand-in component `internal` in the model is
+/// replaced by `input`
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SubcircuitInput {
+    pub name: Id,
+    pub internal: Id,
+    pub input: Input,
+}
+
+/// Boundary output, forwards output `internal_field` of component
+/// `internal_id` in the model
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SubcircuitOutput {
+    pub name: Id,
+    pub internal_id: Id,
+    pub internal_field: Id,
+}
+
+/// Instance of another saved model
+///
+/// On simulator creation the components of the model are added to the
+/// simulator with ids prefixed by `<id>/`. Each boundary input replaces a
+/// stand-in component (e.g., a `ProbeStim` used to test the model on its
+/// own), which is removed. The subcircuit itself forwards the boundary
+/// outputs, so all outputs are updated together. A combinatorial path from
+/// an output back to an input of the same instance is a combinatorial loop,
+/// which `Simulator::new` reports as an error. A relative path is resolved
+/// against the directory of the model containing the subcircuit.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Subcircuit {
+    pub(crate) id: Id,
+    pub(crate) pos: (f32, f32),
+    pub(crate) file: PathBuf,
+    pub(crate) inputs: Vec<SubcircuitInput>,
+    pub(crate) outputs: Vec<SubcircuitOutput>,
+    // render the instantiated components instead of a box
+    #[serde(default)]
+    pub(crate) open: bool,
+    // the instantiated components, kept for rendering
+    #[serde(skip)]
+    pub(crate) components: RefCell<Components>,
+    // directory of the model containing the subcircuit
+    #[serde(skip)]
+    pub(crate) model_dir: PathBuf,
+}
+
+#[typetag::serde]
+impl Component for Subcircuit {
+    fn to_(&self) {
+        trace!("Subcircuit {} of {:?}", self.id, self.file);
+    }
+
+    #[cfg(feature = "gui-egui")]
+    fn dummy(&self, id: &str, pos: (f32, f32)) -> Box<Rc<dyn EguiComponent>> {
+        let dummy_input = Input::new("dummy", "out");
+        let mut subcircuit = Subcircuit::new(
+            id,
+            pos,
+            self.file.clone(),
+            self.inputs
+                .iter()
+                .map(|i| SubcircuitInput {
+                    input: dummy_input.clone(),
+                    ..i.clone()
+                })
+                .collect(),
+            self.outputs.clone(),
+        );
+        subcircuit.model_dir = self.model_dir.clone();
+        Box::new(Rc::new(subcircuit))
+    }
+
+    fn get_id_ports(&self) -> (Id, Ports) {
+        // only the forwarded signals, the boundary inputs are read by the
+        // instantiated components
+        let inputs: Vec<InputPort> = self
+            .outputs
+            .iter()
+            .map(|output| InputPort {
+                port_id: output.name.clone(),
+                input: self.source(output),
+            })
+            .collect();
+        (
+            self.id.clone(),
+            Ports::new(
+                inputs.iter().collect(),
+                OutputType::Combinatorial,
+                self.outputs.iter().map(|o| o.name.as_str()).collect(),
+            ),
+        )
+    }
+
+    fn set_id_port(&mut self, target_port_id: Id, new_input: Input) {
+        if let Some(port) = self.inputs.iter_mut().find(|i| i.name == target_port_id) {
+            port.input = new_input;
+        }
+    }
+
+    fn clock(&self, simulator: &mut Simulator) -> Result<(), Condition> {
+        for output in &self.outputs {
+            let value = simulator.get_input_value(&self.source(output));
+            simulator.set_out_value(&self.id, &output.name, value);
+        }
+        Ok(())
+    }
+
+    fn set_model_dir(&mut self, dir: &Path) {
+        self.model_dir = dir.to_path_buf();
+    }
+
+    fn sub_components(&self) -> Result<Components, String> {
+        let components = self.instantiate()?;
+        *self.components.borrow_mut() = components.clone();
+        Ok(components)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl Subcircuit {
+    pub fn new(
+        id: &str,
+        pos: (f32, f32),
+        file: impl Into<PathBuf>,
+        inputs: Vec<SubcircuitInput>,
+        outputs: Vec<SubcircuitOutput>,
+    ) -> Self {
+        Subcircuit {
+            id: id.to_string(),
+            pos,
+            file: file.into(),
+            inputs,
+            outputs,
+            open: false,
+            components: RefCell::new(vec![]),
+            model_dir: PathBuf::new(),
+        }
+    }
+
+    pub fn rc_new(
+        id: &str,
+        pos: (f32, f32),
+        file: impl Into<PathBuf>,
+        inputs: Vec<SubcircuitInput>,
+        outputs: Vec<SubcircuitOutput>,
+    ) -> Rc<Self> {
+        Rc::new(Subcircuit::new(id, pos, file, inputs, outputs))
+    }
+
+    // id of a component in the model after instantiation
+    fn prefixed(&self, id: &str) -> Id {
+        format!("{}{}{}", self.id, SUBCIRCUIT_SEPARATOR, id)
+    }
+
+    // the instantiated signal forwarded by `output`
+    fn source(&self, output: &SubcircuitOutput) -> Input {
+        match self
+            .inputs
+            .iter()
+            .find(|i| i.internal == output.internal_id)
+        {
+            Some(input) => input.input.clone(),
+            None => Input::new(&self.prefixed(&output.internal_id), &output.internal_field),
+        }
+    }
+
+    /// Load the model and rename its components into the scope of this instance
+    pub fn instantiate(&self) -> Result<Components, String> {
+        if self.id.matches(SUBCIRCUIT_SEPARATOR).count() >= SUBCIRCUIT_MAX_DEPTH {
+            return Err(format!(
+                "subcircuit {} nested too deep, does {:?} instantiate itself?",
+                self.id, self.file
+            ));
+        }
+        let error = |e: String| format!("subcircuit {}: {:?}: {}", self.id, self.file, e);
+        let file = self.model_dir.join(&self.file);
+        let json = fs::read_to_string(&file).map_err(|e| error(e.to_string()))?;
+        let mut model: Value = serde_json::from_str(&json).map_err(|e| error(e.to_string()))?;
+        let Some(store) = model.get_mut("store").and_then(Value::as_array_mut) else {
+            return Err(error("no component store".to_string()));
+        };
+
+        // drop the stand-ins for the boundary inputs
+        store.retain(|c| {
+            !self
+                .inputs
+                .iter()
+                .any(|i| c.get("id").and_then(Value::as_str) == Some(&i.internal))
+        });
+        for component in store.iter_mut() {
+            self.rename(component);
+            if let Some(Value::String(id)) = component.get_mut("id") {
+                *id = self.prefixed(id);
+            }
+        }
+        let mut store: ComponentStore =
+            serde_json::from_value(model).map_err(|e| error(e.to_string()))?;
+        store.set_model_dir(file.parent().unwrap_or(Path::new("")));
+        Ok(store.store)
+    }
+
+    // rewrite all inputs in `value`, any object with exactly an `id` and a
+    // `field` is taken to be an input
+    fn rename(&self, value: &mut Value) {
+        match value {
+            Value::Object(map) => {
+                if map.len() == 2 {
+                    if let (Some(Value::String(id)), Some(Value::String(_))) =
+                        (map.get("id"), map.get("field"))
+                    {
+                        let id = id.clone();
+                        match self.inputs.iter().find(|i| i.internal == id) {
+                            Some(port) => {
+                                map.insert("id".to_string(), Value::String(port.input.id.clone()));
+                                map.insert(
+                                    "field".to_string(),
+                                    Value::String(port.input.field.clone()),
+                                );
+                            }
+                            None => {
+                                map.insert("id".to_string(), Value::String(self.prefixed(&id)));
+                            }
+                        }
+                        return;
+                    }
+                }
+                map.values_mut().for_each(|v| self.rename(v));
+            }
+            Value::Array(values) => values.iter_mut().for_each(|v| self.rename(v)),
+            _ => (),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::components::{Add, Constant, Register};
+
+    // a counter, incremented by the `step` input
+    fn counter_model(path: &std::path::Path) {
+        let cs = ComponentStore {
+            store: vec![
+                Constant::rc_new("step", (0.0, 0.0), 1),
+                Register::rc_new("reg", (0.0, 0.0), Input::new("add", "out")),
+                Add::rc_new(
+                    "add",
+                    (0.0, 0.0),
+                    Input::new("reg", "out"),
+                    Input::new("step", "out"),
+                ),
+            ],
+        };
+        cs.save_file(&path.to_path_buf());
+    }
+
+    fn counter(id: &str, file: &std::path::Path, step: Input) -> Rc<Subcircuit> {
+        Subcircuit::rc_new(
+            id,
+            (0.0, 0.0),
+            file,
+            vec![SubcircuitInput {
+                name: "step".to_string(),
+                internal: "step".to_string(),
+                input: step,
+            }],
+            vec![SubcircuitOutput {
+                name: "count".to_string(),
+                internal_id: "reg".to_string(),
+                internal_field: "out".to_string(),
+            }],
+        )
+    }
+
+    #[test]
+    fn test_subcircuit() {
+        let dir = std::env::temp_dir().join(format!("syncrim_subcircuit_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("counter.json");
+        counter_model(&file);
+
+        let cs = ComponentStore {
+            store: vec![
+                Constant::rc_new("two", (0.0, 0.0), 2),
+                counter("c1", &file, Input::new("two", "out")),
+                // the second counter steps by the count of the first
+                counter("c2", &file, Input::new("c1", "count")),
+            ],
+        };
+        let mut simulator = Simulator::new(cs).unwrap();
+        assert!(simulator.component_ids.contains(&"c1/reg".to_string()));
+        assert!(simulator.component_ids.contains(&"c2/add".to_string()));
+        // the stand-ins are replaced
+        assert!(!simulator.component_ids.contains(&"c1/step".to_string()));
+
+        simulator.step(3);
+        let c1: Vec<_> = (1..=4)
+            .map(|cycle| simulator.get_input_value_at(&Input::new("c1", "count"), cycle))
+            .collect();
+        assert_eq!(c1, vec![0.into(), 2.into(), 4.into(), 6.into()]);
+        assert_eq!(
+            simulator.get_input_value(&Input::new("c2", "count")),
+            (2 + 4).into()
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_subcircuit_recursive() {
+        let dir =
+            std::env::temp_dir().join(format!("syncrim_subcircuit_rec_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("self.json");
+        let cs = ComponentStore {
+            store: vec![counter("c", &file, Input::new("c", "count"))],
+        };
+        cs.save_file(&file);
+        assert!(Simulator::new(cs).is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_subcircuit_relative_path() {
+        let dir =
+            std::env::temp_dir().join(format!("syncrim_subcircuit_rel_{}", std::process::id()));
+        fs::create_dir_all(dir.join("parts")).unwrap();
+        counter_model(&dir.join("parts").join("counter.json"));
+        let top = dir.join("top.json");
+        ComponentStore {
+            store: vec![
+                Constant::rc_new("two", (0.0, 0.0), 2),
+                counter(
+                    "c",
+                    Path::new("parts/counter.json"),
+                    Input::new("two", "out"),
+                ),
+            ],
+        }
+        .save_file(&top);
+
+        // resolved against the directory of the model, not the working directory
+        let mut simulator = Simulator::new(ComponentStore::load_file(&top)).unwrap();
+        simulator.step(2);
+        assert_eq!(
+            simulator.get_input_value(&Input::new("c", "count")),
+            4.into()
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_subcircuit_loop() {
+        let dir =
+            std::env::temp_dir().join(format!("syncrim_subcircuit_loop_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("counter.json");
+        counter_model(&file);
+
+        // forwarding the adder, which adds the forwarded value
+        let cs = ComponentStore {
+            store: vec![Subcircuit::rc_new(
+                "c",
+                (0.0, 0.0),
+                &file,
+                vec![SubcircuitInput {
+                    name: "step".to_string(),
+                    internal: "step".to_string(),
+                    input: Input::new("c", "sum"),
+                }],
+                vec![SubcircuitOutput {
+                    name: "sum".to_string(),
+                    internal_id: "add".to_string(),
+                    internal_field: "out".to_string(),
+                }],
+            )],
+        };
+        assert!(Simulator::new(cs).is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
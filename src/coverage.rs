@@ -0,0 +1,224 @@
+// Functional coverage
+//
+// Components declare cover points, an input (or own output) and a set of
+// bins for the values of interest. Coverage is computed from the simulator
+// history, so it covers all cycles simulated since the last reset.
+use crate::common::{Id, Input, SignalUnsigned, SignalValue, Simulator};
+use std::fmt;
+
+/// Value, or set of values, to be covered, hit if the masked sample equals `value`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CoverBin {
+    pub name: String,
+    pub mask: SignalUnsigned,
+    pub value: SignalUnsigned,
+}
+
+impl CoverBin {
+    pub fn new(name: &str, value: SignalUnsigned) -> Self {
+        CoverBin::masked(name, SignalUnsigned::MAX, value)
+    }
+
+    pub fn masked(name: &str, mask: SignalUnsigned, value: SignalUnsigned) -> Self {
+        CoverBin {
+            name: name.to_string(),
+            mask,
+            value,
+        }
+    }
+
+    pub fn matches(&self, value: SignalUnsigned) -> bool {
+        value & self.mask == self.value
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CoverPoint {
+    pub name: String,
+    pub input: Input,
+    pub bins: Vec<CoverBin>,
+}
+
+impl CoverPoint {
+    pub fn new(name: &str, input: Input, bins: Vec<CoverBin>) -> Self {
+        CoverPoint {
+            name: name.to_string(),
+            input,
+            bins,
+        }
+    }
+
+    /// one bin per value, named by the value
+    pub fn values(
+        name: &str,
+        input: Input,
+        values: impl IntoIterator<Item = SignalUnsigned>,
+    ) -> Self {
+        let bins = values
+            .into_iter()
+            .map(|v| CoverBin::new(&v.to_string(), v))
+            .collect();
+        CoverPoint::new(name, input, bins)
+    }
+}
+
+/// Hit counts for the bins of a cover point
+#[derive(Debug, Clone)]
+pub struct PointCoverage {
+    // id of the component declaring the point
+    pub id: Id,
+    pub point: CoverPoint,
+    pub hits: Vec<usize>,
+}
+
+impl PointCoverage {
+    pub fn covered(&self) -> usize {
+        self.hits.iter().filter(|h| **h > 0).count()
+    }
+
+    /// bins never hit
+    pub fn holes(&self) -> Vec<&CoverBin> {
+        self.point
+            .bins
+            .iter()
+            .zip(&self.hits)
+            .filter(|(_, h)| **h == 0)
+            .map(|(b, _)| b)
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Coverage {
+    pub cycles: usize,
+    pub points: Vec<PointCoverage>,
+}
+
+impl Coverage {
+    pub fn covered(&self) -> usize {
+        self.points.iter().map(|p| p.covered()).sum()
+    }
+
+    pub fn bins(&self) -> usize {
+        self.points.iter().map(|p| p.hits.len()).sum()
+    }
+
+    /// covered bins in percent, 100 if there is nothing to cover
+    pub fn percent(&self) -> f64 {
+        match self.bins() {
+            0 => 100.0,
+            bins => 100.0 * self.covered() as f64 / bins as f64,
+        }
+    }
+}
+
+impl fmt::Display for Coverage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "coverage {}/{} bins ({:.1}%) over {} cycles",
+            self.covered(),
+            self.bins(),
+            self.percent(),
+            self.cycles
+        )?;
+        for point in &self.points {
+            write!(
+                f,
+                "{}.{}: {}/{}",
+                point.id,
+                point.point.name,
+                point.covered(),
+                point.hits.len()
+            )?;
+            let holes: Vec<&str> = point.holes().iter().map(|b| b.name.as_str()).collect();
+            if holes.is_empty() {
+                writeln!(f)?;
+            } else {
+                writeln!(f, ", holes: {}", holes.join(" "))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Simulator {
+    /// coverage of the cover points of all components, over all cycles simulated
+    pub fn coverage(&self) -> Coverage {
+        let mut points = vec![];
+        for component in &self.ordered_components {
+            let id = component.get_id_ports().0;
+            for point in component.cover_points() {
+                let mut hits = vec![0; point.bins.len()];
+                for cycle in 1..=self.history.len() {
+                    if let SignalValue::Data(value) = self.get_input_value_at(&point.input, cycle) {
+                        for (bin, hit) in point.bins.iter().zip(hits.iter_mut()) {
+                            if bin.matches(value) {
+                                *hit += 1;
+                            }
+                        }
+                    }
+                }
+                points.push(PointCoverage {
+                    id: id.clone(),
+                    point,
+                    hits,
+                });
+            }
+        }
+        Coverage {
+            cycles: self.cycle,
+            points,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::common::ComponentStore;
+    use crate::components::{Constant, Mux, ProbeStim};
+
+    #[test]
+    fn test_mux_coverage() {
+        let cs = ComponentStore {
+            store: vec![
+                ProbeStim::rc_new("sel", (0.0, 0.0), vec![0, 2, 0, 2]),
+                Constant::rc_new("c", (0.0, 0.0), 0),
+                Mux::rc_new(
+                    "mux",
+                    (0.0, 0.0),
+                    Input::new("sel", "out"),
+                    vec![
+                        Input::new("c", "out"),
+                        Input::new("c", "out"),
+                        Input::new("c", "out"),
+                    ],
+                ),
+            ],
+        };
+        let mut simulator = Simulator::new(cs).unwrap();
+        simulator.step(3);
+
+        let coverage = simulator.coverage();
+        assert_eq!(coverage.cycles, 4);
+        assert_eq!(coverage.points.len(), 1);
+        assert_eq!(coverage.points[0].hits, vec![2, 0, 2]);
+        assert_eq!(coverage.points[0].holes(), vec![&CoverBin::new("1", 1)]);
+        assert_eq!(
+            coverage.to_string(),
+            "coverage 2/3 bins (66.7%) over 4 cycles\nmux.select: 2/3, holes: 1\n"
+        );
+
+        // coverage follows the history
+        simulator.un_clock();
+        assert_eq!(simulator.coverage().points[0].hits, vec![2, 0, 1]);
+    }
+
+    #[test]
+    fn test_masked_bin() {
+        let bin = CoverBin::masked("lw", 0x707f, 0x2003);
+        assert!(bin.matches(0x0004_2083));
+        assert!(!bin.matches(0x0004_4083));
+    }
+}
@@ -1,4 +1,5 @@
 use crate::common::{Input, Ports};
+use crate::components::{ResetInput, ResetKind};
 use crate::gui_egui::editor::{EditorMode, GridOptions};
 use crate::gui_egui::editor_wire_mode::get_grid_snap;
 use crate::gui_egui::helper::{
@@ -150,6 +151,48 @@ pub fn input_selector(
     input_selector_removeable(ui, input, port_name, id_ports, own_id, false).0
 }
 
+/// Enable, disable and configure an optional reset input
+pub fn reset_selector(
+    ui: &mut Ui,
+    reset: &mut Option<ResetInput>,
+    port_name: crate::common::Id,
+    id_ports: &[(crate::common::Id, Ports)],
+    own_id: crate::common::Id,
+) -> bool {
+    let mut clicked_dropdown = false;
+    let mut enable = reset.is_some();
+    ui.checkbox(&mut enable, "reset input");
+    match (enable, reset.is_some()) {
+        (true, false) => {
+            *reset = Some(ResetInput::new(
+                Input::new("dummy", "out"),
+                0,
+                ResetKind::Sync,
+            ))
+        }
+        (false, true) => *reset = None,
+        _ => (),
+    }
+    if let Some(reset) = reset {
+        clicked_dropdown |= input_selector(ui, &mut reset.input, port_name, id_ports, own_id);
+        ui.horizontal(|ui| {
+            ui.label("reset value");
+            ui.add(DragValue::new(&mut reset.value));
+            ComboBox::from_label("reset kind")
+                .selected_text(format!("{:?}", reset.kind))
+                .show_ui(ui, |ui| {
+                    clicked_dropdown |= ui
+                        .selectable_value(&mut reset.kind, ResetKind::Sync, "Sync")
+                        .clicked();
+                    clicked_dropdown |= ui
+                        .selectable_value(&mut reset.kind, ResetKind::Async, "Async")
+                        .clicked();
+                });
+        });
+    }
+    clicked_dropdown
+}
+
 pub fn input_change_id(
     ui: &mut Ui,
     id_tmp: &mut String,
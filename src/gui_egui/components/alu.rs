@@ -0,0 +1,248 @@
+use crate::common::{EguiComponent, Input, Ports, Simulator};
+use crate::components::{
+    Alu, AluOp, AluOpCode, ALU_A_IN_ID, ALU_B_IN_ID, ALU_CARRY_OUT_ID, ALU_NEGATIVE_OUT_ID,
+    ALU_OP_IN_ID, ALU_OUT_ID, ALU_OVERFLOW_OUT_ID, ALU_ZERO_OUT_ID,
+};
+use crate::gui_egui::component_ui::{
+    drag_logic, input_change_id, input_selector, pos_drag_value, properties_window,
+    rect_with_hover, visualize_ports,
+};
+use crate::gui_egui::editor::{EditorMode, EditorRenderReturn, GridOptions};
+use crate::gui_egui::gui::EguiExtra;
+use crate::gui_egui::helper::offset_helper;
+use egui::{
+    Align2, Color32, ComboBox, DragValue, FontId, Pos2, Rect, Response, Shape, Stroke, Ui, Vec2,
+};
+
+// output ports on the right edge, top to bottom
+const ALU_OUTPUTS: [(&str, f32); 5] = [
+    (ALU_CARRY_OUT_ID, -16f32),
+    (ALU_ZERO_OUT_ID, -8f32),
+    (ALU_OUT_ID, 0f32),
+    (ALU_NEGATIVE_OUT_ID, 8f32),
+    (ALU_OVERFLOW_OUT_ID, 16f32),
+];
+
+// editor for the op table
+fn op_table_editor(ui: &mut Ui, ops: &mut Vec<AluOpCode>) -> bool {
+    let mut clicked_dropdown = false;
+    let mut remove = None;
+    for (i, entry) in ops.iter_mut().enumerate() {
+        ui.horizontal(|ui| {
+            ui.add(DragValue::new(&mut entry.code));
+            ComboBox::from_id_source(i)
+                .selected_text(entry.op.name())
+                .show_ui(ui, |ui| {
+                    for op in AluOp::ALL {
+                        clicked_dropdown |=
+                            ui.selectable_value(&mut entry.op, op, op.name()).clicked();
+                    }
+                });
+            if ui.button("x").clicked() {
+                remove = Some(i);
+            }
+        });
+    }
+    if let Some(i) = remove {
+        ops.remove(i);
+    }
+    ui.horizontal(|ui| {
+        if ui.button("+ Add new op").clicked() {
+            let code = ops.iter().map(|e| e.code + 1).max().unwrap_or(0);
+            ops.push(AluOpCode::new(code, AluOp::Add));
+        }
+        if ui.button("MIPS").clicked() {
+            *ops = Alu::mips_ops();
+        }
+        if ui.button("RISC-V").clicked() {
+            *ops = Alu::riscv_ops();
+        }
+    });
+    clicked_dropdown
+}
+
+#[typetag::serde]
+impl EguiComponent for Alu {
+    fn render(
+        &self,
+        ui: &mut Ui,
+        _context: &mut EguiExtra,
+        simulator: Option<&mut Simulator>,
+        offset: Vec2,
+        scale: f32,
+        clip_rect: Rect,
+        editor_mode: EditorMode,
+    ) -> Option<Vec<Response>> {
+        // 41x81
+        // middle: 21x 41y (0 0)
+        let oh: fn((f32, f32), f32, Vec2) -> Pos2 = offset_helper;
+        let offset_old = offset;
+        let mut offset = offset;
+        offset.x += self.pos.0 * scale;
+        offset.y += self.pos.1 * scale;
+        let s = scale;
+        let o = offset;
+        // The shape
+        ui.painter().add(Shape::closed_line(
+            vec![
+                oh((-20f32, -40f32), s, o),
+                oh((0f32, -40f32), s, o),
+                oh((20f32, -20f32), s, o),
+                oh((20f32, 20f32), s, o),
+                oh((0f32, 40f32), s, o),
+                oh((-20f32, 40f32), s, o),
+                oh((-20f32, 20f32), s, o),
+                oh((-10f32, 0f32), s, o),
+                oh((-20f32, -20f32), s, o),
+            ],
+            Stroke {
+                width: scale,
+                color: Color32::BLACK,
+            },
+        ));
+
+        let rect = Rect {
+            min: oh((-20f32, -40f32), s, o),
+            max: oh((20f32, 40f32), s, o),
+        };
+        let (op, outputs) = match simulator {
+            Some(s) => (
+                self.format_op(s.get_input_value(&self.op_in)),
+                ALU_OUTPUTS
+                    .iter()
+                    .map(|(id, _)| {
+                        format!("{}: {:?}", id, s.get_input_value(&Input::new(&self.id, id)))
+                    })
+                    .collect(),
+            ),
+            None => ("no sim".to_string(), vec![]),
+        };
+        ui.painter().text(
+            oh((5f32, 0f32), s, o),
+            Align2::CENTER_CENTER,
+            format!("ALU\n{}", op),
+            FontId::monospace(8.0 * scale),
+            Color32::BLACK,
+        );
+
+        let r = rect_with_hover(rect, clip_rect, editor_mode, ui, self.id.clone(), |ui| {
+            ui.label(format!("Id: {}", self.id.clone()));
+            ui.label(format!("ALU {} bits", self.width));
+            ui.label(format!("op: {}", op));
+            for output in &outputs {
+                ui.label(output);
+            }
+        });
+        match editor_mode {
+            EditorMode::Simulator => (),
+            _ => visualize_ports(ui, self.ports_location(), offset_old, scale, clip_rect),
+        }
+        Some(vec![r])
+    }
+
+    fn render_editor(
+        &mut self,
+        ui: &mut Ui,
+        context: &mut EguiExtra,
+        simulator: Option<&mut Simulator>,
+        offset: Vec2,
+        scale: f32,
+        clip_rect: Rect,
+        id_ports: &[(crate::common::Id, Ports)],
+        grid: &GridOptions,
+        editor_mode: EditorMode,
+    ) -> EditorRenderReturn {
+        let r_vec = Alu::render(
+            self,
+            ui,
+            context,
+            simulator,
+            offset,
+            scale,
+            clip_rect,
+            editor_mode,
+        )
+        .unwrap();
+        let resp = &r_vec[0];
+        let delete = drag_logic(
+            ui.ctx(),
+            resp,
+            &mut self.pos,
+            &mut context.pos_tmp,
+            scale,
+            offset,
+            grid,
+        );
+
+        properties_window(
+            ui,
+            self.id.clone(),
+            resp,
+            &mut context.properties_window,
+            |ui| {
+                let mut clicked_dropdown = false;
+                input_change_id(ui, &mut context.id_tmp, &mut self.id, id_ports);
+                pos_drag_value(ui, &mut self.pos);
+                clicked_dropdown |= input_selector(
+                    ui,
+                    &mut self.a_in,
+                    ALU_A_IN_ID.to_string(),
+                    id_ports,
+                    self.id.clone(),
+                );
+                clicked_dropdown |= input_selector(
+                    ui,
+                    &mut self.b_in,
+                    ALU_B_IN_ID.to_string(),
+                    id_ports,
+                    self.id.clone(),
+                );
+                clicked_dropdown |= input_selector(
+                    ui,
+                    &mut self.op_in,
+                    ALU_OP_IN_ID.to_string(),
+                    id_ports,
+                    self.id.clone(),
+                );
+                ui.horizontal(|ui| {
+                    ui.label("width");
+                    ui.add(DragValue::new(&mut self.width).range(1..=32));
+                });
+                clicked_dropdown |= op_table_editor(ui, &mut self.ops);
+                clicked_dropdown
+            },
+        );
+        EditorRenderReturn {
+            delete,
+            resp: Some(r_vec),
+        }
+    }
+
+    fn ports_location(&self) -> Vec<(crate::common::Id, Pos2)> {
+        let own_pos = Vec2::new(self.pos.0, self.pos.1);
+        let mut v = vec![
+            (ALU_A_IN_ID.to_string(), Pos2::new(-20f32, -30f32) + own_pos),
+            (ALU_B_IN_ID.to_string(), Pos2::new(-20f32, 30f32) + own_pos),
+            (
+                ALU_OP_IN_ID.to_string(),
+                Pos2::new(-10f32, -40f32) + own_pos,
+            ),
+        ];
+        for (id, y) in ALU_OUTPUTS {
+            v.push((id.to_string(), Pos2::new(20f32, y) + own_pos));
+        }
+        v
+    }
+
+    fn top_padding(&self) -> f32 {
+        40f32
+    }
+
+    fn set_pos(&mut self, pos: (f32, f32)) {
+        self.pos = pos;
+    }
+
+    fn get_pos(&self) -> (f32, f32) {
+        self.pos
+    }
+}
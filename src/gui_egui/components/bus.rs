@@ -0,0 +1,215 @@
+use crate::common::{EguiComponent, Ports, Simulator};
+use crate::components::{
+    Bus, BusDevice, BUS_ADDR_ID, BUS_CTRL_ID, BUS_FAULT_OUT_ID, BUS_SELECT_OUT_ID,
+};
+use crate::gui_egui::component_ui::{
+    drag_logic, input_change_id, input_selector, pos_drag_value, properties_window,
+    rect_with_hover, visualize_ports,
+};
+use crate::gui_egui::editor::{EditorMode, EditorRenderReturn, GridOptions};
+use crate::gui_egui::gui::EguiExtra;
+use crate::gui_egui::helper::offset_helper;
+use egui::{
+    Align2, Color32, DragValue, FontId, Pos2, Rect, Response, Rounding, Shape, Stroke, Ui, Vec2,
+};
+
+const BUS_WIDTH: f32 = 60.0;
+// space for each device on the right side
+const BUS_DEVICE_HEIGHT: f32 = 20.0;
+
+impl Bus {
+    fn height(&self) -> f32 {
+        (self.map.len() as f32 * BUS_DEVICE_HEIGHT + BUS_DEVICE_HEIGHT).max(60.0)
+    }
+
+    // y of the ctrl output of device `i`, the addr output is below
+    fn device_y(&self, i: usize) -> f32 {
+        -self.height() / 2.0 + BUS_DEVICE_HEIGHT / 2.0 + 5.0 + i as f32 * BUS_DEVICE_HEIGHT
+    }
+}
+
+#[typetag::serde]
+impl EguiComponent for Bus {
+    fn render(
+        &self,
+        ui: &mut Ui,
+        _context: &mut EguiExtra,
+        _simulator: Option<&mut Simulator>,
+        offset: Vec2,
+        scale: f32,
+        clip_rect: Rect,
+        editor_mode: EditorMode,
+    ) -> Option<Vec<Response>> {
+        let oh: fn((f32, f32), f32, Vec2) -> Pos2 = offset_helper;
+        let offset_old = offset;
+        let mut offset = offset;
+        offset.x += self.pos.0 * scale;
+        offset.y += self.pos.1 * scale;
+        let s = scale;
+        let o = offset;
+        let (w, h) = (BUS_WIDTH / 2.0, self.height() / 2.0);
+
+        // The shape
+        let rect = Rect {
+            min: oh((-w, -h), s, o),
+            max: oh((w, h), s, o),
+        };
+        ui.painter().add(Shape::rect_stroke(
+            rect,
+            Rounding::ZERO,
+            Stroke {
+                width: scale,
+                color: Color32::BLACK,
+            },
+        ));
+        ui.painter().text(
+            oh((-w + 4.0, 0.0), s, o),
+            Align2::LEFT_CENTER,
+            "Bus",
+            FontId::monospace(8.0 * scale),
+            Color32::BLACK,
+        );
+        for (i, device) in self.map.iter().enumerate() {
+            ui.painter().text(
+                oh((w - 4.0, self.device_y(i) + 5.0), s, o),
+                Align2::RIGHT_CENTER,
+                &device.name,
+                FontId::monospace(6.0 * scale),
+                Color32::BLACK,
+            );
+        }
+
+        let r = rect_with_hover(rect, clip_rect, editor_mode, ui, self.id.clone(), |ui| {
+            ui.label(format!("Id: {}", self.id.clone()));
+            ui.label("Bus");
+            for (i, device) in self.map.iter().enumerate() {
+                ui.label(format!(
+                    "{}: {} {:#010x}..{:#010x}",
+                    i, device.name, device.range.start, device.range.end
+                ));
+            }
+        });
+        match editor_mode {
+            EditorMode::Simulator => (),
+            _ => visualize_ports(ui, self.ports_location(), offset_old, scale, clip_rect),
+        }
+        Some(vec![r])
+    }
+
+    fn render_editor(
+        &mut self,
+        ui: &mut Ui,
+        context: &mut EguiExtra,
+        simulator: Option<&mut Simulator>,
+        offset: Vec2,
+        scale: f32,
+        clip_rect: Rect,
+        id_ports: &[(crate::common::Id, Ports)],
+        grid: &GridOptions,
+        editor_mode: EditorMode,
+    ) -> EditorRenderReturn {
+        let r_vec = Bus::render(
+            self,
+            ui,
+            context,
+            simulator,
+            offset,
+            scale,
+            clip_rect,
+            editor_mode,
+        )
+        .unwrap();
+        let resp = &r_vec[0];
+        let delete = drag_logic(
+            ui.ctx(),
+            resp,
+            &mut self.pos,
+            &mut context.pos_tmp,
+            scale,
+            offset,
+            grid,
+        );
+
+        properties_window(
+            ui,
+            self.id.clone(),
+            resp,
+            &mut context.properties_window,
+            |ui| {
+                let mut clicked_dropdown = false;
+                input_change_id(ui, &mut context.id_tmp, &mut self.id, id_ports);
+                pos_drag_value(ui, &mut self.pos);
+                clicked_dropdown |= input_selector(
+                    ui,
+                    &mut self.addr,
+                    BUS_ADDR_ID.to_string(),
+                    id_ports,
+                    self.id.clone(),
+                );
+                clicked_dropdown |= input_selector(
+                    ui,
+                    &mut self.ctrl,
+                    BUS_CTRL_ID.to_string(),
+                    id_ports,
+                    self.id.clone(),
+                );
+                // the memory map, renaming a device renames its outputs
+                let mut remove = None;
+                for (i, device) in self.map.iter_mut().enumerate() {
+                    ui.horizontal(|ui| {
+                        ui.add(egui::TextEdit::singleline(&mut device.name).desired_width(60.0));
+                        ui.add(DragValue::new(&mut device.range.start).hexadecimal(8, false, true));
+                        ui.label("..");
+                        ui.add(DragValue::new(&mut device.range.end).hexadecimal(8, false, true));
+                        if ui.button("x").clicked() {
+                            remove = Some(i);
+                        }
+                    });
+                }
+                if let Some(i) = remove {
+                    self.map.remove(i);
+                }
+                if ui.button("add device").clicked() {
+                    let start = self.map.iter().map(|d| d.range.end).max().unwrap_or(0);
+                    let name = format!("dev{}", self.map.len());
+                    self.map
+                        .push(BusDevice::new(&name, start..start.saturating_add(0x1000)));
+                }
+                clicked_dropdown
+            },
+        );
+        EditorRenderReturn {
+            delete,
+            resp: Some(r_vec),
+        }
+    }
+
+    fn ports_location(&self) -> Vec<(crate::common::Id, Pos2)> {
+        let own_pos = Vec2::new(self.pos.0, self.pos.1);
+        let (w, h) = (BUS_WIDTH / 2.0, self.height() / 2.0);
+        let mut ports = vec![
+            (BUS_ADDR_ID.to_string(), Pos2::new(-w, -10.0) + own_pos),
+            (BUS_CTRL_ID.to_string(), Pos2::new(-w, 10.0) + own_pos),
+            (BUS_SELECT_OUT_ID.to_string(), Pos2::new(-10.0, h) + own_pos),
+            (BUS_FAULT_OUT_ID.to_string(), Pos2::new(10.0, h) + own_pos),
+        ];
+        for (i, device) in self.map.iter().enumerate() {
+            let y = self.device_y(i);
+            ports.push((device.ctrl_out(), Pos2::new(w, y) + own_pos));
+            ports.push((device.addr_out(), Pos2::new(w, y + 10.0) + own_pos));
+        }
+        ports
+    }
+
+    fn top_padding(&self) -> f32 {
+        self.height() / 2.0
+    }
+
+    fn set_pos(&mut self, pos: (f32, f32)) {
+        self.pos = pos;
+    }
+
+    fn get_pos(&self) -> (f32, f32) {
+        self.pos
+    }
+}
@@ -0,0 +1,296 @@
+use crate::common::{EguiComponent, Ports, Simulator};
+use crate::components::{
+    Cache, Replacement, WritePolicy, CACHE_ADDR_ID, CACHE_CTRL_ID, CACHE_DATA_ID, CACHE_HIT_OUT_ID,
+    CACHE_MEM_ADDR_OUT_ID, CACHE_MEM_CTRL_OUT_ID, CACHE_MEM_DATA_OUT_ID, CACHE_MEM_SEXT_OUT_ID,
+    CACHE_MEM_SIZE_OUT_ID, CACHE_SEXT_ID, CACHE_SIZE_ID, CACHE_STALL_OUT_ID,
+};
+use crate::gui_egui::component_ui::{
+    drag_logic, input_change_id, input_selector, pos_drag_value, properties_window,
+    rect_with_hover, visualize_ports,
+};
+use crate::gui_egui::editor::{EditorMode, EditorRenderReturn, GridOptions};
+use crate::gui_egui::gui::EguiExtra;
+use crate::gui_egui::helper::offset_helper;
+use egui::{
+    Align2, Color32, ComboBox, Context, DragValue, FontId, Grid, Pos2, Rect, Response, RichText,
+    Rounding, ScrollArea, Shape, Stroke, Ui, Vec2, Window,
+};
+
+impl Cache {
+    // tag, valid and dirty arrays, one row per set
+    fn side_panel(&self, ctx: &Context) {
+        let state = self.state();
+        let stats = state.stats;
+        Window::new(format!("Cache {}", self.id)).show(ctx, |ui| {
+            ui.label(format!(
+                "hits {} misses {} write backs {} hit rate {:.1}%",
+                stats.hits,
+                stats.misses,
+                stats.write_backs,
+                stats.hit_rate() * 100.0
+            ));
+            ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                Grid::new((&self.id, "cache_lines"))
+                    .striped(true)
+                    .show(ui, |ui| {
+                        ui.label("set");
+                        for way in 0..self.ways {
+                            ui.label(format!("way {} v d tag", way));
+                        }
+                        ui.end_row();
+                        for (set, lines) in state.lines.iter().enumerate() {
+                            ui.monospace(format!("{}", set));
+                            for line in lines {
+                                let text = RichText::new(format!(
+                                    "{} {} {:#x}",
+                                    line.valid as u8, line.dirty as u8, line.tag
+                                ))
+                                .monospace();
+                                ui.label(if line.valid {
+                                    text
+                                } else {
+                                    text.color(Color32::GRAY)
+                                });
+                            }
+                            ui.end_row();
+                        }
+                    });
+            });
+        });
+    }
+}
+
+#[typetag::serde]
+impl EguiComponent for Cache {
+    fn render(
+        &self,
+        ui: &mut Ui,
+        _context: &mut EguiExtra,
+        _simulator: Option<&mut Simulator>,
+        offset: Vec2,
+        scale: f32,
+        clip_rect: Rect,
+        editor_mode: EditorMode,
+    ) -> Option<Vec<Response>> {
+        // 81x61
+        // middle: 41x 31y (0 0)
+        let oh: fn((f32, f32), f32, Vec2) -> Pos2 = offset_helper;
+        let offset_old = offset;
+        let mut offset = offset;
+        offset.x += self.pos.0 * scale;
+        offset.y += self.pos.1 * scale;
+        let s = scale;
+        let o = offset;
+        let stats = self.stats();
+
+        // The shape
+        let rect = Rect {
+            min: oh((-40f32, -30f32), s, o),
+            max: oh((40f32, 30f32), s, o),
+        };
+        ui.painter().add(Shape::rect_stroke(
+            rect,
+            Rounding::ZERO,
+            Stroke {
+                width: scale,
+                color: Color32::BLACK,
+            },
+        ));
+        for (y, text) in [
+            (-15f32, "Cache".to_string()),
+            (
+                0f32,
+                format!("{}x{}x{}", self.sets, self.ways, self.line_size),
+            ),
+            (
+                15f32,
+                format!("{}/{}", stats.hits, stats.hits + stats.misses),
+            ),
+        ] {
+            ui.painter().text(
+                oh((0f32, y), s, o),
+                Align2::CENTER_CENTER,
+                text,
+                FontId::monospace(7.0 * scale),
+                Color32::BLACK,
+            );
+        }
+
+        let r = rect_with_hover(rect, clip_rect, editor_mode, ui, self.id.clone(), |ui| {
+            ui.label(format!("Id: {}", self.id.clone()));
+            ui.label(format!(
+                "{} sets, {} ways, {} byte lines",
+                self.sets, self.ways, self.line_size
+            ));
+            ui.label(format!(
+                "{:?}, {:?}, miss penalty {}",
+                self.write_policy, self.replacement, self.miss_penalty
+            ));
+            ui.label(format!(
+                "hits {} misses {} write backs {}",
+                stats.hits, stats.misses, stats.write_backs
+            ));
+        });
+        match editor_mode {
+            EditorMode::Simulator => self.side_panel(ui.ctx()),
+            _ => visualize_ports(ui, self.ports_location(), offset_old, scale, clip_rect),
+        }
+        Some(vec![r])
+    }
+
+    fn render_editor(
+        &mut self,
+        ui: &mut Ui,
+        context: &mut EguiExtra,
+        simulator: Option<&mut Simulator>,
+        offset: Vec2,
+        scale: f32,
+        clip_rect: Rect,
+        id_ports: &[(crate::common::Id, Ports)],
+        grid: &GridOptions,
+        editor_mode: EditorMode,
+    ) -> EditorRenderReturn {
+        let r_vec = Cache::render(
+            self,
+            ui,
+            context,
+            simulator,
+            offset,
+            scale,
+            clip_rect,
+            editor_mode,
+        )
+        .unwrap();
+        let resp = &r_vec[0];
+        let delete = drag_logic(
+            ui.ctx(),
+            resp,
+            &mut self.pos,
+            &mut context.pos_tmp,
+            scale,
+            offset,
+            grid,
+        );
+
+        properties_window(
+            ui,
+            self.id.clone(),
+            resp,
+            &mut context.properties_window,
+            |ui| {
+                let mut clicked_dropdown = false;
+                input_change_id(ui, &mut context.id_tmp, &mut self.id, id_ports);
+                pos_drag_value(ui, &mut self.pos);
+                for (port, input) in [
+                    (CACHE_DATA_ID, &mut self.data),
+                    (CACHE_ADDR_ID, &mut self.addr),
+                    (CACHE_CTRL_ID, &mut self.ctrl),
+                    (CACHE_SEXT_ID, &mut self.sext),
+                    (CACHE_SIZE_ID, &mut self.size),
+                ] {
+                    clicked_dropdown |=
+                        input_selector(ui, input, port.to_string(), id_ports, self.id.clone());
+                }
+                // powers of two, edited as the exponent
+                for (label, value, max_bits) in [
+                    ("sets", &mut self.sets, 16),
+                    ("ways", &mut self.ways, 6),
+                    ("line size", &mut self.line_size, 10),
+                ] {
+                    let mut bits = (*value).max(1).trailing_zeros();
+                    ui.horizontal(|ui| {
+                        ui.label(label);
+                        ui.add(
+                            DragValue::new(&mut bits)
+                                .range(0..=max_bits)
+                                .custom_formatter(|bits, _| format!("{}", 1u32 << bits as u32)),
+                        );
+                    });
+                    *value = 1 << bits;
+                }
+                ui.horizontal(|ui| {
+                    ui.label("miss penalty");
+                    ui.add(DragValue::new(&mut self.miss_penalty).range(0..=1000));
+                });
+                ui.horizontal(|ui| {
+                    clicked_dropdown |= ComboBox::from_id_source(format!("{}_write", self.id))
+                        .selected_text(format!("{:?}", self.write_policy))
+                        .show_ui(ui, |ui| {
+                            for policy in [WritePolicy::WriteThrough, WritePolicy::WriteBack] {
+                                ui.selectable_value(
+                                    &mut self.write_policy,
+                                    policy,
+                                    format!("{:?}", policy),
+                                );
+                            }
+                        })
+                        .inner
+                        .is_some();
+                    clicked_dropdown |= ComboBox::from_id_source(format!("{}_repl", self.id))
+                        .selected_text(format!("{:?}", self.replacement))
+                        .show_ui(ui, |ui| {
+                            for replacement in [Replacement::Lru, Replacement::Fifo] {
+                                ui.selectable_value(
+                                    &mut self.replacement,
+                                    replacement,
+                                    format!("{:?}", replacement),
+                                );
+                            }
+                        })
+                        .inner
+                        .is_some();
+                });
+                clicked_dropdown
+            },
+        );
+        EditorRenderReturn {
+            delete,
+            resp: Some(r_vec),
+        }
+    }
+
+    fn ports_location(&self) -> Vec<(crate::common::Id, Pos2)> {
+        let own_pos = Vec2::new(self.pos.0, self.pos.1);
+        let inputs = [
+            CACHE_DATA_ID,
+            CACHE_ADDR_ID,
+            CACHE_CTRL_ID,
+            CACHE_SEXT_ID,
+            CACHE_SIZE_ID,
+        ];
+        let outputs = [
+            CACHE_MEM_DATA_OUT_ID,
+            CACHE_MEM_ADDR_OUT_ID,
+            CACHE_MEM_CTRL_OUT_ID,
+            CACHE_MEM_SEXT_OUT_ID,
+            CACHE_MEM_SIZE_OUT_ID,
+        ];
+        let mut ports = vec![];
+        for (i, (input, output)) in inputs.iter().zip(outputs).enumerate() {
+            let y = -20f32 + i as f32 * 10f32;
+            ports.push((input.to_string(), Pos2::new(-40f32, y) + own_pos));
+            ports.push((output.to_string(), Pos2::new(40f32, y) + own_pos));
+        }
+        ports.push((
+            CACHE_STALL_OUT_ID.to_string(),
+            Pos2::new(-20f32, 30f32) + own_pos,
+        ));
+        ports.push((
+            CACHE_HIT_OUT_ID.to_string(),
+            Pos2::new(20f32, 30f32) + own_pos,
+        ));
+        ports
+    }
+
+    fn top_padding(&self) -> f32 {
+        30f32
+    }
+
+    fn set_pos(&mut self, pos: (f32, f32)) {
+        self.pos = pos;
+    }
+
+    fn get_pos(&self) -> (f32, f32) {
+        self.pos
+    }
+}
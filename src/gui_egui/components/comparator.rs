@@ -0,0 +1,202 @@
+use crate::common::{EguiComponent, Input, Ports, Simulator};
+use crate::components::{
+    Comparator, COMPARATOR_A_IN_ID, COMPARATOR_B_IN_ID, COMPARATOR_EQ_OUT_ID, COMPARATOR_GT_OUT_ID,
+    COMPARATOR_LT_OUT_ID,
+};
+use crate::gui_egui::component_ui::{
+    drag_logic, input_change_id, input_selector, pos_drag_value, properties_window,
+    rect_with_hover, visualize_ports,
+};
+use crate::gui_egui::editor::{EditorMode, EditorRenderReturn, GridOptions};
+use crate::gui_egui::gui::EguiExtra;
+use crate::gui_egui::helper::offset_helper;
+use egui::{
+    Align2, Color32, DragValue, FontId, Pos2, Rect, Response, Rounding, Shape, Stroke, Ui, Vec2,
+};
+
+// outputs on the right edge, top to bottom
+const COMPARATOR_OUTPUTS: [(&str, &str, f32); 3] = [
+    (COMPARATOR_LT_OUT_ID, "<", -10f32),
+    (COMPARATOR_EQ_OUT_ID, "=", 0f32),
+    (COMPARATOR_GT_OUT_ID, ">", 10f32),
+];
+
+#[typetag::serde]
+impl EguiComponent for Comparator {
+    fn render(
+        &self,
+        ui: &mut Ui,
+        _context: &mut EguiExtra,
+        simulator: Option<&mut Simulator>,
+        offset: Vec2,
+        scale: f32,
+        clip_rect: Rect,
+        editor_mode: EditorMode,
+    ) -> Option<Vec<Response>> {
+        // 41x41
+        // middle: 21x 21y (0 0)
+        let oh: fn((f32, f32), f32, Vec2) -> Pos2 = offset_helper;
+        let offset_old = offset;
+        let mut offset = offset;
+        offset.x += self.pos.0 * scale;
+        offset.y += self.pos.1 * scale;
+        let s = scale;
+        let o = offset;
+
+        // The shape
+        let rect = Rect {
+            min: oh((-20f32, -20f32), s, o),
+            max: oh((20f32, 20f32), s, o),
+        };
+        ui.painter().add(Shape::rect_stroke(
+            rect,
+            Rounding::ZERO,
+            Stroke {
+                width: scale,
+                color: Color32::BLACK,
+            },
+        ));
+        ui.painter().text(
+            oh((-5f32, 0f32), s, o),
+            Align2::CENTER_CENTER,
+            if self.signed { "CMP\ns" } else { "CMP\nu" },
+            FontId::monospace(8.0 * scale),
+            Color32::BLACK,
+        );
+        // the true output is highlighted while simulating
+        let values: Vec<bool> = match &simulator {
+            Some(s) => COMPARATOR_OUTPUTS
+                .iter()
+                .map(|(id, _, _)| s.get_input_value(&Input::new(&self.id, id)) == true.into())
+                .collect(),
+            None => vec![false; 3],
+        };
+        for ((_, label, y), value) in COMPARATOR_OUTPUTS.iter().zip(&values) {
+            ui.painter().text(
+                oh((17f32, *y), s, o),
+                Align2::RIGHT_CENTER,
+                label,
+                FontId::monospace(8.0 * scale),
+                if *value { Color32::RED } else { Color32::BLACK },
+            );
+        }
+
+        let r = rect_with_hover(rect, clip_rect, editor_mode, ui, self.id.clone(), |ui| {
+            ui.label(format!("Id: {}", self.id.clone()));
+            ui.label(format!(
+                "{} comparator {} bits",
+                if self.signed { "Signed" } else { "Unsigned" },
+                self.width
+            ));
+            for ((id, _, _), value) in COMPARATOR_OUTPUTS.iter().zip(&values) {
+                ui.label(format!("{}: {}", id, value));
+            }
+        });
+        match editor_mode {
+            EditorMode::Simulator => (),
+            _ => visualize_ports(ui, self.ports_location(), offset_old, scale, clip_rect),
+        }
+        Some(vec![r])
+    }
+
+    fn render_editor(
+        &mut self,
+        ui: &mut Ui,
+        context: &mut EguiExtra,
+        simulator: Option<&mut Simulator>,
+        offset: Vec2,
+        scale: f32,
+        clip_rect: Rect,
+        id_ports: &[(crate::common::Id, Ports)],
+        grid: &GridOptions,
+        editor_mode: EditorMode,
+    ) -> EditorRenderReturn {
+        let r_vec = Comparator::render(
+            self,
+            ui,
+            context,
+            simulator,
+            offset,
+            scale,
+            clip_rect,
+            editor_mode,
+        )
+        .unwrap();
+        let resp = &r_vec[0];
+        let delete = drag_logic(
+            ui.ctx(),
+            resp,
+            &mut self.pos,
+            &mut context.pos_tmp,
+            scale,
+            offset,
+            grid,
+        );
+
+        properties_window(
+            ui,
+            self.id.clone(),
+            resp,
+            &mut context.properties_window,
+            |ui| {
+                let mut clicked_dropdown = false;
+                input_change_id(ui, &mut context.id_tmp, &mut self.id, id_ports);
+                pos_drag_value(ui, &mut self.pos);
+                clicked_dropdown |= input_selector(
+                    ui,
+                    &mut self.a_in,
+                    COMPARATOR_A_IN_ID.to_string(),
+                    id_ports,
+                    self.id.clone(),
+                );
+                clicked_dropdown |= input_selector(
+                    ui,
+                    &mut self.b_in,
+                    COMPARATOR_B_IN_ID.to_string(),
+                    id_ports,
+                    self.id.clone(),
+                );
+                ui.horizontal(|ui| {
+                    ui.label("width");
+                    ui.add(DragValue::new(&mut self.width).range(1..=32));
+                });
+                ui.checkbox(&mut self.signed, "signed");
+                clicked_dropdown
+            },
+        );
+        EditorRenderReturn {
+            delete,
+            resp: Some(r_vec),
+        }
+    }
+
+    fn ports_location(&self) -> Vec<(crate::common::Id, Pos2)> {
+        let own_pos = Vec2::new(self.pos.0, self.pos.1);
+        let mut v = vec![
+            (
+                COMPARATOR_A_IN_ID.to_string(),
+                Pos2::new(-20f32, -10f32) + own_pos,
+            ),
+            (
+                COMPARATOR_B_IN_ID.to_string(),
+                Pos2::new(-20f32, 10f32) + own_pos,
+            ),
+        ];
+        for (id, _, y) in COMPARATOR_OUTPUTS {
+            v.push((id.to_string(), Pos2::new(20f32, y) + own_pos));
+        }
+        v
+    }
+
+    fn top_padding(&self) -> f32 {
+        20f32
+    }
+
+    fn set_pos(&mut self, pos: (f32, f32)) {
+        self.pos = pos;
+    }
+
+    fn get_pos(&self) -> (f32, f32) {
+        self.pos
+    }
+}
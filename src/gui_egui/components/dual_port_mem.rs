@@ -0,0 +1,215 @@
+use crate::common::{EguiComponent, Ports, Simulator};
+use crate::components::{
+    DualPortMem, WriteConflict, DPMEM_A_DATA_OUT_ID, DPMEM_A_ERR_OUT_ID, DPMEM_B_DATA_OUT_ID,
+    DPMEM_B_ERR_OUT_ID,
+};
+use crate::gui_egui::component_ui::{
+    drag_logic, input_change_id, input_selector, pos_drag_value, properties_window,
+    rect_with_hover, visualize_ports,
+};
+use crate::gui_egui::editor::{EditorMode, EditorRenderReturn, GridOptions};
+use crate::gui_egui::gui::EguiExtra;
+use crate::gui_egui::helper::offset_helper;
+use crate::gui_egui::mem_view::mem_window;
+use egui::{
+    Align2, Color32, ComboBox, FontId, Pos2, Rect, Response, Rounding, Shape, Slider, Stroke, Ui,
+    Vec2,
+};
+
+#[typetag::serde]
+impl EguiComponent for DualPortMem {
+    fn render(
+        &self,
+        ui: &mut Ui,
+        _context: &mut EguiExtra,
+        simulator: Option<&mut Simulator>,
+        offset: Vec2,
+        scale: f32,
+        clip_rect: Rect,
+        editor_mode: EditorMode,
+    ) -> Option<Vec<Response>> {
+        let oh: fn((f32, f32), f32, Vec2) -> Pos2 = offset_helper;
+        let offset_old = offset;
+        let mut offset = offset;
+        offset.x += self.pos.0 * scale;
+        offset.y += self.pos.1 * scale;
+        let s = scale;
+        let o = offset;
+
+        // The shape
+        let rect = Rect {
+            min: oh((-self.width / 2f32, -self.height / 2f32), s, o),
+            max: oh((self.width / 2f32, self.height / 2f32), s, o),
+        };
+        ui.painter().add(Shape::rect_stroke(
+            rect,
+            Rounding::ZERO,
+            Stroke {
+                width: scale,
+                color: Color32::BLACK,
+            },
+        ));
+        for (y, text) in [(-10f32, "A"), (0f32, "Mem"), (10f32, "B")] {
+            ui.painter().text(
+                oh((0f32, y), s, o),
+                Align2::CENTER_CENTER,
+                text,
+                FontId::monospace(7.0 * scale),
+                Color32::BLACK,
+            );
+        }
+
+        let r = rect_with_hover(rect, clip_rect, editor_mode, ui, self.id.clone(), |ui| {
+            ui.label(format!("Id: {}", self.id.clone()));
+            ui.label("Dual port Mem");
+            ui.label(format!("write conflict {:?}", self.write_conflict));
+        });
+        match editor_mode {
+            EditorMode::Simulator => mem_window(
+                ui.ctx(),
+                &format!("Memory {}", self.id),
+                &self.id,
+                &self.memory.borrow(),
+                &self.writes.borrow(),
+                self.range.start as usize..self.range.end as usize,
+                simulator.map_or(0, |s| s.cycle),
+            ),
+            _ => visualize_ports(ui, self.ports_location(), offset_old, scale, clip_rect),
+        }
+        Some(vec![r])
+    }
+
+    fn render_editor(
+        &mut self,
+        ui: &mut Ui,
+        context: &mut EguiExtra,
+        simulator: Option<&mut Simulator>,
+        offset: Vec2,
+        scale: f32,
+        clip_rect: Rect,
+        id_ports: &[(crate::common::Id, Ports)],
+        grid: &GridOptions,
+        editor_mode: EditorMode,
+    ) -> EditorRenderReturn {
+        let r_vec = DualPortMem::render(
+            self,
+            ui,
+            context,
+            simulator,
+            offset,
+            scale,
+            clip_rect,
+            editor_mode,
+        )
+        .unwrap();
+        let resp = &r_vec[0];
+        context.size_rect = resp.rect;
+        let delete = drag_logic(
+            ui.ctx(),
+            resp,
+            &mut self.pos,
+            &mut context.pos_tmp,
+            scale,
+            offset,
+            grid,
+        );
+
+        properties_window(
+            ui,
+            self.id.clone(),
+            resp,
+            &mut context.properties_window,
+            |ui| {
+                let mut clicked_dropdown = false;
+                input_change_id(ui, &mut context.id_tmp, &mut self.id, id_ports);
+                pos_drag_value(ui, &mut self.pos);
+                ui.horizontal(|ui| {
+                    ui.add(Slider::new(&mut self.width, 0f32..=400f32).text("width"));
+                    ui.add(Slider::new(&mut self.height, 0f32..=400f32).text("height"));
+                });
+                let id = self.id.clone();
+                for (prefix, port) in [("a", &mut self.port_a), ("b", &mut self.port_b)] {
+                    for (name, input) in [
+                        ("data", &mut port.data),
+                        ("addr", &mut port.addr),
+                        ("ctrl", &mut port.ctrl),
+                        ("sext", &mut port.sext),
+                        ("size", &mut port.size),
+                    ] {
+                        clicked_dropdown |= input_selector(
+                            ui,
+                            input,
+                            format!("{}_{}", prefix, name),
+                            id_ports,
+                            id.clone(),
+                        );
+                    }
+                }
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut self.big_endian, "big endian");
+                    ui.label("write conflict");
+                    clicked_dropdown |= ComboBox::from_id_source(format!("{}_conflict", self.id))
+                        .selected_text(format!("{:?}", self.write_conflict))
+                        .show_ui(ui, |ui| {
+                            for conflict in [
+                                WriteConflict::PortA,
+                                WriteConflict::PortB,
+                                WriteConflict::Warning,
+                            ] {
+                                ui.selectable_value(
+                                    &mut self.write_conflict,
+                                    conflict,
+                                    format!("{:?}", conflict),
+                                );
+                            }
+                        })
+                        .inner
+                        .is_some();
+                });
+                clicked_dropdown
+            },
+        );
+
+        EditorRenderReturn {
+            delete,
+            resp: Some(r_vec),
+        }
+    }
+
+    // port a inputs on top, port b inputs below, outputs to the right
+    fn ports_location(&self) -> Vec<(crate::common::Id, Pos2)> {
+        let own_pos = Vec2::new(self.pos.0, self.pos.1);
+        let (w, h) = (self.width / 2f32, self.height / 2f32);
+        let mut ports = vec![];
+        for (i, (a, b)) in self.port_ids()[..5]
+            .iter()
+            .zip(&self.port_ids()[5..])
+            .enumerate()
+        {
+            let x = self.width / 6f32 * (i + 1) as f32 - w;
+            ports.push((a.0.to_string(), Pos2::new(x, -h) + own_pos));
+            ports.push((b.0.to_string(), Pos2::new(x, h) + own_pos));
+        }
+        for (y, output) in [
+            (-h / 2f32, DPMEM_A_DATA_OUT_ID),
+            (-h / 4f32, DPMEM_A_ERR_OUT_ID),
+            (h / 4f32, DPMEM_B_ERR_OUT_ID),
+            (h / 2f32, DPMEM_B_DATA_OUT_ID),
+        ] {
+            ports.push((output.to_string(), Pos2::new(w, y) + own_pos));
+        }
+        ports
+    }
+
+    fn top_padding(&self) -> f32 {
+        self.height / 2f32
+    }
+
+    fn set_pos(&mut self, pos: (f32, f32)) {
+        self.pos = pos;
+    }
+
+    fn get_pos(&self) -> (f32, f32) {
+        self.pos
+    }
+}
@@ -0,0 +1,244 @@
+use crate::common::{EguiComponent, Ports, SignalValue, Simulator};
+use crate::components::{
+    Fifo, FIFO_COUNT_OUT_ID, FIFO_DATA_IN_ID, FIFO_EMPTY_OUT_ID, FIFO_FULL_OUT_ID, FIFO_OUT_ID,
+    FIFO_POP_IN_ID, FIFO_PUSH_IN_ID,
+};
+use crate::gui_egui::component_ui::{
+    drag_logic, input_change_id, input_selector, pos_drag_value, properties_window,
+    rect_with_hover, visualize_ports,
+};
+use crate::gui_egui::editor::{EditorMode, EditorRenderReturn, GridOptions};
+use crate::gui_egui::gui::EguiExtra;
+use crate::gui_egui::helper::offset_helper;
+use egui::{
+    Align2, Color32, DragValue, FontId, Pos2, Rect, Response, Rounding, Shape, Stroke, Ui, Vec2,
+};
+
+// number of entries drawn in the component, the rest is shown on hover
+const FIFO_SLOTS: usize = 4;
+
+fn format_entry(value: &SignalValue) -> String {
+    match value {
+        SignalValue::Data(data) => format!("{:#x}", data),
+        other => format!("{:?}", other),
+    }
+}
+
+#[typetag::serde]
+impl EguiComponent for Fifo {
+    fn render(
+        &self,
+        ui: &mut Ui,
+        _context: &mut EguiExtra,
+        _simulator: Option<&mut Simulator>,
+        offset: Vec2,
+        scale: f32,
+        clip_rect: Rect,
+        editor_mode: EditorMode,
+    ) -> Option<Vec<Response>> {
+        // 61x61
+        // middle: 31x 31y (0 0)
+        let oh: fn((f32, f32), f32, Vec2) -> Pos2 = offset_helper;
+        let offset_old = offset;
+        let mut offset = offset;
+        offset.x += self.pos.0 * scale;
+        offset.y += self.pos.1 * scale;
+        let s = scale;
+        let o = offset;
+        let contents = self.contents();
+
+        // The shape
+        let rect = Rect {
+            min: oh((-30f32, -30f32), s, o),
+            max: oh((30f32, 30f32), s, o),
+        };
+        ui.painter().add(Shape::rect_stroke(
+            rect,
+            Rounding::ZERO,
+            Stroke {
+                width: scale,
+                color: Color32::BLACK,
+            },
+        ));
+        ui.painter().text(
+            oh((0f32, -24f32), s, o),
+            Align2::CENTER_CENTER,
+            format!("FIFO {}/{}", contents.len(), self.depth),
+            FontId::monospace(6.0 * scale),
+            Color32::BLACK,
+        );
+
+        // the slots, head on top
+        for i in 0..self.depth.min(FIFO_SLOTS) {
+            let y = -16f32 + i as f32 * 10f32;
+            let slot = Rect {
+                min: oh((-16f32, y), s, o),
+                max: oh((16f32, y + 10f32), s, o),
+            };
+            ui.painter().add(Shape::rect_stroke(
+                slot,
+                Rounding::ZERO,
+                Stroke {
+                    width: scale * 0.5,
+                    color: Color32::DARK_GRAY,
+                },
+            ));
+            if let Some(value) = contents.get(i) {
+                ui.painter().text(
+                    slot.center(),
+                    Align2::CENTER_CENTER,
+                    format_entry(value),
+                    FontId::monospace(5.0 * scale),
+                    Color32::BLACK,
+                );
+            }
+        }
+        if contents.len() > FIFO_SLOTS {
+            ui.painter().text(
+                oh((0f32, 27f32), s, o),
+                Align2::CENTER_CENTER,
+                format!("+{}", contents.len() - FIFO_SLOTS),
+                FontId::monospace(5.0 * scale),
+                Color32::DARK_GRAY,
+            );
+        }
+
+        let r = rect_with_hover(rect, clip_rect, editor_mode, ui, self.id.clone(), |ui| {
+            ui.label(format!("Id: {}", self.id.clone()));
+            ui.label(format!("FIFO {} x {} bits", self.depth, self.width));
+            ui.label(format!("count {}", contents.len()));
+            for (i, value) in contents.iter().enumerate() {
+                ui.label(format!("{}: {}", i, format_entry(value)));
+            }
+        });
+        match editor_mode {
+            EditorMode::Simulator => (),
+            _ => visualize_ports(ui, self.ports_location(), offset_old, scale, clip_rect),
+        }
+        Some(vec![r])
+    }
+
+    fn render_editor(
+        &mut self,
+        ui: &mut Ui,
+        context: &mut EguiExtra,
+        simulator: Option<&mut Simulator>,
+        offset: Vec2,
+        scale: f32,
+        clip_rect: Rect,
+        id_ports: &[(crate::common::Id, Ports)],
+        grid: &GridOptions,
+        editor_mode: EditorMode,
+    ) -> EditorRenderReturn {
+        let r_vec = Fifo::render(
+            self,
+            ui,
+            context,
+            simulator,
+            offset,
+            scale,
+            clip_rect,
+            editor_mode,
+        )
+        .unwrap();
+        let resp = &r_vec[0];
+        let delete = drag_logic(
+            ui.ctx(),
+            resp,
+            &mut self.pos,
+            &mut context.pos_tmp,
+            scale,
+            offset,
+            grid,
+        );
+
+        properties_window(
+            ui,
+            self.id.clone(),
+            resp,
+            &mut context.properties_window,
+            |ui| {
+                let mut clicked_dropdown = false;
+                input_change_id(ui, &mut context.id_tmp, &mut self.id, id_ports);
+                pos_drag_value(ui, &mut self.pos);
+                clicked_dropdown |= input_selector(
+                    ui,
+                    &mut self.data_in,
+                    FIFO_DATA_IN_ID.to_string(),
+                    id_ports,
+                    self.id.clone(),
+                );
+                clicked_dropdown |= input_selector(
+                    ui,
+                    &mut self.push_in,
+                    FIFO_PUSH_IN_ID.to_string(),
+                    id_ports,
+                    self.id.clone(),
+                );
+                clicked_dropdown |= input_selector(
+                    ui,
+                    &mut self.pop_in,
+                    FIFO_POP_IN_ID.to_string(),
+                    id_ports,
+                    self.id.clone(),
+                );
+                ui.horizontal(|ui| {
+                    ui.label("depth");
+                    ui.add(DragValue::new(&mut self.depth).range(1..=1024));
+                });
+                ui.horizontal(|ui| {
+                    ui.label("width");
+                    ui.add(DragValue::new(&mut self.width).range(1..=32));
+                });
+                clicked_dropdown
+            },
+        );
+        EditorRenderReturn {
+            delete,
+            resp: Some(r_vec),
+        }
+    }
+
+    fn ports_location(&self) -> Vec<(crate::common::Id, Pos2)> {
+        let own_pos = Vec2::new(self.pos.0, self.pos.1);
+        vec![
+            (
+                FIFO_DATA_IN_ID.to_string(),
+                Pos2::new(-30f32, -20f32) + own_pos,
+            ),
+            (
+                FIFO_PUSH_IN_ID.to_string(),
+                Pos2::new(-30f32, 0f32) + own_pos,
+            ),
+            (
+                FIFO_POP_IN_ID.to_string(),
+                Pos2::new(-30f32, 20f32) + own_pos,
+            ),
+            (FIFO_OUT_ID.to_string(), Pos2::new(30f32, -20f32) + own_pos),
+            (
+                FIFO_COUNT_OUT_ID.to_string(),
+                Pos2::new(30f32, -5f32) + own_pos,
+            ),
+            (
+                FIFO_FULL_OUT_ID.to_string(),
+                Pos2::new(30f32, 10f32) + own_pos,
+            ),
+            (
+                FIFO_EMPTY_OUT_ID.to_string(),
+                Pos2::new(30f32, 20f32) + own_pos,
+            ),
+        ]
+    }
+
+    fn top_padding(&self) -> f32 {
+        30f32
+    }
+
+    fn set_pos(&mut self, pos: (f32, f32)) {
+        self.pos = pos;
+    }
+
+    fn get_pos(&self) -> (f32, f32) {
+        self.pos
+    }
+}
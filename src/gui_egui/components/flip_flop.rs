@@ -0,0 +1,321 @@
+use crate::common::{EguiComponent, Input, Ports, Simulator};
+use crate::components::{
+    FlipFlop, FlipFlopKind, ResetInput, ResetKind, FLIP_FLOP_ENABLE_ID, FLIP_FLOP_K_ID,
+    FLIP_FLOP_Q_ID, FLIP_FLOP_Q_N_ID, FLIP_FLOP_RESET_ID, FLIP_FLOP_SET_ID,
+};
+use crate::gui_egui::component_ui::{
+    drag_logic, input_change_id, input_selector, pos_drag_value, properties_window,
+    rect_with_hover, visualize_ports,
+};
+use crate::gui_egui::editor::{EditorMode, EditorRenderReturn, GridOptions};
+use crate::gui_egui::gui::EguiExtra;
+use crate::gui_egui::helper::offset_helper;
+use egui::{
+    Align2, Color32, ComboBox, FontId, Pos2, Rect, Response, Rounding, Shape, Stroke, Ui, Vec2,
+};
+
+// enable, disable and configure an optional set or reset input
+fn control_selector(
+    ui: &mut Ui,
+    control: &mut Option<ResetInput>,
+    port_name: &str,
+    id_ports: &[(crate::common::Id, Ports)],
+    own_id: crate::common::Id,
+) -> bool {
+    let mut clicked_dropdown = false;
+    let mut enable = control.is_some();
+    ui.checkbox(&mut enable, format!("{} input", port_name));
+    match (enable, control.is_some()) {
+        (true, false) => {
+            *control = Some(ResetInput::new(
+                Input::new("dummy", "out"),
+                0,
+                ResetKind::Sync,
+            ))
+        }
+        (false, true) => *control = None,
+        _ => (),
+    }
+    if let Some(control) = control {
+        clicked_dropdown |= input_selector(
+            ui,
+            &mut control.input,
+            port_name.to_string(),
+            id_ports,
+            own_id,
+        );
+        ComboBox::from_label(format!("{} kind", port_name))
+            .selected_text(format!("{:?}", control.kind))
+            .show_ui(ui, |ui| {
+                clicked_dropdown |= ui
+                    .selectable_value(&mut control.kind, ResetKind::Sync, "Sync")
+                    .clicked();
+                clicked_dropdown |= ui
+                    .selectable_value(&mut control.kind, ResetKind::Async, "Async")
+                    .clicked();
+            });
+    }
+    clicked_dropdown
+}
+
+#[typetag::serde]
+impl EguiComponent for FlipFlop {
+    fn render(
+        &self,
+        ui: &mut Ui,
+        _context: &mut EguiExtra,
+        simulator: Option<&mut Simulator>,
+        offset: Vec2,
+        scale: f32,
+        clip_rect: Rect,
+        editor_mode: EditorMode,
+    ) -> Option<Vec<Response>> {
+        // 51x61
+        // middle: 26x 31y (0 0)
+        let oh: fn((f32, f32), f32, Vec2) -> Pos2 = offset_helper;
+        let offset_old = offset;
+        let mut offset = offset;
+        offset.x += self.pos.0 * scale;
+        offset.y += self.pos.1 * scale;
+        let s = scale;
+        let o = offset;
+
+        let state = match simulator {
+            Some(s) => format!(
+                "{:?}",
+                s.get_input_value(&Input::new(&self.id, FLIP_FLOP_Q_ID))
+            ),
+            None => "no sim".to_string(),
+        };
+
+        // The shape
+        let rect = Rect {
+            min: oh((-25f32, -30f32), s, o),
+            max: oh((25f32, 30f32), s, o),
+        };
+        ui.painter().add(Shape::rect_stroke(
+            rect,
+            Rounding::ZERO,
+            Stroke {
+                width: scale,
+                color: Color32::BLACK,
+            },
+        ));
+        // clock edge
+        ui.painter().add(Shape::line(
+            vec![
+                oh((-25f32, -5f32), s, o),
+                oh((-19f32, 0f32), s, o),
+                oh((-25f32, 5f32), s, o),
+            ],
+            Stroke {
+                width: scale,
+                color: Color32::BLACK,
+            },
+        ));
+
+        // pin labels
+        let mut labels = vec![
+            (
+                self.d_id().to_uppercase(),
+                (-22f32, -20f32),
+                Align2::LEFT_CENTER,
+            ),
+            ("Q".to_string(), (22f32, -20f32), Align2::RIGHT_CENTER),
+            ("Q'".to_string(), (22f32, 20f32), Align2::RIGHT_CENTER),
+        ];
+        if self.kind == FlipFlopKind::JK {
+            labels.push(("K".to_string(), (-22f32, 20f32), Align2::LEFT_CENTER));
+        }
+        if self.enable.is_some() {
+            labels.push(("EN".to_string(), (-22f32, 10f32), Align2::LEFT_CENTER));
+        }
+        if self.set.is_some() {
+            labels.push(("S".to_string(), (0f32, -25f32), Align2::CENTER_CENTER));
+        }
+        if self.reset.is_some() {
+            labels.push(("R".to_string(), (0f32, 25f32), Align2::CENTER_CENTER));
+        }
+        for (text, pos, align) in labels {
+            ui.painter().text(
+                oh(pos, s, o),
+                align,
+                text,
+                FontId::monospace(8.0 * scale),
+                Color32::BLACK,
+            );
+        }
+
+        let r = rect_with_hover(rect, clip_rect, editor_mode, ui, self.id.clone(), |ui| {
+            ui.label(format!("Id: {}", self.id.clone()));
+            ui.label(format!("{:?} flip-flop", self.kind));
+            ui.label(format!("Q: {}", state));
+        });
+        match editor_mode {
+            EditorMode::Simulator => (),
+            _ => visualize_ports(ui, self.ports_location(), offset_old, scale, clip_rect),
+        }
+        Some(vec![r])
+    }
+
+    fn render_editor(
+        &mut self,
+        ui: &mut Ui,
+        context: &mut EguiExtra,
+        simulator: Option<&mut Simulator>,
+        offset: Vec2,
+        scale: f32,
+        clip_rect: Rect,
+        id_ports: &[(crate::common::Id, Ports)],
+        grid: &GridOptions,
+        editor_mode: EditorMode,
+    ) -> EditorRenderReturn {
+        let r_vec = FlipFlop::render(
+            self,
+            ui,
+            context,
+            simulator,
+            offset,
+            scale,
+            clip_rect,
+            editor_mode,
+        )
+        .unwrap();
+        let resp = &r_vec[0];
+        let delete = drag_logic(
+            ui.ctx(),
+            resp,
+            &mut self.pos,
+            &mut context.pos_tmp,
+            scale,
+            offset,
+            grid,
+        );
+
+        properties_window(
+            ui,
+            self.id.clone(),
+            resp,
+            &mut context.properties_window,
+            |ui| {
+                let mut clicked_dropdown = false;
+                input_change_id(ui, &mut context.id_tmp, &mut self.id, id_ports);
+                pos_drag_value(ui, &mut self.pos);
+                ComboBox::from_label("kind")
+                    .selected_text(format!("{:?}", self.kind))
+                    .show_ui(ui, |ui| {
+                        for kind in [FlipFlopKind::D, FlipFlopKind::T, FlipFlopKind::JK] {
+                            clicked_dropdown |= ui
+                                .selectable_value(&mut self.kind, kind, format!("{:?}", kind))
+                                .clicked();
+                        }
+                    });
+                match (self.kind, self.k_in.is_some()) {
+                    (FlipFlopKind::JK, false) => self.k_in = Some(Input::new("dummy", "out")),
+                    (FlipFlopKind::D | FlipFlopKind::T, true) => self.k_in = None,
+                    _ => (),
+                }
+                let d_id = self.d_id().to_string();
+                clicked_dropdown |=
+                    input_selector(ui, &mut self.d_in, d_id, id_ports, self.id.clone());
+                if let Some(k_in) = &mut self.k_in {
+                    clicked_dropdown |= input_selector(
+                        ui,
+                        k_in,
+                        FLIP_FLOP_K_ID.to_string(),
+                        id_ports,
+                        self.id.clone(),
+                    );
+                }
+                let mut enable = self.enable.is_some();
+                ui.checkbox(&mut enable, "enable input");
+                match (enable, self.enable.is_some()) {
+                    (true, false) => self.enable = Some(Input::new("dummy", "out")),
+                    (false, true) => self.enable = None,
+                    _ => (),
+                }
+                if let Some(enable) = &mut self.enable {
+                    clicked_dropdown |= input_selector(
+                        ui,
+                        enable,
+                        FLIP_FLOP_ENABLE_ID.to_string(),
+                        id_ports,
+                        self.id.clone(),
+                    );
+                }
+                clicked_dropdown |= control_selector(
+                    ui,
+                    &mut self.set,
+                    FLIP_FLOP_SET_ID,
+                    id_ports,
+                    self.id.clone(),
+                );
+                clicked_dropdown |= control_selector(
+                    ui,
+                    &mut self.reset,
+                    FLIP_FLOP_RESET_ID,
+                    id_ports,
+                    self.id.clone(),
+                );
+                clicked_dropdown
+            },
+        );
+
+        EditorRenderReturn {
+            delete,
+            resp: Some(r_vec),
+        }
+    }
+
+    fn ports_location(&self) -> Vec<(crate::common::Id, Pos2)> {
+        let own_pos = Vec2::new(self.pos.0, self.pos.1);
+        let mut v = vec![
+            (self.d_id().to_string(), Pos2::new(-25f32, -20f32) + own_pos),
+            (
+                FLIP_FLOP_Q_ID.to_string(),
+                Pos2::new(25f32, -20f32) + own_pos,
+            ),
+            (
+                FLIP_FLOP_Q_N_ID.to_string(),
+                Pos2::new(25f32, 20f32) + own_pos,
+            ),
+        ];
+        if self.k_in.is_some() {
+            v.push((
+                FLIP_FLOP_K_ID.to_string(),
+                Pos2::new(-25f32, 20f32) + own_pos,
+            ));
+        }
+        if self.enable.is_some() {
+            v.push((
+                FLIP_FLOP_ENABLE_ID.to_string(),
+                Pos2::new(-25f32, 10f32) + own_pos,
+            ));
+        }
+        if self.set.is_some() {
+            v.push((
+                FLIP_FLOP_SET_ID.to_string(),
+                Pos2::new(0f32, -30f32) + own_pos,
+            ));
+        }
+        if self.reset.is_some() {
+            v.push((
+                FLIP_FLOP_RESET_ID.to_string(),
+                Pos2::new(0f32, 30f32) + own_pos,
+            ));
+        }
+        v
+    }
+
+    fn top_padding(&self) -> f32 {
+        30f32
+    }
+
+    fn set_pos(&mut self, pos: (f32, f32)) {
+        self.pos = pos;
+    }
+
+    fn get_pos(&self) -> (f32, f32) {
+        self.pos
+    }
+}
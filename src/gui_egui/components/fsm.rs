@@ -0,0 +1,524 @@
+use crate::common::{EguiComponent, Input, Ports, Simulator};
+use crate::components::{
+    Fsm, FsmAssign, FsmCondition, FsmInput, FsmMealy, FsmState, FsmTransition, FSM_STATE_OUT_ID,
+};
+use crate::gui_egui::component_ui::{
+    drag_logic, input_change_id, input_selector_removeable, pos_drag_value, properties_window,
+    rect_with_hover, visualize_ports,
+};
+use crate::gui_egui::editor::{EditorMode, EditorRenderReturn, GridOptions};
+use crate::gui_egui::gui::EguiExtra;
+use crate::gui_egui::helper::offset_helper;
+use egui::{
+    epaint::CircleShape, Align2, Color32, ComboBox, DragValue, FontId, Pos2, Rect, Response,
+    Rounding, Shape, Stroke, TextEdit, Ui, Vec2,
+};
+
+// radius of a state in the diagram
+const STATE_RADIUS: f32 = 15f32;
+
+// bounding box of the diagram, (min x, min y, max x, max y) relative to the fsm
+fn bounds(fsm: &Fsm) -> (f32, f32, f32, f32) {
+    let (mut min_x, mut min_y, mut max_x, mut max_y) = (0f32, 0f32, 0f32, 0f32);
+    for state in &fsm.states {
+        min_x = min_x.min(state.pos.0 - STATE_RADIUS - 10f32);
+        // room for self loops above
+        min_y = min_y.min(state.pos.1 - STATE_RADIUS - 20f32);
+        max_x = max_x.max(state.pos.0 + STATE_RADIUS + 10f32);
+        max_y = max_y.max(state.pos.1 + STATE_RADIUS + 10f32);
+    }
+    let ports = fsm.inputs.len().max(fsm.outputs.len() + 1) as f32;
+    (min_x, min_y, max_x, max_y.max(min_y + 20f32 * ports))
+}
+
+// ports on the left (inputs) or right (outputs) edge
+fn port_pos(fsm: &Fsm, index: usize, output: bool) -> (f32, f32) {
+    let (min_x, min_y, max_x, _) = bounds(fsm);
+    let x = if output { max_x } else { min_x };
+    (x, min_y + 10f32 + 20f32 * index as f32)
+}
+
+fn condition_label(transition: &FsmTransition) -> String {
+    let when: Vec<String> = transition
+        .when
+        .iter()
+        .map(|c| format!("{}={}", c.input, c.value))
+        .collect();
+    let mut label = when.join("&");
+    if !transition.outputs.is_empty() {
+        let outputs: Vec<String> = transition
+            .outputs
+            .iter()
+            .map(|a| format!("{}={}", a.output, a.value))
+            .collect();
+        label.push_str(&format!("/{}", outputs.join(",")));
+    }
+    label
+}
+
+// editor for a list of assignments, `names` are the outputs or inputs to choose from
+fn assign_editor(
+    ui: &mut Ui,
+    salt: &str,
+    entries: &mut Vec<(String, u32)>,
+    names: &[String],
+) -> bool {
+    let mut clicked_dropdown = false;
+    let mut remove = None;
+    for (i, (name, value)) in entries.iter_mut().enumerate() {
+        ui.horizontal(|ui| {
+            ComboBox::from_id_source(format!("{}{}", salt, i))
+                .selected_text(name.clone())
+                .show_ui(ui, |ui| {
+                    for n in names {
+                        clicked_dropdown |= ui.selectable_value(name, n.clone(), n).clicked();
+                    }
+                });
+            ui.add(DragValue::new(value));
+            if ui.button("x").clicked() {
+                remove = Some(i);
+            }
+        });
+    }
+    if let Some(i) = remove {
+        entries.remove(i);
+    }
+    if !names.is_empty() && ui.button(format!("+ {}", salt)).clicked() {
+        entries.push((names[0].clone(), 0));
+    }
+    clicked_dropdown
+}
+
+fn assigns_editor(ui: &mut Ui, salt: &str, assigns: &mut Vec<FsmAssign>, names: &[String]) -> bool {
+    let mut entries = assigns
+        .iter()
+        .map(|a| (a.output.clone(), a.value))
+        .collect();
+    let clicked_dropdown = assign_editor(ui, salt, &mut entries, names);
+    *assigns = entries
+        .iter()
+        .map(|(output, value)| FsmAssign::new(output, *value))
+        .collect();
+    clicked_dropdown
+}
+
+fn conditions_editor(
+    ui: &mut Ui,
+    salt: &str,
+    conditions: &mut Vec<FsmCondition>,
+    names: &[String],
+) -> bool {
+    let mut entries = conditions
+        .iter()
+        .map(|c| (c.input.clone(), c.value))
+        .collect();
+    let clicked_dropdown = assign_editor(ui, salt, &mut entries, names);
+    *conditions = entries
+        .iter()
+        .map(|(input, value)| FsmCondition::new(input, *value))
+        .collect();
+    clicked_dropdown
+}
+
+// editor for a state and its transitions
+fn state_editor(
+    ui: &mut Ui,
+    index: usize,
+    state: &mut FsmState,
+    state_names: &[String],
+    inputs: &[String],
+    outputs: &[String],
+) -> bool {
+    let mut clicked_dropdown = false;
+    ui.horizontal(|ui| {
+        ui.add(TextEdit::singleline(&mut state.name).desired_width(80.0));
+        ui.add(DragValue::new(&mut state.pos.0).prefix("x: "));
+        ui.add(DragValue::new(&mut state.pos.1).prefix("y: "));
+    });
+    clicked_dropdown |= assigns_editor(
+        ui,
+        &format!("moore output {}", index),
+        &mut state.outputs,
+        outputs,
+    );
+    let mut remove = None;
+    for (i, transition) in state.transitions.iter_mut().enumerate() {
+        ui.separator();
+        ui.horizontal(|ui| {
+            ComboBox::from_label(format!("to ({})", i))
+                .selected_text(transition.to.clone())
+                .show_ui(ui, |ui| {
+                    for name in state_names {
+                        clicked_dropdown |= ui
+                            .selectable_value(&mut transition.to, name.clone(), name)
+                            .clicked();
+                    }
+                });
+            if ui.button("x").clicked() {
+                remove = Some(i);
+            }
+        });
+        clicked_dropdown |= conditions_editor(
+            ui,
+            &format!("condition {}.{}", index, i),
+            &mut transition.when,
+            inputs,
+        );
+        clicked_dropdown |= assigns_editor(
+            ui,
+            &format!("mealy output {}.{}", index, i),
+            &mut transition.outputs,
+            outputs,
+        );
+    }
+    if let Some(i) = remove {
+        state.transitions.remove(i);
+    }
+    if ui.button("+ Add transition").clicked() {
+        state
+            .transitions
+            .push(FsmTransition::new(&state.name, vec![]));
+    }
+    clicked_dropdown
+}
+
+#[typetag::serde]
+impl EguiComponent for Fsm {
+    fn render(
+        &self,
+        ui: &mut Ui,
+        _context: &mut EguiExtra,
+        simulator: Option<&mut Simulator>,
+        offset: Vec2,
+        scale: f32,
+        clip_rect: Rect,
+        editor_mode: EditorMode,
+    ) -> Option<Vec<Response>> {
+        // the state diagram, states at their positions relative to (0 0)
+        let oh: fn((f32, f32), f32, Vec2) -> Pos2 = offset_helper;
+        let offset_old = offset;
+        let mut offset = offset;
+        offset.x += self.pos.0 * scale;
+        offset.y += self.pos.1 * scale;
+        let s = scale;
+        let o = offset;
+        let stroke = Stroke {
+            width: scale,
+            color: Color32::BLACK,
+        };
+        let font = FontId::monospace(7.0 * scale);
+
+        let state = simulator
+            .as_ref()
+            .map(|s| s.get_input_value(&Input::new(&self.id, FSM_STATE_OUT_ID)));
+        let current = match state {
+            Some(crate::common::SignalValue::Data(i)) => Some(i as usize),
+            _ => None,
+        };
+
+        // The shape
+        let (min_x, min_y, max_x, max_y) = bounds(self);
+        let rect = Rect {
+            min: oh((min_x, min_y), s, o),
+            max: oh((max_x, max_y), s, o),
+        };
+        ui.painter()
+            .add(Shape::rect_stroke(rect, Rounding::ZERO, stroke));
+
+        // transitions
+        for state in &self.states {
+            let from = Vec2::new(state.pos.0, state.pos.1);
+            for transition in &state.transitions {
+                let label = condition_label(transition);
+                let Some(to) = self.state_index(&transition.to) else {
+                    continue;
+                };
+                let to = Vec2::new(self.states[to].pos.0, self.states[to].pos.1);
+                if to == from {
+                    let center = (from.x, from.y - STATE_RADIUS - 5f32);
+                    ui.painter().add(Shape::Circle(CircleShape {
+                        center: oh(center, s, o),
+                        radius: 6f32 * scale,
+                        fill: Color32::TRANSPARENT,
+                        stroke,
+                    }));
+                    ui.painter().text(
+                        oh((center.0, center.1 - 8f32), s, o),
+                        Align2::CENTER_BOTTOM,
+                        label,
+                        font.clone(),
+                        Color32::DARK_GRAY,
+                    );
+                    continue;
+                }
+                let dir = (to - from).normalized();
+                // transitions in both directions are drawn side by side
+                let side = Vec2::new(-dir.y, dir.x) * 3f32;
+                let start = from + dir * STATE_RADIUS + side;
+                let end = to - dir * STATE_RADIUS + side;
+                ui.painter()
+                    .arrow(oh((start.x, start.y), s, o), (end - start) * scale, stroke);
+                let middle = (start + end) / 2f32 + side * 2f32;
+                ui.painter().text(
+                    oh((middle.x, middle.y), s, o),
+                    Align2::CENTER_CENTER,
+                    label,
+                    font.clone(),
+                    Color32::DARK_GRAY,
+                );
+            }
+        }
+
+        // states, the current one highlighted and the initial with a double circle
+        for (i, state) in self.states.iter().enumerate() {
+            ui.painter().add(Shape::Circle(CircleShape {
+                center: oh(state.pos, s, o),
+                radius: STATE_RADIUS * scale,
+                fill: if current == Some(i) {
+                    Color32::LIGHT_RED
+                } else {
+                    Color32::WHITE
+                },
+                stroke,
+            }));
+            if i == 0 {
+                ui.painter().add(Shape::Circle(CircleShape {
+                    center: oh(state.pos, s, o),
+                    radius: (STATE_RADIUS - 3f32) * scale,
+                    fill: Color32::TRANSPARENT,
+                    stroke,
+                }));
+            }
+            let outputs: Vec<String> = state
+                .outputs
+                .iter()
+                .map(|a| format!("{}={}", a.output, a.value))
+                .collect();
+            ui.painter().text(
+                oh(state.pos, s, o),
+                Align2::CENTER_CENTER,
+                format!("{}\n{}", state.name, outputs.join(",")),
+                font.clone(),
+                Color32::BLACK,
+            );
+        }
+
+        // port labels
+        for (i, input) in self.inputs.iter().enumerate() {
+            let (x, y) = port_pos(self, i, false);
+            ui.painter().text(
+                oh((x + 2f32, y), s, o),
+                Align2::LEFT_CENTER,
+                &input.name,
+                font.clone(),
+                Color32::DARK_GRAY,
+            );
+        }
+        for (i, output) in [FSM_STATE_OUT_ID.to_string()]
+            .iter()
+            .chain(&self.outputs)
+            .enumerate()
+        {
+            let (x, y) = port_pos(self, i, true);
+            ui.painter().text(
+                oh((x - 2f32, y), s, o),
+                Align2::RIGHT_CENTER,
+                output,
+                font.clone(),
+                Color32::DARK_GRAY,
+            );
+        }
+
+        let r = rect_with_hover(rect, clip_rect, editor_mode, ui, self.id.clone(), |ui| {
+            ui.label(format!("Id: {}", self.id.clone()));
+            ui.label(format!("FSM, {} states", self.states.len()));
+            if let Some(state) = state {
+                ui.label(format!("state: {}", self.state_name(state)));
+            }
+        });
+        match editor_mode {
+            EditorMode::Simulator => (),
+            _ => visualize_ports(ui, self.ports_location(), offset_old, scale, clip_rect),
+        }
+        Some(vec![r])
+    }
+
+    fn render_editor(
+        &mut self,
+        ui: &mut Ui,
+        context: &mut EguiExtra,
+        simulator: Option<&mut Simulator>,
+        offset: Vec2,
+        scale: f32,
+        clip_rect: Rect,
+        id_ports: &[(crate::common::Id, Ports)],
+        grid: &GridOptions,
+        editor_mode: EditorMode,
+    ) -> EditorRenderReturn {
+        let r_vec = Fsm::render(
+            self,
+            ui,
+            context,
+            simulator,
+            offset,
+            scale,
+            clip_rect,
+            editor_mode,
+        )
+        .unwrap();
+        let resp = &r_vec[0];
+        let delete = drag_logic(
+            ui.ctx(),
+            resp,
+            &mut self.pos,
+            &mut context.pos_tmp,
+            scale,
+            offset,
+            grid,
+        );
+
+        properties_window(
+            ui,
+            self.id.clone(),
+            resp,
+            &mut context.properties_window,
+            |ui| {
+                let mut clicked_dropdown = false;
+                input_change_id(ui, &mut context.id_tmp, &mut self.id, id_ports);
+                pos_drag_value(ui, &mut self.pos);
+
+                ui.label("inputs");
+                let mut remove = None;
+                for (i, input) in self.inputs.iter_mut().enumerate() {
+                    ui.add(TextEdit::singleline(&mut input.name).desired_width(80.0));
+                    let (clicked, removed) = input_selector_removeable(
+                        ui,
+                        &mut input.input,
+                        input.name.clone(),
+                        id_ports,
+                        self.id.clone(),
+                        true,
+                    );
+                    clicked_dropdown |= clicked;
+                    if removed {
+                        remove = Some(i);
+                    }
+                }
+                if let Some(i) = remove {
+                    self.inputs.remove(i);
+                }
+                if ui.button("+ Add input").clicked() {
+                    self.inputs.push(FsmInput::new(
+                        &format!("in{}", self.inputs.len()),
+                        Input::new("dummy", "out"),
+                    ));
+                }
+
+                ui.separator();
+                ui.label("outputs");
+                let mut remove = None;
+                for (i, output) in self.outputs.iter_mut().enumerate() {
+                    ui.horizontal(|ui| {
+                        ui.add(TextEdit::singleline(output).desired_width(80.0));
+                        if ui.button("x").clicked() {
+                            remove = Some(i);
+                        }
+                    });
+                }
+                if let Some(i) = remove {
+                    self.outputs.remove(i);
+                }
+                if ui.button("+ Add output").clicked() {
+                    self.outputs.push(format!("out{}", self.outputs.len()));
+                }
+
+                let state_names: Vec<String> = self.states.iter().map(|s| s.name.clone()).collect();
+                let inputs: Vec<String> = self.inputs.iter().map(|i| i.name.clone()).collect();
+                let mut remove = None;
+                for (i, state) in self.states.iter_mut().enumerate() {
+                    ui.separator();
+                    ui.horizontal(|ui| {
+                        ui.label(format!("state {}", i));
+                        if ui.button("x").clicked() {
+                            remove = Some(i);
+                        }
+                    });
+                    clicked_dropdown |=
+                        state_editor(ui, i, state, &state_names, &inputs, &self.outputs);
+                }
+                if let Some(i) = remove {
+                    self.states.remove(i);
+                }
+                ui.separator();
+                if ui.button("+ Add state").clicked() {
+                    let pos = self
+                        .states
+                        .last()
+                        .map_or((0f32, 0f32), |s| (s.pos.0 + 60f32, s.pos.1));
+                    self.states.push(FsmState::new(
+                        &format!("s{}", self.states.len()),
+                        pos,
+                        vec![],
+                        vec![],
+                    ));
+                }
+                clicked_dropdown
+            },
+        );
+
+        EditorRenderReturn {
+            delete,
+            resp: Some(r_vec),
+        }
+    }
+
+    fn ports_location(&self) -> Vec<(crate::common::Id, Pos2)> {
+        let own_pos = Vec2::new(self.pos.0, self.pos.1);
+        let mut v = vec![];
+        for (i, input) in self.inputs.iter().enumerate() {
+            let (x, y) = port_pos(self, i, false);
+            v.push((input.name.clone(), Pos2::new(x, y) + own_pos));
+        }
+        for (i, output) in [FSM_STATE_OUT_ID.to_string()]
+            .iter()
+            .chain(&self.outputs)
+            .enumerate()
+        {
+            let (x, y) = port_pos(self, i, true);
+            v.push((output.clone(), Pos2::new(x, y) + own_pos));
+        }
+        v
+    }
+
+    fn top_padding(&self) -> f32 {
+        -bounds(self).1
+    }
+
+    fn set_pos(&mut self, pos: (f32, f32)) {
+        self.pos = pos;
+    }
+
+    fn get_pos(&self) -> (f32, f32) {
+        self.pos
+    }
+}
+
+// the Mealy outputs are not drawn, they are shown as part of the fsm
+#[typetag::serde]
+impl EguiComponent for FsmMealy {
+    fn ports_location(&self) -> Vec<(crate::common::Id, Pos2)> {
+        vec![]
+    }
+
+    fn top_padding(&self) -> f32 {
+        0f32
+    }
+
+    fn set_pos(&mut self, pos: (f32, f32)) {
+        self.fsm.pos = pos;
+    }
+
+    fn get_pos(&self) -> (f32, f32) {
+        self.fsm.pos
+    }
+}
@@ -0,0 +1,233 @@
+use crate::common::{EguiComponent, Input, Ports, Simulator};
+use crate::components::{Gate, GateOp};
+use crate::gui_egui::component_ui::{
+    drag_logic, input_change_id, input_selector_removeable, pos_drag_value, properties_window,
+    rect_with_hover, visualize_ports,
+};
+use crate::gui_egui::editor::{EditorMode, EditorRenderReturn, GridOptions};
+use crate::gui_egui::gui::EguiExtra;
+use crate::gui_egui::helper::offset_helper;
+use egui::{
+    Align2, Color32, ComboBox, FontId, Pos2, Rect, Response, Rounding, Shape, Stroke, Ui, Vec2,
+};
+use epaint::CircleShape;
+
+impl Gate {
+    fn half_height(&self) -> f32 {
+        10f32 * self.inputs.len().max(2) as f32
+    }
+
+    fn input_y(&self, index: usize) -> f32 {
+        20f32 * index as f32 - 10f32 * (self.inputs.len() as f32 - 1f32)
+    }
+}
+
+#[typetag::serde]
+impl EguiComponent for Gate {
+    fn render(
+        &self,
+        ui: &mut Ui,
+        _context: &mut EguiExtra,
+        simulator: Option<&mut Simulator>,
+        offset: Vec2,
+        scale: f32,
+        clip_rect: Rect,
+        editor_mode: EditorMode,
+    ) -> Option<Vec<Response>> {
+        // 41x(20*max(inputs, 2) + 1)
+        // middle: 21x (inputs*10 + 1)y (0 0)
+        let oh: fn((f32, f32), f32, Vec2) -> Pos2 = offset_helper;
+        let offset_old = offset;
+        let mut offset = offset;
+        offset.x += self.pos.0 * scale;
+        offset.y += self.pos.1 * scale;
+        let s = scale;
+        let o = offset;
+        let h = self.half_height();
+
+        // highlight a high output
+        let high = match simulator {
+            Some(s) => {
+                let out: Result<u32, String> = s
+                    .get_input_value(&Input::new(&self.id, crate::components::GATE_OUT_ID))
+                    .try_into();
+                out == Ok(1)
+            }
+            None => false,
+        };
+        let stroke = Stroke {
+            width: scale,
+            color: if high { Color32::RED } else { Color32::BLACK },
+        };
+
+        // The shape, IEC style
+        let rect = Rect {
+            min: oh((-20f32, -h), s, o),
+            max: oh((20f32, h), s, o),
+        };
+        ui.painter()
+            .add(Shape::rect_stroke(rect, Rounding::ZERO, stroke));
+        let symbol = match self.op {
+            GateOp::And => "&",
+            GateOp::Or => "≥1",
+            GateOp::Xor => "=1",
+        };
+        ui.painter().text(
+            oh((0f32, -h + 8f32), s, o),
+            Align2::CENTER_CENTER,
+            symbol,
+            FontId::monospace(10.0 * scale),
+            Color32::BLACK,
+        );
+
+        // inversion bubbles
+        let bubble = |center: Pos2| {
+            Shape::Circle(CircleShape {
+                center,
+                radius: 3f32 * scale,
+                fill: Color32::WHITE,
+                stroke,
+            })
+        };
+        for i in 0..self.inputs.len() {
+            if self.is_inverted(i) {
+                ui.painter()
+                    .add(bubble(oh((-23f32, self.input_y(i)), s, o)));
+            }
+        }
+        if self.invert_out {
+            ui.painter().add(bubble(oh((23f32, 0f32), s, o)));
+        }
+
+        let r = rect_with_hover(rect, clip_rect, editor_mode, ui, self.id.clone(), |ui| {
+            ui.label(format!("Id: {}", self.id.clone()));
+            ui.label(self.name());
+        });
+        match editor_mode {
+            EditorMode::Simulator => (),
+            _ => visualize_ports(ui, self.ports_location(), offset_old, scale, clip_rect),
+        }
+        Some(vec![r])
+    }
+
+    fn render_editor(
+        &mut self,
+        ui: &mut Ui,
+        context: &mut EguiExtra,
+        simulator: Option<&mut Simulator>,
+        offset: Vec2,
+        scale: f32,
+        clip_rect: Rect,
+        id_ports: &[(crate::common::Id, Ports)],
+        grid: &GridOptions,
+        editor_mode: EditorMode,
+    ) -> EditorRenderReturn {
+        let r_vec = Gate::render(
+            self,
+            ui,
+            context,
+            simulator,
+            offset,
+            scale,
+            clip_rect,
+            editor_mode,
+        )
+        .unwrap();
+        let resp = &r_vec[0];
+        let delete = drag_logic(
+            ui.ctx(),
+            resp,
+            &mut self.pos,
+            &mut context.pos_tmp,
+            scale,
+            offset,
+            grid,
+        );
+
+        properties_window(
+            ui,
+            self.id.clone(),
+            resp,
+            &mut context.properties_window,
+            |ui| {
+                let mut clicked_dropdown = false;
+                input_change_id(ui, &mut context.id_tmp, &mut self.id, id_ports);
+                pos_drag_value(ui, &mut self.pos);
+                ui.horizontal(|ui| {
+                    ComboBox::from_label("operation")
+                        .selected_text(format!("{:?}", self.op))
+                        .show_ui(ui, |ui| {
+                            for op in [GateOp::And, GateOp::Or, GateOp::Xor] {
+                                clicked_dropdown |= ui
+                                    .selectable_value(&mut self.op, op, format!("{:?}", op))
+                                    .clicked();
+                            }
+                        });
+                    ui.checkbox(&mut self.invert_out, "invert output");
+                });
+                self.invert_in.resize(self.inputs.len(), false);
+                let mut removed = vec![];
+                for (i, (input, invert)) in
+                    self.inputs.iter_mut().zip(&mut self.invert_in).enumerate()
+                {
+                    ui.horizontal(|ui| {
+                        ui.checkbox(invert, "invert");
+                        let (clicked, delete) = input_selector_removeable(
+                            ui,
+                            input,
+                            format!("{}{}", crate::components::GATE_TEMPLATE_ID, i),
+                            id_ports,
+                            self.id.clone(),
+                            i != 0,
+                        );
+                        clicked_dropdown |= clicked;
+                        removed.push(delete);
+                    });
+                }
+                let mut keep = removed.iter().map(|r| !r);
+                self.inputs.retain(|_| keep.next().unwrap());
+                let mut keep = removed.iter().map(|r| !r);
+                self.invert_in.retain(|_| keep.next().unwrap());
+                if ui.button("+ Add new input").clicked() {
+                    self.inputs.push(Input::new("id", "field"));
+                    self.invert_in.push(false);
+                }
+                clicked_dropdown
+            },
+        );
+
+        EditorRenderReturn {
+            delete,
+            resp: Some(r_vec),
+        }
+    }
+
+    fn ports_location(&self) -> Vec<(crate::common::Id, Pos2)> {
+        let own_pos = Vec2::new(self.pos.0, self.pos.1);
+        let mut v: Vec<_> = (0..self.inputs.len())
+            .map(|i| {
+                (
+                    format!("{}{}", crate::components::GATE_TEMPLATE_ID, i),
+                    Pos2::new(-20f32, self.input_y(i)) + own_pos,
+                )
+            })
+            .collect();
+        v.push((
+            crate::components::GATE_OUT_ID.to_string(),
+            Pos2::new(20f32, 0f32) + own_pos,
+        ));
+        v
+    }
+
+    fn top_padding(&self) -> f32 {
+        self.half_height()
+    }
+
+    fn set_pos(&mut self, pos: (f32, f32)) {
+        self.pos = pos;
+    }
+
+    fn get_pos(&self) -> (f32, f32) {
+        self.pos
+    }
+}
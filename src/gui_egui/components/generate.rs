@@ -0,0 +1,201 @@
+use crate::common::{EguiComponent, Id, Ports, Simulator};
+use crate::components::Generate;
+use crate::gui_egui::component_ui::{
+    drag_logic, input_change_id, pos_drag_value, properties_window, rect_with_hover,
+};
+use crate::gui_egui::editor::{EditorMode, EditorRenderReturn, GridOptions};
+use crate::gui_egui::gui::{create_contexts, EguiExtra};
+use crate::gui_egui::helper::offset_helper;
+use egui::{
+    Align2, Color32, DragValue, FontId, Pos2, Rect, Response, Rounding, Shape, Stroke, TextEdit,
+    Ui, Vec2,
+};
+use serde_json::Value;
+
+#[typetag::serde]
+impl EguiComponent for Generate {
+    fn render(
+        &self,
+        ui: &mut Ui,
+        _context: &mut EguiExtra,
+        mut simulator: Option<&mut Simulator>,
+        offset: Vec2,
+        scale: f32,
+        clip_rect: Rect,
+        editor_mode: EditorMode,
+    ) -> Option<Vec<Response>> {
+        // 61x21
+        // middle: 31x 11y (0 0)
+        let oh: fn((f32, f32), f32, Vec2) -> Pos2 = offset_helper;
+        let mut offset_own = offset;
+        offset_own.x += self.pos.0 * scale;
+        offset_own.y += self.pos.1 * scale;
+        let s = scale;
+        let o = offset_own;
+
+        // in the editor the generator is not expanded until simulated
+        if self.components.borrow().is_empty() {
+            match self.expand() {
+                Ok(components) => *self.components.borrow_mut() = components,
+                Err(e) => {
+                    ui.painter().text(
+                        oh((0f32, 20f32), s, o),
+                        Align2::CENTER_CENTER,
+                        e,
+                        FontId::monospace(8.0 * scale),
+                        Color32::RED,
+                    );
+                }
+            }
+        }
+        // the generated components are placed relative to the model
+        let components = self.components.borrow().clone();
+        let mut contexts = create_contexts(&components);
+        for c in &components {
+            let mut context = contexts.remove(&c.get_id_ports().0).unwrap();
+            c.render(
+                ui,
+                &mut context,
+                simulator.as_deref_mut(),
+                offset,
+                scale,
+                clip_rect,
+                EditorMode::Simulator,
+            );
+        }
+
+        // The label
+        let rect = Rect {
+            min: oh((-30f32, -10f32), s, o),
+            max: oh((30f32, 10f32), s, o),
+        };
+        ui.painter().add(Shape::rect_stroke(
+            rect,
+            Rounding::same(5f32 * scale),
+            Stroke {
+                width: scale,
+                color: Color32::GRAY,
+            },
+        ));
+        ui.painter().text(
+            o.to_pos2(),
+            Align2::CENTER_CENTER,
+            format!("{} x{}", self.id, self.count),
+            FontId::monospace(8.0 * scale),
+            Color32::GRAY,
+        );
+
+        let r = rect_with_hover(rect, clip_rect, editor_mode, ui, self.id.clone(), |ui| {
+            ui.label(format!("Id: {}", self.id.clone()));
+            ui.label(format!(
+                "Generates {} x {} components",
+                self.count,
+                self.template.len()
+            ));
+        });
+        Some(vec![r])
+    }
+
+    fn render_editor(
+        &mut self,
+        ui: &mut Ui,
+        context: &mut EguiExtra,
+        simulator: Option<&mut Simulator>,
+        offset: Vec2,
+        scale: f32,
+        clip_rect: Rect,
+        id_ports: &[(Id, Ports)],
+        grid: &GridOptions,
+        editor_mode: EditorMode,
+    ) -> EditorRenderReturn {
+        let r_vec = Generate::render(
+            self,
+            ui,
+            context,
+            simulator,
+            offset,
+            scale,
+            clip_rect,
+            editor_mode,
+        )
+        .unwrap();
+        let resp = &r_vec[0];
+        let delete = drag_logic(
+            ui.ctx(),
+            resp,
+            &mut self.pos,
+            &mut context.pos_tmp,
+            scale,
+            offset,
+            grid,
+        );
+
+        properties_window(
+            ui,
+            self.id.clone(),
+            resp,
+            &mut context.properties_window,
+            |ui| {
+                input_change_id(ui, &mut context.id_tmp, &mut self.id, id_ports);
+                pos_drag_value(ui, &mut self.pos);
+                let (count, step) = (self.count, self.step);
+                ui.horizontal(|ui| {
+                    ui.label("count");
+                    ui.add(DragValue::new(&mut self.count).range(0..=1024));
+                });
+                ui.horizontal(|ui| {
+                    ui.label("step");
+                    ui.add(DragValue::new(&mut self.step.0).prefix("x: "));
+                    ui.add(DragValue::new(&mut self.step.1).prefix("y: "));
+                });
+                // the template is edited as json, applied once it parses
+                ui.label("template");
+                let text_id = egui::Id::new((self.id.clone(), "template"));
+                let mut text = ui
+                    .ctx()
+                    .data_mut(|d| d.get_temp::<String>(text_id))
+                    .unwrap_or_else(|| serde_json::to_string_pretty(&self.template).unwrap());
+                let template_changed = ui
+                    .add(
+                        TextEdit::multiline(&mut text)
+                            .code_editor()
+                            .desired_width(300f32),
+                    )
+                    .changed();
+                match serde_json::from_str::<Vec<Value>>(&text) {
+                    Ok(template) if template_changed => self.template = template,
+                    Ok(_) => (),
+                    Err(e) => {
+                        ui.colored_label(Color32::RED, e.to_string());
+                    }
+                }
+                ui.ctx().data_mut(|d| d.insert_temp(text_id, text));
+                if (count, step) != (self.count, self.step) || template_changed {
+                    // expand again on next render
+                    self.components.borrow_mut().clear();
+                }
+                false
+            },
+        );
+        EditorRenderReturn {
+            delete,
+            resp: Some(r_vec),
+        }
+    }
+
+    fn ports_location(&self) -> Vec<(Id, Pos2)> {
+        vec![]
+    }
+
+    fn top_padding(&self) -> f32 {
+        10f32
+    }
+
+    fn set_pos(&mut self, pos: (f32, f32)) {
+        self.pos = pos;
+    }
+
+    fn get_pos(&self) -> (f32, f32) {
+        self.pos
+    }
+}
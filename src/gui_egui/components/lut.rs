@@ -0,0 +1,228 @@
+use crate::common::{EguiComponent, Input, Ports, Simulator};
+use crate::components::{Lut, LutRow, LUT_ADDR_IN_ID};
+use crate::gui_egui::component_ui::{
+    drag_logic, input_change_id, input_selector, pos_drag_value, properties_window,
+    rect_with_hover, visualize_ports,
+};
+use crate::gui_egui::components::splitter::{bar_half_height, bar_port_y, bit_fields_editor};
+use crate::gui_egui::editor::{EditorMode, EditorRenderReturn, GridOptions};
+use crate::gui_egui::gui::EguiExtra;
+use crate::gui_egui::helper::offset_helper;
+use egui::{
+    Align2, Color32, DragValue, FontId, Pos2, Rect, Response, Rounding, Shape, Stroke, TextEdit,
+    Ui, Vec2,
+};
+use std::path::PathBuf;
+
+// editor for the truth table, a row per line
+fn rows_editor(ui: &mut Ui, rows: &mut Vec<LutRow>, fields: usize, addr_width: u32) {
+    let mut remove = None;
+    for (i, row) in rows.iter_mut().enumerate() {
+        row.values.resize(fields, 0);
+        ui.horizontal(|ui| {
+            ui.add(TextEdit::singleline(&mut row.pattern).desired_width(60.0));
+            for value in &mut row.values {
+                ui.add(DragValue::new(value));
+            }
+            if ui.button("x").clicked() {
+                remove = Some(i);
+            }
+        });
+    }
+    if let Some(i) = remove {
+        rows.remove(i);
+    }
+    if ui.button("+ Add new row").clicked() {
+        rows.push(LutRow::new(
+            &"-".repeat(addr_width as usize),
+            vec![0; fields],
+        ));
+    }
+}
+
+#[typetag::serde]
+impl EguiComponent for Lut {
+    fn render(
+        &self,
+        ui: &mut Ui,
+        _context: &mut EguiExtra,
+        simulator: Option<&mut Simulator>,
+        offset: Vec2,
+        scale: f32,
+        clip_rect: Rect,
+        editor_mode: EditorMode,
+    ) -> Option<Vec<Response>> {
+        // 41x(20*fields + 1)
+        // middle: 21x (fields*10 + 1)y (0 0)
+        let oh: fn((f32, f32), f32, Vec2) -> Pos2 = offset_helper;
+        let offset_old = offset;
+        let mut offset = offset;
+        offset.x += self.pos.0 * scale;
+        offset.y += self.pos.1 * scale;
+        let s = scale;
+        let o = offset;
+        let n = self.fields.len();
+        let h = bar_half_height(n);
+
+        // The shape
+        let rect = Rect {
+            min: oh((-20f32, -h), s, o),
+            max: oh((20f32, h), s, o),
+        };
+        ui.painter().add(Shape::rect_stroke(
+            rect,
+            Rounding::ZERO,
+            Stroke {
+                width: scale,
+                color: Color32::BLACK,
+            },
+        ));
+        ui.painter().text(
+            oh((-17f32, 0f32), s, o),
+            Align2::LEFT_CENTER,
+            "LUT",
+            FontId::monospace(8.0 * scale),
+            Color32::BLACK,
+        );
+        for (i, field) in self.fields.iter().enumerate() {
+            ui.painter().text(
+                oh((17f32, bar_port_y(i, n)), s, o),
+                Align2::RIGHT_CENTER,
+                &field.name,
+                FontId::monospace(6.0 * scale),
+                Color32::DARK_GRAY,
+            );
+        }
+        let values: Vec<String> = match &simulator {
+            Some(s) => self
+                .fields
+                .iter()
+                .map(|f| format!(" = {:?}", s.get_input_value(&Input::new(&self.id, &f.name))))
+                .collect(),
+            None => vec![String::new(); n],
+        };
+
+        let r = rect_with_hover(rect, clip_rect, editor_mode, ui, self.id.clone(), |ui| {
+            ui.label(format!("Id: {}", self.id.clone()));
+            match &self.file {
+                Some(file) => ui.label(format!("Lookup table {:?}", file)),
+                None => ui.label(format!("Lookup table, {} rows", self.rows.len())),
+            };
+            for (field, value) in self.fields.iter().zip(&values) {
+                ui.label(format!("{}{}", field.name, value));
+            }
+        });
+        match editor_mode {
+            EditorMode::Simulator => (),
+            _ => visualize_ports(ui, self.ports_location(), offset_old, scale, clip_rect),
+        }
+        Some(vec![r])
+    }
+
+    fn render_editor(
+        &mut self,
+        ui: &mut Ui,
+        context: &mut EguiExtra,
+        simulator: Option<&mut Simulator>,
+        offset: Vec2,
+        scale: f32,
+        clip_rect: Rect,
+        id_ports: &[(crate::common::Id, Ports)],
+        grid: &GridOptions,
+        editor_mode: EditorMode,
+    ) -> EditorRenderReturn {
+        let r_vec = Lut::render(
+            self,
+            ui,
+            context,
+            simulator,
+            offset,
+            scale,
+            clip_rect,
+            editor_mode,
+        )
+        .unwrap();
+        let resp = &r_vec[0];
+        let delete = drag_logic(
+            ui.ctx(),
+            resp,
+            &mut self.pos,
+            &mut context.pos_tmp,
+            scale,
+            offset,
+            grid,
+        );
+
+        properties_window(
+            ui,
+            self.id.clone(),
+            resp,
+            &mut context.properties_window,
+            |ui| {
+                let mut clicked_dropdown = false;
+                input_change_id(ui, &mut context.id_tmp, &mut self.id, id_ports);
+                pos_drag_value(ui, &mut self.pos);
+                clicked_dropdown |= input_selector(
+                    ui,
+                    &mut self.addr_in,
+                    LUT_ADDR_IN_ID.to_string(),
+                    id_ports,
+                    self.id.clone(),
+                );
+                ui.horizontal(|ui| {
+                    ui.label("address width");
+                    ui.add(DragValue::new(&mut self.addr_width).range(1..=16));
+                });
+                let mut file = self
+                    .file
+                    .as_ref()
+                    .map_or(String::new(), |f| f.to_string_lossy().to_string());
+                ui.horizontal(|ui| {
+                    ui.label("file");
+                    ui.text_edit_singleline(&mut file);
+                });
+                self.file = (!file.is_empty()).then(|| PathBuf::from(file));
+                bit_fields_editor(ui, &mut self.fields);
+                if self.file.is_none() {
+                    rows_editor(ui, &mut self.rows, self.fields.len(), self.addr_width);
+                }
+                // contents are reloaded after editing
+                *self.contents.get_mut() = None;
+                clicked_dropdown
+            },
+        );
+
+        EditorRenderReturn {
+            delete,
+            resp: Some(r_vec),
+        }
+    }
+
+    fn ports_location(&self) -> Vec<(crate::common::Id, Pos2)> {
+        let own_pos = Vec2::new(self.pos.0, self.pos.1);
+        let n = self.fields.len();
+        let mut v = vec![(
+            LUT_ADDR_IN_ID.to_string(),
+            Pos2::new(-20f32, 0f32) + own_pos,
+        )];
+        for (i, field) in self.fields.iter().enumerate() {
+            v.push((
+                field.name.clone(),
+                Pos2::new(20f32, bar_port_y(i, n)) + own_pos,
+            ));
+        }
+        v
+    }
+
+    fn top_padding(&self) -> f32 {
+        bar_half_height(self.fields.len())
+    }
+
+    fn set_pos(&mut self, pos: (f32, f32)) {
+        self.pos = pos;
+    }
+
+    fn get_pos(&self) -> (f32, f32) {
+        self.pos
+    }
+}
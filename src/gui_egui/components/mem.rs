@@ -1,13 +1,47 @@
 use crate::common::{EguiComponent, Ports, Simulator};
-use crate::components::Mem;
+use crate::components::{Mem, MemLatency, UninitRead, MEM_PC_ID};
 use crate::gui_egui::component_ui::{
     drag_logic, input_change_id, input_selector, pos_drag_value, properties_window,
-    rect_with_hover, visualize_ports,
+    rect_with_hover, reset_selector, visualize_ports,
 };
 use crate::gui_egui::editor::{EditorMode, EditorRenderReturn, GridOptions};
 use crate::gui_egui::gui::EguiExtra;
 use crate::gui_egui::helper::offset_helper;
-use egui::{Color32, Pos2, Rect, Response, Rounding, Shape, Slider, Stroke, Ui, Vec2};
+use crate::gui_egui::mem_view::{
+    faults_editor, latency_editor, mem_window, trace_window, uninit_read_editor,
+};
+use crate::mem_image::{MemImage, MemImageFormat};
+use egui::{
+    Color32, ComboBox, DragValue, Pos2, Rect, Response, Rounding, Shape, Slider, Stroke, Ui, Vec2,
+};
+
+// editor for the optional image file, an empty file name removes the image
+pub(crate) fn image_editor(ui: &mut Ui, image: &mut Option<MemImage>, id: &str) {
+    let mut file = image
+        .as_ref()
+        .map_or(String::new(), |i| i.file.to_string_lossy().to_string());
+    ui.horizontal(|ui| {
+        ui.label("image");
+        ui.text_edit_singleline(&mut file);
+    });
+    if file.is_empty() {
+        *image = None;
+        return;
+    }
+    let image = image.get_or_insert(MemImage::new("", MemImageFormat::Auto, 0));
+    image.file = file.into();
+    ui.horizontal(|ui| {
+        ComboBox::from_id_source(format!("{}_image_format", id))
+            .selected_text(format!("{:?}", image.format))
+            .show_ui(ui, |ui| {
+                for format in MemImageFormat::ALL {
+                    ui.selectable_value(&mut image.format, format, format!("{:?}", format));
+                }
+            });
+        ui.label("offset");
+        ui.add(DragValue::new(&mut image.offset).hexadecimal(8, false, true));
+    });
+}
 
 #[typetag::serde]
 impl EguiComponent for Mem {
@@ -15,7 +49,7 @@ impl EguiComponent for Mem {
         &self,
         ui: &mut Ui,
         _context: &mut EguiExtra,
-        _simulator: Option<&mut Simulator>,
+        simulator: Option<&mut Simulator>,
         offset: Vec2,
         scale: f32,
         clip_rect: Rect,
@@ -48,9 +82,44 @@ impl EguiComponent for Mem {
         let r = rect_with_hover(rect, clip_rect, editor_mode, ui, self.id.clone(), |ui| {
             ui.label(format!("Id: {}", self.id.clone()));
             ui.label("Mem");
+            if let Some(image) = &self.image {
+                ui.label(format!("image {:?}", image.file));
+            }
+            if self.latency != MemLatency::default() {
+                ui.label(format!(
+                    "wait states read {} write {}, {} regions",
+                    self.latency.read_wait,
+                    self.latency.write_wait,
+                    self.latency.regions.len()
+                ));
+            }
+            if self.uninit_read != UninitRead::Zero {
+                ui.label(format!("uninitialised read: {:?}", self.uninit_read));
+            }
+            if let Some((_, passed)) = self.wait.borrow().waiting() {
+                ui.label(format!("waiting, {} wait states passed", passed));
+            }
         });
         match editor_mode {
-            EditorMode::Simulator => (),
+            EditorMode::Simulator => {
+                mem_window(
+                    ui.ctx(),
+                    &format!("Memory {}", self.id),
+                    &self.id,
+                    &self.memory.borrow(),
+                    &self.writes.borrow(),
+                    self.range.start as usize..self.range.end as usize,
+                    simulator.map_or(0, |s| s.cycle),
+                );
+                if self.record_trace {
+                    trace_window(
+                        ui.ctx(),
+                        &format!("Trace {}", self.id),
+                        &self.id,
+                        &self.trace.borrow(),
+                    );
+                }
+            }
             _ => visualize_ports(ui, self.ports_location(), offset_old, scale, clip_rect),
         }
         Some(vec![r])
@@ -139,7 +208,23 @@ impl EguiComponent for Mem {
                     id_ports,
                     self.id.clone(),
                 );
-                // todo: something about memory?
+                clicked_dropdown |= reset_selector(
+                    ui,
+                    &mut self.reset,
+                    crate::components::MEM_RESET_ID.to_string(),
+                    id_ports,
+                    self.id.clone(),
+                );
+                image_editor(ui, &mut self.image, &self.id);
+                latency_editor(ui, &mut self.latency);
+                ui.checkbox(&mut self.record_trace, "record trace");
+                clicked_dropdown |= faults_editor(ui, &self.id, &mut self.faults);
+                clicked_dropdown |=
+                    uninit_read_editor(ui, &self.id, &mut self.uninit_read, &mut self.pc);
+                if let Some(pc) = &mut self.pc {
+                    clicked_dropdown |=
+                        input_selector(ui, pc, MEM_PC_ID.to_string(), id_ports, self.id.clone());
+                }
                 clicked_dropdown
             },
         );
@@ -152,7 +237,7 @@ impl EguiComponent for Mem {
 
     fn ports_location(&self) -> Vec<(crate::common::Id, Pos2)> {
         let own_pos = Vec2::new(self.pos.0, self.pos.1);
-        vec![
+        let mut ports = vec![
             (
                 crate::components::MEM_DATA_ID.to_string(),
                 Pos2::new(
@@ -195,7 +280,48 @@ impl EguiComponent for Mem {
                     -self.height / 2f32,
                 ) + own_pos,
             ),
+        ];
+        ports.push((
+            crate::components::MEM_STALL_OUT_ID.to_string(),
+            Pos2::new(
+                -self.width / 10f32 * 1f32 + self.width / 2f32,
+                self.height / 2f32,
+            ) + own_pos,
+        ));
+        if self.reset.is_some() {
+            ports.push((
+                crate::components::MEM_RESET_ID.to_string(),
+                Pos2::new(
+                    self.width / 10f32 * 1f32 - self.width / 2f32,
+                    self.height / 2f32,
+                ) + own_pos,
+            ));
+        }
+        for (i, output) in [
+            crate::components::MEM_TRAP_OUT_ID,
+            crate::components::MEM_TRAP_CAUSE_OUT_ID,
         ]
+        .iter()
+        .enumerate()
+        {
+            ports.push((
+                output.to_string(),
+                Pos2::new(
+                    -self.width / 10f32 * (i + 2) as f32 + self.width / 2f32,
+                    self.height / 2f32,
+                ) + own_pos,
+            ));
+        }
+        if self.pc.is_some() {
+            ports.push((
+                crate::components::MEM_PC_ID.to_string(),
+                Pos2::new(
+                    self.width / 10f32 * 2f32 - self.width / 2f32,
+                    self.height / 2f32,
+                ) + own_pos,
+            ));
+        }
+        ports
     }
 
     fn top_padding(&self) -> f32 {
@@ -0,0 +1,190 @@
+use crate::common::{EguiComponent, Input, Ports, Simulator};
+use crate::components::{Merger, MergerField, MERGER_OUT_ID, MERGER_TEMPLATE_ID};
+use crate::gui_egui::component_ui::{
+    drag_logic, input_change_id, input_selector_removeable, pos_drag_value, properties_window,
+    rect_with_hover, visualize_ports,
+};
+use crate::gui_egui::components::splitter::{bar_half_height, bar_port_y};
+use crate::gui_egui::editor::{EditorMode, EditorRenderReturn, GridOptions};
+use crate::gui_egui::gui::EguiExtra;
+use crate::gui_egui::helper::offset_helper;
+use egui::{
+    Align2, Color32, DragValue, FontId, Pos2, Rect, Response, Rounding, Shape, Stroke, Ui, Vec2,
+};
+
+#[typetag::serde]
+impl EguiComponent for Merger {
+    fn render(
+        &self,
+        ui: &mut Ui,
+        _context: &mut EguiExtra,
+        simulator: Option<&mut Simulator>,
+        offset: Vec2,
+        scale: f32,
+        clip_rect: Rect,
+        editor_mode: EditorMode,
+    ) -> Option<Vec<Response>> {
+        // 11x(20*fields + 1)
+        // middle: 6x (fields*10 + 1)y (0 0)
+        let oh: fn((f32, f32), f32, Vec2) -> Pos2 = offset_helper;
+        let offset_old = offset;
+        let mut offset = offset;
+        offset.x += self.pos.0 * scale;
+        offset.y += self.pos.1 * scale;
+        let s = scale;
+        let o = offset;
+        let n = self.fields.len();
+        let h = bar_half_height(n);
+
+        // The shape, a bar with the bit range of each field
+        let rect = Rect {
+            min: oh((-5f32, -h), s, o),
+            max: oh((5f32, h), s, o),
+        };
+        ui.painter()
+            .add(Shape::rect_filled(rect, Rounding::ZERO, Color32::BLACK));
+        let mut lsb = 0;
+        for (i, field) in self.fields.iter().enumerate() {
+            ui.painter().text(
+                oh((-8f32, bar_port_y(i, n) - 5f32), s, o),
+                Align2::RIGHT_CENTER,
+                format!("[{}:{}]", lsb + field.width.max(1) - 1, lsb),
+                FontId::monospace(7.0 * scale),
+                Color32::DARK_GRAY,
+            );
+            ui.painter().line_segment(
+                [
+                    oh((-10f32, bar_port_y(i, n)), s, o),
+                    oh((-5f32, bar_port_y(i, n)), s, o),
+                ],
+                Stroke {
+                    width: scale,
+                    color: Color32::BLACK,
+                },
+            );
+            lsb += field.width;
+        }
+        let value = match &simulator {
+            Some(s) => format!(
+                "{:?}",
+                s.get_input_value(&Input::new(&self.id, MERGER_OUT_ID))
+            ),
+            None => "no sim".to_string(),
+        };
+
+        let r = rect_with_hover(rect, clip_rect, editor_mode, ui, self.id.clone(), |ui| {
+            ui.label(format!("Id: {}", self.id.clone()));
+            ui.label("Merger");
+            ui.label(format!("out: {}", value));
+        });
+        match editor_mode {
+            EditorMode::Simulator => (),
+            _ => visualize_ports(ui, self.ports_location(), offset_old, scale, clip_rect),
+        }
+        Some(vec![r])
+    }
+
+    fn render_editor(
+        &mut self,
+        ui: &mut Ui,
+        context: &mut EguiExtra,
+        simulator: Option<&mut Simulator>,
+        offset: Vec2,
+        scale: f32,
+        clip_rect: Rect,
+        id_ports: &[(crate::common::Id, Ports)],
+        grid: &GridOptions,
+        editor_mode: EditorMode,
+    ) -> EditorRenderReturn {
+        let r_vec = Merger::render(
+            self,
+            ui,
+            context,
+            simulator,
+            offset,
+            scale,
+            clip_rect,
+            editor_mode,
+        )
+        .unwrap();
+        let resp = &r_vec[0];
+        let delete = drag_logic(
+            ui.ctx(),
+            resp,
+            &mut self.pos,
+            &mut context.pos_tmp,
+            scale,
+            offset,
+            grid,
+        );
+
+        properties_window(
+            ui,
+            self.id.clone(),
+            resp,
+            &mut context.properties_window,
+            |ui| {
+                let mut clicked_dropdown = false;
+                input_change_id(ui, &mut context.id_tmp, &mut self.id, id_ports);
+                pos_drag_value(ui, &mut self.pos);
+                let mut i = 0;
+                self.fields.retain_mut(|field| {
+                    let mut delete = false;
+                    ui.horizontal(|ui| {
+                        ui.label("width");
+                        ui.add(DragValue::new(&mut field.width).range(1..=32));
+                        let (clicked, d) = input_selector_removeable(
+                            ui,
+                            &mut field.input,
+                            format!("{}{}", MERGER_TEMPLATE_ID, i),
+                            id_ports,
+                            self.id.clone(),
+                            i != 0,
+                        );
+                        clicked_dropdown |= clicked;
+                        delete = d;
+                    });
+                    i += 1;
+                    !delete
+                });
+                if ui.button("+ Add new field").clicked() {
+                    self.fields
+                        .push(MergerField::new(Input::new("id", "field"), 1));
+                }
+                clicked_dropdown
+            },
+        );
+
+        EditorRenderReturn {
+            delete,
+            resp: Some(r_vec),
+        }
+    }
+
+    fn ports_location(&self) -> Vec<(crate::common::Id, Pos2)> {
+        let own_pos = Vec2::new(self.pos.0, self.pos.1);
+        let n = self.fields.len();
+        let mut v: Vec<_> = (0..n)
+            .map(|i| {
+                (
+                    format!("{}{}", MERGER_TEMPLATE_ID, i),
+                    Pos2::new(-10f32, bar_port_y(i, n)) + own_pos,
+                )
+            })
+            .collect();
+        v.push((MERGER_OUT_ID.to_string(), Pos2::new(5f32, 0f32) + own_pos));
+        v
+    }
+
+    fn top_padding(&self) -> f32 {
+        bar_half_height(self.fields.len())
+    }
+
+    fn set_pos(&mut self, pos: (f32, f32)) {
+        self.pos = pos;
+    }
+
+    fn get_pos(&self) -> (f32, f32) {
+        self.pos
+    }
+}
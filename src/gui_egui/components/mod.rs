@@ -1,18 +1,36 @@
 mod add;
+mod alu;
+mod bus;
+mod cache;
 mod clk;
+mod comparator;
 mod constant;
 mod cross;
+mod dual_port_mem;
+mod fifo;
+mod flip_flop;
+mod fsm;
 mod full_adder;
+mod gate;
+mod generate;
+mod lut;
 mod mem;
+mod merger;
 mod mips_controll_unit;
 mod mips_im;
 mod mips_reg_file;
+mod mul_div;
 mod mux;
 mod probe;
 mod probe_assert;
 mod probe_edit;
 mod probe_out;
 mod probe_stim;
+mod property_assert;
 mod register;
+mod reset;
 mod sext;
+mod shifter;
+mod splitter;
+mod subcircuit;
 mod wire;
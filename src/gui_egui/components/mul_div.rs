@@ -0,0 +1,194 @@
+use crate::common::{EguiComponent, Input, Ports, Simulator};
+use crate::components::{
+    MulDiv, MUL_DIV_A_IN_ID, MUL_DIV_BUSY_OUT_ID, MUL_DIV_B_IN_ID, MUL_DIV_DONE_OUT_ID,
+    MUL_DIV_OP_IN_ID, MUL_DIV_OUT_ID, MUL_DIV_START_IN_ID,
+};
+use crate::gui_egui::component_ui::{
+    drag_logic, input_change_id, input_selector, pos_drag_value, properties_window,
+    rect_with_hover, visualize_ports,
+};
+use crate::gui_egui::editor::{EditorMode, EditorRenderReturn, GridOptions};
+use crate::gui_egui::gui::EguiExtra;
+use crate::gui_egui::helper::offset_helper;
+use egui::{
+    Align2, Color32, DragValue, FontId, Pos2, Rect, Response, Rounding, Shape, Stroke, Ui, Vec2,
+};
+
+#[typetag::serde]
+impl EguiComponent for MulDiv {
+    fn render(
+        &self,
+        ui: &mut Ui,
+        _context: &mut EguiExtra,
+        simulator: Option<&mut Simulator>,
+        offset: Vec2,
+        scale: f32,
+        clip_rect: Rect,
+        editor_mode: EditorMode,
+    ) -> Option<Vec<Response>> {
+        // 61x61
+        // middle: 31x 31y (0 0)
+        let oh: fn((f32, f32), f32, Vec2) -> Pos2 = offset_helper;
+        let offset_old = offset;
+        let mut offset = offset;
+        offset.x += self.pos.0 * scale;
+        offset.y += self.pos.1 * scale;
+        let s = scale;
+        let o = offset;
+
+        let state = match simulator {
+            Some(s) => {
+                if s.get_input_value(&Input::new(&self.id, MUL_DIV_BUSY_OUT_ID)) == true.into() {
+                    format!("busy {}", self.remaining())
+                } else if s.get_input_value(&Input::new(&self.id, MUL_DIV_DONE_OUT_ID))
+                    == true.into()
+                {
+                    "done".to_string()
+                } else {
+                    "idle".to_string()
+                }
+            }
+            None => "no sim".to_string(),
+        };
+
+        // The shape
+        let rect = Rect {
+            min: oh((-30f32, -30f32), s, o),
+            max: oh((30f32, 30f32), s, o),
+        };
+        ui.painter().add(Shape::rect_stroke(
+            rect,
+            Rounding::ZERO,
+            Stroke {
+                width: scale,
+                color: Color32::BLACK,
+            },
+        ));
+        ui.painter().text(
+            oh((0f32, 0f32), s, o),
+            Align2::CENTER_CENTER,
+            format!("MUL/DIV\n{}", state),
+            FontId::monospace(8.0 * scale),
+            Color32::BLACK,
+        );
+
+        let r = rect_with_hover(rect, clip_rect, editor_mode, ui, self.id.clone(), |ui| {
+            ui.label(format!("Id: {}", self.id.clone()));
+            ui.label(format!("Multiplier/divider, {} cycles", self.cycles));
+            ui.label(state.clone());
+        });
+        match editor_mode {
+            EditorMode::Simulator => (),
+            _ => visualize_ports(ui, self.ports_location(), offset_old, scale, clip_rect),
+        }
+        Some(vec![r])
+    }
+
+    fn render_editor(
+        &mut self,
+        ui: &mut Ui,
+        context: &mut EguiExtra,
+        simulator: Option<&mut Simulator>,
+        offset: Vec2,
+        scale: f32,
+        clip_rect: Rect,
+        id_ports: &[(crate::common::Id, Ports)],
+        grid: &GridOptions,
+        editor_mode: EditorMode,
+    ) -> EditorRenderReturn {
+        let r_vec = MulDiv::render(
+            self,
+            ui,
+            context,
+            simulator,
+            offset,
+            scale,
+            clip_rect,
+            editor_mode,
+        )
+        .unwrap();
+        let resp = &r_vec[0];
+        let delete = drag_logic(
+            ui.ctx(),
+            resp,
+            &mut self.pos,
+            &mut context.pos_tmp,
+            scale,
+            offset,
+            grid,
+        );
+
+        properties_window(
+            ui,
+            self.id.clone(),
+            resp,
+            &mut context.properties_window,
+            |ui| {
+                let mut clicked_dropdown = false;
+                input_change_id(ui, &mut context.id_tmp, &mut self.id, id_ports);
+                pos_drag_value(ui, &mut self.pos);
+                for (input, port_id) in [
+                    (&mut self.a_in, MUL_DIV_A_IN_ID),
+                    (&mut self.b_in, MUL_DIV_B_IN_ID),
+                    (&mut self.op_in, MUL_DIV_OP_IN_ID),
+                    (&mut self.start_in, MUL_DIV_START_IN_ID),
+                ] {
+                    clicked_dropdown |=
+                        input_selector(ui, input, port_id.to_string(), id_ports, self.id.clone());
+                }
+                ui.horizontal(|ui| {
+                    ui.label("cycles");
+                    ui.add(DragValue::new(&mut self.cycles).range(0..=64));
+                });
+                clicked_dropdown
+            },
+        );
+        EditorRenderReturn {
+            delete,
+            resp: Some(r_vec),
+        }
+    }
+
+    fn ports_location(&self) -> Vec<(crate::common::Id, Pos2)> {
+        let own_pos = Vec2::new(self.pos.0, self.pos.1);
+        vec![
+            (
+                MUL_DIV_A_IN_ID.to_string(),
+                Pos2::new(-30f32, -20f32) + own_pos,
+            ),
+            (
+                MUL_DIV_B_IN_ID.to_string(),
+                Pos2::new(-30f32, 0f32) + own_pos,
+            ),
+            (
+                MUL_DIV_OP_IN_ID.to_string(),
+                Pos2::new(-30f32, 20f32) + own_pos,
+            ),
+            (
+                MUL_DIV_START_IN_ID.to_string(),
+                Pos2::new(0f32, -30f32) + own_pos,
+            ),
+            (MUL_DIV_OUT_ID.to_string(), Pos2::new(30f32, 0f32) + own_pos),
+            (
+                MUL_DIV_BUSY_OUT_ID.to_string(),
+                Pos2::new(30f32, -20f32) + own_pos,
+            ),
+            (
+                MUL_DIV_DONE_OUT_ID.to_string(),
+                Pos2::new(30f32, 20f32) + own_pos,
+            ),
+        ]
+    }
+
+    fn top_padding(&self) -> f32 {
+        30f32
+    }
+
+    fn set_pos(&mut self, pos: (f32, f32)) {
+        self.pos = pos;
+    }
+
+    fn get_pos(&self) -> (f32, f32) {
+        self.pos
+    }
+}
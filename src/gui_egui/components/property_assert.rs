@@ -0,0 +1,234 @@
+use crate::common::{Condition, EguiComponent, Input, Ports, Simulator};
+use crate::components::{
+    Compare, Operand, Predicate, PropertyAssert, PROPERTY_ASSERT_CHECK_ID,
+    PROPERTY_ASSERT_CHECK_OPERAND_ID, PROPERTY_ASSERT_TRIGGER_ID,
+    PROPERTY_ASSERT_TRIGGER_OPERAND_ID,
+};
+use crate::gui_egui::component_ui::{
+    drag_logic, input_change_id, input_selector, pos_drag_value, properties_window,
+    rect_with_hover, visualize_ports,
+};
+use crate::gui_egui::editor::{EditorMode, EditorRenderReturn, GridOptions};
+use crate::gui_egui::gui::EguiExtra;
+use crate::gui_egui::helper::offset_helper;
+use egui::{
+    Align2, Color32, ComboBox, DragValue, FontId, Pos2, Rect, Response, Rounding, Shape, Stroke,
+    Ui, Vec2,
+};
+
+// inputs on the left edge, top to bottom
+const PROPERTY_ASSERT_INPUTS: [(&str, f32); 4] = [
+    (PROPERTY_ASSERT_TRIGGER_ID, -15f32),
+    (PROPERTY_ASSERT_TRIGGER_OPERAND_ID, -5f32),
+    (PROPERTY_ASSERT_CHECK_ID, 5f32),
+    (PROPERTY_ASSERT_CHECK_OPERAND_ID, 15f32),
+];
+
+#[typetag::serde]
+impl EguiComponent for PropertyAssert {
+    fn render(
+        &self,
+        ui: &mut Ui,
+        _context: &mut EguiExtra,
+        simulator: Option<&mut Simulator>,
+        offset: Vec2,
+        scale: f32,
+        clip_rect: Rect,
+        editor_mode: EditorMode,
+    ) -> Option<Vec<Response>> {
+        let oh: fn((f32, f32), f32, Vec2) -> Pos2 = offset_helper;
+        let offset_old = offset;
+        let mut offset = offset;
+        offset.x += self.pos.0 * scale;
+        offset.y += self.pos.1 * scale;
+        let s = scale;
+        let o = offset;
+
+        // the assertion failing in this cycle, if any
+        let failure = simulator.as_ref().and_then(|s| {
+            s.component_condition
+                .iter()
+                .find_map(|(id, condition)| match condition {
+                    Condition::Assert(msg) if *id == self.id => Some(msg.clone()),
+                    _ => None,
+                })
+        });
+
+        // The shape
+        let rect = Rect {
+            min: oh((-20f32, -20f32), s, o),
+            max: oh((20f32, 20f32), s, o),
+        };
+        ui.painter().add(Shape::rect_stroke(
+            rect,
+            Rounding::ZERO,
+            Stroke {
+                width: scale,
+                color: if failure.is_some() {
+                    Color32::RED
+                } else {
+                    Color32::BLACK
+                },
+            },
+        ));
+        ui.painter().text(
+            oh((0f32, 0f32), s, o),
+            Align2::CENTER_CENTER,
+            format!("PROP\n≤{}", self.within),
+            FontId::monospace(8.0 * scale),
+            Color32::BLACK,
+        );
+
+        let r = rect_with_hover(rect, clip_rect, editor_mode, ui, self.id.clone(), |ui| {
+            ui.label(format!("Id: {}", self.id.clone()));
+            ui.label(format!("when {}", self.trigger));
+            ui.label(format!("then {} within {} cycles", self.check, self.within));
+            match &failure {
+                Some(msg) => ui.label(msg),
+                None => ui.label("holds"),
+            };
+        });
+        match editor_mode {
+            EditorMode::Simulator => (),
+            _ => visualize_ports(ui, self.ports_location(), offset_old, scale, clip_rect),
+        }
+        Some(vec![r])
+    }
+
+    fn render_editor(
+        &mut self,
+        ui: &mut Ui,
+        context: &mut EguiExtra,
+        simulator: Option<&mut Simulator>,
+        offset: Vec2,
+        scale: f32,
+        clip_rect: Rect,
+        id_ports: &[(crate::common::Id, Ports)],
+        grid: &GridOptions,
+        editor_mode: EditorMode,
+    ) -> EditorRenderReturn {
+        let r_vec = PropertyAssert::render(
+            self,
+            ui,
+            context,
+            simulator,
+            offset,
+            scale,
+            clip_rect,
+            editor_mode,
+        )
+        .unwrap();
+        let resp = &r_vec[0];
+        let delete = drag_logic(
+            ui.ctx(),
+            resp,
+            &mut self.pos,
+            &mut context.pos_tmp,
+            scale,
+            offset,
+            grid,
+        );
+
+        properties_window(
+            ui,
+            self.id.clone(),
+            resp,
+            &mut context.properties_window,
+            |ui| {
+                let mut clicked_dropdown = false;
+                input_change_id(ui, &mut context.id_tmp, &mut self.id, id_ports);
+                pos_drag_value(ui, &mut self.pos);
+                let id = self.id.clone();
+                for (predicate, port_id, operand_id) in [
+                    (
+                        &mut self.trigger,
+                        PROPERTY_ASSERT_TRIGGER_ID,
+                        PROPERTY_ASSERT_TRIGGER_OPERAND_ID,
+                    ),
+                    (
+                        &mut self.check,
+                        PROPERTY_ASSERT_CHECK_ID,
+                        PROPERTY_ASSERT_CHECK_OPERAND_ID,
+                    ),
+                ] {
+                    clicked_dropdown |=
+                        predicate_editor(ui, predicate, port_id, operand_id, id_ports, &id);
+                }
+                ui.horizontal(|ui| {
+                    ui.label("within cycles");
+                    ui.add(DragValue::new(&mut self.within));
+                });
+                clicked_dropdown
+            },
+        );
+        EditorRenderReturn {
+            delete,
+            resp: Some(r_vec),
+        }
+    }
+
+    fn ports_location(&self) -> Vec<(crate::common::Id, Pos2)> {
+        let own_pos = Vec2::new(self.pos.0, self.pos.1);
+        PROPERTY_ASSERT_INPUTS
+            .iter()
+            .map(|(id, y)| (id.to_string(), Pos2::new(-20f32, *y) + own_pos))
+            .collect()
+    }
+
+    fn top_padding(&self) -> f32 {
+        20f32
+    }
+
+    fn set_pos(&mut self, pos: (f32, f32)) {
+        self.pos = pos;
+    }
+
+    fn get_pos(&self) -> (f32, f32) {
+        self.pos
+    }
+}
+
+// the input, comparison and (constant or signal) operand of a predicate
+fn predicate_editor(
+    ui: &mut Ui,
+    predicate: &mut Predicate,
+    port_id: &str,
+    operand_id: &str,
+    id_ports: &[(crate::common::Id, Ports)],
+    id: &str,
+) -> bool {
+    let mut clicked_dropdown = input_selector(
+        ui,
+        &mut predicate.input,
+        port_id.to_string(),
+        id_ports,
+        id.to_string(),
+    );
+    ui.horizontal(|ui| {
+        clicked_dropdown |= ComboBox::from_id_source(format!("{}_{}_compare", id, port_id))
+            .selected_text(format!("{:?}", predicate.compare))
+            .show_ui(ui, |ui| {
+                for compare in Compare::ALL {
+                    ui.selectable_value(&mut predicate.compare, compare, format!("{:?}", compare));
+                }
+            })
+            .inner
+            .is_some();
+        let mut signal = matches!(predicate.operand, Operand::Signal(_));
+        if ui.checkbox(&mut signal, "signal operand").changed() {
+            predicate.operand = if signal {
+                Operand::Signal(Input::new("dummy", "out"))
+            } else {
+                Operand::Const(0)
+            };
+        }
+        if let Operand::Const(c) = &mut predicate.operand {
+            ui.add(DragValue::new(c).hexadecimal(8, false, true));
+        }
+    });
+    if let Operand::Signal(input) = &mut predicate.operand {
+        clicked_dropdown |=
+            input_selector(ui, input, operand_id.to_string(), id_ports, id.to_string());
+    }
+    clicked_dropdown
+}
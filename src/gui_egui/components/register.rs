@@ -2,7 +2,7 @@ use crate::common::{EguiComponent, Input, Ports, SignalUnsigned, Simulator};
 use crate::components::Register;
 use crate::gui_egui::component_ui::{
     drag_logic, input_change_id, input_selector, pos_drag_value, properties_window,
-    rect_with_hover, visualize_ports,
+    rect_with_hover, reset_selector, visualize_ports,
 };
 use crate::gui_egui::editor::{EditorMode, EditorRenderReturn, GridOptions};
 use crate::gui_egui::gui::EguiExtra;
@@ -134,6 +134,13 @@ impl EguiComponent for Register {
                     id_ports,
                     self.id.clone(),
                 );
+                clicked_dropdown |= reset_selector(
+                    ui,
+                    &mut self.reset,
+                    crate::components::REGISTER_RESET_ID.to_string(),
+                    id_ports,
+                    self.id.clone(),
+                );
                 clicked_dropdown
             },
         );
@@ -146,7 +153,7 @@ impl EguiComponent for Register {
 
     fn ports_location(&self) -> Vec<(crate::common::Id, Pos2)> {
         let own_pos = Vec2::new(self.pos.0, self.pos.1);
-        vec![
+        let mut ports = vec![
             (
                 crate::components::REGISTER_R_IN_ID.to_string(),
                 Pos2::new(-10f32, 0f32) + own_pos,
@@ -155,7 +162,14 @@ impl EguiComponent for Register {
                 crate::components::REGISTER_OUT_ID.to_string(),
                 Pos2::new(10f32, 0f32) + own_pos,
             ),
-        ]
+        ];
+        if self.reset.is_some() {
+            ports.push((
+                crate::components::REGISTER_RESET_ID.to_string(),
+                Pos2::new(0f32, 20f32) + own_pos,
+            ));
+        }
+        ports
     }
 
     fn top_padding(&self) -> f32 {
@@ -0,0 +1,181 @@
+use crate::common::{EguiComponent, Input, Ports, SignalUnsigned, Simulator};
+use crate::components::Reset;
+use crate::gui_egui::component_ui::{
+    drag_logic, input_change_id, input_selector, pos_drag_value, properties_window,
+    rect_with_hover, visualize_ports,
+};
+use crate::gui_egui::editor::{EditorMode, EditorRenderReturn, GridOptions};
+use crate::gui_egui::gui::EguiExtra;
+use crate::gui_egui::helper::offset_helper;
+use egui::{Color32, DragValue, FontId, Pos2, Rect, Response, Rounding, Shape, Stroke, Ui, Vec2};
+
+#[typetag::serde]
+impl EguiComponent for Reset {
+    fn render(
+        &self,
+        ui: &mut Ui,
+        _context: &mut EguiExtra,
+        simulator: Option<&mut Simulator>,
+        offset: Vec2,
+        scale: f32,
+        clip_rect: Rect,
+        editor_mode: EditorMode,
+    ) -> Option<Vec<Response>> {
+        // 41x21
+        // middle: 21x 11y (0 0)
+        let oh: fn((f32, f32), f32, Vec2) -> Pos2 = offset_helper;
+        let offset_old = offset;
+        let mut offset = offset;
+        offset.x += self.pos.0 * scale;
+        offset.y += self.pos.1 * scale;
+        let s = scale;
+        let o = offset;
+
+        // highlight while asserted
+        let asserted = match &simulator {
+            Some(s) => {
+                let r: Result<SignalUnsigned, String> = s
+                    .get_input_value(&Input::new(&self.id, crate::components::RESET_OUT_ID))
+                    .try_into();
+                matches!(r, Ok(v) if v != 0)
+            }
+            None => false,
+        };
+
+        // The shape
+        let rect = Rect {
+            min: oh((-20f32, -10f32), s, o),
+            max: oh((20f32, 10f32), s, o),
+        };
+        ui.painter().add(Shape::rect_stroke(
+            rect,
+            Rounding::ZERO,
+            Stroke {
+                width: scale,
+                color: if asserted {
+                    Color32::RED
+                } else {
+                    Color32::BLACK
+                },
+            },
+        ));
+        ui.painter().text(
+            o.to_pos2(),
+            egui::Align2::CENTER_CENTER,
+            "RST",
+            FontId::monospace(10.0 * scale),
+            Color32::BLACK,
+        );
+
+        let r = rect_with_hover(rect, clip_rect, editor_mode, ui, self.id.clone(), |ui| {
+            ui.label(format!("Id: {}", self.id.clone()));
+            ui.label(format!("Power on reset cycles: {}", self.por_cycles));
+            ui.label(format!("Asserted: {}", asserted));
+        });
+        match editor_mode {
+            EditorMode::Simulator => (),
+            _ => visualize_ports(ui, self.ports_location(), offset_old, scale, clip_rect),
+        }
+        Some(vec![r])
+    }
+
+    fn render_editor(
+        &mut self,
+        ui: &mut Ui,
+        context: &mut EguiExtra,
+        simulator: Option<&mut Simulator>,
+        offset: Vec2,
+        scale: f32,
+        clip_rect: Rect,
+        id_ports: &[(crate::common::Id, Ports)],
+        grid: &GridOptions,
+        editor_mode: EditorMode,
+    ) -> EditorRenderReturn {
+        let r_vec = Reset::render(
+            self,
+            ui,
+            context,
+            simulator,
+            offset,
+            scale,
+            clip_rect,
+            editor_mode,
+        )
+        .unwrap();
+        let resp = &r_vec[0];
+        let delete = drag_logic(
+            ui.ctx(),
+            resp,
+            &mut self.pos,
+            &mut context.pos_tmp,
+            scale,
+            offset,
+            grid,
+        );
+
+        properties_window(
+            ui,
+            self.id.clone(),
+            resp,
+            &mut context.properties_window,
+            |ui| {
+                let mut clicked_dropdown = false;
+                input_change_id(ui, &mut context.id_tmp, &mut self.id, id_ports);
+                pos_drag_value(ui, &mut self.pos);
+                ui.horizontal(|ui| {
+                    ui.label("power on reset cycles");
+                    ui.add(DragValue::new(&mut self.por_cycles));
+                });
+                let mut enable = self.trigger.is_some();
+                ui.checkbox(&mut enable, "trigger input");
+                match (enable, self.trigger.is_some()) {
+                    (true, false) => self.trigger = Some(Input::new("dummy", "out")),
+                    (false, true) => self.trigger = None,
+                    _ => (),
+                }
+                if let Some(trigger) = &mut self.trigger {
+                    clicked_dropdown |= input_selector(
+                        ui,
+                        trigger,
+                        crate::components::RESET_TRIGGER_ID.to_string(),
+                        id_ports,
+                        self.id.clone(),
+                    );
+                }
+                clicked_dropdown
+            },
+        );
+
+        EditorRenderReturn {
+            delete,
+            resp: Some(r_vec),
+        }
+    }
+
+    fn ports_location(&self) -> Vec<(crate::common::Id, Pos2)> {
+        let own_pos = Vec2::new(self.pos.0, self.pos.1);
+        let mut ports = vec![(
+            crate::components::RESET_OUT_ID.to_string(),
+            Pos2::new(20f32, 0f32) + own_pos,
+        )];
+        if self.trigger.is_some() {
+            ports.push((
+                crate::components::RESET_TRIGGER_ID.to_string(),
+                Pos2::new(-20f32, 0f32) + own_pos,
+            ));
+        }
+        ports
+    }
+
+    fn top_padding(&self) -> f32 {
+        10f32
+    }
+
+    fn set_pos(&mut self, pos: (f32, f32)) {
+        self.pos = pos;
+    }
+
+    fn get_pos(&self) -> (f32, f32) {
+        self.pos
+    }
+}
@@ -0,0 +1,222 @@
+use crate::common::{EguiComponent, Ports, Simulator};
+use crate::components::{BitField, Splitter, SPLITTER_IN_ID};
+use crate::gui_egui::component_ui::{
+    drag_logic, input_change_id, input_selector, pos_drag_value, properties_window,
+    rect_with_hover, visualize_ports,
+};
+use crate::gui_egui::editor::{EditorMode, EditorRenderReturn, GridOptions};
+use crate::gui_egui::gui::EguiExtra;
+use crate::gui_egui::helper::offset_helper;
+use egui::{
+    Align2, Color32, DragValue, FontId, Pos2, Rect, Response, Rounding, Shape, Stroke, Ui, Vec2,
+};
+
+// half the height of a bus bar with `n` ports on the wide side
+pub(crate) fn bar_half_height(n: usize) -> f32 {
+    10f32 * n.max(1) as f32
+}
+
+// port `index` of `n` on the wide side of a bus bar
+pub(crate) fn bar_port_y(index: usize, n: usize) -> f32 {
+    20f32 * index as f32 - 10f32 * (n.max(1) as f32 - 1f32)
+}
+
+// editor for a list of bit fields
+pub(crate) fn bit_fields_editor(ui: &mut Ui, fields: &mut Vec<BitField>) {
+    let mut remove = None;
+    for (i, field) in fields.iter_mut().enumerate() {
+        ui.horizontal(|ui| {
+            ui.text_edit_singleline(&mut field.name);
+            ui.label("lsb");
+            ui.add(DragValue::new(&mut field.lsb).range(0..=31));
+            ui.label("width");
+            ui.add(DragValue::new(&mut field.width).range(1..=32));
+            if ui.button("x").clicked() {
+                remove = Some(i);
+            }
+        });
+    }
+    if let Some(i) = remove {
+        fields.remove(i);
+    }
+    if ui.button("+ Add new field").clicked() {
+        let lsb = fields.last().map_or(0, |f| (f.lsb + f.width).min(31));
+        fields.push(BitField::new(&format!("f{}", fields.len()), lsb, 1));
+    }
+}
+
+#[typetag::serde]
+impl EguiComponent for Splitter {
+    fn render(
+        &self,
+        ui: &mut Ui,
+        _context: &mut EguiExtra,
+        simulator: Option<&mut Simulator>,
+        offset: Vec2,
+        scale: f32,
+        clip_rect: Rect,
+        editor_mode: EditorMode,
+    ) -> Option<Vec<Response>> {
+        // 11x(20*fields + 1)
+        // middle: 6x (fields*10 + 1)y (0 0)
+        let oh: fn((f32, f32), f32, Vec2) -> Pos2 = offset_helper;
+        let offset_old = offset;
+        let mut offset = offset;
+        offset.x += self.pos.0 * scale;
+        offset.y += self.pos.1 * scale;
+        let s = scale;
+        let o = offset;
+        let n = self.fields.len();
+        let h = bar_half_height(n);
+
+        // The shape, a bar with a label per field
+        let rect = Rect {
+            min: oh((-5f32, -h), s, o),
+            max: oh((5f32, h), s, o),
+        };
+        ui.painter()
+            .add(Shape::rect_filled(rect, Rounding::ZERO, Color32::BLACK));
+        let values: Vec<String> = match &simulator {
+            Some(s) => self
+                .fields
+                .iter()
+                .map(|f| {
+                    format!(
+                        " = {:?}",
+                        s.get_input_value(&crate::common::Input::new(&self.id, &f.name))
+                    )
+                })
+                .collect(),
+            None => vec![String::new(); n],
+        };
+        for (i, field) in self.fields.iter().enumerate() {
+            ui.painter().text(
+                oh((8f32, bar_port_y(i, n) - 5f32), s, o),
+                Align2::LEFT_CENTER,
+                format!(
+                    "{}[{}:{}]",
+                    field.name,
+                    field.lsb + field.width.max(1) - 1,
+                    field.lsb
+                ),
+                FontId::monospace(7.0 * scale),
+                Color32::DARK_GRAY,
+            );
+            ui.painter().line_segment(
+                [
+                    oh((5f32, bar_port_y(i, n)), s, o),
+                    oh((10f32, bar_port_y(i, n)), s, o),
+                ],
+                Stroke {
+                    width: scale,
+                    color: Color32::BLACK,
+                },
+            );
+        }
+
+        let r = rect_with_hover(rect, clip_rect, editor_mode, ui, self.id.clone(), |ui| {
+            ui.label(format!("Id: {}", self.id.clone()));
+            ui.label("Splitter");
+            for (field, value) in self.fields.iter().zip(&values) {
+                ui.label(format!(
+                    "{}: bits {}..{}{}",
+                    field.name,
+                    field.lsb,
+                    field.lsb + field.width,
+                    value
+                ));
+            }
+        });
+        match editor_mode {
+            EditorMode::Simulator => (),
+            _ => visualize_ports(ui, self.ports_location(), offset_old, scale, clip_rect),
+        }
+        Some(vec![r])
+    }
+
+    fn render_editor(
+        &mut self,
+        ui: &mut Ui,
+        context: &mut EguiExtra,
+        simulator: Option<&mut Simulator>,
+        offset: Vec2,
+        scale: f32,
+        clip_rect: Rect,
+        id_ports: &[(crate::common::Id, Ports)],
+        grid: &GridOptions,
+        editor_mode: EditorMode,
+    ) -> EditorRenderReturn {
+        let r_vec = Splitter::render(
+            self,
+            ui,
+            context,
+            simulator,
+            offset,
+            scale,
+            clip_rect,
+            editor_mode,
+        )
+        .unwrap();
+        let resp = &r_vec[0];
+        let delete = drag_logic(
+            ui.ctx(),
+            resp,
+            &mut self.pos,
+            &mut context.pos_tmp,
+            scale,
+            offset,
+            grid,
+        );
+
+        properties_window(
+            ui,
+            self.id.clone(),
+            resp,
+            &mut context.properties_window,
+            |ui| {
+                let mut clicked_dropdown = false;
+                input_change_id(ui, &mut context.id_tmp, &mut self.id, id_ports);
+                pos_drag_value(ui, &mut self.pos);
+                clicked_dropdown |= input_selector(
+                    ui,
+                    &mut self.bus,
+                    SPLITTER_IN_ID.to_string(),
+                    id_ports,
+                    self.id.clone(),
+                );
+                bit_fields_editor(ui, &mut self.fields);
+                clicked_dropdown
+            },
+        );
+
+        EditorRenderReturn {
+            delete,
+            resp: Some(r_vec),
+        }
+    }
+
+    fn ports_location(&self) -> Vec<(crate::common::Id, Pos2)> {
+        let own_pos = Vec2::new(self.pos.0, self.pos.1);
+        let n = self.fields.len();
+        let mut v = vec![(SPLITTER_IN_ID.to_string(), Pos2::new(-5f32, 0f32) + own_pos)];
+        for (i, field) in self.fields.iter().enumerate() {
+            v.push((
+                field.name.clone(),
+                Pos2::new(10f32, bar_port_y(i, n)) + own_pos,
+            ));
+        }
+        v
+    }
+
+    fn top_padding(&self) -> f32 {
+        bar_half_height(self.fields.len())
+    }
+
+    fn set_pos(&mut self, pos: (f32, f32)) {
+        self.pos = pos;
+    }
+
+    fn get_pos(&self) -> (f32, f32) {
+        self.pos
+    }
+}
@@ -0,0 +1,286 @@
+use crate::common::{EguiComponent, Id, Input, Ports, Simulator};
+use crate::components::{Subcircuit, SubcircuitInput, SubcircuitOutput};
+use crate::gui_egui::component_ui::{
+    drag_logic, input_change_id, input_selector_removeable, pos_drag_value, properties_window,
+    rect_with_hover, visualize_ports,
+};
+use crate::gui_egui::editor::{EditorMode, EditorRenderReturn, GridOptions};
+use crate::gui_egui::gui::create_contexts;
+use crate::gui_egui::gui::EguiExtra;
+use crate::gui_egui::helper::offset_helper;
+use egui::{
+    Align2, Color32, FontId, Pos2, Rect, Response, Rounding, Shape, Stroke, TextEdit, Ui, Vec2,
+};
+use std::path::PathBuf;
+
+impl Subcircuit {
+    // half the height of the box, 20 per port
+    fn half_height(&self) -> f32 {
+        10f32 * (self.inputs.len().max(self.outputs.len()).max(1) + 1) as f32
+    }
+
+    fn port_y(&self, index: usize) -> f32 {
+        20f32 * (index + 1) as f32 - self.half_height()
+    }
+}
+
+#[typetag::serde]
+impl EguiComponent for Subcircuit {
+    fn render(
+        &self,
+        ui: &mut Ui,
+        _context: &mut EguiExtra,
+        mut simulator: Option<&mut Simulator>,
+        offset: Vec2,
+        scale: f32,
+        clip_rect: Rect,
+        editor_mode: EditorMode,
+    ) -> Option<Vec<Response>> {
+        // 81 wide, 20 high per port
+        // middle: (0 0)
+        let oh: fn((f32, f32), f32, Vec2) -> Pos2 = offset_helper;
+        let offset_old = offset;
+        let mut offset = offset;
+        offset.x += self.pos.0 * scale;
+        offset.y += self.pos.1 * scale;
+        let s = scale;
+        let o = offset;
+        let h = self.half_height();
+
+        if self.open {
+            // in the editor the model is not instantiated until simulated
+            if self.components.borrow().is_empty() {
+                match self.instantiate() {
+                    Ok(components) => *self.components.borrow_mut() = components,
+                    Err(e) => {
+                        ui.painter().text(
+                            oh((0f32, h + 10f32), s, o),
+                            Align2::CENTER_CENTER,
+                            e,
+                            FontId::monospace(8.0 * scale),
+                            Color32::RED,
+                        );
+                    }
+                }
+            }
+            let components = self.components.borrow().clone();
+            let mut contexts = create_contexts(&components);
+            for c in &components {
+                let mut context = contexts.remove(&c.get_id_ports().0).unwrap();
+                c.render(
+                    ui,
+                    &mut context,
+                    simulator.as_deref_mut(),
+                    offset,
+                    scale,
+                    clip_rect,
+                    EditorMode::Simulator,
+                );
+            }
+        }
+
+        // The shape, greyed out when opened
+        let rect = Rect {
+            min: oh((-40f32, -h), s, o),
+            max: oh((40f32, h), s, o),
+        };
+        ui.painter().add(Shape::rect_stroke(
+            rect,
+            Rounding::ZERO,
+            Stroke {
+                width: scale,
+                color: if self.open {
+                    Color32::LIGHT_GRAY
+                } else {
+                    Color32::BLACK
+                },
+            },
+        ));
+        if !self.open {
+            ui.painter().text(
+                o.to_pos2(),
+                Align2::CENTER_CENTER,
+                &self.id,
+                FontId::monospace(10.0 * scale),
+                Color32::BLACK,
+            );
+            for (i, input) in self.inputs.iter().enumerate() {
+                ui.painter().text(
+                    oh((-37f32, self.port_y(i)), s, o),
+                    Align2::LEFT_CENTER,
+                    &input.name,
+                    FontId::monospace(7.0 * scale),
+                    Color32::DARK_GRAY,
+                );
+            }
+            for (i, output) in self.outputs.iter().enumerate() {
+                ui.painter().text(
+                    oh((37f32, self.port_y(i)), s, o),
+                    Align2::RIGHT_CENTER,
+                    &output.name,
+                    FontId::monospace(7.0 * scale),
+                    Color32::DARK_GRAY,
+                );
+            }
+        }
+
+        let r = rect_with_hover(rect, clip_rect, editor_mode, ui, self.id.clone(), |ui| {
+            ui.label(format!("Id: {}", self.id.clone()));
+            ui.label(format!("Subcircuit: {}", self.file.display()));
+        });
+        match editor_mode {
+            EditorMode::Simulator => (),
+            _ => visualize_ports(ui, self.ports_location(), offset_old, scale, clip_rect),
+        }
+        Some(vec![r])
+    }
+
+    fn render_editor(
+        &mut self,
+        ui: &mut Ui,
+        context: &mut EguiExtra,
+        simulator: Option<&mut Simulator>,
+        offset: Vec2,
+        scale: f32,
+        clip_rect: Rect,
+        id_ports: &[(Id, Ports)],
+        grid: &GridOptions,
+        editor_mode: EditorMode,
+    ) -> EditorRenderReturn {
+        let r_vec = Subcircuit::render(
+            self,
+            ui,
+            context,
+            simulator,
+            offset,
+            scale,
+            clip_rect,
+            editor_mode,
+        )
+        .unwrap();
+        let resp = &r_vec[0];
+        let delete = drag_logic(
+            ui.ctx(),
+            resp,
+            &mut self.pos,
+            &mut context.pos_tmp,
+            scale,
+            offset,
+            grid,
+        );
+
+        properties_window(
+            ui,
+            self.id.clone(),
+            resp,
+            &mut context.properties_window,
+            |ui| {
+                let mut clicked_dropdown = false;
+                input_change_id(ui, &mut context.id_tmp, &mut self.id, id_ports);
+                pos_drag_value(ui, &mut self.pos);
+                let mut file = self.file.to_string_lossy().to_string();
+                ui.horizontal(|ui| {
+                    ui.label("model");
+                    if ui.text_edit_singleline(&mut file).changed() {
+                        self.file = PathBuf::from(&file);
+                        // instantiated again when opened
+                        self.components.get_mut().clear();
+                    }
+                });
+                ui.checkbox(&mut self.open, "open");
+
+                ui.separator();
+                ui.label("inputs, replacing component");
+                let mut remove = None;
+                for (i, input) in self.inputs.iter_mut().enumerate() {
+                    ui.horizontal(|ui| {
+                        ui.add(TextEdit::singleline(&mut input.name).desired_width(60.0));
+                        ui.add(TextEdit::singleline(&mut input.internal).desired_width(60.0));
+                    });
+                    let (clicked, removed) = input_selector_removeable(
+                        ui,
+                        &mut input.input,
+                        input.name.clone(),
+                        id_ports,
+                        self.id.clone(),
+                        true,
+                    );
+                    clicked_dropdown |= clicked;
+                    if removed {
+                        remove = Some(i);
+                    }
+                }
+                if let Some(i) = remove {
+                    self.inputs.remove(i);
+                }
+                if ui.button("+ Add input").clicked() {
+                    self.inputs.push(SubcircuitInput {
+                        name: format!("in{}", self.inputs.len()),
+                        internal: String::new(),
+                        input: Input::new("dummy", "out"),
+                    });
+                }
+
+                ui.separator();
+                ui.label("outputs, forwarding component field");
+                let mut remove = None;
+                for (i, output) in self.outputs.iter_mut().enumerate() {
+                    ui.horizontal(|ui| {
+                        ui.add(TextEdit::singleline(&mut output.name).desired_width(60.0));
+                        ui.add(TextEdit::singleline(&mut output.internal_id).desired_width(60.0));
+                        ui.add(
+                            TextEdit::singleline(&mut output.internal_field).desired_width(60.0),
+                        );
+                        if ui.button("x").clicked() {
+                            remove = Some(i);
+                        }
+                    });
+                }
+                if let Some(i) = remove {
+                    self.outputs.remove(i);
+                }
+                if ui.button("+ Add output").clicked() {
+                    self.outputs.push(SubcircuitOutput {
+                        name: format!("out{}", self.outputs.len()),
+                        internal_id: String::new(),
+                        internal_field: "out".to_string(),
+                    });
+                }
+                clicked_dropdown
+            },
+        );
+        EditorRenderReturn {
+            delete,
+            resp: Some(r_vec),
+        }
+    }
+
+    fn ports_location(&self) -> Vec<(Id, Pos2)> {
+        let own_pos = Vec2::new(self.pos.0, self.pos.1);
+        let inputs = self.inputs.iter().enumerate().map(|(i, input)| {
+            (
+                input.name.clone(),
+                Pos2::new(-40f32, self.port_y(i)) + own_pos,
+            )
+        });
+        let outputs = self.outputs.iter().enumerate().map(|(i, output)| {
+            (
+                output.name.clone(),
+                Pos2::new(40f32, self.port_y(i)) + own_pos,
+            )
+        });
+        inputs.chain(outputs).collect()
+    }
+
+    fn top_padding(&self) -> f32 {
+        self.half_height()
+    }
+
+    fn set_pos(&mut self, pos: (f32, f32)) {
+        self.pos = pos;
+    }
+
+    fn get_pos(&self) -> (f32, f32) {
+        self.pos
+    }
+}
@@ -12,9 +12,10 @@ use crate::gui_egui::{
 use eframe::{egui, Frame};
 use egui::{Color32, Context, LayerId, PointerButton, Pos2, Rect, Shape, Vec2};
 use std::{
+    cell::RefCell,
     collections::{BTreeMap, HashMap},
     ops::Range,
-    path::Path,
+    path::{Path, PathBuf},
     rc::Rc,
 };
 
@@ -116,12 +117,46 @@ impl Default for Library {
                 ctrl: dummy_input.clone(),
                 size: dummy_input.clone(),
                 sext: dummy_input.clone(),
+                reset: None,
                 range: Range {
                     start: 0,
                     end: 0x20,
                 },
                 memory: Memory::new(BTreeMap::new()),
+                image: None,
+                writes: RefCell::default(),
+                latency: MemLatency::default(),
+                wait: RefCell::default(),
+                record_trace: false,
+                trace: RefCell::default(),
+                uninit_read: UninitRead::default(),
+                pc: None,
+                faults: MemFaults::default(),
+                model_dir: PathBuf::new(),
             }),
+            Rc::new(DualPortMem::new(
+                "dual_port_mem",
+                (0.0, 0.0),
+                100.0,
+                80.0,
+                true,
+                MemPort::new(
+                    dummy_input.clone(),
+                    dummy_input.clone(),
+                    dummy_input.clone(),
+                    dummy_input.clone(),
+                    dummy_input.clone(),
+                ),
+                MemPort::new(
+                    dummy_input.clone(),
+                    dummy_input.clone(),
+                    dummy_input.clone(),
+                    dummy_input.clone(),
+                    dummy_input.clone(),
+                ),
+                BTreeMap::new(),
+                0..0x20,
+            )),
             Rc::new(Mux {
                 id: "mux".to_string(),
                 pos: (0.0, 0.0),
@@ -132,6 +167,13 @@ impl Default for Library {
                 id: "reg".to_string(),
                 pos: (0.0, 0.0),
                 r_in: dummy_input.clone(),
+                reset: None,
+            }),
+            Rc::new(Reset {
+                id: "rst".to_string(),
+                pos: (0.0, 0.0),
+                por_cycles: 1,
+                trigger: None,
             }),
             Rc::new(MIPSCLK {
                 id: "clk".to_string(),
@@ -158,6 +200,155 @@ impl Default for Library {
             //     symbols: HashMap::new(),
             //     le: true,
             // }),
+            Rc::new(Gate::new(
+                "and",
+                (0.0, 0.0),
+                GateOp::And,
+                vec![dummy_input.clone(), dummy_input.clone()],
+            )),
+            Rc::new(Gate::new_inverted(
+                "not",
+                (0.0, 0.0),
+                GateOp::And,
+                vec![dummy_input.clone()],
+                vec![],
+                true,
+            )),
+            Rc::new(FlipFlop::new(
+                "ff",
+                (0.0, 0.0),
+                FlipFlopKind::D,
+                dummy_input.clone(),
+            )),
+            Rc::new(Splitter::new(
+                "split",
+                (0.0, 0.0),
+                dummy_input.clone(),
+                vec![BitField::new("lo", 0, 16), BitField::new("hi", 16, 16)],
+            )),
+            Rc::new(Merger::new(
+                "merge",
+                (0.0, 0.0),
+                vec![
+                    MergerField::new(dummy_input.clone(), 16),
+                    MergerField::new(dummy_input.clone(), 16),
+                ],
+            )),
+            Rc::new(Alu::new(
+                "alu",
+                (0.0, 0.0),
+                dummy_input.clone(),
+                dummy_input.clone(),
+                dummy_input.clone(),
+                32,
+                Alu::mips_ops(),
+            )),
+            Rc::new(Shifter::new(
+                "shift",
+                (0.0, 0.0),
+                dummy_input.clone(),
+                dummy_input.clone(),
+                dummy_input.clone(),
+                32,
+            )),
+            Rc::new(Comparator::new(
+                "cmp",
+                (0.0, 0.0),
+                dummy_input.clone(),
+                dummy_input.clone(),
+                32,
+                true,
+            )),
+            Rc::new(MulDiv::new(
+                "muldiv",
+                (0.0, 0.0),
+                dummy_input.clone(),
+                dummy_input.clone(),
+                dummy_input.clone(),
+                dummy_input.clone(),
+                4,
+            )),
+            Rc::new(Lut::new(
+                "lut",
+                (0.0, 0.0),
+                dummy_input.clone(),
+                2,
+                vec![BitField::new("out", 0, 1)],
+                vec![LutRow::new("--", vec![0])],
+            )),
+            Rc::new(Bus::new(
+                "bus",
+                (0.0, 0.0),
+                dummy_input.clone(),
+                dummy_input.clone(),
+                vec![
+                    BusDevice::new("ram", 0..0x1000),
+                    BusDevice::new("io", 0x1000..0x1100),
+                ],
+            )),
+            Rc::new(Cache::new(
+                "cache",
+                (0.0, 0.0),
+                dummy_input.clone(),
+                dummy_input.clone(),
+                dummy_input.clone(),
+                dummy_input.clone(),
+                dummy_input.clone(),
+                16,
+                1,
+                16,
+                10,
+            )),
+            Rc::new(Fifo::new(
+                "fifo",
+                (0.0, 0.0),
+                dummy_input.clone(),
+                dummy_input.clone(),
+                dummy_input.clone(),
+                4,
+                32,
+            )),
+            Rc::new(Fsm::new(
+                "fsm",
+                (0.0, 0.0),
+                vec![FsmInput::new("go", dummy_input.clone())],
+                vec!["busy"],
+                vec![
+                    FsmState::new(
+                        "idle",
+                        (0.0, 0.0),
+                        vec![],
+                        vec![FsmTransition::new("run", vec![FsmCondition::new("go", 1)])],
+                    ),
+                    FsmState::new(
+                        "run",
+                        (60.0, 0.0),
+                        vec![FsmAssign::new("busy", 1)],
+                        vec![FsmTransition::new("idle", vec![FsmCondition::new("go", 0)])],
+                    ),
+                ],
+            )),
+            Rc::new(Subcircuit::new(
+                "subcircuit",
+                (0.0, 0.0),
+                "model.json",
+                vec![],
+                vec![],
+            )),
+            Generate::rc_new_from_components(
+                "generate",
+                (0.0, 0.0),
+                4,
+                (40.0, 0.0),
+                vec![Constant::rc_new("c{i}", (0.0, 0.0), 0)],
+            ),
+            Rc::new(PropertyAssert::new(
+                "property",
+                (0.0, 0.0),
+                Predicate::new(dummy_input.clone(), Compare::Ne, 0),
+                Predicate::new(dummy_input.clone(), Compare::Eq, dummy_input.clone()),
+                1,
+            )),
             Rc::new(
                 (FullAdd {
                     id: "dummy".to_string(),
@@ -133,7 +133,8 @@ impl Gui {
             let sim = self.simulator.as_mut().unwrap();
             ui.set_clip_rect(self.clip_rect);
             // Don't draw over the rest of the ui
-            for c in &sim.ordered_components.clone() {
+            // instantiated components are rendered by their subcircuit
+            for c in &sim.model_components() {
                 let old_key = c.as_ref().get_id_ports().0;
                 let mut context = self.contexts.remove(&old_key).unwrap();
                 c.render(
@@ -288,7 +288,7 @@ pub fn file_save_fn(gui: &mut Gui) {
             }
         }
         false => ComponentStore {
-            store: gui.simulator.clone().unwrap().ordered_components,
+            store: gui.simulator.as_ref().unwrap().model_components(),
         }
         .save_file(&PathBuf::from("file.json")),
     }
@@ -323,7 +323,7 @@ pub fn file_editor_toggle_fn(gui: &mut Gui) {
             let editor_existed: bool = gui.editor.as_mut().is_some();
 
             let simulator = gui.simulator.take().unwrap();
-            let components = simulator.ordered_components;
+            let components = simulator.model_components();
 
             if !editor_existed {
                 gui.editor = Some(Editor::gui(components, &gui.path, &gui.library));
@@ -0,0 +1,373 @@
+// Memory view shared by the memory components
+//
+// A window with a hex dump of the memory range, where the bytes written in
+// the current cycle are highlighted, a comparison of the contents between two
+// cycles and dumping of a range to a file. The settings are kept in the egui
+// memory, keyed by the component id.
+//
+// A window with the loads and stores of a memory, as a heat map of the
+// accesses by address region and a list, exportable as csv.
+//
+// Also the editors for the wait states, the fault handling and the reads of
+// uninitialised bytes of a memory.
+use crate::common::Input;
+use crate::components::{
+    FaultAction, LatencyRegion, MemFaults, MemLatency, MemTrace, PagedMemory, UninitRead,
+    WriteHistory,
+};
+use crate::mem_image::{dump_image, MemImageFormat};
+use egui::{
+    Color32, ComboBox, Context, DragValue, Label, RichText, ScrollArea, Sense, Ui, Vec2, Window,
+};
+use std::ops::Range;
+use std::path::Path;
+
+const BYTES_PER_ROW: usize = 16;
+const WRITTEN_COLOR: Color32 = Color32::LIGHT_RED;
+const CHANGED_COLOR: Color32 = Color32::LIGHT_YELLOW;
+
+#[derive(Clone, Default)]
+struct MemViewState {
+    // compare the contents at cycle `from` with cycle `to`
+    compare: bool,
+    from: usize,
+    to: usize,
+    dump_file: String,
+    dump_format: MemImageFormat,
+    dump_range: Option<Range<usize>>,
+    message: String,
+}
+
+/// Window `title` showing `memory` in `range` for the component `id`
+pub fn mem_window(
+    ctx: &Context,
+    title: &str,
+    id: &str,
+    memory: &PagedMemory,
+    writes: &WriteHistory,
+    range: Range<usize>,
+    cycle: usize,
+) {
+    let state_id = egui::Id::new((id, "mem_view"));
+    let mut state: MemViewState = ctx.data_mut(|d| d.get_temp(state_id).unwrap_or_default());
+    Window::new(title).id(state_id).show(ctx, |ui| {
+        mem_view(ui, &mut state, id, memory, writes, range, cycle);
+    });
+    ctx.data_mut(|d| d.insert_temp(state_id, state));
+}
+
+fn mem_view(
+    ui: &mut Ui,
+    state: &mut MemViewState,
+    id: &str,
+    memory: &PagedMemory,
+    writes: &WriteHistory,
+    range: Range<usize>,
+    cycle: usize,
+) {
+    let written = writes.written();
+    ui.horizontal(|ui| {
+        ui.checkbox(&mut state.compare, "compare cycle");
+        ui.add(DragValue::new(&mut state.from).range(0..=cycle));
+        ui.label("with");
+        ui.add(DragValue::new(&mut state.to).range(0..=cycle));
+        if ui.button("now").clicked() {
+            state.to = cycle;
+        }
+    });
+    let diff = if state.compare {
+        writes.diff(state.from, state.to.max(state.from))
+    } else {
+        Default::default()
+    };
+    ui.horizontal(|ui| {
+        ui.label(RichText::new("written").background_color(WRITTEN_COLOR));
+        if state.compare {
+            ui.label(
+                RichText::new(format!("{} bytes changed", diff.len()))
+                    .background_color(CHANGED_COLOR),
+            );
+        }
+    });
+
+    // hex dump, only the visible rows are laid out
+    let rows = range.len().div_ceil(BYTES_PER_ROW);
+    let row_height = ui.text_style_height(&egui::TextStyle::Monospace);
+    ScrollArea::vertical()
+        .id_source((id, "mem_view_rows"))
+        .max_height(300.0)
+        .show_rows(ui, row_height, rows, |ui, visible| {
+            for row in visible {
+                let start = range.start + row * BYTES_PER_ROW;
+                let end = (start + BYTES_PER_ROW).min(range.end);
+                ui.horizontal(|ui| {
+                    ui.spacing_mut().item_spacing.x = 4.0;
+                    ui.monospace(format!("{:08x}", start));
+                    let mut ascii = String::new();
+                    for addr in start..end {
+                        let byte = *memory.get(&addr).unwrap_or(&0);
+                        let mut text = RichText::new(format!("{:02x}", byte)).monospace();
+                        if !memory.is_init(addr) {
+                            text = text.color(Color32::GRAY);
+                        }
+                        if written.contains(&addr) {
+                            text = text.background_color(WRITTEN_COLOR);
+                        } else if diff.contains_key(&addr) {
+                            text = text.background_color(CHANGED_COLOR);
+                        }
+                        let label = ui.label(text);
+                        if let Some((old, new)) = diff.get(&addr) {
+                            label.on_hover_text(format!("{:#x}: {:02x} -> {:02x}", addr, old, new));
+                        }
+                        ascii.push(if byte.is_ascii_graphic() || byte == b' ' {
+                            byte as char
+                        } else {
+                            '.'
+                        });
+                    }
+                    ui.add(Label::new(RichText::new(ascii).monospace()).truncate());
+                });
+            }
+        });
+
+    ui.separator();
+    let dump_range = state.dump_range.get_or_insert(range.clone());
+    ui.horizontal(|ui| {
+        ui.label("dump");
+        ui.add(DragValue::new(&mut dump_range.start).hexadecimal(8, false, true));
+        ui.label("..");
+        ui.add(DragValue::new(&mut dump_range.end).hexadecimal(8, false, true));
+    });
+    ui.horizontal(|ui| {
+        ui.text_edit_singleline(&mut state.dump_file);
+        ComboBox::from_id_source((id, "mem_view_format"))
+            .selected_text(format!("{:?}", state.dump_format))
+            .show_ui(ui, |ui| {
+                for format in MemImageFormat::ALL {
+                    ui.selectable_value(&mut state.dump_format, format, format!("{:?}", format));
+                }
+            });
+        if ui.button("Dump").clicked() {
+            let bytes = memory.bytes(dump_range.clone());
+            let path = Path::new(&state.dump_file);
+            state.message = match dump_image(path, state.dump_format, &bytes, dump_range.start) {
+                Ok(()) => format!("{} bytes written to {:?}", bytes.len(), path),
+                Err(e) => e,
+            };
+        }
+    });
+    if !state.message.is_empty() {
+        ui.label(&state.message);
+    }
+}
+
+const REGIONS_PER_ROW: u32 = 16;
+
+#[derive(Clone)]
+struct TraceViewState {
+    // regions of 2^region_bits bytes
+    region_bits: u32,
+    export_file: String,
+    message: String,
+}
+
+impl Default for TraceViewState {
+    fn default() -> Self {
+        TraceViewState {
+            region_bits: 6,
+            export_file: String::new(),
+            message: String::new(),
+        }
+    }
+}
+
+/// Window `title` showing the accesses in `trace` for the component `id`
+pub fn trace_window(ctx: &Context, title: &str, id: &str, trace: &MemTrace) {
+    let state_id = egui::Id::new((id, "trace_view"));
+    let mut state: TraceViewState = ctx.data_mut(|d| d.get_temp(state_id).unwrap_or_default());
+    Window::new(title)
+        .id(state_id)
+        .default_open(false)
+        .show(ctx, |ui| {
+            trace_view(ui, &mut state, id, trace);
+        });
+    ctx.data_mut(|d| d.insert_temp(state_id, state));
+}
+
+fn trace_view(ui: &mut Ui, state: &mut TraceViewState, id: &str, trace: &MemTrace) {
+    let entries = trace.entries();
+    ui.horizontal(|ui| {
+        ui.label(format!("{} accesses, region", entries.len()));
+        ui.add(
+            DragValue::new(&mut state.region_bits)
+                .range(0..=20)
+                .custom_formatter(|bits, _| format!("{}", 1u32 << bits as u32)),
+        );
+        ui.label("bytes");
+    });
+
+    // only the rows with accesses, white to red by the number of accesses
+    let region_size = 1 << state.region_bits;
+    let heat_map = trace.heat_map(region_size);
+    let max = heat_map.values().map(|r| r.total()).max().unwrap_or(1);
+    let row_size = region_size * REGIONS_PER_ROW;
+    let mut rows: Vec<u32> = heat_map.keys().map(|r| r - r % row_size).collect();
+    rows.dedup();
+    let cell = ui.text_style_height(&egui::TextStyle::Monospace);
+    ScrollArea::vertical()
+        .id_source((id, "trace_view_heat_map"))
+        .max_height(200.0)
+        .show(ui, |ui| {
+            for row in rows {
+                ui.horizontal(|ui| {
+                    ui.spacing_mut().item_spacing.x = 1.0;
+                    ui.monospace(format!("{:08x}", row));
+                    for i in 0..REGIONS_PER_ROW {
+                        let start = row + i * region_size;
+                        let accesses = heat_map.get(&start).copied().unwrap_or_default();
+                        let heat = 255 - (accesses.total() * 255 / max) as u8;
+                        let (rect, response) =
+                            ui.allocate_exact_size(Vec2::splat(cell), Sense::hover());
+                        ui.painter()
+                            .rect_filled(rect, 0.0, Color32::from_rgb(255, heat, heat));
+                        response.on_hover_text(format!(
+                            "{:#010x}: {} reads, {} writes",
+                            start, accesses.reads, accesses.writes
+                        ));
+                    }
+                });
+            }
+        });
+
+    // the accesses, only the visible rows are laid out
+    ui.separator();
+    let row_height = ui.text_style_height(&egui::TextStyle::Monospace);
+    ScrollArea::vertical()
+        .id_source((id, "trace_view_entries"))
+        .max_height(200.0)
+        .stick_to_bottom(true)
+        .show_rows(ui, row_height, entries.len(), |ui, visible| {
+            for e in &entries[visible] {
+                ui.monospace(format!(
+                    "{:6} {} {:08x} {} {:08x}",
+                    e.cycle,
+                    if e.write { "W" } else { "R" },
+                    e.addr,
+                    e.size,
+                    e.value
+                ));
+            }
+        });
+
+    ui.separator();
+    ui.horizontal(|ui| {
+        ui.label("export");
+        ui.text_edit_singleline(&mut state.export_file);
+        if ui.button("Export").clicked() {
+            let path = Path::new(&state.export_file);
+            state.message = match trace.export(path) {
+                Ok(()) => format!("{} accesses written to {:?}", entries.len(), path),
+                Err(e) => e,
+            };
+        }
+    });
+    if !state.message.is_empty() {
+        ui.label(&state.message);
+    }
+}
+
+/// Editor for the wait states of a memory, the first matching region decides
+pub fn latency_editor(ui: &mut Ui, latency: &mut MemLatency) {
+    ui.horizontal(|ui| {
+        ui.label("wait states read");
+        ui.add(DragValue::new(&mut latency.read_wait).range(0..=100));
+        ui.label("write");
+        ui.add(DragValue::new(&mut latency.write_wait).range(0..=100));
+    });
+    let mut remove = None;
+    for (i, region) in latency.regions.iter_mut().enumerate() {
+        ui.horizontal(|ui| {
+            ui.add(DragValue::new(&mut region.range.start).hexadecimal(8, false, true));
+            ui.label("..");
+            ui.add(DragValue::new(&mut region.range.end).hexadecimal(8, false, true));
+            ui.label("read");
+            ui.add(DragValue::new(&mut region.read_wait).range(0..=100));
+            ui.label("write");
+            ui.add(DragValue::new(&mut region.write_wait).range(0..=100));
+            if ui.button("x").clicked() {
+                remove = Some(i);
+            }
+        });
+    }
+    if let Some(i) = remove {
+        latency.regions.remove(i);
+    }
+    if ui.button("add region").clicked() {
+        latency.regions.push(LatencyRegion::new(
+            0..0,
+            latency.read_wait,
+            latency.write_wait,
+        ));
+    }
+}
+
+/// Editor for the handling of faulty accesses of the memory `id`
+pub fn faults_editor(ui: &mut Ui, id: &str, faults: &mut MemFaults) -> bool {
+    let mut clicked_dropdown = false;
+    for (label, action) in [
+        ("misaligned", &mut faults.misaligned),
+        ("out of range", &mut faults.out_of_range),
+        ("illegal size", &mut faults.illegal_size),
+    ] {
+        ui.horizontal(|ui| {
+            ui.label(label);
+            clicked_dropdown |= ComboBox::from_id_source((id, "faults", label))
+                .selected_text(format!("{:?}", action))
+                .show_ui(ui, |ui| {
+                    for a in FaultAction::ALL {
+                        ui.selectable_value(action, a, format!("{:?}", a));
+                    }
+                })
+                .inner
+                .is_some();
+        });
+    }
+    clicked_dropdown
+}
+
+/// Editor for the reads of uninitialised bytes of the memory `id`, with the
+/// optional pc input named in warnings
+pub fn uninit_read_editor(
+    ui: &mut Ui,
+    id: &str,
+    uninit_read: &mut UninitRead,
+    pc: &mut Option<Input>,
+) -> bool {
+    let mut clicked_dropdown = false;
+    ui.horizontal(|ui| {
+        ui.label("uninitialised read");
+        clicked_dropdown |= ComboBox::from_id_source((id, "uninit_read"))
+            .selected_text(format!("{:?}", uninit_read))
+            .show_ui(ui, |ui| {
+                for u in [
+                    UninitRead::Zero,
+                    UninitRead::Uninitialized,
+                    UninitRead::Warning,
+                ] {
+                    ui.selectable_value(uninit_read, u, format!("{:?}", u));
+                }
+            })
+            .inner
+            .is_some();
+    });
+    if let UninitRead::Warning = uninit_read {
+        let mut enable = pc.is_some();
+        ui.checkbox(&mut enable, "pc input");
+        match (enable, pc.is_some()) {
+            (true, false) => *pc = Some(Input::new("dummy", "out")),
+            (false, true) => *pc = None,
+            _ => (),
+        }
+    }
+    clicked_dropdown
+}
@@ -72,6 +72,25 @@ impl Menu {
                             }),
                     );
                 }
+
+                // coverage is computed from history, only when hovered and
+                // once per cycle
+                ui.separator();
+                ui.label("Coverage").on_hover_ui(|ui| {
+                    let cache_id = egui::Id::new("coverage");
+                    let cached: Option<(usize, String)> =
+                        ui.ctx().data_mut(|d| d.get_temp(cache_id));
+                    let text = match cached {
+                        Some((cycle, text)) if cycle == s.cycle => text,
+                        _ => {
+                            let text = s.coverage().to_string();
+                            ui.ctx()
+                                .data_mut(|d| d.insert_temp(cache_id, (s.cycle, text.clone())));
+                            text
+                        }
+                    };
+                    ui.label(RichText::new(text).monospace());
+                });
             }
         });
     }
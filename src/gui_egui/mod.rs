@@ -9,5 +9,7 @@ mod menu;
 
 #[cfg(feature = "components")]
 pub mod components;
+#[cfg(feature = "components")]
+pub mod mem_view;
 
 pub use gui::*;
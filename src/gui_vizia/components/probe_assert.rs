@@ -1,7 +1,8 @@
 use crate::{
-    common::{SignalValue, Simulator},
+    common::Simulator,
     components::ProbeAssert,
     gui_vizia::{GuiData, ViziaComponent, V},
+    harness::value_matches,
 };
 use log::*;
 use vizia::prelude::*;
@@ -13,7 +14,7 @@ impl ViziaComponent for ProbeAssert {
         V::new(cx, self, |cx| {
             trace!("---- Create ProbeAssert View");
 
-            let values = self.values.clone();
+            let probe = self.clone();
 
             let input = self.input.clone();
             VStack::new(cx, |cx| {
@@ -22,15 +23,11 @@ impl ViziaComponent for ProbeAssert {
                     crate::gui_vizia::GuiData::simulator.then(Simulator::cycle),
                     move |cx, cycle| {
                         let cycle = cycle.get(cx);
-                        let assert = if let Some(value) = values.get(cycle - 1) {
-                            *value
-                        } else {
-                            (SignalValue::Unknown).into()
-                        };
+                        let assert = probe.expected(cycle - 1);
                         let simulator = GuiData::simulator.view(cx.data().unwrap()).unwrap();
                         //let simulator = GuiData::simulator.get(cx);
                         let signal = simulator.get_input_signal(&input);
-                        if signal == assert {
+                        if value_matches(&assert.get_value(), &signal.get_value()) {
                             Label::new(cx, &format!("{} == {}", signal, assert))
                                 .background_color(Color::lightgreen())
                         } else {
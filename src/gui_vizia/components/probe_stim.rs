@@ -1,5 +1,5 @@
 use crate::{
-    common::{SignalValue, Simulator},
+    common::Simulator,
     components::ProbeStim,
     gui_vizia::{ViziaComponent, V},
 };
@@ -12,18 +12,14 @@ impl ViziaComponent for ProbeStim {
     fn view<'a>(&self, cx: &'a mut Context) -> Handle<'a, V> {
         V::new(cx, self, |cx| {
             trace!("---- Create ProbeStim View");
-            let values = self.values.clone();
+            let stim = self.clone();
             VStack::new(cx, |cx| {
                 Binding::new(
                     cx,
                     crate::gui_vizia::GuiData::simulator.then(Simulator::cycle),
                     move |cx, cycle| {
                         let cycle = cycle.get(cx);
-                        let rhs = if let Some(value) = values.get(cycle - 1) {
-                            *value
-                        } else {
-                            (SignalValue::Unknown).into()
-                        };
+                        let rhs = stim.signal(cycle - 1);
                         Label::new(cx, &format!("{}", rhs)).hoverable(false);
                     },
                 );
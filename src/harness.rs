@@ -0,0 +1,265 @@
+// Helpers for driving a simulation from Rust, e.g., in component regression tests
+//
+// Signals are addressed by path strings of the form "id.field", where a
+// path without a field refers to the "out" field of the component. Ids may
+// contain "." (e.g., flattened ids), the part after the last "." is only
+// taken as the field if it is an output of that component.
+use crate::common::{Input, Signal, SignalValue, Simulator};
+use std::fmt;
+
+// number of cycles of signal history shown in failure reports
+const REPORT_CYCLES: usize = 16;
+
+/// Error returned by the harness, `Debug` prints the full report so that
+/// `unwrap` in a test gives a readable failure message
+pub struct HarnessError(pub String);
+
+impl fmt::Display for HarnessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl fmt::Debug for HarnessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "\n{}", self.0)
+    }
+}
+
+// format an expected value, `DontCare` as "-"
+fn fmt_expected(value: &SignalValue) -> String {
+    match value {
+        SignalValue::DontCare => "-".to_string(),
+        SignalValue::Data(data) => format!("{:#x}", data),
+        _ => format!("{:?}", value),
+    }
+}
+
+/// An expected value matches if equal or if `DontCare`
+pub fn value_matches(expected: &SignalValue, actual: &SignalValue) -> bool {
+    *expected == SignalValue::DontCare || expected == actual
+}
+
+impl Simulator {
+    /// Parse a path "id.field" into an `Input`, "id" alone refers to "id.out",
+    /// none if no such signal is in the model
+    pub fn parse_path(&self, path: &str) -> Option<Input> {
+        let has = |id: &str, field: &str| {
+            self.id_field_index
+                .contains_key(&(id.to_string(), field.to_string()))
+        };
+        match path.rsplit_once('.') {
+            Some((id, field)) if has(id, field) => Some(Input::new(id, field)),
+            _ if has(path, "out") => Some(Input::new(path, "out")),
+            _ => None,
+        }
+    }
+
+    // resolve a path, panics with the path if not found
+    fn path_input(&self, path: &str) -> Input {
+        self.parse_path(path)
+            .unwrap_or_else(|| panic!("Signal {:?} not found in model.", path))
+    }
+
+    /// read the current value of a signal by path
+    pub fn peek(&self, path: &str) -> SignalValue {
+        self.get_input_value(&self.path_input(path))
+    }
+
+    /// set the current value of a signal by path
+    pub fn poke(&mut self, path: &str, value: impl Into<SignalValue>) {
+        let input = self.path_input(path);
+        self.set_out_value(&input.id, &input.field, value);
+    }
+
+    /// clock the simulator `cycles` times
+    pub fn step(&mut self, cycles: usize) {
+        for _ in 0..cycles {
+            self.clock();
+        }
+    }
+
+    /// clock until `predicate` holds, at most `max_cycles` times
+    ///
+    /// Returns the number of cycles clocked, the predicate is checked
+    /// before the first clock as well.
+    pub fn step_until<P>(
+        &mut self,
+        mut predicate: P,
+        max_cycles: usize,
+    ) -> Result<usize, HarnessError>
+    where
+        P: FnMut(&Simulator) -> bool,
+    {
+        for n in 0..=max_cycles {
+            if predicate(self) {
+                return Ok(n);
+            }
+            if n < max_cycles {
+                self.clock();
+            }
+        }
+        Err(HarnessError(format!(
+            "step_until: predicate did not hold within {} cycles, now at cycle {}",
+            max_cycles, self.cycle
+        )))
+    }
+
+    /// signal values by path for all cycles so far, index 0 is cycle 1
+    pub fn signal_history(&self, path: &str) -> Vec<Signal> {
+        let input = self.path_input(path);
+        let index = self.get_id_start_index(&input.id)
+            + self.id_field_index[&(input.id.clone(), input.field.clone())];
+        self.history
+            .iter()
+            .skip(1)
+            .chain(std::iter::once(&self.sim_state))
+            .map(|state| state[index])
+            .collect()
+    }
+
+    /// check `path` against `expected`, one value per cycle starting at the
+    /// current cycle and clocking in between, `SignalValue::DontCare` matches any value
+    pub fn expect_sequence(
+        &mut self,
+        path: &str,
+        expected: &[SignalValue],
+    ) -> Result<(), HarnessError> {
+        let start = self.cycle;
+        for (i, e) in expected.iter().enumerate() {
+            if i > 0 {
+                self.clock();
+            }
+            let actual = self.peek(path);
+            if !value_matches(e, &actual) {
+                return Err(HarnessError(format!(
+                    "{} at cycle {}: expected {}, got {}\n{}",
+                    path,
+                    self.cycle,
+                    fmt_expected(e),
+                    self.get_input_signal(&self.path_input(path)),
+                    self.history_report(path, start, &expected[..=i]),
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// table of the recent history of `path`, with expected values from cycle `start`
+    pub fn history_report(&self, path: &str, start: usize, expected: &[SignalValue]) -> String {
+        let history = self.signal_history(path);
+        let first = history.len().saturating_sub(REPORT_CYCLES);
+        let mut report = format!("{:>8} | {:>12} | {:>12}\n", "cycle", "expected", path);
+        for (i, signal) in history.iter().enumerate().skip(first) {
+            let cycle = i + 1;
+            let (expected, mark) = match cycle.checked_sub(start).and_then(|i| expected.get(i)) {
+                Some(e) if !value_matches(e, &signal.get_value()) => (fmt_expected(e), " <--"),
+                Some(e) => (fmt_expected(e), ""),
+                None => (String::new(), ""),
+            };
+            report.push_str(&format!(
+                "{:>8} | {:>12} | {:>12}{}\n",
+                cycle,
+                expected,
+                signal.to_string(),
+                mark
+            ));
+        }
+        report
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::common::ComponentStore;
+    use crate::components::{Add, Constant, Register};
+
+    fn counter() -> Simulator {
+        let cs = ComponentStore {
+            store: vec![
+                Constant::rc_new("c", (0.0, 0.0), 1),
+                Add::rc_new(
+                    "add",
+                    (0.0, 0.0),
+                    Input::new("c", "out"),
+                    Input::new("reg", "out"),
+                ),
+                Register::rc_new("reg", (0.0, 0.0), Input::new("add", "out")),
+            ],
+        };
+        Simulator::new(cs).unwrap()
+    }
+
+    #[test]
+    fn test_peek_poke() {
+        let mut simulator = counter();
+        assert_eq!(simulator.peek("reg"), 0.into());
+        assert_eq!(simulator.peek("add.out"), 1.into());
+        simulator.poke("reg.out", 41);
+        assert_eq!(simulator.peek("reg.out"), 41.into());
+        simulator.clock();
+        assert_eq!(simulator.peek("reg.out"), 1.into());
+    }
+
+    #[test]
+    fn test_path_with_dot() {
+        let cs = ComponentStore {
+            store: vec![Constant::rc_new("gen/a.b", (0.0, 0.0), 3)],
+        };
+        let simulator = Simulator::new(cs).unwrap();
+        assert_eq!(simulator.peek("gen/a.b"), 3.into());
+        assert_eq!(simulator.peek("gen/a.b.out"), 3.into());
+        assert!(simulator.parse_path("gen/a.c").is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "Signal \"reg.missing\" not found in model.")]
+    fn test_peek_missing() {
+        counter().peek("reg.missing");
+    }
+
+    #[test]
+    fn test_step_until() {
+        let mut simulator = counter();
+        let n = simulator
+            .step_until(|s| s.peek("reg") == 5.into(), 10)
+            .unwrap();
+        assert_eq!(n, 5);
+        assert!(simulator
+            .step_until(|s| s.peek("reg") == 0.into(), 3)
+            .is_err());
+    }
+
+    #[test]
+    fn test_expect_sequence() {
+        let mut simulator = counter();
+        simulator
+            .expect_sequence(
+                "reg.out",
+                &[0.into(), SignalValue::DontCare, 2.into(), 3.into()],
+            )
+            .unwrap();
+        assert_eq!(simulator.cycle, 4);
+
+        let err = simulator
+            .expect_sequence("reg.out", &[3.into(), 4.into(), 7.into()])
+            .unwrap_err();
+        assert!(err
+            .0
+            .starts_with("reg.out at cycle 6: expected 0x7, got 0x5"));
+        assert!(err.0.contains("0x5 <--"));
+    }
+
+    #[test]
+    fn test_signal_history() {
+        let mut simulator = counter();
+        simulator.step(3);
+        let history: Vec<SignalValue> = simulator
+            .signal_history("reg")
+            .iter()
+            .map(|s| s.get_value())
+            .collect();
+        assert_eq!(history, vec![0.into(), 1.into(), 2.into(), 3.into()]);
+    }
+}
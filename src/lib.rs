@@ -1,13 +1,21 @@
 pub mod common;
 pub mod component_store;
+pub mod coverage;
 pub mod fern;
+pub mod harness;
+pub mod mem_image;
 pub mod signal;
 pub mod simulator;
+pub mod vector_table;
 
 // Default provided components
 #[cfg(feature = "components")]
 pub mod components;
 
+// Headless assertion runs and reports
+#[cfg(feature = "components")]
+pub mod report;
+
 // Vizia frontend
 #[cfg(feature = "gui-vizia")]
 pub mod gui_vizia;
@@ -0,0 +1,565 @@
+// Memory images for `Mem` and instruction memories
+//
+// An image is a file named in the model, read on simulator creation and
+// reset. Supported formats are:
+//
+// - Intel HEX (`.hex`, `.ihex`, `.ihx`), data and extended address records
+// - Motorola S-record (`.srec`, `.s19`, `.s28`, `.s37`, `.mot`)
+// - ELF, 32 or 64 bit of either endianness, the allocated sections are loaded
+//   at their addresses and `.bss` like sections are zero filled, as far as
+//   they are within the memory range
+// - raw binary, anything else
+//
+// With `Auto` the format is found from the ELF magic number or the file
+// extension. `offset` is added to all addresses, i.e., it is the load address
+// of a raw binary. A relative file name is relative to the model.
+//
+// Memory ranges can be dumped as Intel HEX, S-record or raw binary, see
+// `dump_image`.
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fs,
+    ops::Range,
+    path::{Path, PathBuf},
+};
+
+const ELF_MAGIC: &[u8] = b"\x7fELF";
+// section types and flags
+const SHT_NULL: u64 = 0;
+const SHT_NOBITS: u64 = 8;
+const SHF_ALLOC: u64 = 0x2;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MemImageFormat {
+    #[default]
+    Auto,
+    IntelHex,
+    SRecord,
+    Elf,
+    Binary,
+}
+
+impl MemImageFormat {
+    pub const ALL: [MemImageFormat; 5] = [
+        MemImageFormat::Auto,
+        MemImageFormat::IntelHex,
+        MemImageFormat::SRecord,
+        MemImageFormat::Elf,
+        MemImageFormat::Binary,
+    ];
+
+    /// The format of a file with `path` and content `bytes`
+    pub fn detect(path: &Path, bytes: &[u8]) -> Self {
+        if bytes.starts_with(ELF_MAGIC) {
+            return MemImageFormat::Elf;
+        }
+        let extension = path
+            .extension()
+            .map(|e| e.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        match extension.as_str() {
+            "hex" | "ihex" | "ihx" => MemImageFormat::IntelHex,
+            "srec" | "s19" | "s28" | "s37" | "mot" => MemImageFormat::SRecord,
+            "elf" => MemImageFormat::Elf,
+            _ => MemImageFormat::Binary,
+        }
+    }
+}
+
+/// Reference to an image file
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct MemImage {
+    pub file: PathBuf,
+    #[serde(default)]
+    pub format: MemImageFormat,
+    #[serde(default)]
+    pub offset: usize,
+}
+
+impl MemImage {
+    pub fn new(file: impl Into<PathBuf>, format: MemImageFormat, offset: usize) -> Self {
+        MemImage {
+            file: file.into(),
+            format,
+            offset,
+        }
+    }
+
+    /// Read the file, relative to `dir`, the bytes by address
+    ///
+    /// Zero filled sections are limited to the memory `range`.
+    pub fn load(&self, dir: &Path, range: Range<usize>) -> Result<BTreeMap<usize, u8>, String> {
+        let bytes =
+            fs::read(dir.join(&self.file)).map_err(|e| format!("{:?}: {}", self.file, e))?;
+        let format = match self.format {
+            MemImageFormat::Auto => MemImageFormat::detect(&self.file, &bytes),
+            format => format,
+        };
+        MemImage::parse(format, &bytes, self.offset, range)
+            .map_err(|e| format!("{:?}: {}", self.file, e))
+    }
+
+    /// Parse `bytes` in `format`, `Auto` is taken as raw binary
+    pub fn parse(
+        format: MemImageFormat,
+        bytes: &[u8],
+        offset: usize,
+        range: Range<usize>,
+    ) -> Result<BTreeMap<usize, u8>, String> {
+        let text = || std::str::from_utf8(bytes).map_err(|e| e.to_string());
+        let mem = match format {
+            MemImageFormat::IntelHex => parse_intel_hex(text()?)?,
+            MemImageFormat::SRecord => parse_srecord(text()?)?,
+            MemImageFormat::Elf => parse_elf(
+                bytes,
+                range.start.saturating_sub(offset)..range.end.saturating_sub(offset),
+            )?,
+            MemImageFormat::Auto | MemImageFormat::Binary => {
+                bytes.iter().copied().enumerate().collect()
+            }
+        };
+        mem.into_iter()
+            .map(|(addr, byte)| {
+                addr.checked_add(offset)
+                    .map(|addr| (addr, byte))
+                    .ok_or(format!(
+                        "address {:#x} + offset {:#x} overflows",
+                        addr, offset
+                    ))
+            })
+            .collect()
+    }
+}
+
+/// Write `bytes`, starting at address `start`, to `path` in `format`
+///
+/// With `Auto` the format is found from the extension, ELF is not supported.
+pub fn dump_image(
+    path: &Path,
+    format: MemImageFormat,
+    bytes: &[u8],
+    start: usize,
+) -> Result<(), String> {
+    let format = match format {
+        MemImageFormat::Auto => MemImageFormat::detect(path, &[]),
+        format => format,
+    };
+    let content = match format {
+        MemImageFormat::IntelHex => format_intel_hex(bytes, start)?.into_bytes(),
+        MemImageFormat::SRecord => format_srecord(bytes, start)?.into_bytes(),
+        MemImageFormat::Elf => return Err("dumping to ELF is not supported".to_string()),
+        MemImageFormat::Auto | MemImageFormat::Binary => bytes.to_vec(),
+    };
+    fs::write(path, content).map_err(|e| format!("{:?}: {}", path, e))
+}
+
+/// Big or little endian word at `addr`, `None` if no byte of it is in `mem`
+///
+/// Bytes of the word missing in `mem` are 0.
+pub fn mem_word(mem: &BTreeMap<usize, u8>, addr: usize, big_endian: bool) -> Option<u32> {
+    let end = addr.checked_add(4)?;
+    mem.range(addr..end).next()?;
+    let bytes = [0, 1, 2, 3].map(|j| *mem.get(&(addr + j)).unwrap_or(&0));
+    Some(if big_endian {
+        u32::from_be_bytes(bytes)
+    } else {
+        u32::from_le_bytes(bytes)
+    })
+}
+
+// the bytes of a hex record, with the checksum verified by `check`
+fn record_bytes(record: &str, line: usize, check: fn(u8) -> bool) -> Result<Vec<u8>, String> {
+    if !record.is_ascii() {
+        return Err(format!("line {}: illegal characters", line));
+    }
+    if !record.len().is_multiple_of(2) {
+        return Err(format!("line {}: odd number of digits", line));
+    }
+    let bytes = (0..record.len())
+        .step_by(2)
+        .map(|i| {
+            u8::from_str_radix(&record[i..i + 2], 16)
+                .map_err(|_| format!("line {}: illegal hex digits {:?}", line, &record[i..i + 2]))
+        })
+        .collect::<Result<Vec<u8>, String>>()?;
+    let sum = bytes.iter().fold(0u8, |sum, b| sum.wrapping_add(*b));
+    if !check(sum) {
+        return Err(format!("line {}: checksum error", line));
+    }
+    Ok(bytes)
+}
+
+// a hex record with its checksum, `negate` for the two's complement used by
+// Intel HEX
+fn format_record(bytes: &[u8], negate: bool) -> String {
+    let sum = bytes.iter().fold(0u8, |sum, b| sum.wrapping_add(*b));
+    let check = if negate { sum.wrapping_neg() } else { !sum };
+    bytes
+        .iter()
+        .chain([check].iter())
+        .map(|b| format!("{:02X}", b))
+        .collect()
+}
+
+// bytes per data record in dumps
+const DUMP_RECORD_LEN: usize = 16;
+
+pub fn format_intel_hex(bytes: &[u8], start: usize) -> Result<String, String> {
+    if start + bytes.len() > 1 << 32 {
+        return Err("address range exceeds 32 bits".to_string());
+    }
+    let mut text = String::new();
+    let mut base = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        let addr = start + i;
+        // records do not cross a 64k boundary
+        let len = DUMP_RECORD_LEN
+            .min(bytes.len() - i)
+            .min(0x1_0000 - addr % 0x1_0000);
+        if addr >> 16 != base {
+            base = addr >> 16;
+            let record = [2, 0, 0, 4, (base >> 8) as u8, base as u8];
+            text += &format!(":{}\n", format_record(&record, true));
+        }
+        let mut record = vec![len as u8, (addr >> 8) as u8, addr as u8, 0];
+        record.extend(&bytes[i..i + len]);
+        text += &format!(":{}\n", format_record(&record, true));
+        i += len;
+    }
+    text += ":00000001FF\n";
+    Ok(text)
+}
+
+pub fn format_srecord(bytes: &[u8], start: usize) -> Result<String, String> {
+    if start + bytes.len() > 1 << 32 {
+        return Err("address range exceeds 32 bits".to_string());
+    }
+    let mut text = String::new();
+    for (i, chunk) in bytes.chunks(DUMP_RECORD_LEN).enumerate() {
+        let addr = (start + i * DUMP_RECORD_LEN) as u32;
+        let mut record = vec![chunk.len() as u8 + 5];
+        record.extend(addr.to_be_bytes());
+        record.extend(chunk);
+        text += &format!("S3{}\n", format_record(&record, false));
+    }
+    text += &format!("S7{}\n", format_record(&[5, 0, 0, 0, 0], false));
+    Ok(text)
+}
+
+pub fn parse_intel_hex(text: &str) -> Result<BTreeMap<usize, u8>, String> {
+    let mut mem = BTreeMap::new();
+    let mut base = 0usize;
+    for (i, line) in text.lines().enumerate() {
+        let line_nr = i + 1;
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let record = line
+            .strip_prefix(':')
+            .ok_or(format!("line {}: record does not start with ':'", line_nr))?;
+        // length, address, type, data and checksum summing to 0
+        let bytes = record_bytes(record, line_nr, |sum| sum == 0)?;
+        if bytes.len() < 5 || bytes.len() != bytes[0] as usize + 5 {
+            return Err(format!("line {}: wrong record length", line_nr));
+        }
+        let addr = u16::from_be_bytes([bytes[1], bytes[2]]) as usize;
+        let data = &bytes[4..bytes.len() - 1];
+        match bytes[3] {
+            0x00 => {
+                for (j, byte) in data.iter().enumerate() {
+                    mem.insert(base + addr + j, *byte);
+                }
+            }
+            0x01 => break,
+            0x02 if data.len() == 2 => {
+                base = (u16::from_be_bytes([data[0], data[1]]) as usize) << 4
+            }
+            0x04 if data.len() == 2 => {
+                base = (u16::from_be_bytes([data[0], data[1]]) as usize) << 16
+            }
+            // start addresses
+            0x03 | 0x05 => {}
+            t => return Err(format!("line {}: illegal record type {:02x}", line_nr, t)),
+        }
+    }
+    Ok(mem)
+}
+
+pub fn parse_srecord(text: &str) -> Result<BTreeMap<usize, u8>, String> {
+    let mut mem = BTreeMap::new();
+    for (i, line) in text.lines().enumerate() {
+        let line_nr = i + 1;
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let (t, record) = line
+            .strip_prefix('S')
+            .and_then(|r| r.split_at_checked(1))
+            .ok_or(format!("line {}: record does not start with 'S'", line_nr))?;
+        // count, address, data and checksum summing to 0xff
+        let bytes = record_bytes(record, line_nr, |sum| sum == 0xff)?;
+        if bytes.is_empty() || bytes.len() != bytes[0] as usize + 1 {
+            return Err(format!("line {}: wrong record length", line_nr));
+        }
+        let addr_len = match t {
+            "1" => 2,
+            "2" => 3,
+            "3" => 4,
+            // header, count and start address
+            "0" | "5" | "6" | "7" | "8" | "9" => continue,
+            _ => return Err(format!("line {}: illegal record type S{}", line_nr, t)),
+        };
+        if bytes.len() < addr_len + 2 {
+            return Err(format!("line {}: wrong record length", line_nr));
+        }
+        let addr = bytes[1..=addr_len]
+            .iter()
+            .fold(0usize, |addr, b| addr << 8 | *b as usize);
+        for (j, byte) in bytes[addr_len + 1..bytes.len() - 1].iter().enumerate() {
+            mem.insert(addr + j, *byte);
+        }
+    }
+    Ok(mem)
+}
+
+pub fn parse_elf(bytes: &[u8], fill: Range<usize>) -> Result<BTreeMap<usize, u8>, String> {
+    if !bytes.starts_with(ELF_MAGIC) || bytes.len() < 16 {
+        return Err("not an ELF file".to_string());
+    }
+    let wide = match bytes[4] {
+        1 => false,
+        2 => true,
+        c => return Err(format!("illegal ELF class {}", c)),
+    };
+    let big_endian = match bytes[5] {
+        1 => false,
+        2 => true,
+        d => return Err(format!("illegal ELF data encoding {}", d)),
+    };
+    // unsigned field of `size` bytes at `at` in `header`
+    let field = |header: &[u8], at: usize, size: usize| -> Result<u64, String> {
+        let b = header
+            .get(at..at + size)
+            .ok_or("ELF file truncated".to_string())?;
+        Ok(if big_endian {
+            b.iter().fold(0, |v, b| v << 8 | *b as u64)
+        } else {
+            b.iter().rev().fold(0, |v, b| v << 8 | *b as u64)
+        })
+    };
+    // address sized fields
+    let word = if wide { 8 } else { 4 };
+    let (shoff, shentsize, shnum) = if wide {
+        (
+            field(bytes, 0x28, 8)?,
+            field(bytes, 0x3a, 2)?,
+            field(bytes, 0x3c, 2)?,
+        )
+    } else {
+        (
+            field(bytes, 0x20, 4)?,
+            field(bytes, 0x2e, 2)?,
+            field(bytes, 0x30, 2)?,
+        )
+    };
+
+    let mut mem = BTreeMap::new();
+    for i in 0..shnum {
+        let outside = || format!("ELF section {} outside of file", i);
+        let sh = i
+            .checked_mul(shentsize)
+            .and_then(|sh| sh.checked_add(shoff))
+            .and_then(|sh| usize::try_from(sh).ok())
+            .ok_or_else(outside)?;
+        let header = bytes.get(sh..).ok_or_else(outside)?;
+        let sh_type = field(header, 4, 4)?;
+        let sh_flags = field(header, 8, word)?;
+        let sh_addr = field(header, 8 + word, word)? as usize;
+        let sh_offset = field(header, 8 + 2 * word, word)? as usize;
+        let sh_size = field(header, 8 + 3 * word, word)? as usize;
+        if sh_type == SHT_NULL || sh_flags & SHF_ALLOC == 0 {
+            continue;
+        }
+        if sh_type == SHT_NOBITS {
+            // only the part in the memory, the size is not bounded by the file
+            let start = sh_addr.max(fill.start);
+            let end = sh_addr.saturating_add(sh_size).min(fill.end);
+            mem.extend((start..end).map(|addr| (addr, 0)));
+        } else {
+            let data = sh_offset
+                .checked_add(sh_size)
+                .and_then(|end| bytes.get(sh_offset..end))
+                .ok_or_else(outside)?;
+            if sh_addr.checked_add(sh_size).is_none() {
+                return Err(format!("ELF section {} outside of the address space", i));
+            }
+            mem.extend(data.iter().enumerate().map(|(j, b)| (sh_addr + j, *b)));
+        }
+    }
+    Ok(mem)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_intel_hex() {
+        let mem = parse_intel_hex(
+            ":0400100001020304E2\n\
+             :020000040001F9\n\
+             :02000000AABB99\n\
+             :00000001FF\n",
+        )
+        .unwrap();
+        assert_eq!(
+            mem,
+            BTreeMap::from([
+                (0x10, 1),
+                (0x11, 2),
+                (0x12, 3),
+                (0x13, 4),
+                (0x1_0000, 0xaa),
+                (0x1_0001, 0xbb)
+            ])
+        );
+        assert_eq!(
+            parse_intel_hex(":0400100001020304E3"),
+            Err("line 1: checksum error".to_string())
+        );
+        assert!(parse_intel_hex("0400100001020304E2").is_err());
+    }
+
+    #[test]
+    fn test_srecord() {
+        let mem = parse_srecord(
+            "S00600004844521B\n\
+             S107001001020304DE\n\
+             S30700010000AABB92\n\
+             S9030000FC\n",
+        )
+        .unwrap();
+        assert_eq!(
+            mem,
+            BTreeMap::from([
+                (0x10, 1),
+                (0x11, 2),
+                (0x12, 3),
+                (0x13, 4),
+                (0x1_0000, 0xaa),
+                (0x1_0001, 0xbb)
+            ])
+        );
+        assert_eq!(
+            parse_srecord("S107001001020304DF"),
+            Err("line 1: checksum error".to_string())
+        );
+    }
+
+    // a 32 bit big endian ELF with a `.text` and a `.bss` section
+    fn elf32_be() -> Vec<u8> {
+        let mut elf = vec![0u8; 0x34];
+        elf[..6].copy_from_slice(b"\x7fELF\x01\x02");
+        // section headers at 0x3c, 40 bytes each, 3 sections
+        elf[0x20..0x24].copy_from_slice(&0x3cu32.to_be_bytes());
+        elf[0x2e..0x30].copy_from_slice(&40u16.to_be_bytes());
+        elf[0x30..0x32].copy_from_slice(&3u16.to_be_bytes());
+        // .text content
+        elf.extend([0u8; 4]);
+        elf.extend([0x12, 0x34, 0x56, 0x78]);
+        // null section
+        elf.extend([0u8; 40]);
+        for (sh_type, addr, offset, size) in [(1u32, 0x100u32, 0x38u32, 4u32), (8, 0x200, 0, 2)] {
+            let mut sh = vec![];
+            for field in [0, sh_type, SHF_ALLOC as u32, addr, offset, size, 0, 0, 4, 0] {
+                sh.extend(field.to_be_bytes());
+            }
+            elf.extend(sh);
+        }
+        elf
+    }
+
+    #[test]
+    fn test_elf() {
+        let elf = elf32_be();
+        assert_eq!(
+            MemImageFormat::detect(Path::new("prog"), &elf),
+            MemImageFormat::Elf
+        );
+        let mem = MemImage::parse(MemImageFormat::Elf, &elf, 0, 0..0x1000).unwrap();
+        assert_eq!(
+            mem,
+            BTreeMap::from([
+                (0x100, 0x12),
+                (0x101, 0x34),
+                (0x102, 0x56),
+                (0x103, 0x78),
+                (0x200, 0),
+                (0x201, 0)
+            ])
+        );
+        assert!(parse_elf(&elf[..0x50], 0..0x1000).is_err());
+
+        // a huge .bss is only filled within the memory range
+        let mut huge = elf.clone();
+        let size_at = huge.len() - 40 + 20;
+        huge[size_at..size_at + 4].copy_from_slice(&0xffff_fff0u32.to_be_bytes());
+        let mem = MemImage::parse(MemImageFormat::Elf, &huge, 0x10, 0x100..0x214).unwrap();
+        assert_eq!(mem.range(0x200..).count(), 4);
+        assert_eq!(mem.get(&0x213), Some(&0));
+
+        // section headers past the end of the address space
+        let mut elf64 = vec![0u8; 0x40];
+        elf64[..6].copy_from_slice(b"\x7fELF\x02\x01");
+        elf64[0x28..0x30].copy_from_slice(&u64::MAX.to_le_bytes());
+        elf64[0x3a..0x3c].copy_from_slice(&64u16.to_le_bytes());
+        elf64[0x3c..0x3e].copy_from_slice(&2u16.to_le_bytes());
+        assert!(parse_elf(&elf64, 0..0x1000).is_err());
+    }
+
+    #[test]
+    fn test_dump() {
+        // crossing a 64k boundary
+        let bytes: Vec<u8> = (0..40).collect();
+        let hex = format_intel_hex(&bytes, 0xfff0).unwrap();
+        assert!(hex.starts_with(":10FFF000000102030405060708090A0B0C0D0E0F89\n:020000040001F9\n"));
+        let mem = BTreeMap::from_iter((0..40).map(|i| (0xfff0 + i as usize, i)));
+        assert_eq!(parse_intel_hex(&hex).unwrap(), mem);
+        assert_eq!(
+            parse_srecord(&format_srecord(&bytes, 0xfff0).unwrap()).unwrap(),
+            mem
+        );
+
+        let path = std::env::temp_dir().join("syncrim_test_dump.srec");
+        dump_image(&path, MemImageFormat::Auto, &bytes, 0x100).unwrap();
+        let image = MemImage::new(&path, MemImageFormat::Auto, 0);
+        assert_eq!(
+            image.load(Path::new(""), 0..0x1000).unwrap().get(&0x127),
+            Some(&39)
+        );
+        fs::remove_file(path).unwrap();
+        assert!(dump_image(Path::new("x.elf"), MemImageFormat::Elf, &bytes, 0).is_err());
+    }
+
+    #[test]
+    fn test_binary_and_words() {
+        assert_eq!(
+            MemImageFormat::detect(Path::new("prog.bin"), b"\x01\x02"),
+            MemImageFormat::Binary
+        );
+        assert_eq!(
+            MemImageFormat::detect(Path::new("prog.S19"), b"S1"),
+            MemImageFormat::SRecord
+        );
+        let mem = MemImage::parse(MemImageFormat::Binary, &[1, 2, 3, 4, 5], 4, 0..0x10).unwrap();
+        assert_eq!(mem_word(&mem, 4, true), Some(0x0102_0304));
+        assert_eq!(mem_word(&mem, 8, true), Some(0x0500_0000));
+        assert_eq!(mem_word(&mem, 8, false), Some(0x0000_0005));
+        assert_eq!(mem_word(&mem, 0, true), None);
+        assert_eq!(mem_word(&mem, 12, true), None);
+    }
+}
@@ -0,0 +1,393 @@
+// Headless assertion runs with JUnit XML or TAP reports
+//
+// Each model is simulated until all `ProbeAssert` components have checked
+// their expected values (or a given number of cycles), failed assertions do
+// not stop the run but component errors and halts do. Each `ProbeAssert` and
+// `PropertyAssert` is one test case, a model without either is a single test
+// case failing on any error or assertion.
+use crate::common::{ComponentStore, Condition, Id, Signal, Simulator};
+use crate::components::{ProbeAssert, PropertyAssert};
+use crate::coverage::Coverage;
+use std::{fmt::Write, fs, path::Path};
+
+/// A single failed check
+#[derive(Debug, Clone, PartialEq)]
+pub struct Failure {
+    pub cycle: usize,
+    pub expected: Option<Signal>,
+    pub actual: Option<Signal>,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TestCase {
+    pub name: String,
+    pub failures: Vec<Failure>,
+    // the test could not be run to completion
+    pub error: Option<String>,
+}
+
+impl TestCase {
+    fn new(name: &str) -> Self {
+        TestCase {
+            name: name.to_string(),
+            failures: vec![],
+            error: None,
+        }
+    }
+
+    pub fn passed(&self) -> bool {
+        self.failures.is_empty() && self.error.is_none()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ModelReport {
+    pub model: String,
+    // number of cycles simulated
+    pub cycles: usize,
+    pub cases: Vec<TestCase>,
+    pub coverage: Coverage,
+}
+
+impl ModelReport {
+    pub fn passed(&self) -> bool {
+        self.cases.iter().all(|case| case.passed())
+    }
+}
+
+/// Load and run the model in `path`, see `run_store`
+pub fn run_model(path: &Path, max_cycles: Option<usize>) -> ModelReport {
+    let name = path.display().to_string();
+    let cs = fs::read_to_string(path)
+        .map_err(|e| e.to_string())
+        .and_then(|json| serde_json::from_str::<ComponentStore>(&json).map_err(|e| e.to_string()));
+    match cs {
+        Ok(mut cs) => {
+            cs.set_model_dir(path.parent().unwrap_or(Path::new("")));
+            run_store(&name, cs, max_cycles)
+        }
+        Err(e) => error_report(&name, format!("could not load model: {}", e)),
+    }
+}
+
+fn error_report(name: &str, error: String) -> ModelReport {
+    let mut case = TestCase::new(name);
+    case.error = Some(error);
+    ModelReport {
+        model: name.to_string(),
+        cycles: 0,
+        cases: vec![case],
+        coverage: Coverage {
+            cycles: 0,
+            points: vec![],
+        },
+    }
+}
+
+/// Run the model for `max_cycles`, by default until the last expected value
+pub fn run_store(name: &str, cs: ComponentStore, max_cycles: Option<usize>) -> ModelReport {
+    let probes: Vec<ProbeAssert> = cs
+        .store
+        .iter()
+        .filter_map(|c| c.as_any().downcast_ref::<ProbeAssert>().cloned())
+        .collect();
+    let properties: Vec<Id> = cs
+        .store
+        .iter()
+        .filter(|c| c.as_any().is::<PropertyAssert>())
+        .map(|c| c.get_id_ports().0)
+        .collect();
+    // one case per probe followed by one per property
+    let case_ids: Vec<&Id> = probes.iter().map(|p| &p.id).chain(&properties).collect();
+    let mut cases: Vec<TestCase> = if case_ids.is_empty() {
+        vec![TestCase::new(name)]
+    } else {
+        case_ids.iter().map(|id| TestCase::new(id)).collect()
+    };
+
+    let mut cycles = 0;
+    for (probe, case) in probes.iter().zip(cases.iter_mut()) {
+        match probe.cycles() {
+            Ok(n) => cycles = cycles.max(n),
+            Err(e) => case.error = Some(e),
+        }
+    }
+    let cycles = max_cycles.unwrap_or(cycles).max(1);
+
+    let mut simulator = match Simulator::new(cs) {
+        Ok(simulator) => simulator,
+        Err(e) => return error_report(name, e.to_string()),
+    };
+    loop {
+        let mut stop = false;
+        // the conditions are those of the last evaluated cycle, i.e., the
+        // cycle the probes checked, before the cycle count was advanced
+        let cycle = simulator.cycle - 1;
+        for (id, condition) in simulator.component_condition.clone() {
+            let case = case_ids.iter().position(|case_id| **case_id == id);
+            match condition {
+                Condition::Assert(message) => {
+                    let (expected, actual) = match case {
+                        Some(i) if i < probes.len() => (
+                            Some(probes[i].expected(cycle)),
+                            Some(simulator.get_input_signal(&probes[i].input)),
+                        ),
+                        _ => (None, None),
+                    };
+                    let failure = Failure {
+                        cycle,
+                        expected,
+                        actual,
+                        message: format!("{}: {}", id, message),
+                    };
+                    // assertions not raised by a probe fail all cases
+                    match case {
+                        Some(i) => cases[i].failures.push(failure),
+                        None => cases
+                            .iter_mut()
+                            .for_each(|c| c.failures.push(failure.clone())),
+                    }
+                }
+                Condition::Error(message) => {
+                    for case in cases.iter_mut().filter(|c| c.error.is_none()) {
+                        case.error = Some(format!("cycle {}: {}: {}", cycle, id, message));
+                    }
+                    stop = true;
+                }
+                Condition::Halt(_) => stop = true,
+                Condition::Warning(_) => (),
+            }
+        }
+        if stop || simulator.cycle >= cycles {
+            break;
+        }
+        simulator.clock();
+    }
+
+    ModelReport {
+        model: name.to_string(),
+        cycles: simulator.cycle,
+        cases,
+        coverage: simulator.coverage(),
+    }
+}
+
+// escape text for use in xml attributes and content
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+fn fmt_signal(signal: &Option<Signal>) -> String {
+    match signal {
+        Some(signal) => signal.to_string(),
+        None => "-".to_string(),
+    }
+}
+
+fn fmt_failure(failure: &Failure) -> String {
+    match (&failure.expected, &failure.actual) {
+        (None, None) => format!("cycle {}: {}", failure.cycle, failure.message),
+        _ => format!(
+            "cycle {}: expected {}, got {}",
+            failure.cycle,
+            fmt_signal(&failure.expected),
+            fmt_signal(&failure.actual)
+        ),
+    }
+}
+
+/// JUnit XML report, one test suite per model
+pub fn junit(reports: &[ModelReport]) -> String {
+    let count = |f: fn(&TestCase) -> bool| -> usize {
+        reports
+            .iter()
+            .map(|r| r.cases.iter().filter(|c| f(c)).count())
+            .sum()
+    };
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    writeln!(
+        xml,
+        "<testsuites tests=\"{}\" failures=\"{}\" errors=\"{}\">",
+        count(|_| true),
+        count(|c| !c.failures.is_empty() && c.error.is_none()),
+        count(|c| c.error.is_some())
+    )
+    .unwrap();
+    for report in reports {
+        let model = xml_escape(&report.model);
+        writeln!(
+            xml,
+            "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"{}\">",
+            model,
+            report.cases.len(),
+            report
+                .cases
+                .iter()
+                .filter(|c| !c.failures.is_empty() && c.error.is_none())
+                .count(),
+            report.cases.iter().filter(|c| c.error.is_some()).count()
+        )
+        .unwrap();
+        for case in &report.cases {
+            write!(
+                xml,
+                "    <testcase classname=\"{}\" name=\"{}\"",
+                model,
+                xml_escape(&case.name)
+            )
+            .unwrap();
+            if case.passed() {
+                xml.push_str("/>\n");
+                continue;
+            }
+            xml.push_str(">\n");
+            if let Some(error) = &case.error {
+                writeln!(xml, "      <error message=\"{}\"/>", xml_escape(error)).unwrap();
+            } else {
+                let details: Vec<String> = case
+                    .failures
+                    .iter()
+                    .map(|f| format!("{}\n{}", fmt_failure(f), f.message))
+                    .collect();
+                writeln!(
+                    xml,
+                    "      <failure type=\"assert\" message=\"{}\">{}</failure>",
+                    xml_escape(&fmt_failure(&case.failures[0])),
+                    xml_escape(&details.join("\n"))
+                )
+                .unwrap();
+            }
+            xml.push_str("    </testcase>\n");
+        }
+        xml.push_str("  </testsuite>\n");
+    }
+    xml.push_str("</testsuites>\n");
+    xml
+}
+
+/// TAP version 13 report, one test point per test case
+pub fn tap(reports: &[ModelReport]) -> String {
+    let total: usize = reports.iter().map(|r| r.cases.len()).sum();
+    let mut tap = format!("TAP version 13\n1..{}\n", total);
+    let cases = reports
+        .iter()
+        .flat_map(|r| r.cases.iter().map(move |c| (r, c)));
+    for (i, (report, case)) in cases.enumerate() {
+        let name = if case.name == report.model {
+            case.name.clone()
+        } else {
+            format!("{} {}", report.model, case.name)
+        };
+        if case.passed() {
+            writeln!(tap, "ok {} - {}", i + 1, name).unwrap();
+            continue;
+        }
+        writeln!(tap, "not ok {} - {}", i + 1, name).unwrap();
+        tap.push_str("  ---\n");
+        if let Some(error) = &case.error {
+            writeln!(tap, "  error: {:?}", error).unwrap();
+        }
+        if let Some(failure) = case.failures.first() {
+            writeln!(tap, "  cycle: {}", failure.cycle).unwrap();
+            writeln!(tap, "  expected: {:?}", fmt_signal(&failure.expected)).unwrap();
+            writeln!(tap, "  actual: {:?}", fmt_signal(&failure.actual)).unwrap();
+            writeln!(tap, "  message: {:?}", failure.message).unwrap();
+            writeln!(tap, "  failures: {}", case.failures.len()).unwrap();
+        }
+        tap.push_str("  ...\n");
+    }
+    tap
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::common::Input;
+    use crate::components::ProbeStim;
+
+    fn failing_report() -> ModelReport {
+        ModelReport {
+            model: "alu.json".to_string(),
+            cycles: 4,
+            coverage: Coverage {
+                cycles: 4,
+                points: vec![],
+            },
+            cases: vec![
+                TestCase::new("res"),
+                TestCase {
+                    name: "zero".to_string(),
+                    failures: vec![Failure {
+                        cycle: 3,
+                        expected: Some(1.into()),
+                        actual: Some(0.into()),
+                        message: "zero: assertion failed Data(0) != Data(1)".to_string(),
+                    }],
+                    error: None,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_run_store() {
+        let cs = ComponentStore {
+            store: vec![
+                ProbeStim::rc_new("stim", (0.0, 0.0), vec![0, 1, 2, 3]),
+                ProbeAssert::rc_new(
+                    "assert",
+                    (0.0, 0.0),
+                    Input::new("stim", "out"),
+                    vec![0, 1, 2, 3],
+                ),
+                ProbeAssert::rc_new(
+                    "assert_out",
+                    (0.0, 0.0),
+                    Input::new("stim", "out"),
+                    vec![0, 1, 2, 3],
+                ),
+            ],
+        };
+        let report = run_store("model", cs, None);
+        assert_eq!(report.cycles, 4);
+        assert_eq!(report.cases.len(), 2);
+        assert_eq!(report.cases[1].name, "assert_out");
+        assert!(report.passed());
+
+        // a model without probes is a single case
+        let cs = ComponentStore {
+            store: vec![ProbeStim::rc_new("stim", (0.0, 0.0), vec![0, 1])],
+        };
+        let report = run_store("model", cs, Some(2));
+        assert_eq!(report.cases, vec![TestCase::new("model")]);
+        assert_eq!(report.cycles, 2);
+    }
+
+    #[test]
+    fn test_junit() {
+        let xml = junit(&[failing_report()]);
+        assert!(xml.contains("<testsuites tests=\"2\" failures=\"1\" errors=\"0\">"));
+        assert!(xml.contains("<testcase classname=\"alu.json\" name=\"res\"/>"));
+        assert!(
+            xml.contains("<failure type=\"assert\" message=\"cycle 3: expected 0x1, got 0x0\">")
+        );
+    }
+
+    #[test]
+    fn test_tap() {
+        let mut report = failing_report();
+        report.cases[0].error = Some("no column \"res\" in table".to_string());
+        let tap = tap(&[report]);
+        assert!(tap.starts_with("TAP version 13\n1..2\n"));
+        assert!(tap.contains(
+            "not ok 1 - alu.json res\n  ---\n  error: \"no column \\\"res\\\" in table\"\n"
+        ));
+        assert!(tap.contains("not ok 2 - alu.json zero\n"));
+        assert!(tap.contains("  cycle: 3\n  expected: \"0x1\"\n  actual: \"0x0\"\n"));
+    }
+}
@@ -4,6 +4,7 @@ use serde::{Deserialize, Serialize};
 use std::{
     convert::{From, TryFrom},
     fmt,
+    sync::Mutex,
 };
 
 pub type Id = String;
@@ -164,6 +165,41 @@ pub enum SignalFmt {
     Hex(SignalSize, bool), // bool == true for padding
     Binary(u8),            // just to set a limit to the number of bits
     Bool,                  // treats it as true/false
+    Names(ValueNames),     // e.g., the operation names of an op table
+}
+
+// the registered name tables, indexed by `ValueNames`
+static VALUE_NAMES: Mutex<Vec<Vec<(SignalUnsigned, String)>>> = Mutex::new(Vec::new());
+
+/// Handle to a table naming values, e.g., the op codes of an `Alu`
+///
+/// The table is registered once, keeping the format `Copy`. Handles are only
+/// valid in the running program, values without a name show as `UNDEF(n)`.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Copy, Clone)]
+pub struct ValueNames(usize);
+
+impl ValueNames {
+    /// Register `names`, equal tables share a handle
+    pub fn new(names: Vec<(SignalUnsigned, String)>) -> Self {
+        let mut tables = VALUE_NAMES.lock().unwrap();
+        match tables.iter().position(|table| *table == names) {
+            Some(index) => ValueNames(index),
+            None => {
+                tables.push(names);
+                ValueNames(tables.len() - 1)
+            }
+        }
+    }
+
+    /// The name of `value`, none if not in the table
+    pub fn name(&self, value: SignalUnsigned) -> Option<String> {
+        let tables = VALUE_NAMES.lock().unwrap();
+        tables
+            .get(self.0)?
+            .iter()
+            .find(|(v, _)| *v == value)
+            .map(|(_, name)| name.clone())
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Copy, Clone, IntoPrimitive)]
@@ -237,6 +273,10 @@ impl fmt::Display for Signal {
                     }
                 ),
                 SignalFmt::Bool => write!(f, "{}", value != 0),
+                SignalFmt::Names(names) => match names.name(value) {
+                    Some(name) => write!(f, "{}", name),
+                    None => write!(f, "UNDEF({})", value),
+                },
             },
             _ => write!(f, "{:?}", self.data),
         }
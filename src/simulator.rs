@@ -1,27 +1,50 @@
 use crate::common::{
-    Component, ComponentStore, Condition, Id, Input, OutputType, RunningState, Signal, SignalFmt,
-    SignalValue, Simulator, SimulatorError,
+    Component, ComponentStore, Components, Condition, Id, Input, OutputType, RunningState, Signal,
+    SignalFmt, SignalValue, Simulator, SimulatorError,
 };
 use log::*;
 use petgraph::{
-    algo::toposort,
+    algo::{has_path_connecting, toposort},
     dot::{Config, Dot},
     Graph,
 };
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::{fs::File, io::prelude::*, path::PathBuf};
 
+/// A step of evaluating a clock, by index in `ordered_components`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Evaluation {
+    // clock a sequential component, reading the state before the clock
+    Sample(usize),
+    Clock(usize),
+    // applied after the reset inputs are evaluated, see `Component::async_reset`
+    AsyncReset(usize),
+}
+
 pub struct IdComponent(pub HashMap<String, Box<dyn Component>>);
 
 // Notice:
-// The topological order does not enforce any specific order of registers
-// Thus registers cannot point to other registers in a cyclic fashion
-// This is (likely) not occurring in practice.
-//
-// A solution is to evaluate register updates separately from other components
-// ... but not currently implemented ...
+// The topological order does not enforce any specific order of registers,
+// instead all sequential components read the state from before the clock.
+// Thus registers may point to other registers, also in a cyclic fashion.
 impl Simulator {
     pub fn new(component_store: ComponentStore) -> Result<Self, &'static str> {
+        // flatten subcircuits, instantiated components may in turn be subcircuits
+        let mut component_store = component_store;
+        let mut sub_component_ids = HashSet::new();
+        let mut i = 0;
+        while i < component_store.store.len() {
+            let sub_components = component_store.store[i].sub_components().map_err(|e| {
+                error!("{}", e);
+                "Failed to instantiate subcircuit"
+            })?;
+            for c in sub_components {
+                sub_component_ids.insert(c.get_id_ports().0);
+                component_store.store.push(c);
+            }
+            i += 1;
+        }
+
         for component in &component_store.store {
             component.reset();
         }
@@ -83,6 +106,7 @@ impl Simulator {
         }
 
         // insert edges
+        let mut async_reset_edges = vec![];
         for (to_id, c) in &id_component {
             let to_component = id_component.get(to_id).unwrap();
             let (_, ports) = to_component.get_id_ports();
@@ -111,12 +135,41 @@ impl Simulator {
                         to_node
                     );
                 }
+            } else {
+                // an asynchronous reset is applied once its input is evaluated
+                let to_node = id_node.get(to_id).unwrap();
+                for input in c.async_reset_inputs() {
+                    let Some(from_node) = id_node.get(&input.id) else {
+                        println!("to id: {} from port {} is not connected", to_id, input.id);
+                        return Err("A port left unconnected");
+                    };
+                    graph.add_edge(*from_node, *to_node, ());
+                    async_reset_edges.push((*from_node, *to_node));
+                }
             }
         }
 
         // topological order
-        let top =
-            toposort(&graph, None).expect("Topological sort failed, your model contains loops.");
+        let top = toposort(&graph, None).map_err(|cycle| {
+            // an asynchronous reset derived from the output it resets would
+            // have to settle within the cycle, use a synchronous reset instead
+            if let Some((from, to)) = async_reset_edges
+                .iter()
+                .find(|(from, to)| has_path_connecting(&graph, *to, *from, None))
+            {
+                error!(
+                    "asynchronous reset of {} depends on its own output through {}",
+                    graph[*to].as_str(),
+                    graph[*from].as_str()
+                );
+                return "Topological sort failed, an asynchronous reset depends on the output it resets.";
+            }
+            error!(
+                "combinatorial loop through {}",
+                graph[cycle.node_id()].as_str()
+            );
+            "Topological sort failed, your model contains loops."
+        })?;
         trace!("--- topologically ordered graph \n{:?}", top);
         //two passes, first add all sequential roots
         let mut ordered_components = vec![];
@@ -138,6 +191,30 @@ impl Simulator {
             }
         }
 
+        // clock the sequential components, then evaluate the rest in
+        // topological order, applying asynchronous resets along the way
+        let mut evaluation_order = vec![];
+        let mut async_resets = HashMap::new();
+        for (index, c) in ordered_components.iter().enumerate() {
+            if c.get_id_ports().1.out_type == OutputType::Sequential {
+                evaluation_order.push(Evaluation::Sample(index));
+                if !c.async_reset_inputs().is_empty() {
+                    async_resets.insert(c.get_id_ports().0, index);
+                }
+            }
+        }
+        // the combinatorial components follow the sequential ones
+        let mut index = evaluation_order.len();
+        for node in &top {
+            let c = node_comp.get(node).unwrap();
+            if let Some(reset) = async_resets.get(&c.get_id_ports().0) {
+                evaluation_order.push(Evaluation::AsyncReset(*reset));
+            } else if c.get_id_ports().1.out_type == OutputType::Combinatorial {
+                evaluation_order.push(Evaluation::Clock(index));
+                index += 1;
+            }
+        }
+
         let component_ids: Vec<Id> = ordered_components
             .iter()
             .map(|c| c.get_id_ports().0)
@@ -161,6 +238,9 @@ impl Simulator {
             halt_on_warning: false,
             running_state: RunningState::Stopped,
             component_condition: vec![],
+            sub_component_ids,
+            evaluation_order,
+            pre_clock: false,
         };
 
         trace!("sim_state {:?}", simulator.sim_state);
@@ -168,9 +248,13 @@ impl Simulator {
         Ok(simulator)
     }
 
-    /// get input by index
+    /// get input by index, from the state before the clock while clocking
+    /// sequential components
     pub(crate) fn get(&self, index: usize) -> Signal {
-        self.sim_state[index]
+        match self.history.last() {
+            Some(state) if self.pre_clock => state[index],
+            _ => self.sim_state[index],
+        }
     }
 
     /// get input signal
@@ -205,6 +289,35 @@ impl Simulator {
         self.get_input_signal(input).get_value()
     }
 
+    /// the components of the model, i.e., without those instantiated by subcircuits
+    pub fn model_components(&self) -> Components {
+        self.ordered_components
+            .iter()
+            .filter(|c| !self.sub_component_ids.contains(&c.get_id_ports().0))
+            .cloned()
+            .collect()
+    }
+
+    /// get input value as it was in `cycle`, the current cycle reads the current state
+    ///
+    /// While clocking, the cycle being evaluated is `history.len()`.
+    pub fn get_input_value_at(&self, input: &Input, cycle: usize) -> SignalValue {
+        if cycle >= self.history.len() {
+            return self.get_input_value(input);
+        }
+        let index = self.get_id_start_index(&input.id)
+            + *self
+                .id_field_index
+                .get(&(input.id.clone(), input.field.clone()))
+                .unwrap_or_else(|| {
+                    panic!(
+                        "Component {:?}, field {:?} not found.",
+                        input.id, input.field
+                    )
+                });
+        self.history[cycle][index].get_value()
+    }
+
     /// get input fmt
     pub fn get_input_fmt(&self, input: &Input) -> SignalFmt {
         self.get_input_signal(input).get_fmt()
@@ -256,34 +369,43 @@ impl Simulator {
         // TODO push component state
         // clear component condition data for this new cycle
         self.component_condition.clear();
-        for component in self.ordered_components.clone() {
-            //trace!("evaling component:{}", component.get_id_ports().0);
-            match component.clock(self) {
-                Ok(_) => {}
-                Err(cond) => {
-                    self.component_condition
-                        .push((component.get_id_ports().0, cond.clone()));
-                    match cond {
-                        Condition::Warning(warn) => {
-                            trace!("warning {}", warn);
-                            if self.halt_on_warning {
+        for evaluation in self.evaluation_order.clone() {
+            match evaluation {
+                Evaluation::Sample(index) | Evaluation::Clock(index) => {
+                    let component = self.ordered_components[index].clone();
+                    self.pre_clock = matches!(evaluation, Evaluation::Sample(_));
+                    //trace!("evaling component:{}", component.get_id_ports().0);
+                    let result = component.clock(self);
+                    self.pre_clock = false;
+                    if let Err(cond) = result {
+                        self.component_condition
+                            .push((component.get_id_ports().0, cond.clone()));
+                        match cond {
+                            Condition::Warning(warn) => {
+                                trace!("warning {}", warn);
+                                if self.halt_on_warning {
+                                    self.running_state = RunningState::Halt;
+                                }
+                            }
+                            Condition::Error(err) => {
+                                error!("component error {}", err);
+                                self.running_state = RunningState::Err;
+                            }
+                            Condition::Assert(assert) => {
+                                error!("assertion failed {}", assert);
+                                self.running_state = RunningState::Halt;
+                            }
+                            Condition::Halt(halt) => {
+                                info!("halt {}", halt);
                                 self.running_state = RunningState::Halt;
                             }
-                        }
-                        Condition::Error(err) => {
-                            error!("component error {}", err);
-                            self.running_state = RunningState::Err;
-                        }
-                        Condition::Assert(assert) => {
-                            error!("assertion failed {}", assert);
-                            self.running_state = RunningState::Halt;
-                        }
-                        Condition::Halt(halt) => {
-                            info!("halt {}", halt);
-                            self.running_state = RunningState::Halt;
                         }
                     }
                 }
+                Evaluation::AsyncReset(index) => {
+                    let component = self.ordered_components[index].clone();
+                    component.async_reset(self);
+                }
             }
         }
         self.cycle = self.history.len();
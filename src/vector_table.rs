@@ -0,0 +1,283 @@
+// Test vector tables for `ProbeStim` and `ProbeAssert`
+//
+// A table is a text file with one column per signal, e.g.:
+//
+// # stimuli for the alu
+// repeat, a,    b,   res
+//      1, 0,    0,   0
+//      3, 0x10, 0b1, -
+//       , 7,    1,   8
+//
+// The first non comment line holds the column names, values are separated by
+// `,` or whitespace. Values are given in decimal (possibly negative), `0x` hex
+// or `0b` binary, `-` is don't-care. The optional `repeat` column holds the
+// number of consecutive cycles a row applies, an empty field repeats once.
+// `#` starts a comment.
+use crate::common::{Signal, SignalValue};
+use serde::{Deserialize, Serialize};
+use std::{
+    cell::RefCell,
+    fs,
+    path::{Path, PathBuf},
+};
+
+pub const REPEAT_COLUMN: &str = "repeat";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct VectorRow {
+    // line number in the source, starting at 1
+    pub line: usize,
+    pub repeat: usize,
+    pub values: Vec<SignalValue>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct VectorTable {
+    pub columns: Vec<String>,
+    pub rows: Vec<VectorRow>,
+}
+
+/// The value of a column for a single cycle, with its origin in the table
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VectorEntry {
+    pub value: SignalValue,
+    // data row number, starting at 1
+    pub row: usize,
+    pub line: usize,
+}
+
+// split a line into fields, on `,` if present else on whitespace
+fn split_fields(line: &str) -> Vec<&str> {
+    if line.contains(',') {
+        line.split(',').map(|f| f.trim()).collect()
+    } else {
+        line.split_whitespace().collect()
+    }
+}
+
+/// Parse a single table value, `-` is don't-care
+pub fn parse_value(field: &str) -> Result<SignalValue, String> {
+    let field = field.replace('_', "");
+    let parsed = if field == "-" {
+        return Ok(SignalValue::DontCare);
+    } else if let Some(hex) = field.strip_prefix("0x").or(field.strip_prefix("0X")) {
+        u32::from_str_radix(hex, 16).ok()
+    } else if let Some(bin) = field.strip_prefix("0b").or(field.strip_prefix("0B")) {
+        u32::from_str_radix(bin, 2).ok()
+    } else if field.starts_with('-') {
+        field.parse::<i32>().ok().map(|v| v as u32)
+    } else {
+        field.parse::<u32>().ok()
+    };
+    parsed
+        .map(SignalValue::Data)
+        .ok_or(format!("invalid value {:?}", field))
+}
+
+impl VectorTable {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut columns: Option<Vec<String>> = None;
+        let mut rows = vec![];
+        for (i, line) in text.lines().enumerate() {
+            let line_nr = i + 1;
+            let line = line.split('#').next().unwrap().trim();
+            if line.is_empty() {
+                continue;
+            }
+            let fields = split_fields(line);
+            let Some(columns) = &columns else {
+                columns = Some(fields.iter().map(|f| f.to_string()).collect());
+                continue;
+            };
+            if fields.len() != columns.len() {
+                return Err(format!(
+                    "line {}: expected {} fields, found {}",
+                    line_nr,
+                    columns.len(),
+                    fields.len()
+                ));
+            }
+            let mut repeat = 1;
+            let mut values = vec![];
+            for (column, field) in columns.iter().zip(fields) {
+                if column == REPEAT_COLUMN {
+                    if !field.is_empty() {
+                        repeat = field.parse().map_err(|_| {
+                            format!("line {}: invalid repeat count {:?}", line_nr, field)
+                        })?;
+                    }
+                } else {
+                    values
+                        .push(parse_value(field).map_err(|e| {
+                            format!("line {}, column {:?}: {}", line_nr, column, e)
+                        })?);
+                }
+            }
+            rows.push(VectorRow {
+                line: line_nr,
+                repeat,
+                values,
+            });
+        }
+        Ok(VectorTable {
+            columns: columns
+                .ok_or("table has no header".to_string())?
+                .into_iter()
+                .filter(|c| c != REPEAT_COLUMN)
+                .collect(),
+            rows,
+        })
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = fs::read_to_string(path).map_err(|e| format!("{:?}: {}", path, e))?;
+        VectorTable::parse(&text).map_err(|e| format!("{:?}: {}", path, e))
+    }
+
+    /// The values of `column` with repeats expanded, one entry per cycle
+    pub fn column(&self, column: &str) -> Result<Vec<VectorEntry>, String> {
+        let index = self
+            .columns
+            .iter()
+            .position(|c| c == column)
+            .ok_or(format!("no column {:?} in table", column))?;
+        Ok(self
+            .rows
+            .iter()
+            .enumerate()
+            .flat_map(|(row, r)| {
+                std::iter::repeat_n(
+                    VectorEntry {
+                        value: r.values[index],
+                        row: row + 1,
+                        line: r.line,
+                    },
+                    r.repeat,
+                )
+            })
+            .collect())
+    }
+}
+
+/// Reference to a column in a table file
+///
+/// The file is read on first use and re-read on every evaluation of cycle 0,
+/// i.e., on simulator creation and reset, so that vectors can be changed
+/// without touching the model. A relative path is resolved against the
+/// directory of the model, see `set_model_dir`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct VectorSource {
+    pub file: PathBuf,
+    pub column: String,
+    #[serde(skip)]
+    model_dir: PathBuf,
+    #[serde(skip)]
+    entries: RefCell<Option<Result<Vec<VectorEntry>, String>>>,
+}
+
+impl VectorSource {
+    pub fn new(file: impl Into<PathBuf>, column: &str) -> Self {
+        VectorSource {
+            file: file.into(),
+            column: column.to_string(),
+            model_dir: PathBuf::new(),
+            entries: RefCell::new(None),
+        }
+    }
+
+    /// Resolve a relative file against `dir`, the file is read again
+    pub fn set_model_dir(&mut self, dir: &Path) {
+        self.model_dir = dir.to_path_buf();
+        *self.entries.get_mut() = None;
+    }
+
+    /// The entry for `cycle`, `None` if the table ends before
+    pub fn get(&self, cycle: usize) -> Result<Option<VectorEntry>, String> {
+        self.with_entries(|entries| entries.get(cycle).copied())
+    }
+
+    // apply `f` to the entries, loading the file if needed
+    fn with_entries<R>(&self, f: impl FnOnce(&[VectorEntry]) -> R) -> Result<R, String> {
+        let mut entries = self.entries.borrow_mut();
+        if entries.is_none() {
+            *entries = Some(
+                VectorTable::load(&self.model_dir.join(&self.file))
+                    .and_then(|table| table.column(&self.column)),
+            );
+        }
+        match entries.as_ref().unwrap() {
+            Ok(entries) => Ok(f(entries)),
+            Err(e) => Err(e.clone()),
+        }
+    }
+
+    /// As `get`, but reads the file again for cycle 0
+    pub fn get_reload(&self, cycle: usize) -> Result<Option<VectorEntry>, String> {
+        if cycle == 0 {
+            self.clear();
+        }
+        self.get(cycle)
+    }
+
+    /// Number of cycles defined by the column
+    pub fn cycles(&self) -> Result<usize, String> {
+        self.with_entries(|entries| entries.len())
+    }
+
+    /// The signal for `cycle`, `Unknown` if not defined
+    pub fn signal(&self, cycle: usize) -> Signal {
+        match self.get(cycle) {
+            Ok(Some(entry)) => entry.value.into(),
+            _ => SignalValue::Unknown.into(),
+        }
+    }
+
+    /// drop the loaded entries, the file is read again on next use
+    pub fn clear(&self) {
+        *self.entries.borrow_mut() = None;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let table = VectorTable::parse(
+            "# comment\n\
+             repeat, a, b\n\
+             1, 0x10, -  # trailing comment\n\
+             \n\
+             3, 0b101, -1\n\
+             , 7, 8\n",
+        )
+        .unwrap();
+        assert_eq!(table.columns, vec!["a", "b"]);
+        assert_eq!(table.rows.len(), 3);
+        assert_eq!(table.rows[1].line, 5);
+
+        let a: Vec<_> = table.column("a").unwrap().iter().map(|e| e.value).collect();
+        assert_eq!(a, vec![16.into(), 5.into(), 5.into(), 5.into(), 7.into()]);
+        let b = table.column("b").unwrap();
+        assert_eq!(b[0].value, SignalValue::DontCare);
+        assert_eq!(b[3].value, 0xffff_ffff.into());
+        assert_eq!((b[4].row, b[4].line), (3, 6));
+        assert!(table.column("c").is_err());
+    }
+
+    #[test]
+    fn test_parse_whitespace_and_errors() {
+        let table = VectorTable::parse("a b\n1 2\n3 -\n").unwrap();
+        assert_eq!(table.column("b").unwrap()[1].value, SignalValue::DontCare);
+
+        assert_eq!(
+            VectorTable::parse("a, b\n1, 2, 3\n").unwrap_err(),
+            "line 2: expected 2 fields, found 3"
+        );
+        assert_eq!(
+            VectorTable::parse("a, b\n1, zz\n").unwrap_err(),
+            "line 2, column \"b\": invalid value \"zz\""
+        );
+    }
+}
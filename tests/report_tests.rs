@@ -0,0 +1,33 @@
+use syncrim::common::{ComponentStore, Input};
+use syncrim::components::{ProbeAssert, ProbeStim};
+use syncrim::report::{junit, run_store, tap};
+
+// outside of the library unit tests a failed assertion does not panic but is
+// reported, with the cycle of the failing vector
+#[test]
+fn test_run_store_failure() {
+    let cs = ComponentStore {
+        store: vec![
+            ProbeStim::rc_new("stim", (0.0, 0.0), vec![0, 1, 2, 3]),
+            ProbeAssert::rc_new(
+                "assert",
+                (0.0, 0.0),
+                Input::new("stim", "out"),
+                vec![0, 1, 5, 3],
+            ),
+        ],
+    };
+    let report = run_store("model", cs, None);
+    assert!(!report.passed());
+    assert_eq!(report.cycles, 4);
+
+    let failures = &report.cases[0].failures;
+    assert_eq!(failures.len(), 1);
+    assert_eq!(failures[0].cycle, 2);
+    assert_eq!(failures[0].expected, Some(5.into()));
+    assert_eq!(failures[0].actual, Some(2.into()));
+
+    let report = [report];
+    assert!(junit(&report).contains("message=\"cycle 2: expected 0x5, got 0x2\""));
+    assert!(tap(&report).contains("  cycle: 2\n"));
+}